@@ -0,0 +1,151 @@
+//! 转移函数的 criterion 基准：`cargo bench --bench transformers`
+//!
+//! 每个运算在三类输入上计时：常数（singleton）、少量未知位（sparse，每个操作数 4 位）与大量未知位
+//! （dense，每个操作数约 48 位）。每类是固定种子生成的 [`PAIRS`] 对操作数，一次迭代跑完全部，
+//! 吞吐量按对数计。除数的最低位固定为 1，不会是 0；移位量只取低 6 位。
+//!
+//! `mul_skip` 组在左操作数只有少数几位为 1 或未知时，比较 `mul` 与逐位循环的旧实现 [`mul_bitwise`]。
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tnum_z3_verification::tnum::Tnum;
+
+/// 每类输入的操作数对数
+const PAIRS: usize = 256;
+
+type Op = fn(&Tnum, Tnum) -> Tnum;
+
+/// 操作数的形状与每个操作数的未知位数
+const KINDS: &[(&str, u32)] = &[("singleton", 0), ("sparse", 4), ("dense", 48)];
+
+/// 右操作数的约束
+#[derive(Clone, Copy)]
+enum Rhs {
+    Any,
+    /// 除数：最低位为 1
+    Divisor,
+    /// 移位量：只有低 6 位
+    Shift,
+}
+
+const OPS: &[(&str, Op, Rhs)] = &[
+    ("add", Tnum::add, Rhs::Any),
+    ("mul", Tnum::mul, Rhs::Any),
+    ("mul_opt", Tnum::mul_opt, Rhs::Any),
+    ("xtnum_mul_top", Tnum::xtnum_mul_top, Rhs::Any),
+    ("udiv", Tnum::udiv, Rhs::Divisor),
+    ("sdiv", Tnum::sdiv, Rhs::Divisor),
+    ("fast_divide", Tnum::fast_divide, Rhs::Divisor),
+    ("shl", |a, b| a.shl(&b), Rhs::Shift),
+    ("lshr", |a, b| a.lshr(&b), Rhs::Shift),
+];
+
+/// xorshift64，固定种子使每次运行的输入相同
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// 在 allowed 的位中随机选 bits 位作为未知位，其余位随机取值
+    fn tnum(&mut self, bits: u32, allowed: u64) -> Tnum {
+        let mut mask = 0u64;
+        while mask.count_ones() < bits.min(allowed.count_ones()) {
+            mask |= (1 << (self.next() % 64)) & allowed;
+        }
+        Tnum::new(self.next() & allowed & !mask, mask)
+    }
+}
+
+fn inputs(unknown: u32, rhs: Rhs) -> Vec<(Tnum, Tnum)> {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15 ^ unknown as u64);
+    (0..PAIRS)
+        .map(|_| {
+            let a = rng.tnum(unknown, u64::MAX);
+            let b = match rhs {
+                Rhs::Any => rng.tnum(unknown, u64::MAX),
+                Rhs::Divisor => {
+                    let b = rng.tnum(unknown, !1);
+                    Tnum::new(b.value | 1, b.mask)
+                }
+                Rhs::Shift => rng.tnum(unknown.min(3), 63),
+            };
+            (a, b)
+        })
+        .collect()
+}
+
+/// 逐位扫描左操作数的旧 `mul`，作为跳过已知 0 位的对照
+fn mul_bitwise(a: &Tnum, b: Tnum) -> Tnum {
+    if a.is_bottom() || b.is_bottom() {
+        return Tnum::bottom();
+    } else if a.is_top() || b.is_top() {
+        return Tnum::top();
+    }
+    let (mut a, mut b) = (*a, b);
+    let acc_v = a.value.wrapping_mul(b.value);
+    let mut acc_m = Tnum::const_val(0);
+    while a.value != 0 || a.mask != 0 {
+        if a.value & 1 != 0 {
+            acc_m = acc_m.add(Tnum::new(0, b.mask));
+        } else if a.mask & 1 != 0 {
+            acc_m = acc_m.add(Tnum::new(0, b.value | b.mask));
+        }
+        a = a.lshr_const(1);
+        b = b.shl_const(1);
+    }
+    Tnum::const_val(acc_v).add(acc_m)
+}
+
+/// 左操作数 value|mask 只有 set 个位、分布在全部 64 位上
+fn sparse_lhs(set: u32) -> Vec<(Tnum, Tnum)> {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d ^ set as u64);
+    (0..PAIRS)
+        .map(|_| {
+            let bits = rng.tnum(set, u64::MAX).mask;
+            let mask = bits & rng.next();
+            (Tnum::new(bits & !mask, mask), rng.tnum(16, u64::MAX))
+        })
+        .collect()
+}
+
+fn mul_skip(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mul_skip");
+    group.throughput(Throughput::Elements(PAIRS as u64));
+    for set in [2, 8, 32] {
+        let pairs = sparse_lhs(set);
+        for (name, op) in [("mul", Tnum::mul as Op), ("bitwise", mul_bitwise)] {
+            group.bench_with_input(BenchmarkId::new(name, set), &pairs, |bench, pairs| {
+                bench.iter(|| {
+                    for &(a, b) in pairs {
+                        black_box(op(black_box(&a), black_box(b)));
+                    }
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn transformers(c: &mut Criterion) {
+    for &(name, op, rhs) in OPS {
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Elements(PAIRS as u64));
+        for &(kind, unknown) in KINDS {
+            let pairs = inputs(unknown, rhs);
+            group.bench_with_input(BenchmarkId::from_parameter(kind), &pairs, |bench, pairs| {
+                bench.iter(|| {
+                    for &(a, b) in pairs {
+                        black_box(op(black_box(&a), black_box(b)));
+                    }
+                })
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, transformers, mul_skip);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+//! 启用 `kernel-diff` 特性时编译内核的 kernel/bpf/tnum.c，供 src/kernel_diff.rs 差分测试
+//!
+//! tnum.c 的位置由 `KERNEL_TNUM_C`（文件）或 `KERNEL_SRC`（内核源码树）指定；
+//! 内核头文件由 kernel-diff/include 中的最小替代品提供。
+//!
+//! 启用 `ffi` 特性时用 cbindgen 由 src/ffi.rs 生成 `$OUT_DIR/tnum_ffi.h`；设置 `TNUM_FFI_HEADER` 时
+//! 另外写到该路径，如 `TNUM_FFI_HEADER=$PWD/include/tnum_ffi.h` 更新仓库中的头文件。
+
+fn main() {
+    #[cfg(feature = "kernel-diff")]
+    {
+        println!("cargo:rerun-if-env-changed=KERNEL_TNUM_C");
+        println!("cargo:rerun-if-env-changed=KERNEL_SRC");
+        println!("cargo:rerun-if-changed=kernel-diff/include");
+        let source = std::env::var("KERNEL_TNUM_C")
+            .or_else(|_| {
+                std::env::var("KERNEL_SRC").map(|src| format!("{}/kernel/bpf/tnum.c", src))
+            })
+            .expect("kernel-diff 需要设置 KERNEL_TNUM_C 或 KERNEL_SRC 指向内核的 tnum.c");
+        println!("cargo:rerun-if-changed={}", source);
+        cc::Build::new()
+            .file(&source)
+            .include("kernel-diff/include")
+            .warnings(false)
+            .compile("kernel_tnum");
+    }
+    #[cfg(feature = "ffi")]
+    {
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-env-changed=TNUM_FFI_HEADER");
+        let bindings = cbindgen::Builder::new()
+            .with_src("src/ffi.rs")
+            .with_language(cbindgen::Language::C)
+            .with_style(cbindgen::Style::Tag)
+            .with_include_guard("TNUM_FFI_H")
+            .with_no_includes()
+            .with_sys_include("stdint.h")
+            .generate()
+            .expect("cbindgen 无法解析 src/ffi.rs");
+        let out_dir = std::env::var("OUT_DIR").expect("cargo 总会设置 OUT_DIR");
+        bindings.write_to_file(format!("{}/tnum_ffi.h", out_dir));
+        if let Ok(header) = std::env::var("TNUM_FFI_HEADER") {
+            bindings.write_to_file(header);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! 输入字节依次解码为 a、b 的 (value, mask)、两个具体值的随机位与一个参数字节，
+//! 不足的部分补 0。对每个转移函数检查不 panic，以及具体结果在抽象结果中。
+//!
+//! 运行：`cargo fuzz run transformers`
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tnum_z3_verification::tnum::Tnum;
+use tnum_z3_verification::verify;
+
+/// 已知不可靠的运算（见 pipeline 模式），只检查不 panic
+const KNOWN_UNSOUND: &[&str] = &["mul_rec", "xtnum_mul_top", "fast_divide"];
+
+fn word(data: &[u8], i: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    for (b, d) in bytes.iter_mut().zip(data.iter().skip(i * 8)) {
+        *b = *d;
+    }
+    u64::from_le_bytes(bytes)
+}
+
+fn check(name: &str, a: Tnum, x: u64, result: Tnum, r: u64) {
+    assert!(
+        result.contains_value(r),
+        "{}: a={:?}, x={:#x}, 结果 {:#x} 不在 {:?} 中",
+        name,
+        a,
+        x,
+        r,
+        result
+    );
+}
+
+fuzz_target!(|data: &[u8]| {
+    let w = |i| word(data, i);
+    let a = Tnum::new(w(0) & !w(1), w(1));
+    let b = Tnum::new(w(2) & !w(3), w(3));
+    let (x, y) = (a.value | (w(4) & a.mask), b.value | (w(5) & b.mask));
+    let param = data.get(48).copied().unwrap_or(0);
+
+    for op in verify::BINARY_OPS {
+        let result = (op.abs)(&a, b);
+        if KNOWN_UNSOUND.contains(&op.name) {
+            continue;
+        }
+        if let Some(r) = (op.con)(x, y) {
+            assert!(
+                result.contains_value(r),
+                "{}: a={:?}, b={:?}, x={:#x}, y={:#x}, 结果 {:#x} 不在 {:?} 中",
+                op.name,
+                a,
+                b,
+                x,
+                y,
+                r,
+                result
+            );
+        }
+    }
+
+    let result = a.signed_div(b);
+    if y != 0 {
+        let r = (x as i64).wrapping_div(y as i64) as u64;
+        check("signed_div", a, x, result, r);
+    }
+
+    check("not", a, x, a.not(), !x);
+    check("neg", a, x, a.neg(), x.wrapping_neg());
+    check("popcount", a, x, a.popcount(), x.count_ones() as u64);
+    check("bswap16", a, x, a.bswap16(), (x as u16).swap_bytes() as u64);
+    check("bswap32", a, x, a.bswap32(), (x as u32).swap_bytes() as u64);
+    check("bswap64", a, x, a.bswap64(), x.swap_bytes());
+
+    let size = param % 8 + 1;
+    let low = u64::MAX >> (64 - size as u32 * 8);
+    check("cast", a, x, a.cast(size), x & low);
+    let bits = param % 64 + 1;
+    let shift = 64 - bits as u32;
+    check("trunc", a, x, a.trunc(bits as u32), x << shift >> shift);
+    let sext = ((x << shift) as i64 >> shift) as u64;
+    check("sign_extend", a, x, a.sign_extend(bits), sext);
+
+    let lane_size = [1u32, 2, 4][param as usize % 3];
+    let offset = (param as u32 / 3) % (9 - lane_size);
+    let lane = (u64::MAX >> (64 - lane_size * 8)) << (offset * 8);
+    let stored = (x & !lane) | ((y << (offset * 8)) & lane);
+    check("store_bytes", a, x, a.store_bytes(offset, lane_size, b), stored);
+    let field = (x & lane) >> (offset * 8);
+    check("load_bytes", a, x, a.load_bytes(offset, lane_size, false), field);
+    let shift = 64 - lane_size * 8;
+    let field = ((field << shift) as i64 >> shift) as u64;
+    check("load_bytes_sx", a, x, a.load_bytes(offset, lane_size, true), field);
+
+    let k = w(5);
+    let amount = (k % 64) as u32;
+    check("shl_const", a, x, a.shl_const(k), x << amount);
+    check("lshr_const", a, x, a.lshr_const(k), x >> amount);
+    check("ashr_const", a, x, a.ashr_const(k), ((x as i64) >> amount) as u64);
+    check("rotl_const", a, x, a.rotl_const(k), x.rotate_left(amount));
+    check("rotr_const", a, x, a.rotr_const(k), x.rotate_right(amount));
+
+    let c = w(2);
+    if c != 0 {
+        check("fast_urem_const", a, x, a.fast_urem_const(c), x % c);
+        let r = (x as i64).wrapping_rem(c as i64) as u64;
+        check("fast_srem_const", a, x, a.fast_srem_const(c as i64), r);
+    }
+});
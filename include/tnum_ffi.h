@@ -0,0 +1,90 @@
+#ifndef TNUM_FFI_H
+#define TNUM_FFI_H
+
+#include <stdint.h>
+
+/**
+ * 与内核 `struct tnum` 相同的内存布局
+ */
+struct tnum {
+  uint64_t value;
+  uint64_t mask;
+};
+
+/**
+ * 常数 `value`
+ */
+struct tnum tnum_const(uint64_t value);
+
+/**
+ * 包含 [min, max] 的最小 tnum
+ */
+struct tnum tnum_range(uint64_t min, uint64_t max);
+
+struct tnum tnum_lshift(struct tnum a, uint8_t shift);
+
+struct tnum tnum_rshift(struct tnum a, uint8_t shift);
+
+/**
+ * 算术右移，`insn_bitness` 为 32 时按 32 位子寄存器计算
+ */
+struct tnum tnum_arshift(struct tnum a, uint8_t min_shift, uint8_t insn_bitness);
+
+struct tnum tnum_add(struct tnum a, struct tnum b);
+
+struct tnum tnum_sub(struct tnum a, struct tnum b);
+
+struct tnum tnum_mul(struct tnum a, struct tnum b);
+
+struct tnum tnum_and(struct tnum a, struct tnum b);
+
+struct tnum tnum_or(struct tnum a, struct tnum b);
+
+struct tnum tnum_xor(struct tnum a, struct tnum b);
+
+/**
+ * 无符号除法（`Tnum::udiv`）
+ */
+struct tnum tnum_udiv(struct tnum a, struct tnum b);
+
+/**
+ * 基于 fastdivide 的无符号除法（`Tnum::best_udiv`）
+ *
+ * 与 `udiv` 的结果取 meet；除数未知位过多时 `fast_divide` 回退到有符号的 `sdiv`，
+ * 对无符号除法不可靠，此时只用 `udiv`。
+ */
+struct tnum tnum_fast_divide(struct tnum a,
+                             struct tnum b);
+
+/**
+ * 有符号除法（`Tnum::sdiv`）
+ */
+struct tnum tnum_sdiv(struct tnum a, struct tnum b);
+
+/**
+ * 无符号取余（`Tnum::urem`）
+ */
+struct tnum tnum_umod(struct tnum a, struct tnum b);
+
+/**
+ * 有符号取余（`Tnum::srem`）
+ */
+struct tnum tnum_smod(struct tnum a, struct tnum b);
+
+/**
+ * 与内核相同：value 取两者之或，mask 取两者之与；已知位冲突时不产生 bottom
+ */
+struct tnum tnum_intersect(struct tnum a,
+                           struct tnum b);
+
+/**
+ * 格上的 join（`Tnum::join`）
+ */
+struct tnum tnum_union(struct tnum a, struct tnum b);
+
+/**
+ * 截断到低 `size` 个字节
+ */
+struct tnum tnum_cast(struct tnum a, uint8_t size);
+
+#endif /* TNUM_FFI_H */
@@ -0,0 +1,880 @@
+//! eBPF 字节码的抽象解释驱动
+//!
+//! [`decode`] 把原始指令字节（每条 8 字节、小端，`lddw` 占两条）解码为 [`Insn`]；
+//! [`analyze`] 在控制流图上用 [`RegState`] 做前向数据流分析：汇合点求 join，回边上加宽，
+//! 得到每条指令执行前的寄存器事实。内存不建模，加载的值只保留访问宽度带来的已知零位。
+//!
+//! bpf-to-bpf 调用（`call` 且 src 为 1）按调用串区分上下文分析被调函数：进入与返回时的
+//! r6–r10 由 [`CallStack`] 保存与恢复，r10 按 sBPF 的栈帧布局切换。sBPF 的 `callx` 在目标寄存器为
+//! 常数时同样进入被调函数，否则按默认的 [`CallSummary`] 处理。helper 调用只把 r0–r5 变为 top。
+use crate::frame::{
+    CallStack, CallSummary, CallxTarget, FrameError, SbpfVersion, MM_PROGRAM_START,
+};
+use crate::interp::{
+    abstract_atomic, AluInsn, Bitness, BpfAluOp, BpfAtomicOp, BpfJmpOp, Endian, InterpError,
+    IsaProfile, RegState, Src, FRAME_POINTER,
+};
+use crate::pqr;
+use crate::regval::RegValue;
+use crate::tnum::Tnum;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+const BPF_LD: u8 = 0x00;
+const BPF_LDX: u8 = 0x01;
+const BPF_ST: u8 = 0x02;
+const BPF_STX: u8 = 0x03;
+const BPF_ALU: u8 = 0x04;
+const BPF_JMP: u8 = 0x05;
+const BPF_JMP32: u8 = 0x06;
+const BPF_ALU64: u8 = 0x07;
+
+const BPF_X: u8 = 0x08;
+const BPF_MEM: u8 = 0x60;
+const BPF_MEMSX: u8 = 0x80;
+const BPF_ATOMIC: u8 = 0xc0;
+const BPF_FETCH: i32 = 0x01;
+const LDDW: u8 = 0x18;
+const CALL: u8 = 0x85;
+const CALLX: u8 = 0x8d;
+const EXIT: u8 = 0x95;
+/// `call` 的 src 为 1 时立即数是相对被调函数入口的偏移
+const BPF_PSEUDO_CALL: u8 = 1;
+
+/// 一个指令槽；`lddw` 的第二个槽只提供立即数的高 32 位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Insn {
+    pub opcode: u8,
+    pub dst: u8,
+    pub src: u8,
+    pub off: i16,
+    pub imm: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriverError {
+    /// 字节数不是 8 的倍数
+    TruncatedInsn,
+    /// `lddw` 缺少第二个槽
+    IncompleteLddw(usize),
+    UnknownOpcode(usize, u8),
+    /// 跳转目标超出程序
+    JumpOutOfRange(usize),
+    /// 跳转目标是 `lddw` 的第二个槽
+    JumpIntoLddw(usize),
+    /// 最后一条指令之后没有 exit
+    FallsOffEnd(usize),
+    Interp(usize, InterpError),
+    Frame(usize, FrameError),
+}
+
+impl fmt::Display for DriverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DriverError::TruncatedInsn => f.write_str("字节数不是 8 的倍数"),
+            DriverError::IncompleteLddw(pc) => write!(f, "{}: lddw 缺少第二个槽", pc),
+            DriverError::UnknownOpcode(pc, op) => write!(f, "{}: 未知的操作码 0x{:02x}", pc, op),
+            DriverError::JumpOutOfRange(pc) => write!(f, "{}: 跳转目标超出程序", pc),
+            DriverError::JumpIntoLddw(pc) => write!(f, "{}: 跳转目标是 lddw 的第二个槽", pc),
+            DriverError::FallsOffEnd(pc) => write!(f, "{}: 执行越过程序末尾", pc),
+            DriverError::Interp(pc, e) => write!(f, "{}: {}", pc, e),
+            DriverError::Frame(pc, e) => write!(f, "{}: {}", pc, e),
+        }
+    }
+}
+
+/// 解码原始指令字节
+pub fn decode(bytes: &[u8]) -> Result<Vec<Insn>, DriverError> {
+    if !bytes.len().is_multiple_of(8) {
+        return Err(DriverError::TruncatedInsn);
+    }
+    let insns: Vec<Insn> = bytes
+        .chunks_exact(8)
+        .map(|b| Insn {
+            opcode: b[0],
+            dst: b[1] & 0x0f,
+            src: b[1] >> 4,
+            off: i16::from_le_bytes([b[2], b[3]]),
+            imm: i32::from_le_bytes([b[4], b[5], b[6], b[7]]),
+        })
+        .collect();
+    let mut pc = 0;
+    while pc < insns.len() {
+        if insns[pc].opcode != LDDW {
+            pc += 1;
+        } else if pc + 1 == insns.len() {
+            return Err(DriverError::IncompleteLddw(pc));
+        } else {
+            pc += 2;
+        }
+    }
+    Ok(insns)
+}
+
+fn alu_op(insn: &Insn) -> Option<BpfAluOp> {
+    Some(match insn.opcode & 0xf0 {
+        0x00 => BpfAluOp::Add,
+        0x10 => BpfAluOp::Sub,
+        0x20 => BpfAluOp::Mul,
+        0x30 if insn.off == 1 => BpfAluOp::Sdiv,
+        0x30 => BpfAluOp::Div,
+        0x40 => BpfAluOp::Or,
+        0x50 => BpfAluOp::And,
+        0x60 => BpfAluOp::Lsh,
+        0x70 => BpfAluOp::Rsh,
+        0x80 => BpfAluOp::Neg,
+        0x90 if insn.off == 1 => BpfAluOp::Smod,
+        0x90 => BpfAluOp::Mod,
+        0xa0 => BpfAluOp::Xor,
+        0xb0 if insn.off == 0 => BpfAluOp::Mov,
+        0xb0 => BpfAluOp::Movsx(u8::try_from(insn.off).ok()?),
+        0xc0 => BpfAluOp::Arsh,
+        0xd0 => {
+            let endian = if insn.opcode & BPF_X != 0 {
+                Endian::Be
+            } else {
+                Endian::Le
+            };
+            BpfAluOp::End(endian, u8::try_from(insn.imm).ok()?)
+        }
+        _ => return None,
+    })
+}
+
+fn jmp_op(code: u8) -> Option<BpfJmpOp> {
+    Some(match code {
+        0x10 => BpfJmpOp::Jeq,
+        0x20 => BpfJmpOp::Jgt,
+        0x30 => BpfJmpOp::Jge,
+        0x40 => BpfJmpOp::Jset,
+        0x50 => BpfJmpOp::Jne,
+        0x60 => BpfJmpOp::Jsgt,
+        0x70 => BpfJmpOp::Jsge,
+        0xa0 => BpfJmpOp::Jlt,
+        0xb0 => BpfJmpOp::Jle,
+        0xc0 => BpfJmpOp::Jslt,
+        0xd0 => BpfJmpOp::Jsle,
+        _ => return None,
+    })
+}
+
+fn atomic_op(imm: i32, r0: Tnum) -> Option<BpfAtomicOp> {
+    Some(match imm {
+        0x00 => BpfAtomicOp::Add,
+        0x01 => BpfAtomicOp::FetchAdd,
+        0x40 => BpfAtomicOp::Or,
+        0x41 => BpfAtomicOp::FetchOr,
+        0x50 => BpfAtomicOp::And,
+        0x51 => BpfAtomicOp::FetchAnd,
+        0xa0 => BpfAtomicOp::Xor,
+        0xa1 => BpfAtomicOp::FetchXor,
+        0xe1 => BpfAtomicOp::Xchg,
+        0xf1 => BpfAtomicOp::Cmpxchg(r0),
+        _ => return None,
+    })
+}
+
+/// 访问宽度（字节）
+fn access_size(opcode: u8) -> u32 {
+    match opcode & 0x18 {
+        0x00 => 4,
+        0x08 => 2,
+        0x10 => 1,
+        _ => 8,
+    }
+}
+
+/// 写目的寄存器，r10 只读
+fn write(state: &mut RegState, reg: u8, value: Tnum) -> Result<(), InterpError> {
+    if reg == FRAME_POINTER {
+        return Err(InterpError::ReadOnlyFramePointer);
+    }
+    state.set(reg, RegValue::from_tnum(value))
+}
+
+/// pc 是否为 `lddw` 的第二个槽
+///
+/// 紧挨在 pc 之前的一串 0x18 槽里，第一个必然是指令的开头（它前面的槽要么是普通指令，
+/// 要么是某条 `lddw` 的第二个槽），之后两两成对，所以串长为奇数时 pc 是第二个槽。
+fn lddw_tail(insns: &[Insn], pc: usize) -> bool {
+    insns[..pc]
+        .iter()
+        .rev()
+        .take_while(|i| i.opcode == LDDW)
+        .count()
+        % 2
+        == 1
+}
+
+/// pc 处的跳转或调用以 off 为偏移的目标
+fn jump_target(insns: &[Insn], pc: usize, off: i64) -> Result<usize, DriverError> {
+    let t = pc as i64 + 1 + off;
+    if !(0..insns.len() as i64).contains(&t) {
+        Err(DriverError::JumpOutOfRange(pc))
+    } else if lddw_tail(insns, t as usize) {
+        Err(DriverError::JumpIntoLddw(pc))
+    } else {
+        Ok(t as usize)
+    }
+}
+
+/// bpf-to-bpf 调用的被调函数入口；不是这类调用时为 None
+fn callee(insns: &[Insn], pc: usize) -> Option<Result<usize, DriverError>> {
+    let insn = insns[pc];
+    (insn.opcode == CALL && insn.src == BPF_PSEUDO_CALL)
+        .then(|| jump_target(insns, pc, insn.imm as i64))
+}
+
+/// 一条指令的转移：返回每个后继及到达它的状态，不可达的边上为 bottom
+///
+/// 只在函数内转移：call 按 helper 处理，直接到达下一条指令；进入被调函数与从中返回由 [`analyze`] 处理。
+fn transfer(
+    profile: IsaProfile,
+    insns: &[Insn],
+    pc: usize,
+    mut state: RegState,
+) -> Result<Vec<(usize, RegState)>, DriverError> {
+    let insn = insns[pc];
+    let unknown = DriverError::UnknownOpcode(pc, insn.opcode);
+    let interp = |e| DriverError::Interp(pc, e);
+    let target = || jump_target(insns, pc, insn.off as i64);
+    let src = if insn.opcode & BPF_X != 0 {
+        Src::Reg(insn.src)
+    } else {
+        Src::Imm(insn.imm)
+    };
+    let class = insn.opcode & 0x07;
+    match class {
+        BPF_ALU | BPF_ALU64 => {
+            let bitness = if class == BPF_ALU64 {
+                Bitness::B64
+            } else {
+                Bitness::B32
+            };
+            let op = alu_op(&insn).ok_or(unknown)?;
+            let alu = AluInsn {
+                op,
+                bitness,
+                dst: insn.dst,
+                src,
+            };
+            state.step(profile, &alu).map_err(interp)?;
+            Ok(vec![(pc + 1, state)])
+        }
+        BPF_JMP32 if profile.is_sbf() => {
+            let pqr = match profile {
+                IsaProfile::SbfV2 => pqr::decode(insn.opcode).ok_or(unknown)?,
+                _ => return Err(unknown),
+            };
+            let dst = state.tnum(insn.dst).map_err(interp)?;
+            let src = match src {
+                Src::Reg(r) => state.tnum(r).map_err(interp)?,
+                Src::Imm(imm) => pqr::imm_operand(imm),
+            };
+            write(&mut state, insn.dst, pqr::transfer(pqr, dst, src)).map_err(interp)?;
+            Ok(vec![(pc + 1, state)])
+        }
+        BPF_JMP | BPF_JMP32 => {
+            let bitness = if class == BPF_JMP {
+                Bitness::B64
+            } else {
+                Bitness::B32
+            };
+            match insn.opcode & 0xf0 {
+                0x00 if class == BPF_JMP => Ok(vec![(target()?, state)]),
+                0x80 if class == BPF_JMP && (insn.opcode != CALLX || profile.is_sbf()) => {
+                    for reg in 0..=5 {
+                        state.set(reg, RegValue::top()).map_err(interp)?;
+                    }
+                    Ok(vec![(pc + 1, state)])
+                }
+                0x90 if class == BPF_JMP => Ok(Vec::new()),
+                code => {
+                    let cond = jmp_op(code).ok_or(unknown)?;
+                    let (mut taken, mut fallthrough) = (state, state);
+                    taken
+                        .assume(cond, bitness, insn.dst, src, true)
+                        .map_err(interp)?;
+                    fallthrough
+                        .assume(cond, bitness, insn.dst, src, false)
+                        .map_err(interp)?;
+                    Ok(vec![(target()?, taken), (pc + 1, fallthrough)])
+                }
+            }
+        }
+        BPF_LD if insn.opcode == LDDW => {
+            let high = insns[pc + 1].imm as u32 as u64;
+            let value = (high << 32) | insn.imm as u32 as u64;
+            write(&mut state, insn.dst, Tnum::const_val(value)).map_err(interp)?;
+            Ok(vec![(pc + 2, state)])
+        }
+        BPF_LDX => {
+            let bits = access_size(insn.opcode) * 8;
+            let loaded = Tnum::top().zext(bits);
+            let value = match insn.opcode & 0xe0 {
+                BPF_MEM => loaded,
+                BPF_MEMSX if bits < 64 => loaded.sign_extend(bits as u8),
+                _ => return Err(unknown),
+            };
+            write(&mut state, insn.dst, value).map_err(interp)?;
+            Ok(vec![(pc + 1, state)])
+        }
+        BPF_ST if insn.opcode & 0xe0 == BPF_MEM => Ok(vec![(pc + 1, state)]),
+        BPF_STX => match insn.opcode & 0xe0 {
+            BPF_MEM => Ok(vec![(pc + 1, state)]),
+            BPF_ATOMIC => {
+                let bits = access_size(insn.opcode) * 8;
+                if bits < 32 {
+                    return Err(unknown);
+                }
+                let r0 = state.tnum(0).map_err(interp)?;
+                let op = atomic_op(insn.imm, r0.zext(bits)).ok_or(unknown)?;
+                let value = state.tnum(insn.src).map_err(interp)?.zext(bits);
+                let (_, returned) = abstract_atomic(op, Tnum::top().zext(bits), value);
+                match op {
+                    BpfAtomicOp::Cmpxchg(_) => write(&mut state, 0, returned),
+                    _ if insn.imm & BPF_FETCH != 0 => write(&mut state, insn.src, returned),
+                    _ => Ok(()),
+                }
+                .map_err(interp)?;
+                Ok(vec![(pc + 1, state)])
+            }
+            _ => Err(unknown),
+        },
+        _ => Err(unknown),
+    }
+}
+
+/// 顺序执行的下一条指令，`lddw` 跳过第二个槽
+fn next_pc(insns: &[Insn], pc: usize) -> usize {
+    if insns[pc].opcode == LDDW {
+        pc + 2
+    } else {
+        pc + 1
+    }
+}
+
+/// 按控制流划分基本块，返回每个块的首尾指令下标（含）
+///
+/// 后继由入口状态为 top 的转移得到；无法解码的指令与 `lddw` 的第二个槽视为没有后继。
+/// bpf-to-bpf 调用的被调函数入口也是块首。
+pub fn basic_blocks(profile: IsaProfile, insns: &[Insn]) -> Vec<(usize, usize)> {
+    let succs: Vec<Vec<usize>> = (0..insns.len())
+        .map(|pc| {
+            if lddw_tail(insns, pc) {
+                return Vec::new();
+            }
+            transfer(profile, insns, pc, RegState::top())
+                .map(|outs| outs.into_iter().map(|(s, _)| s).collect())
+                .unwrap_or_default()
+        })
+        .collect();
+    let ends_block = |pc: usize| succs[pc] != [next_pc(insns, pc)];
+    let mut leaders = BTreeSet::from([0]);
+    let mut pc = 0;
+    while pc < insns.len() {
+        if ends_block(pc) {
+            leaders.extend(succs[pc].iter().copied());
+            leaders.insert(next_pc(insns, pc));
+        }
+        if let Some(Ok(entry)) = callee(insns, pc) {
+            leaders.insert(entry);
+        }
+        pc = next_pc(insns, pc);
+    }
+    leaders.retain(|&pc| pc < insns.len());
+    leaders
+        .iter()
+        .map(|&start| {
+            let mut end = start;
+            while !ends_block(end) {
+                let next = next_pc(insns, end);
+                if next >= insns.len() || leaders.contains(&next) {
+                    break;
+                }
+                end = next;
+            }
+            (start, end)
+        })
+        .collect()
+}
+
+/// 到达不动点后的收窄轮数
+const NARROWING_ROUNDS: usize = 2;
+
+/// 调用串：从最外层起各层 call 指令的下标，最外层函数为空
+type Context = Vec<usize>;
+
+/// 过程间分析的结点：指令下标与所在的调用串，先按下标排序
+type Node = (usize, Context);
+
+fn tnums(state: &RegState) -> [Tnum; 11] {
+    std::array::from_fn(|i| state.regs[i].tnum)
+}
+
+/// 把 [`CallStack`] 改写后的 tnum 写回寄存器状态
+///
+/// CallStack 只保存 tnum：寄存器的 tnum 与某个来源状态中的相同时沿用来源的区间，
+/// 与多个来源相同时取 join；top 与新产生的值只保留 tnum。
+fn with_tnums(regs: &[Tnum; 11], sources: &[&RegState]) -> RegState {
+    RegState {
+        regs: std::array::from_fn(|i| {
+            if regs[i].is_top() {
+                return RegValue::top();
+            }
+            sources
+                .iter()
+                .filter(|s| s.regs[i].tnum == regs[i])
+                .map(|s| s.regs[i])
+                .reduce(|a, b| a.join(&b))
+                .unwrap_or_else(|| RegValue::from_tnum(regs[i]))
+        }),
+    }
+}
+
+/// 过程间分析的状态
+struct Interproc<'a> {
+    profile: IsaProfile,
+    insns: &'a [Insn],
+    /// 所有 exit 指令的下标
+    exits: Vec<usize>,
+    /// 各调用串中执行时的调用栈
+    stacks: BTreeMap<Context, CallStack>,
+    facts: BTreeMap<Node, RegState>,
+}
+
+impl<'a> Interproc<'a> {
+    /// 最外层函数的调用栈；sBPF 下入口状态的 r10 设为栈顶。Linux 没有 sBPF 的栈布局，
+    /// 按 v2 处理且 r10 保持入口状态中的值
+    fn new(profile: IsaProfile, insns: &'a [Insn], entry: &mut RegState) -> Self {
+        let version = match profile {
+            IsaProfile::SbfV1 => SbpfVersion::V1 { gaps: true },
+            _ => SbpfVersion::V2,
+        };
+        let mut regs = tnums(entry);
+        let stack = CallStack::new(version, &mut regs);
+        if profile.is_sbf() {
+            entry.regs[FRAME_POINTER as usize] = RegValue::from_tnum(regs[10]);
+        }
+        let mut exits = Vec::new();
+        let mut pc = 0;
+        while pc < insns.len() {
+            if insns[pc].opcode == EXIT {
+                exits.push(pc);
+            }
+            pc = next_pc(insns, pc);
+        }
+        Self {
+            profile,
+            insns,
+            exits,
+            stacks: BTreeMap::from([(Vec::new(), stack)]),
+            facts: BTreeMap::new(),
+        }
+    }
+
+    fn fact(&self, node: &Node) -> RegState {
+        self.facts
+            .get(node)
+            .copied()
+            .unwrap_or_else(RegState::bottom)
+    }
+
+    /// 在 ctx 中以 state 执行 call：压入新帧后的调用栈与寄存器
+    fn call(&self, ctx: &Context, state: &RegState) -> Result<(CallStack, [Tnum; 11]), FrameError> {
+        let mut stack = self.stacks[ctx].clone();
+        let mut regs = tnums(state);
+        stack.call(&mut regs)?;
+        if self.profile == IsaProfile::LinuxEbpf {
+            // Linux 的每一帧有自己的栈，被调函数的 r10 未知
+            regs[10] = Tnum::top();
+        }
+        Ok((stack, regs))
+    }
+
+    /// 被调函数以 exit 状态返回到 ctx 中状态为 caller 的 call 之后
+    fn ret(
+        &self,
+        ctx: &Context,
+        caller: &RegState,
+        exit: &RegState,
+    ) -> Result<RegState, FrameError> {
+        let (mut stack, _) = self.call(ctx, caller)?;
+        let mut regs = tnums(exit);
+        stack.exit(&mut regs);
+        Ok(with_tnums(&regs, &[exit, caller]))
+    }
+
+    /// 进入 entry 处的被调函数：stack 与 regs 是 call 之后的调用栈与寄存器
+    fn enter(
+        &mut self,
+        node: &Node,
+        state: &RegState,
+        entry: usize,
+        stack: CallStack,
+        regs: [Tnum; 11],
+    ) -> Result<Vec<(Node, RegState)>, DriverError> {
+        let (pc, ctx) = node;
+        let mut inner = ctx.clone();
+        inner.push(*pc);
+        self.stacks.insert(inner.clone(), stack);
+        let mut succs = vec![((entry, inner.clone()), with_tnums(&regs, &[state]))];
+        for &e in &self.exits {
+            let exit = self.fact(&(e, inner.clone()));
+            if !exit.is_bottom() {
+                let out = self
+                    .ret(ctx, state, &exit)
+                    .map_err(|e| DriverError::Frame(*pc, e))?;
+                succs.push(((pc + 1, ctx.clone()), out));
+            }
+        }
+        Ok(succs)
+    }
+
+    /// `callx`：v1 的目标寄存器在立即数中，v2 在 src 中，寄存器的值是被调函数入口在程序区中的地址
+    fn callx(
+        &mut self,
+        node: &Node,
+        state: &RegState,
+    ) -> Result<Vec<(Node, RegState)>, DriverError> {
+        let (pc, ctx) = node;
+        let insn = self.insns[*pc];
+        let reg = match self.profile {
+            IsaProfile::SbfV2 => insn.src,
+            _ => u8::try_from(insn.imm).unwrap_or(u8::MAX),
+        };
+        state.tnum(reg).map_err(|e| DriverError::Interp(*pc, e))?;
+        let mut stack = self.stacks[ctx].clone();
+        let mut regs = tnums(state);
+        let target = stack
+            .callx(&mut regs, reg as usize, &CallSummary::default())
+            .map_err(|e| DriverError::Frame(*pc, e))?;
+        match target {
+            CallxTarget::Resolved(addr) => {
+                let offset = addr.wrapping_sub(MM_PROGRAM_START);
+                if !offset.is_multiple_of(8) || offset / 8 >= self.insns.len() as u64 {
+                    return Err(DriverError::JumpOutOfRange(*pc));
+                }
+                let entry = jump_target(self.insns, *pc, (offset / 8) as i64 - *pc as i64 - 1)?;
+                self.enter(node, state, entry, stack, regs)
+            }
+            CallxTarget::Summarized => {
+                Ok(vec![((pc + 1, ctx.clone()), with_tnums(&regs, &[state]))])
+            }
+        }
+    }
+
+    /// 结点的后继及到达它的状态
+    ///
+    /// bpf-to-bpf 调用进入被调函数的调用串，并把被调函数各个 exit 处已有的状态返回到调用之后；
+    /// 被调函数的 exit 按调用者在 call 处的状态返回。其余指令按 [`transfer`] 在同一调用串中转移。
+    fn successors(
+        &mut self,
+        node: &Node,
+        state: &RegState,
+    ) -> Result<Vec<(Node, RegState)>, DriverError> {
+        let (pc, ctx) = node;
+        let frame = |e| DriverError::Frame(*pc, e);
+        if let Some(entry) = callee(self.insns, *pc) {
+            let (stack, regs) = self.call(ctx, state).map_err(frame)?;
+            return self.enter(node, state, entry?, stack, regs);
+        }
+        if self.insns[*pc].opcode == CALLX && self.profile.is_sbf() {
+            return self.callx(node, state);
+        }
+        if let (EXIT, Some((&site, outer))) = (self.insns[*pc].opcode, ctx.split_last()) {
+            let outer = outer.to_vec();
+            let caller = self.fact(&(site, outer.clone()));
+            if caller.is_bottom() {
+                return Ok(Vec::new());
+            }
+            let out = self.ret(&outer, &caller, state).map_err(frame)?;
+            return Ok(vec![((site + 1, outer), out)]);
+        }
+        Ok(transfer(self.profile, self.insns, *pc, *state)?
+            .into_iter()
+            .map(|(succ, out)| ((succ, ctx.clone()), out))
+            .collect())
+    }
+}
+
+fn join_into(states: &mut BTreeMap<Node, RegState>, node: Node, out: &RegState) {
+    let old = states.entry(node).or_insert_with(RegState::bottom);
+    *old = old.join(out);
+}
+
+/// 从入口状态出发的前向数据流分析，返回每条指令执行前的状态，不可达的指令为 bottom
+///
+/// 结点是指令与调用串，同一指令在各调用串中的状态求 join 后作为结果；sBPF 下入口的 r10 为栈顶。
+/// 工作表每次取最小的结点；跳向不大于自身的结点视为回边，在目标处用
+/// [`RegState::widen`] 代替 join，保证迭代终止。之后按结点顺序重新计算若干轮并与原结果求 meet，
+/// 收回加宽丢掉的循环边界。递归调用在调用深度超过上限时报错。
+pub fn analyze(
+    profile: IsaProfile,
+    insns: &[Insn],
+    entry: RegState,
+) -> Result<Vec<RegState>, DriverError> {
+    let mut facts = vec![RegState::bottom(); insns.len()];
+    if insns.is_empty() {
+        return Ok(facts);
+    }
+    let mut entry = entry;
+    let mut cx = Interproc::new(profile, insns, &mut entry);
+    let root: Node = (0, Vec::new());
+    cx.facts.insert(root.clone(), entry);
+    let mut worklist = BTreeSet::from([root.clone()]);
+    while let Some(node) = worklist.pop_first() {
+        let state = cx.fact(&node);
+        if state.is_bottom() {
+            continue;
+        }
+        for (succ, out) in cx.successors(&node, &state)? {
+            if out.is_bottom() {
+                continue;
+            } else if succ.0 >= insns.len() {
+                return Err(DriverError::FallsOffEnd(node.0));
+            }
+            let old = cx.fact(&succ);
+            let new = if succ <= node {
+                old.widen(&out)
+            } else {
+                old.join(&out)
+            };
+            if new != old {
+                cx.facts.insert(succ.clone(), new);
+                worklist.insert(succ);
+            }
+        }
+    }
+    let nodes: Vec<Node> = cx.facts.keys().cloned().collect();
+    for _ in 0..NARROWING_ROUNDS {
+        let mut incoming = BTreeMap::from([(root.clone(), entry)]);
+        for node in &nodes {
+            let state = cx.fact(node);
+            if state.is_bottom() {
+                continue;
+            }
+            for (succ, out) in cx.successors(node, &state)? {
+                if succ <= *node {
+                    join_into(&mut incoming, succ, &out);
+                }
+            }
+        }
+        for node in &nodes {
+            let into = incoming.get(node).copied().unwrap_or_else(RegState::bottom);
+            let narrowed = cx.fact(node).meet(&into);
+            if narrowed.is_bottom() {
+                cx.facts.insert(node.clone(), RegState::bottom());
+                continue;
+            }
+            cx.facts.insert(node.clone(), narrowed);
+            for (succ, out) in cx.successors(node, &narrowed)? {
+                if succ > *node && succ.0 < insns.len() {
+                    join_into(&mut incoming, succ, &out);
+                }
+            }
+        }
+    }
+    for ((pc, _), state) in &cx.facts {
+        facts[*pc] = facts[*pc].join(state);
+    }
+    Ok(facts)
+}
+
+/// 报告中一个寄存器的事实
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegFact {
+    pub reg: u8,
+    /// tnum 的 value 与 mask，十六进制
+    pub value: String,
+    pub mask: String,
+    /// x 记法，高位在前
+    pub bits: String,
+    pub umin: u64,
+    pub umax: u64,
+    pub smin: i64,
+    pub smax: i64,
+    /// 值总是 2^align_log2 的倍数；常数 0 时为 64
+    pub align_log2: u32,
+    pub is_const: bool,
+}
+
+impl RegFact {
+    pub fn new(reg: u8, v: &RegValue) -> Self {
+        Self {
+            reg,
+            value: format!("{:#x}", v.tnum.value),
+            mask: format!("{:#x}", v.tnum.mask),
+            bits: v.tnum.to_sbin(64),
+            umin: v.umin,
+            umax: v.umax,
+            smin: v.smin,
+            smax: v.smax,
+            align_log2: (v.tnum.value | v.tnum.mask).trailing_zeros(),
+            is_const: v.tnum.is_singleton(),
+        }
+    }
+}
+
+/// 报告中一条指令执行前的事实；不可达时 `regs` 为空
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InsnFacts {
+    pub pc: usize,
+    pub opcode: u8,
+    pub reachable: bool,
+    pub regs: Vec<RegFact>,
+}
+
+/// [`analyze`] 结果的报告形式，以 JSON 导出供外部工具使用
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Report {
+    pub profile: String,
+    pub insns: Vec<InsnFacts>,
+}
+
+impl Report {
+    pub fn new(profile: IsaProfile, insns: &[Insn], facts: &[RegState]) -> Self {
+        let insns = insns
+            .iter()
+            .zip(facts)
+            .enumerate()
+            .map(|(pc, (insn, state))| {
+                let reachable = !state.is_bottom();
+                let regs = if reachable {
+                    (0..)
+                        .zip(&state.regs)
+                        .map(|(r, v)| RegFact::new(r, v))
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                InsnFacts {
+                    pc,
+                    opcode: insn.opcode,
+                    reachable,
+                    regs,
+                }
+            })
+            .collect();
+        Self {
+            profile: profile.name().to_string(),
+            insns,
+        }
+    }
+}
+
+/// Graphviz 标签中的一行寄存器事实：x 记法省去前导的已知 0 位
+fn dot_fact(reg: usize, v: &RegValue) -> String {
+    let width = 64 - (v.tnum.value | v.tnum.mask).leading_zeros().min(63);
+    format!(
+        "r{} = {:w$} u=[{}, {}] s=[{}, {}]",
+        reg,
+        v.tnum,
+        v.umin,
+        v.umax,
+        v.smin,
+        v.smax,
+        w = width as usize
+    )
+}
+
+/// 非 top 的寄存器，每个一行
+fn dot_facts(state: &RegState, out: &mut String) {
+    for (r, v) in state.regs.iter().enumerate() {
+        if *v != RegValue::top() {
+            out.push_str(&format!("  {}\\l", dot_fact(r, v)));
+        }
+    }
+}
+
+/// 以 Graphviz dot 格式输出基本块级的控制流图
+///
+/// 每个块标出入口处的寄存器事实、指令与出口处的事实（各出边状态的 join）；
+/// 出边上标出被条件细化、与出口不同的寄存器。只列出非 top 的寄存器，不可达的块为灰色。
+/// bpf-to-bpf 调用另有一条指向被调函数入口的虚线边。
+pub fn to_dot(profile: IsaProfile, insns: &[Insn], facts: &[RegState]) -> String {
+    let mut dot = String::from("digraph cfg {\n    node [shape=box, fontname=monospace];\n");
+    for (start, end) in basic_blocks(profile, insns) {
+        let mut label = String::new();
+        let entry = facts[start];
+        let outs = if entry.is_bottom() {
+            Vec::new()
+        } else {
+            transfer(profile, insns, end, facts[end]).unwrap_or_default()
+        };
+        if !entry.is_bottom() {
+            label.push_str("入口\\l");
+            dot_facts(&entry, &mut label);
+            label.push_str("\\l");
+        }
+        let mut calls = Vec::new();
+        let mut pc = start;
+        while pc <= end {
+            let insn = insns[pc];
+            label.push_str(&format!(
+                "{:>4}: {:02x} r{} r{} {:+} {:#x}\\l",
+                pc, insn.opcode, insn.dst, insn.src, insn.off, insn.imm
+            ));
+            if let Some(Ok(entry)) = callee(insns, pc) {
+                calls.push(entry);
+            }
+            pc = next_pc(insns, pc);
+        }
+        // exit 没有后继，出口即执行它之前的状态
+        let exit = match outs.is_empty() {
+            true => facts[end],
+            false => outs
+                .iter()
+                .fold(RegState::bottom(), |acc, (_, out)| acc.join(out)),
+        };
+        if !exit.is_bottom() {
+            label.push_str("\\l出口\\l");
+            dot_facts(&exit, &mut label);
+        }
+        let style = if entry.is_bottom() {
+            ", style=filled, fillcolor=lightgray"
+        } else {
+            ""
+        };
+        dot.push_str(&format!("    b{} [label=\"{}\"{}];\n", start, label, style));
+        let succs: Vec<(usize, RegState)> = if entry.is_bottom() {
+            transfer(profile, insns, end, RegState::top()).unwrap_or_default()
+        } else {
+            outs
+        };
+        for entry in calls {
+            dot.push_str(&format!(
+                "    b{} -> b{} [label=\"调用\", style=dashed];\n",
+                start, entry
+            ));
+        }
+        let branches = succs.len() > 1;
+        for (i, (succ, out)) in succs.iter().enumerate() {
+            if *succ >= insns.len() {
+                continue;
+            }
+            let mut edge = String::new();
+            if branches {
+                edge.push_str(if i == 0 { "跳转\\l" } else { "不跳转\\l" });
+            }
+            if out.is_bottom() && !entry.is_bottom() {
+                edge.push_str("不可能\\l");
+            } else if !entry.is_bottom() {
+                for (r, v) in out.regs.iter().enumerate() {
+                    if *v != exit.regs[r] {
+                        edge.push_str(&format!("{}\\l", dot_fact(r, v)));
+                    }
+                }
+            }
+            dot.push_str(&format!(
+                "    b{} -> b{} [label=\"{}\"];\n",
+                start, succ, edge
+            ));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
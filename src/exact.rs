@@ -0,0 +1,147 @@
+//! 精确抽象求值：用位图显式表示具体值集合，作为独立的基准
+//!
+//! 对位宽不超过 16 的操作数，枚举 γ(a) × γ(b) 得到所有具体结果（按 2^width 取模），
+//! 再取其最优 tnum 抽象。与见证模式中的暴力枚举和 SMT 后端互相独立。
+use crate::tnum::Tnum;
+
+pub const MAX_WIDTH: u32 = 16;
+
+/// 宽度为 width 的具体值集合，第 x 位为 1 表示 x 在集合中
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GammaSet {
+    width: u32,
+    bits: Vec<u64>,
+}
+
+impl GammaSet {
+    pub fn empty(width: u32) -> Self {
+        assert!(width <= MAX_WIDTH, "位宽至多为 {}", MAX_WIDTH);
+        let words = (1usize << width).div_ceil(64);
+        Self {
+            width,
+            bits: vec![0; words],
+        }
+    }
+
+    fn modulus_mask(&self) -> u64 {
+        (1u64 << self.width) - 1
+    }
+
+    /// γ(t) 截断到低 width 位后的集合
+    pub fn of(t: &Tnum, width: u32) -> Self {
+        let mut set = Self::empty(width);
+        if !t.is_bottom() {
+            let m = set.modulus_mask();
+            crate::tnum::for_each_concrete(&Tnum::new(t.value & m, t.mask & m), |x| set.insert(x));
+        }
+        set
+    }
+
+    pub fn insert(&mut self, x: u64) {
+        let x = x & self.modulus_mask();
+        self.bits[(x / 64) as usize] |= 1u64 << (x % 64);
+    }
+
+    pub fn len(&self) -> u64 {
+        self.bits.iter().map(|w| w.count_ones() as u64).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|&w| w == 0)
+    }
+
+    /// 按从小到大的顺序迭代集合中的值
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.bits.iter().enumerate().flat_map(|(i, &word)| {
+            let mut w = word;
+            std::iter::from_fn(move || {
+                if w == 0 {
+                    return None;
+                }
+                let bit = w.trailing_zeros() as u64;
+                w &= w - 1;
+                Some(i as u64 * 64 + bit)
+            })
+        })
+    }
+
+    /// 最优抽象：包含集合中所有值的最小 tnum，空集为 bottom
+    pub fn best_tnum(&self) -> Tnum {
+        if self.is_empty() {
+            return Tnum::bottom();
+        }
+        let mut ones = self.modulus_mask();
+        let mut any = 0u64;
+        for x in self.iter() {
+            ones &= x;
+            any |= x;
+        }
+        Tnum::new(ones, any ^ ones)
+    }
+
+    /// 集合中的每个值是否都在 γ(t) 中（只看低 width 位），即 t 是否可靠
+    pub fn is_covered_by(&self, t: &Tnum) -> bool {
+        let m = self.modulus_mask();
+        self.iter()
+            .all(|x| x & !(t.mask & m) == t.value & !(t.mask & m))
+    }
+}
+
+/// 计算 { f(x, y) mod 2^width | x ∈ γ(a), y ∈ γ(b) } 的精确集合
+///
+/// 操作数先截断到低 width 位。`f` 返回 `None` 表示该输入无定义（如除以 0），会被跳过。
+pub fn exact_eval<F>(a: &Tnum, b: &Tnum, width: u32, f: F) -> GammaSet
+where
+    F: Fn(u64, u64) -> Option<u64>,
+{
+    let mut result = GammaSet::empty(width);
+    let xs = GammaSet::of(a, width);
+    let ys: Vec<u64> = GammaSet::of(b, width).iter().collect();
+    for x in xs.iter() {
+        for &y in &ys {
+            if let Some(r) = f(x, y) {
+                result.insert(r);
+            }
+        }
+    }
+    result
+}
+
+/// 暴力求二元运算的最优 tnum 抽象：γ(a) × γ(b) 上全部 64 位结果的 join
+///
+/// 具体值对超过 `budget` 个时返回 `None`。与 [`exact_eval`] 不同，不限制位宽，
+/// 用来衡量转移函数相对最优抽象的精度损失。
+pub fn best_abstract<F>(a: Tnum, b: Tnum, f: F, budget: usize) -> Option<Tnum>
+where
+    F: Fn(u64, u64) -> u64,
+{
+    best_abstract_partial(a, b, |x, y| Some(f(x, y)), budget)
+}
+
+/// 同 [`best_abstract`]，`f` 返回 `None` 的输入（如除以 0）被跳过，没有任何结果时为 bottom
+pub fn best_abstract_partial<F>(a: Tnum, b: Tnum, f: F, budget: usize) -> Option<Tnum>
+where
+    F: Fn(u64, u64) -> Option<u64>,
+{
+    if a.cardinality().saturating_mul(b.cardinality()) > budget as u128 {
+        return None;
+    }
+    let ys: Vec<u64> = b.iter_concrete().collect();
+    // 全部结果的按位与为已知的 1，按位或之外为已知的 0
+    let (mut ones, mut any, mut empty) = (u64::MAX, 0u64, true);
+    for x in a.iter_concrete() {
+        for &y in &ys {
+            if let Some(r) = f(x, y) {
+                ones &= r;
+                any |= r;
+                empty = false;
+            }
+        }
+    }
+    Some(if empty {
+        Tnum::bottom()
+    } else {
+        Tnum::new(ones, any ^ ones)
+    })
+}
@@ -0,0 +1,158 @@
+//! 供 C 验证器调用的转移函数（需要 `ffi` 特性）
+//!
+//! [`tnum`] 与内核的 `struct tnum { u64 value; u64 mask; }` 布局相同，按值传递；函数名与内核 tnum.c
+//! 一致，可以直接替换内核的实现。除法与取余按 eBPF 语义处理除数为 0：商为 0，余数为被除数。
+//!
+//! 启用 `ffi` 特性时 build.rs 用 cbindgen 由本文件生成 `$OUT_DIR/tnum_ffi.h`，设置 `TNUM_FFI_HEADER`
+//! 时另外写到该路径；仓库中的 include/tnum_ffi.h 由此更新。静态库用
+//! `cargo rustc --release --lib --no-default-features --features std,ffi --crate-type staticlib` 构建。
+use crate::tnum::{DivZeroPolicy, Tnum};
+
+/// 与内核 `struct tnum` 相同的内存布局
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct tnum {
+    pub value: u64,
+    pub mask: u64,
+}
+
+impl From<Tnum> for tnum {
+    fn from(t: Tnum) -> Self {
+        Self {
+            value: t.value,
+            mask: t.mask,
+        }
+    }
+}
+
+impl From<tnum> for Tnum {
+    fn from(t: tnum) -> Self {
+        Tnum::new(t.value, t.mask)
+    }
+}
+
+/// 常数 `value`
+#[no_mangle]
+pub extern "C" fn tnum_const(value: u64) -> tnum {
+    Tnum::const_val(value).into()
+}
+
+/// 包含 [min, max] 的最小 tnum
+#[no_mangle]
+pub extern "C" fn tnum_range(min: u64, max: u64) -> tnum {
+    Tnum::from_range(min, max).into()
+}
+
+#[no_mangle]
+pub extern "C" fn tnum_lshift(a: tnum, shift: u8) -> tnum {
+    Tnum::from(a).tnum_lshift(shift).into()
+}
+
+#[no_mangle]
+pub extern "C" fn tnum_rshift(a: tnum, shift: u8) -> tnum {
+    Tnum::from(a).tnum_rshift(shift).into()
+}
+
+/// 算术右移，`insn_bitness` 为 32 时按 32 位子寄存器计算
+#[no_mangle]
+pub extern "C" fn tnum_arshift(a: tnum, min_shift: u8, insn_bitness: u8) -> tnum {
+    let a = Tnum::from(a);
+    if insn_bitness == 32 {
+        a.alu32(a, |x, _| x.ashr_const(min_shift as u64)).into()
+    } else {
+        a.ashr_const(min_shift as u64).into()
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn tnum_add(a: tnum, b: tnum) -> tnum {
+    Tnum::from(a).add(b.into()).into()
+}
+
+#[no_mangle]
+pub extern "C" fn tnum_sub(a: tnum, b: tnum) -> tnum {
+    Tnum::from(a).sub(b.into()).into()
+}
+
+#[no_mangle]
+pub extern "C" fn tnum_mul(a: tnum, b: tnum) -> tnum {
+    Tnum::from(a).mul(b.into()).into()
+}
+
+#[no_mangle]
+pub extern "C" fn tnum_and(a: tnum, b: tnum) -> tnum {
+    Tnum::from(a).bit_and(b.into()).into()
+}
+
+#[no_mangle]
+pub extern "C" fn tnum_or(a: tnum, b: tnum) -> tnum {
+    Tnum::from(a).bit_or(b.into()).into()
+}
+
+#[no_mangle]
+pub extern "C" fn tnum_xor(a: tnum, b: tnum) -> tnum {
+    Tnum::from(a).xor(b.into()).into()
+}
+
+/// 无符号除法（`Tnum::udiv`）
+#[no_mangle]
+pub extern "C" fn tnum_udiv(a: tnum, b: tnum) -> tnum {
+    Tnum::from(a)
+        .udiv_with(b.into(), DivZeroPolicy::ReturnZero)
+        .into()
+}
+
+/// 基于 fastdivide 的无符号除法（`Tnum::best_udiv`）
+///
+/// 与 `udiv` 的结果取 meet；除数未知位过多时 `fast_divide` 回退到有符号的 `sdiv`，
+/// 对无符号除法不可靠，此时只用 `udiv`。
+#[no_mangle]
+pub extern "C" fn tnum_fast_divide(a: tnum, b: tnum) -> tnum {
+    Tnum::from(a)
+        .best_udiv_with(b.into(), DivZeroPolicy::ReturnZero)
+        .into()
+}
+
+/// 有符号除法（`Tnum::sdiv`）
+#[no_mangle]
+pub extern "C" fn tnum_sdiv(a: tnum, b: tnum) -> tnum {
+    Tnum::from(a)
+        .sdiv_with(b.into(), DivZeroPolicy::ReturnZero)
+        .into()
+}
+
+/// 无符号取余（`Tnum::urem`）
+#[no_mangle]
+pub extern "C" fn tnum_umod(a: tnum, b: tnum) -> tnum {
+    Tnum::from(a)
+        .urem_with(b.into(), DivZeroPolicy::ReturnDividend)
+        .into()
+}
+
+/// 有符号取余（`Tnum::srem`）
+#[no_mangle]
+pub extern "C" fn tnum_smod(a: tnum, b: tnum) -> tnum {
+    Tnum::from(a)
+        .srem_with(b.into(), DivZeroPolicy::ReturnDividend)
+        .into()
+}
+
+/// 与内核相同：value 取两者之或，mask 取两者之与；已知位冲突时不产生 bottom
+#[no_mangle]
+#[allow(deprecated)]
+pub extern "C" fn tnum_intersect(a: tnum, b: tnum) -> tnum {
+    Tnum::from(a).intersect(b.into()).into()
+}
+
+/// 格上的 join（`Tnum::join`）
+#[no_mangle]
+pub extern "C" fn tnum_union(a: tnum, b: tnum) -> tnum {
+    Tnum::from(a).join(b.into()).into()
+}
+
+/// 截断到低 `size` 个字节
+#[no_mangle]
+pub extern "C" fn tnum_cast(a: tnum, size: u8) -> tnum {
+    Tnum::from(a).cast(size).into()
+}
@@ -0,0 +1,402 @@
+//! SBF 调用约定下的栈帧与 r10 建模
+//!
+//! r10 是只读的帧指针：普通指令不能写它，只有 call / exit 会切换它；sBPF v2 另外允许
+//! `add64 r10, imm` 动态调整栈。v1 中每个栈帧固定 4096 字节（可选帧间空隙），
+//! call 时 r10 上移一帧；v2 中 call 不改变 r10，由函数自行下移，下移得到的 [r10, 原 r10) 属于当前帧。
+//!
+//! 每一帧记录调用者的 r6–r9、r10 以及该帧内 8 字节对齐槽位的 tnum，未记录的槽位为 top。
+use crate::tnum::Tnum;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// 程序区起始地址，第 pc 条指令位于 `MM_PROGRAM_START + 8 * pc`
+pub const MM_PROGRAM_START: u64 = 0x1_0000_0000;
+/// 栈区起始地址
+pub const MM_STACK_START: u64 = 0x2_0000_0000;
+/// v1 单个栈帧大小
+pub const STACK_FRAME_SIZE: u64 = 4096;
+/// 最大调用深度
+pub const MAX_CALL_DEPTH: usize = 64;
+
+const SLOT: u64 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbpfVersion {
+    /// 固定大小的栈帧；`gaps` 为 true 时相邻帧之间留一帧大小的空隙
+    V1 { gaps: bool },
+    /// 动态栈帧
+    V2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    /// 试图写 r10
+    ReadOnlyFramePointer,
+    /// 只有 v2 能调整 r10
+    StackAdjustUnsupported,
+    /// 调整后的 r10 超出栈区
+    StackOutOfBounds,
+    CallDepthExceeded,
+    /// 访问宽度为 0 或访问越过地址空间末尾
+    BadAccess,
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match self {
+            FrameError::ReadOnlyFramePointer => "r10 是只读的帧指针",
+            FrameError::StackAdjustUnsupported => "只有 sBPF v2 能调整 r10",
+            FrameError::StackOutOfBounds => "r10 超出栈区",
+            FrameError::CallDepthExceeded => "超过最大调用深度",
+            FrameError::BadAccess => "访问宽度为 0 或越过地址空间末尾",
+        };
+        f.write_str(msg)
+    }
+}
+
+/// 无法解析目标的 `callx` 采用的保守摘要
+///
+/// r0 总是变为 top，r6–r10 总是保持不变。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallSummary {
+    /// r1–r5 变为 top
+    pub clobber_caller_saved: bool,
+    /// 被调函数可能经由指针参数写栈，所有帧的槽位变为 top
+    pub havoc_stack: bool,
+}
+
+impl Default for CallSummary {
+    fn default() -> Self {
+        Self {
+            clobber_caller_saved: true,
+            havoc_stack: true,
+        }
+    }
+}
+
+/// `callx` 的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallxTarget {
+    /// 目标唯一确定，已经像 call 一样进入新帧，分析应继续到该地址
+    Resolved(u64),
+    /// 目标未知，已经就地应用摘要，分析继续下一条指令
+    Summarized,
+}
+
+/// 一个栈帧
+#[derive(Debug, Clone)]
+struct Frame {
+    /// 进入该帧时的 r10，也是该帧地址区间的上界
+    entry_fp: u64,
+    /// 调用者的 r6–r9、r10，最外层帧为空
+    saved: Option<[Tnum; 5]>,
+    /// 槽位地址 -> 内容
+    slots: BTreeMap<u64, Tnum>,
+}
+
+/// 调用栈：寄存器文件之外的帧状态
+#[derive(Debug, Clone)]
+pub struct CallStack {
+    version: SbpfVersion,
+    frames: Vec<Frame>,
+}
+
+impl CallStack {
+    /// 栈区总大小
+    pub fn stack_size(version: SbpfVersion) -> u64 {
+        match version {
+            SbpfVersion::V1 { gaps: true } => 2 * STACK_FRAME_SIZE * MAX_CALL_DEPTH as u64,
+            _ => STACK_FRAME_SIZE * MAX_CALL_DEPTH as u64,
+        }
+    }
+
+    /// 建立最外层帧并初始化 r10
+    pub fn new(version: SbpfVersion, regs: &mut [Tnum; 11]) -> Self {
+        let fp = match version {
+            SbpfVersion::V1 { .. } => MM_STACK_START + STACK_FRAME_SIZE,
+            SbpfVersion::V2 => MM_STACK_START + Self::stack_size(version),
+        };
+        regs[10] = Tnum::const_val(fp);
+        Self {
+            version,
+            frames: vec![Frame {
+                entry_fp: fp,
+                saved: None,
+                slots: BTreeMap::new(),
+            }],
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// 写普通寄存器；r10 只读
+    pub fn write_reg(regs: &mut [Tnum; 11], reg: usize, value: Tnum) -> Result<(), FrameError> {
+        if reg == 10 {
+            return Err(FrameError::ReadOnlyFramePointer);
+        }
+        regs[reg] = value;
+        Ok(())
+    }
+
+    /// v2 的 `add64 r10, imm`
+    pub fn adjust_fp(&self, regs: &mut [Tnum; 11], imm: i32) -> Result<(), FrameError> {
+        if self.version != SbpfVersion::V2 {
+            return Err(FrameError::StackAdjustUnsupported);
+        }
+        let fp = regs[10].value.wrapping_add(imm as i64 as u64);
+        let top = MM_STACK_START + Self::stack_size(self.version);
+        if !(MM_STACK_START..=top).contains(&fp) {
+            return Err(FrameError::StackOutOfBounds);
+        }
+        regs[10] = Tnum::const_val(fp);
+        Ok(())
+    }
+
+    /// 进入被调函数：保存 r6–r9、r10 并切换帧；r1–r5 作为参数保留
+    pub fn call(&mut self, regs: &mut [Tnum; 11]) -> Result<(), FrameError> {
+        if self.frames.len() >= MAX_CALL_DEPTH {
+            return Err(FrameError::CallDepthExceeded);
+        }
+        let saved = [regs[6], regs[7], regs[8], regs[9], regs[10]];
+        let fp = match self.version {
+            SbpfVersion::V1 { gaps } => {
+                let step = if gaps { 2 } else { 1 } * STACK_FRAME_SIZE;
+                regs[10].value + step
+            }
+            SbpfVersion::V2 => regs[10].value,
+        };
+        regs[10] = Tnum::const_val(fp);
+        self.frames.push(Frame {
+            entry_fp: fp,
+            saved: Some(saved),
+            slots: BTreeMap::new(),
+        });
+        Ok(())
+    }
+
+    /// 从当前函数返回：恢复 r6–r9、r10，r1–r5 变为 top，r0 保留返回值。
+    /// 最外层帧返回时程序结束，结果为 false。
+    pub fn exit(&mut self, regs: &mut [Tnum; 11]) -> bool {
+        if self.frames.len() == 1 {
+            return false;
+        }
+        let frame = self.frames.pop().unwrap();
+        let saved = frame.saved.unwrap();
+        regs[6..=10].copy_from_slice(&saved);
+        for r in &mut regs[1..=5] {
+            *r = Tnum::top();
+        }
+        true
+    }
+
+    /// 间接调用：目标寄存器是单点 tnum 时解析为普通 call，否则按 `summary` 保守处理
+    pub fn callx(
+        &mut self,
+        regs: &mut [Tnum; 11],
+        target_reg: usize,
+        summary: &CallSummary,
+    ) -> Result<CallxTarget, FrameError> {
+        let target = regs[target_reg];
+        if target.is_singleton() {
+            self.call(regs)?;
+            return Ok(CallxTarget::Resolved(target.value));
+        }
+        regs[0] = Tnum::top();
+        if summary.clobber_caller_saved {
+            for r in &mut regs[1..=5] {
+                *r = Tnum::top();
+            }
+        }
+        if summary.havoc_stack {
+            for f in &mut self.frames {
+                f.slots.clear();
+            }
+        }
+        Ok(CallxTarget::Summarized)
+    }
+
+    /// 地址所在帧的下标：v1 帧区间为 [entry_fp - 4096, entry_fp)，
+    /// v2 的帧区间由相邻帧的 entry_fp 分隔
+    fn frame_of(&self, addr: u64) -> Option<usize> {
+        match self.version {
+            SbpfVersion::V1 { .. } => self
+                .frames
+                .iter()
+                .position(|f| (f.entry_fp - STACK_FRAME_SIZE..f.entry_fp).contains(&addr)),
+            SbpfVersion::V2 => {
+                let mut found = None;
+                for (i, f) in self.frames.iter().enumerate() {
+                    if addr < f.entry_fp && addr >= MM_STACK_START {
+                        found = Some(i);
+                    }
+                }
+                found
+            }
+        }
+    }
+
+    /// 可能落在栈区的地址
+    fn may_hit_stack(addr: &Tnum) -> bool {
+        let min = addr.value;
+        let max = addr.value | addr.mask;
+        max >= MM_STACK_START
+            && min < MM_STACK_START + Self::stack_size(SbpfVersion::V1 { gaps: true })
+    }
+
+    /// 写 size 字节到 addr：先让所有帧中被覆盖的槽位变为 top，确定地址且不跨槽位的写再把值
+    /// 合并进槽位原有的值（未记录时为 top）；地址不确定但可能落在栈区时，所有帧的槽位都变为 top
+    pub fn store(&mut self, addr: Tnum, size: u64, value: Tnum) -> Result<(), FrameError> {
+        if addr.is_singleton() {
+            let slot = addr.value & !(SLOT - 1);
+            let last = Self::last_byte(addr.value, size)? & !(SLOT - 1);
+            let old = self.load(Tnum::const_val(slot));
+            for f in &mut self.frames {
+                let stale: Vec<u64> = f.slots.range(slot..=last).map(|(&k, _)| k).collect();
+                for k in stale {
+                    f.slots.remove(&k);
+                }
+            }
+            if slot == last {
+                if let Some(i) = self.frame_of(addr.value) {
+                    let merged = old.store_bytes((addr.value - slot) as u32, size as u32, value);
+                    self.frames[i].slots.insert(slot, merged);
+                }
+            }
+        } else if Self::may_hit_stack(&addr) {
+            for f in &mut self.frames {
+                f.slots.clear();
+            }
+        }
+        Ok(())
+    }
+
+    /// 从 addr 起 size 字节的最后一个字节的地址
+    fn last_byte(addr: u64, size: u64) -> Result<u64, FrameError> {
+        size.checked_sub(1)
+            .and_then(|n| addr.checked_add(n))
+            .ok_or(FrameError::BadAccess)
+    }
+
+    /// 读 8 字节：确定地址且槽位已记录时返回记录值，否则为 top
+    pub fn load(&self, addr: Tnum) -> Tnum {
+        if addr.is_singleton() && addr.value.is_multiple_of(SLOT) {
+            if let Some(i) = self.frame_of(addr.value) {
+                if let Some(v) = self.frames[i].slots.get(&addr.value) {
+                    return *v;
+                }
+            }
+        }
+        Tnum::top()
+    }
+
+    /// 读 size 字节并零扩展或符号扩展：确定地址且不跨槽位时从槽位中取出对应字节，否则为 top
+    pub fn load_bytes(&self, addr: Tnum, size: u64, signed: bool) -> Result<Tnum, FrameError> {
+        if !addr.is_singleton() {
+            return Ok(Tnum::top());
+        }
+        let slot = addr.value & !(SLOT - 1);
+        if Self::last_byte(addr.value, size)? & !(SLOT - 1) != slot {
+            return Ok(Tnum::top());
+        }
+        Ok(self.load(Tnum::const_val(slot)).load_bytes(
+            (addr.value - slot) as u32,
+            size as u32,
+            signed,
+        ))
+    }
+}
+
+/// 帧模型演示：调用者在自己的帧里存值并把地址传给被调函数，被调函数改写自己的槽位与
+/// 调用者的槽位后返回，检查 r10、callee-saved 寄存器和两个帧的槽位。
+pub fn run_frames_mode(version: SbpfVersion) {
+    println!("=== 栈帧建模（{:?}）===", version);
+    let mut regs = [Tnum::top(); 11];
+    let mut stack = CallStack::new(version, &mut regs);
+    println!("初始 r10 = 0x{:x}", regs[10].value);
+    if version == SbpfVersion::V2 {
+        stack.adjust_fp(&mut regs, -64).unwrap();
+    }
+    let caller_fp = regs[10];
+    // v1 的帧在 r10 之下，v2 下移 r10 分配的空间在 r10 之上
+    let local = |fp: Tnum| match version {
+        SbpfVersion::V1 { .. } => fp.sub(Tnum::const_val(8)),
+        SbpfVersion::V2 => fp.add(Tnum::const_val(8)),
+    };
+
+    let slot = local(caller_fp);
+    stack.store(slot, 8, Tnum::const_val(42)).unwrap();
+    CallStack::write_reg(&mut regs, 6, Tnum::const_val(6)).unwrap();
+    CallStack::write_reg(&mut regs, 1, slot).unwrap();
+    if let Err(e) = CallStack::write_reg(&mut regs, 10, Tnum::top()) {
+        println!("写 r10 被拒绝: {}", e);
+    }
+
+    stack.call(&mut regs).unwrap();
+    if version == SbpfVersion::V2 {
+        stack.adjust_fp(&mut regs, -64).unwrap();
+    }
+    println!(
+        "调用深度 {}，被调函数 r10 = 0x{:x}",
+        stack.depth(),
+        regs[10].value
+    );
+    let callee_slot = local(regs[10]);
+    stack.store(callee_slot, 8, Tnum::const_val(7)).unwrap();
+    stack.store(regs[1], 8, Tnum::new(0x40, 0x3)).unwrap();
+    CallStack::write_reg(&mut regs, 6, Tnum::top()).unwrap();
+    let callee = stack.load(callee_slot);
+    regs[0] = callee;
+    println!("被调函数槽位 = ({}, {})", callee.value, callee.mask);
+
+    let returned = stack.exit(&mut regs);
+    let caller = stack.load(slot);
+    println!(
+        "返回调用者: {}，r10 恢复: {}，r6 = ({}, {})，r0 = ({}, {})",
+        returned,
+        regs[10] == caller_fp,
+        regs[6].value,
+        regs[6].mask,
+        regs[0].value,
+        regs[0].mask
+    );
+    println!("调用者槽位 = ({}, {})", caller.value, caller.mask);
+    println!(
+        "被调函数帧已弹出，其槽位读出 top: {}",
+        stack.load(callee_slot).is_top()
+    );
+    println!("最外层 exit 结束程序: {}", !stack.exit(&mut regs));
+
+    println!("--- callx ---");
+    let summary = CallSummary::default();
+    regs[3] = Tnum::const_val(0x1_0000_0120);
+    match stack.callx(&mut regs, 3, &summary) {
+        Ok(target) => println!("单点目标: {:x?}，调用深度 {}", target, stack.depth()),
+        Err(e) => println!("callx 失败: {}", e),
+    }
+    stack.exit(&mut regs);
+
+    stack.store(slot, 8, Tnum::const_val(42)).unwrap();
+    regs[2] = Tnum::const_val(2);
+    regs[3] = Tnum::new(0x1_0000_0120, 0x18);
+    let keep = CallSummary {
+        clobber_caller_saved: false,
+        havoc_stack: false,
+    };
+    for (name, summary) in [("保留", keep), ("默认", summary)] {
+        let target = stack.callx(&mut regs, 3, &summary).unwrap();
+        let kept = stack.load(slot);
+        println!(
+            "未知目标（{}摘要）: {:?}，调用深度 {}，r0 为 top: {}，r2 = ({}, {})，槽位 = ({}, {})",
+            name,
+            target,
+            stack.depth(),
+            regs[0].is_top(),
+            regs[2].value,
+            regs[2].mask,
+            kept.value,
+            kept.mask
+        );
+    }
+}
@@ -0,0 +1,1169 @@
+//! eBPF ALU 指令在 tnum 上的抽象解释
+//!
+//! [`abstract_alu`] 覆盖 BPF_ALU / BPF_ALU64 的全部运算，是验证器调用本 crate 的入口：
+//! 给定目的寄存器和源操作数的 tnum，返回目的寄存器的新值。语义按 Linux eBPF：
+//! 除以 0 得 0，对 0 取余得被除数，`INT_MIN / -1` 回绕；ALU32 的结果零扩展写回。
+//! 立即数源操作数用 [`crate::pqr::imm_operand`] 符号扩展。Solana sBPF 的语义差异由 [`IsaProfile`]
+//! 选择，v2 的乘除法见 [`crate::pqr`]。
+//!
+//! [`RegState`] 在此之上维护 r0–r10 的约化积（见 [`crate::regval`]），供基本块级的数据流分析使用；
+//! [`RegState::assume`] 按条件跳转的方向细化寄存器，[`check_access`] 判断内存访问是否越界，
+//! [`abstract_atomic`] 跟踪原子指令前后的内存与返回值。
+use crate::lattice::Lattice;
+use crate::regval::RegValue;
+use crate::tnum::{DivZeroPolicy, Tnum};
+use crate::verify::{Counterexample, SoundnessReport};
+use std::fmt;
+
+/// 指令集：Linux eBPF 与 Solana sBPF 的两个版本
+///
+/// sBPF 与内核的差异：除数为 0 是运行时错误；没有 SDIV / SMOD、MOVSX 与 BPF_ALU64 形式的 BPF_END；
+/// v1 的 32 位加、减、乘把结果符号扩展。v2 再去掉 ALU 类的乘除法（改用 PQR 类，有符号除法只在这里）、
+/// NEG 与 BPF_TO_LE，立即数形式的减法交换操作数，计算 `imm - dst`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IsaProfile {
+    #[default]
+    LinuxEbpf,
+    SbfV1,
+    SbfV2,
+}
+
+impl IsaProfile {
+    pub fn name(self) -> &'static str {
+        match self {
+            IsaProfile::LinuxEbpf => "linux",
+            IsaProfile::SbfV1 => "sbf-v1",
+            IsaProfile::SbfV2 => "sbf-v2",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "linux" => Some(IsaProfile::LinuxEbpf),
+            "sbf-v1" => Some(IsaProfile::SbfV1),
+            "sbf-v2" => Some(IsaProfile::SbfV2),
+            _ => None,
+        }
+    }
+
+    pub fn is_sbf(self) -> bool {
+        self != IsaProfile::LinuxEbpf
+    }
+}
+
+/// 指令类：BPF_ALU 为 32 位，BPF_ALU64 为 64 位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bitness {
+    B32,
+    B64,
+}
+
+/// BPF_END 的目标字节序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    /// BPF_TO_LE：小端主机上只截断
+    Le,
+    /// BPF_TO_BE：交换字节
+    Be,
+}
+
+/// BPF_ALU / BPF_ALU64 的运算
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BpfAluOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Sdiv,
+    Mod,
+    Smod,
+    And,
+    Or,
+    Xor,
+    Lsh,
+    Rsh,
+    Arsh,
+    /// 不读源操作数
+    Neg,
+    Mov,
+    /// 从低 8、16 或 32 位符号扩展；32 位形式只取 8 或 16
+    Movsx(u8),
+    /// 字节序转换，位宽取 16、32 或 64；BPF_ALU64 形式总是交换字节，忽略字节序
+    End(Endian, u8),
+}
+
+impl BpfAluOp {
+    pub fn name(self) -> String {
+        match self {
+            BpfAluOp::Add => "add".to_string(),
+            BpfAluOp::Sub => "sub".to_string(),
+            BpfAluOp::Mul => "mul".to_string(),
+            BpfAluOp::Div => "div".to_string(),
+            BpfAluOp::Sdiv => "sdiv".to_string(),
+            BpfAluOp::Mod => "mod".to_string(),
+            BpfAluOp::Smod => "smod".to_string(),
+            BpfAluOp::And => "and".to_string(),
+            BpfAluOp::Or => "or".to_string(),
+            BpfAluOp::Xor => "xor".to_string(),
+            BpfAluOp::Lsh => "lsh".to_string(),
+            BpfAluOp::Rsh => "rsh".to_string(),
+            BpfAluOp::Arsh => "arsh".to_string(),
+            BpfAluOp::Neg => "neg".to_string(),
+            BpfAluOp::Mov => "mov".to_string(),
+            BpfAluOp::Movsx(bits) => format!("movsx{}", bits),
+            BpfAluOp::End(Endian::Le, bits) => format!("le{}", bits),
+            BpfAluOp::End(Endian::Be, bits) => format!("be{}", bits),
+        }
+    }
+
+    /// 该指令集与位宽下是否存在这条指令
+    pub fn is_valid(self, profile: IsaProfile, bitness: Bitness) -> bool {
+        if profile.is_sbf() {
+            let removed = match self {
+                BpfAluOp::Sdiv | BpfAluOp::Smod | BpfAluOp::Movsx(_) => true,
+                BpfAluOp::End(_, _) => bitness == Bitness::B64,
+                _ => false,
+            };
+            let removed_v2 = matches!(
+                self,
+                BpfAluOp::Mul
+                    | BpfAluOp::Div
+                    | BpfAluOp::Mod
+                    | BpfAluOp::Neg
+                    | BpfAluOp::End(Endian::Le, _)
+            );
+            if removed || (profile == IsaProfile::SbfV2 && removed_v2) {
+                return false;
+            }
+        }
+        match (self, bitness) {
+            (BpfAluOp::Movsx(8 | 16), _) | (BpfAluOp::Movsx(32), Bitness::B64) => true,
+            (BpfAluOp::Movsx(_), _) => false,
+            (BpfAluOp::End(_, bits), _) => matches!(bits, 16 | 32 | 64),
+            _ => true,
+        }
+    }
+}
+
+/// 全部运算（`Movsx`、`End` 按位宽展开），用于逐条验证
+pub const ALU_OPS: &[BpfAluOp] = &[
+    BpfAluOp::Add,
+    BpfAluOp::Sub,
+    BpfAluOp::Mul,
+    BpfAluOp::Div,
+    BpfAluOp::Sdiv,
+    BpfAluOp::Mod,
+    BpfAluOp::Smod,
+    BpfAluOp::And,
+    BpfAluOp::Or,
+    BpfAluOp::Xor,
+    BpfAluOp::Lsh,
+    BpfAluOp::Rsh,
+    BpfAluOp::Arsh,
+    BpfAluOp::Neg,
+    BpfAluOp::Mov,
+    BpfAluOp::Movsx(8),
+    BpfAluOp::Movsx(16),
+    BpfAluOp::Movsx(32),
+    BpfAluOp::End(Endian::Le, 16),
+    BpfAluOp::End(Endian::Le, 32),
+    BpfAluOp::End(Endian::Le, 64),
+    BpfAluOp::End(Endian::Be, 16),
+    BpfAluOp::End(Endian::Be, 32),
+    BpfAluOp::End(Endian::Be, 64),
+];
+
+fn low32(t: Tnum) -> Tnum {
+    t.zext(32)
+}
+
+fn sext32(t: Tnum) -> Tnum {
+    t.sign_extend(32)
+}
+
+fn bswap(t: Tnum, bits: u8) -> Tnum {
+    match bits {
+        16 => t.bswap16(),
+        32 => t.bswap32(),
+        _ => t.bswap64(),
+    }
+}
+
+/// ALU 指令的转移函数
+///
+/// 32 位形式只看操作数的低 32 位，结果零扩展；有符号除法与取余在符号扩展后计算。
+/// sBPF 中除数为 0 的输入不产生结果，按 [`DivZeroPolicy::Bottom`] 处理。
+/// 不存在的指令（见 [`BpfAluOp::is_valid`]）会 panic。
+pub fn abstract_alu(
+    profile: IsaProfile,
+    op: BpfAluOp,
+    bitness: Bitness,
+    dst: Tnum,
+    src: Tnum,
+) -> Tnum {
+    assert!(
+        op.is_valid(profile, bitness),
+        "{} 中不存在的指令: {:?} {:?}",
+        profile.name(),
+        bitness,
+        op
+    );
+    let (zero, dividend) = if profile.is_sbf() {
+        (DivZeroPolicy::Bottom, DivZeroPolicy::Bottom)
+    } else {
+        (DivZeroPolicy::ReturnZero, DivZeroPolicy::ReturnDividend)
+    };
+    if profile == IsaProfile::SbfV1 && bitness == Bitness::B32 {
+        match op {
+            BpfAluOp::Add => return sext32(dst.add(src)),
+            BpfAluOp::Sub => return sext32(dst.sub(src)),
+            BpfAluOp::Mul => return sext32(dst.mul(src)),
+            _ => {}
+        }
+    }
+    match bitness {
+        Bitness::B64 => match op {
+            BpfAluOp::Add => dst.add(src),
+            BpfAluOp::Sub => dst.sub(src),
+            BpfAluOp::Mul => dst.mul(src),
+            BpfAluOp::Div => dst.udiv_with(src, zero),
+            BpfAluOp::Sdiv => dst.sdiv_with(src, zero),
+            BpfAluOp::Mod => dst.urem_with(src, dividend),
+            BpfAluOp::Smod => dst.srem_with(src, dividend),
+            BpfAluOp::And => dst.bit_and(src),
+            BpfAluOp::Or => dst.bit_or(src),
+            BpfAluOp::Xor => dst.xor(src),
+            BpfAluOp::Lsh => dst.shl(&src),
+            BpfAluOp::Rsh => dst.lshr(&src),
+            BpfAluOp::Arsh => dst.ashr(&src),
+            BpfAluOp::Neg => dst.neg(),
+            BpfAluOp::Mov => src,
+            BpfAluOp::Movsx(bits) => src.sign_extend(bits),
+            BpfAluOp::End(_, bits) => bswap(dst, bits),
+        },
+        Bitness::B32 => match op {
+            BpfAluOp::Add => dst.alu32(src, |a, b| a.add(b)),
+            BpfAluOp::Sub => dst.alu32(src, |a, b| a.sub(b)),
+            BpfAluOp::Mul => dst.alu32(src, |a, b| a.mul(b)),
+            BpfAluOp::Div => dst.alu32(src, |a, b| a.udiv_with(b, zero)),
+            BpfAluOp::Sdiv => low32(sext32(dst).sdiv_with(sext32(src), zero)),
+            BpfAluOp::Mod => dst.alu32(src, |a, b| a.urem_with(b, dividend)),
+            BpfAluOp::Smod => low32(sext32(dst).srem_with(sext32(src), dividend)),
+            BpfAluOp::And => dst.alu32(src, |a, b| a.bit_and(b)),
+            BpfAluOp::Or => dst.alu32(src, |a, b| a.bit_or(b)),
+            BpfAluOp::Xor => dst.alu32(src, |a, b| a.xor(b)),
+            BpfAluOp::Lsh => dst.alu32(src, |a, b| a.shl(&b)),
+            BpfAluOp::Rsh => dst.alu32(src, |a, b| a.lshr(&b)),
+            BpfAluOp::Arsh => dst.alu32(src, |a, b| a.ashr(&b)),
+            BpfAluOp::Neg => dst.alu32(src, |a, _| a.neg()),
+            BpfAluOp::Mov => low32(src),
+            BpfAluOp::Movsx(bits) => low32(src.sign_extend(bits)),
+            BpfAluOp::End(Endian::Le, bits) => dst.zext(bits as u32),
+            BpfAluOp::End(Endian::Be, bits) => bswap(dst, bits),
+        },
+    }
+}
+
+fn sext(x: u64, bits: u32) -> u64 {
+    let shift = 64 - bits;
+    (((x << shift) as i64) >> shift) as u64
+}
+
+/// ALU 指令的具体语义，sBPF 的运行时错误返回 `None`
+pub fn concrete_alu(
+    profile: IsaProfile,
+    op: BpfAluOp,
+    bitness: Bitness,
+    dst: u64,
+    src: u64,
+) -> Option<u64> {
+    let divisor = match bitness {
+        Bitness::B64 => src,
+        Bitness::B32 => src as u32 as u64,
+    };
+    let divides = matches!(
+        op,
+        BpfAluOp::Div | BpfAluOp::Sdiv | BpfAluOp::Mod | BpfAluOp::Smod
+    );
+    if profile.is_sbf() && divides && divisor == 0 {
+        return None;
+    }
+    if profile == IsaProfile::SbfV1 && bitness == Bitness::B32 {
+        let (a, b) = (dst as i32, src as i32);
+        match op {
+            BpfAluOp::Add => return Some(a.wrapping_add(b) as i64 as u64),
+            BpfAluOp::Sub => return Some(a.wrapping_sub(b) as i64 as u64),
+            BpfAluOp::Mul => return Some(a.wrapping_mul(b) as i64 as u64),
+            _ => {}
+        }
+    }
+    Some(match bitness {
+        Bitness::B64 => {
+            let (a, b) = (dst as i64, src as i64);
+            match op {
+                BpfAluOp::Add => dst.wrapping_add(src),
+                BpfAluOp::Sub => dst.wrapping_sub(src),
+                BpfAluOp::Mul => dst.wrapping_mul(src),
+                BpfAluOp::Div => dst.checked_div(src).unwrap_or(0),
+                BpfAluOp::Sdiv if b == 0 => 0,
+                BpfAluOp::Sdiv => a.wrapping_div(b) as u64,
+                BpfAluOp::Mod => dst.checked_rem(src).unwrap_or(dst),
+                BpfAluOp::Smod if b == 0 => dst,
+                BpfAluOp::Smod => a.wrapping_rem(b) as u64,
+                BpfAluOp::And => dst & src,
+                BpfAluOp::Or => dst | src,
+                BpfAluOp::Xor => dst ^ src,
+                BpfAluOp::Lsh => dst.wrapping_shl(src as u32),
+                BpfAluOp::Rsh => dst.wrapping_shr(src as u32),
+                BpfAluOp::Arsh => a.wrapping_shr(src as u32) as u64,
+                BpfAluOp::Neg => dst.wrapping_neg(),
+                BpfAluOp::Mov => src,
+                BpfAluOp::Movsx(bits) => sext(src, bits as u32),
+                BpfAluOp::End(_, 16) => (dst as u16).swap_bytes() as u64,
+                BpfAluOp::End(_, 32) => (dst as u32).swap_bytes() as u64,
+                BpfAluOp::End(_, _) => dst.swap_bytes(),
+            }
+        }
+        Bitness::B32 => {
+            let (x, y) = (dst as u32, src as u32);
+            let (a, b) = (x as i32, y as i32);
+            let r = match op {
+                BpfAluOp::Add => x.wrapping_add(y),
+                BpfAluOp::Sub => x.wrapping_sub(y),
+                BpfAluOp::Mul => x.wrapping_mul(y),
+                BpfAluOp::Div => x.checked_div(y).unwrap_or(0),
+                BpfAluOp::Sdiv if b == 0 => 0,
+                BpfAluOp::Sdiv => a.wrapping_div(b) as u32,
+                BpfAluOp::Mod => x.checked_rem(y).unwrap_or(x),
+                BpfAluOp::Smod if b == 0 => x,
+                BpfAluOp::Smod => a.wrapping_rem(b) as u32,
+                BpfAluOp::And => x & y,
+                BpfAluOp::Or => x | y,
+                BpfAluOp::Xor => x ^ y,
+                BpfAluOp::Lsh => x.wrapping_shl(y),
+                BpfAluOp::Rsh => x.wrapping_shr(y),
+                BpfAluOp::Arsh => a.wrapping_shr(y) as u32,
+                BpfAluOp::Neg => x.wrapping_neg(),
+                BpfAluOp::Mov => y,
+                BpfAluOp::Movsx(bits) => sext(src, bits as u32) as u32,
+                BpfAluOp::End(Endian::Le, 16) => x as u16 as u32,
+                BpfAluOp::End(Endian::Le, 32) => x,
+                BpfAluOp::End(Endian::Le, _) => return Some(dst),
+                BpfAluOp::End(Endian::Be, 16) => (x as u16).swap_bytes() as u32,
+                BpfAluOp::End(Endian::Be, 32) => x.swap_bytes(),
+                BpfAluOp::End(Endian::Be, _) => return Some(dst.swap_bytes()),
+            };
+            r as u64
+        }
+    })
+}
+
+/// 所有 bits 位的 tnum
+fn tnum_space(bits: u32) -> Vec<Tnum> {
+    let limit = 1u64 << bits;
+    (0..limit)
+        .flat_map(|v| (0..limit).map(move |m| (v, m)))
+        .filter(|&(v, m)| v & m == 0)
+        .map(|(v, m)| Tnum::new(v, m))
+        .collect()
+}
+
+/// 在缩减位宽下穷举验证一条 ALU 指令的可靠性
+///
+/// 做法同 [`crate::pqr::check_soundness`]：枚举所有 bits 位的 tnum 对并符号扩展到 64 位，
+/// 要求每个有定义的具体结果都落在抽象结果中。
+pub fn check_alu(
+    profile: IsaProfile,
+    op: BpfAluOp,
+    bitness: Bitness,
+    bits: u32,
+) -> SoundnessReport {
+    let tnums = tnum_space(bits);
+    let wide = |t: &Tnum| t.sign_extend(bits as u8);
+    let mut report = SoundnessReport::default();
+    for a in &tnums {
+        for b in &tnums {
+            let res = abstract_alu(profile, op, bitness, wide(a), wide(b));
+            report.pairs += 1;
+            for x in a.iter_concrete() {
+                for y in b.iter_concrete() {
+                    let (x, y) = (sext(x, bits), sext(y, bits));
+                    let Some(r) = concrete_alu(profile, op, bitness, x, y) else {
+                        continue;
+                    };
+                    report.concrete += 1;
+                    if !res.contains_value(r) {
+                        report.unsound += 1;
+                        report.example.get_or_insert(Counterexample {
+                            a: wide(a),
+                            b: wide(b),
+                            x,
+                            y,
+                            r,
+                            result: res,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    report
+}
+
+/// 在缩减位宽下逐条验证 ALU 转移函数的可靠性，见 [`check_alu`]
+pub fn verify_soundness(profile: IsaProfile, bits: u32) {
+    println!(
+        "=== 穷举验证 {} ALU 转移函数（{} 位，符号扩展）===",
+        profile.name(),
+        bits
+    );
+    for bitness in [Bitness::B64, Bitness::B32] {
+        for &op in ALU_OPS.iter().filter(|op| op.is_valid(profile, bitness)) {
+            let report = check_alu(profile, op, bitness, bits);
+            println!(
+                "{:<6} {:<8} 具体结果 {:>10}，{}",
+                if bitness == Bitness::B64 {
+                    "alu64"
+                } else {
+                    "alu32"
+                },
+                op.name(),
+                report.concrete,
+                if report.is_sound() {
+                    "可靠"
+                } else {
+                    "不可靠"
+                }
+            );
+            if let Some(ce) = report.example {
+                println!(
+                    "    反例: 0x{:x}, 0x{:x} -> 0x{:x} 不在 (0x{:x}, 0x{:x}) 中",
+                    ce.x, ce.y, ce.r, ce.result.value, ce.result.mask
+                );
+            }
+        }
+    }
+}
+
+/// 寄存器个数：r0–r10
+pub const NUM_REGS: usize = 11;
+/// 只读的帧指针
+pub const FRAME_POINTER: u8 = 10;
+
+/// ALU 指令的源操作数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Src {
+    Reg(u8),
+    /// 32 位立即数，按 [`crate::pqr::imm_operand`] 符号扩展
+    Imm(i32),
+}
+
+/// 一条 ALU 指令
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AluInsn {
+    pub op: BpfAluOp,
+    pub bitness: Bitness,
+    pub dst: u8,
+    pub src: Src,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterpError {
+    /// 寄存器编号超过 r10
+    InvalidRegister(u8),
+    /// 试图写 r10
+    ReadOnlyFramePointer,
+    /// 该位宽下不存在的指令
+    InvalidInsn,
+}
+
+impl fmt::Display for InterpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InterpError::InvalidRegister(r) => write!(f, "不存在的寄存器 r{}", r),
+            InterpError::ReadOnlyFramePointer => f.write_str("r10 是只读的帧指针"),
+            InterpError::InvalidInsn => f.write_str("不存在的指令"),
+        }
+    }
+}
+
+/// 基本块内的抽象寄存器状态：r0–r10 各是一个 tnum 与区间的约化积
+///
+/// [`RegState::step`] 逐条执行 ALU 指令，[`RegState::join`] 在汇合点合并前驱的状态，
+/// 据此可以直接写基本块级的数据流分析。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegState {
+    pub regs: [RegValue; NUM_REGS],
+}
+
+impl Default for RegState {
+    fn default() -> Self {
+        Self::top()
+    }
+}
+
+impl RegState {
+    /// 全部寄存器未知
+    pub fn top() -> Self {
+        Self {
+            regs: [RegValue::top(); NUM_REGS],
+        }
+    }
+
+    /// 不可达的状态
+    pub fn bottom() -> Self {
+        Self {
+            regs: [RegValue::bottom(); NUM_REGS],
+        }
+    }
+
+    /// 任一寄存器为 bottom 时整个状态不可达
+    pub fn is_bottom(&self) -> bool {
+        self.regs.iter().any(RegValue::is_bottom)
+    }
+
+    fn index(reg: u8) -> Result<usize, InterpError> {
+        let i = reg as usize;
+        if i < NUM_REGS {
+            Ok(i)
+        } else {
+            Err(InterpError::InvalidRegister(reg))
+        }
+    }
+
+    pub fn reg(&self, reg: u8) -> Result<RegValue, InterpError> {
+        Ok(self.regs[Self::index(reg)?])
+    }
+
+    pub fn tnum(&self, reg: u8) -> Result<Tnum, InterpError> {
+        Ok(self.reg(reg)?.tnum)
+    }
+
+    /// 设置寄存器的值，不检查 r10 是否只读，用于建立初始状态
+    pub fn set(&mut self, reg: u8, value: RegValue) -> Result<(), InterpError> {
+        self.regs[Self::index(reg)?] = value;
+        Ok(())
+    }
+
+    fn operand(&self, src: Src) -> Result<RegValue, InterpError> {
+        match src {
+            Src::Reg(r) => self.reg(r),
+            Src::Imm(imm) => Ok(RegValue::from_tnum(crate::pqr::imm_operand(imm))),
+        }
+    }
+
+    /// 按 profile 的语义执行一条 ALU 指令，更新目的寄存器
+    ///
+    /// 64 位的加、减、乘、异或与 MOV 在约化积上计算以保留区间，其余运算只经过 tnum，
+    /// 区间由结果的 tnum 推出。出错时状态不变。
+    pub fn step(&mut self, profile: IsaProfile, insn: &AluInsn) -> Result<(), InterpError> {
+        let d = Self::index(insn.dst)?;
+        if insn.dst == FRAME_POINTER {
+            return Err(InterpError::ReadOnlyFramePointer);
+        } else if !insn.op.is_valid(profile, insn.bitness) {
+            return Err(InterpError::InvalidInsn);
+        }
+        let (mut dst, mut src) = (self.regs[d], self.operand(insn.src)?);
+        if profile == IsaProfile::SbfV2 && insn.op == BpfAluOp::Sub {
+            if let Src::Imm(_) = insn.src {
+                std::mem::swap(&mut dst, &mut src);
+            }
+        }
+        let tnum = abstract_alu(profile, insn.op, insn.bitness, dst.tnum, src.tnum);
+        let product = match (insn.bitness, insn.op) {
+            (Bitness::B64, BpfAluOp::Add) => Some(dst.add(&src)),
+            (Bitness::B64, BpfAluOp::Sub) => Some(dst.sub(&src)),
+            (Bitness::B64, BpfAluOp::Mul) => Some(dst.mul(&src)),
+            (Bitness::B64, BpfAluOp::Xor) => Some(dst.xor(&src)),
+            (Bitness::B64, BpfAluOp::Mov) => Some(src),
+            _ => None,
+        };
+        let value = RegValue::from_tnum(tnum);
+        self.regs[d] = product.map_or(value, |p| p.meet(&value));
+        Ok(())
+    }
+
+    /// 依次执行一个基本块中的指令，遇到错误即停止并返回出错指令的下标
+    pub fn run(
+        &mut self,
+        profile: IsaProfile,
+        insns: &[AluInsn],
+    ) -> Result<(), (usize, InterpError)> {
+        for (i, insn) in insns.iter().enumerate() {
+            self.step(profile, insn).map_err(|e| (i, e))?;
+        }
+        Ok(())
+    }
+
+    /// 汇合点：逐个寄存器求 join；不可达的一方不参与
+    pub fn join(&self, other: &Self) -> Self {
+        if self.is_bottom() {
+            return *other;
+        } else if other.is_bottom() {
+            return *self;
+        }
+        Self {
+            regs: std::array::from_fn(|i| self.regs[i].join(&other.regs[i])),
+        }
+    }
+
+    /// 回边上的加宽：逐个寄存器按 [`RegValue::widen`]，self 为上一轮的状态
+    pub fn widen(&self, other: &Self) -> Self {
+        if self.is_bottom() {
+            return *other;
+        } else if other.is_bottom() {
+            return *self;
+        }
+        Self {
+            regs: std::array::from_fn(|i| self.regs[i].widen(&other.regs[i])),
+        }
+    }
+
+    /// 逐个寄存器求 meet
+    pub fn meet(&self, other: &Self) -> Self {
+        Self {
+            regs: std::array::from_fn(|i| self.regs[i].meet(&other.regs[i])),
+        }
+    }
+
+    /// 每个寄存器都不比 other 宽
+    pub fn le(&self, other: &Self) -> bool {
+        self.is_bottom()
+            || self
+                .regs
+                .iter()
+                .zip(&other.regs)
+                .all(|(a, b)| a.is_subset_of(b))
+    }
+}
+
+impl Lattice for RegState {
+    fn top() -> Self {
+        RegState::top()
+    }
+
+    fn bottom() -> Self {
+        RegState::bottom()
+    }
+
+    fn is_bottom(&self) -> bool {
+        RegState::is_bottom(self)
+    }
+
+    fn join(&self, other: &Self) -> Self {
+        RegState::join(self, other)
+    }
+
+    fn meet(&self, other: &Self) -> Self {
+        RegState::meet(self, other)
+    }
+
+    fn le(&self, other: &Self) -> bool {
+        RegState::le(self, other)
+    }
+}
+
+/// 条件跳转的比较
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BpfJmpOp {
+    Jeq,
+    Jne,
+    Jgt,
+    Jge,
+    Jlt,
+    Jle,
+    Jsgt,
+    Jsge,
+    Jslt,
+    Jsle,
+    /// dst & src != 0
+    Jset,
+}
+
+impl BpfJmpOp {
+    pub fn name(self) -> &'static str {
+        match self {
+            BpfJmpOp::Jeq => "jeq",
+            BpfJmpOp::Jne => "jne",
+            BpfJmpOp::Jgt => "jgt",
+            BpfJmpOp::Jge => "jge",
+            BpfJmpOp::Jlt => "jlt",
+            BpfJmpOp::Jle => "jle",
+            BpfJmpOp::Jsgt => "jsgt",
+            BpfJmpOp::Jsge => "jsge",
+            BpfJmpOp::Jslt => "jslt",
+            BpfJmpOp::Jsle => "jsle",
+            BpfJmpOp::Jset => "jset",
+        }
+    }
+
+    fn is_signed(self) -> bool {
+        matches!(
+            self,
+            BpfJmpOp::Jsgt | BpfJmpOp::Jsge | BpfJmpOp::Jslt | BpfJmpOp::Jsle
+        )
+    }
+
+    /// 条件的具体语义
+    pub fn holds(self, bitness: Bitness, dst: u64, src: u64) -> bool {
+        let (dst, src) = match bitness {
+            Bitness::B64 => (dst, src),
+            Bitness::B32 if self.is_signed() => (sext(dst, 32), sext(src, 32)),
+            Bitness::B32 => (dst as u32 as u64, src as u32 as u64),
+        };
+        let (a, b) = (dst as i64, src as i64);
+        match self {
+            BpfJmpOp::Jeq => dst == src,
+            BpfJmpOp::Jne => dst != src,
+            BpfJmpOp::Jgt => dst > src,
+            BpfJmpOp::Jge => dst >= src,
+            BpfJmpOp::Jlt => dst < src,
+            BpfJmpOp::Jle => dst <= src,
+            BpfJmpOp::Jsgt => a > b,
+            BpfJmpOp::Jsge => a >= b,
+            BpfJmpOp::Jslt => a < b,
+            BpfJmpOp::Jsle => a <= b,
+            BpfJmpOp::Jset => dst & src != 0,
+        }
+    }
+}
+
+/// 64 位比较成立时细化 (dst, src)；JSET 不成立时 `set` 为 false
+///
+/// 区间按内核 `reg_set_min_max` 的规则收紧，再由约化积推到 tnum；JEQ 与 JSET 直接在位上细化。
+fn refine(op: BpfJmpOp, set: bool, d: RegValue, s: RegValue) -> (RegValue, RegValue) {
+    let bottom = (RegValue::bottom(), RegValue::bottom());
+    // 用 tnum 约束（value, mask）细化
+    let bits =
+        |r: RegValue, value: u64, mask: u64| r.meet(&RegValue::from_tnum(Tnum::new(value, mask)));
+    let singleton = |r: RegValue| (r.umin == r.umax).then_some(r.umin);
+    match op {
+        BpfJmpOp::Jeq => {
+            let m = d.meet(&s);
+            (m, m)
+        }
+        BpfJmpOp::Jne => match (singleton(d), singleton(s)) {
+            (Some(x), Some(y)) if x == y => bottom,
+            // 与常数不等时去掉区间上等于它的端点
+            (_, Some(c)) => (exclude(d, c), s),
+            (Some(c), _) => (d, exclude(s, c)),
+            _ => (d, s),
+        },
+        BpfJmpOp::Jgt => match (s.umin.checked_add(1), d.umax.checked_sub(1)) {
+            (Some(lo), Some(hi)) => (d.with_urange(lo, u64::MAX), s.with_urange(0, hi)),
+            _ => bottom,
+        },
+        BpfJmpOp::Jge => (d.with_urange(s.umin, u64::MAX), s.with_urange(0, d.umax)),
+        BpfJmpOp::Jsgt => match (s.smin.checked_add(1), d.smax.checked_sub(1)) {
+            (Some(lo), Some(hi)) => (d.with_srange(lo, i64::MAX), s.with_srange(i64::MIN, hi)),
+            _ => bottom,
+        },
+        BpfJmpOp::Jsge => (
+            d.with_srange(s.smin, i64::MAX),
+            s.with_srange(i64::MIN, d.smax),
+        ),
+        BpfJmpOp::Jlt | BpfJmpOp::Jle | BpfJmpOp::Jslt | BpfJmpOp::Jsle => {
+            let swapped = match op {
+                BpfJmpOp::Jlt => BpfJmpOp::Jgt,
+                BpfJmpOp::Jle => BpfJmpOp::Jge,
+                BpfJmpOp::Jslt => BpfJmpOp::Jsgt,
+                _ => BpfJmpOp::Jsge,
+            };
+            let (s, d) = refine(swapped, set, s, d);
+            (d, s)
+        }
+        BpfJmpOp::Jset if set => {
+            let (dt, st) = (d.tnum, s.tnum);
+            if (dt.value | dt.mask) & (st.value | st.mask) == 0 {
+                return bottom;
+            }
+            // 对方是只有一位的常数时这一位必为 1
+            let one_bit = |r: RegValue, other: Tnum| match other.pow2_shift() {
+                Some(k) if other.is_singleton() => bits(r, 1 << k, !(1u64 << k)),
+                _ => r,
+            };
+            (one_bit(d, st), one_bit(s, dt))
+        }
+        // dst & src == 0：一方已知为 1 的位在另一方必为 0
+        BpfJmpOp::Jset => (bits(d, 0, !s.tnum.value), bits(s, 0, !d.tnum.value)),
+    }
+}
+
+/// 值不等于 c 时，c 恰为无符号或有符号区间的端点则去掉它
+fn exclude(r: RegValue, c: u64) -> RegValue {
+    let mut r = r;
+    if r.umin == c {
+        r = r.with_urange(c.saturating_add(1), r.umax);
+    } else if r.umax == c {
+        r = r.with_urange(r.umin, c.saturating_sub(1));
+    }
+    let c = c as i64;
+    if r.smin == c {
+        r = r.with_srange(c.saturating_add(1), r.smax);
+    } else if r.smax == c {
+        r = r.with_srange(r.smin, c.saturating_sub(1));
+    }
+    r
+}
+
+impl RegState {
+    /// 假设条件跳转 `if dst <cond> src` 的结果为 taken，细化涉及的寄存器
+    ///
+    /// 不成立的一侧按相反的比较细化（JSET 不成立即 dst & src == 0）。32 位形式只比较低 32 位：
+    /// 把低 32 位零扩展（有符号比较时符号扩展）后按 64 位细化，再把结果写回低 32 位。
+    /// 条件不可能满足时整个状态变为 bottom；立即数源操作数的细化结果被丢弃。
+    pub fn assume(
+        &mut self,
+        cond: BpfJmpOp,
+        bitness: Bitness,
+        dst: u8,
+        src: Src,
+        taken: bool,
+    ) -> Result<(), InterpError> {
+        let d = Self::index(dst)?;
+        let (dv, sv) = (self.regs[d], self.operand(src)?);
+        let op = if taken {
+            cond
+        } else {
+            match cond {
+                BpfJmpOp::Jeq => BpfJmpOp::Jne,
+                BpfJmpOp::Jne => BpfJmpOp::Jeq,
+                BpfJmpOp::Jgt => BpfJmpOp::Jle,
+                BpfJmpOp::Jge => BpfJmpOp::Jlt,
+                BpfJmpOp::Jlt => BpfJmpOp::Jge,
+                BpfJmpOp::Jle => BpfJmpOp::Jgt,
+                BpfJmpOp::Jsgt => BpfJmpOp::Jsle,
+                BpfJmpOp::Jsge => BpfJmpOp::Jslt,
+                BpfJmpOp::Jslt => BpfJmpOp::Jsge,
+                BpfJmpOp::Jsle => BpfJmpOp::Jsgt,
+                BpfJmpOp::Jset => BpfJmpOp::Jset,
+            }
+        };
+        let (new_d, new_s) = match bitness {
+            Bitness::B64 => refine(op, taken, dv, sv),
+            Bitness::B32 => {
+                let signed = op.is_signed();
+                let (ld, ls) = refine(op, taken, low_half(dv, signed), low_half(sv, signed));
+                (with_low_half(dv, ld), with_low_half(sv, ls))
+            }
+        };
+        if new_d.is_bottom() || new_s.is_bottom() {
+            *self = Self::bottom();
+            return Ok(());
+        }
+        match src {
+            Src::Reg(r) if r == dst => self.regs[d] = new_d.meet(&new_s),
+            Src::Reg(r) => {
+                self.regs[d] = new_d;
+                self.regs[r as usize] = new_s;
+            }
+            Src::Imm(_) => self.regs[d] = new_d,
+        }
+        if self.is_bottom() {
+            *self = Self::bottom();
+        }
+        Ok(())
+    }
+}
+
+/// 低 32 位零扩展或符号扩展为 64 位的寄存器值
+fn low_half(r: RegValue, signed: bool) -> RegValue {
+    let low = r.tnum.subreg32().zext64();
+    RegValue::from_tnum(if signed { low.sign_extend(32) } else { low })
+}
+
+/// 用细化后的低 32 位替换 r 的低 32 位
+fn with_low_half(r: RegValue, low: RegValue) -> RegValue {
+    if low.is_bottom() {
+        return RegValue::bottom();
+    }
+    r.meet(&RegValue::from_tnum(r.tnum.with_subreg(low.tnum)))
+}
+
+/// 内存访问的边界检查结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessVerdict {
+    /// 任何具体值下访问都在 [0, limit) 内
+    InBounds,
+    /// 部分具体值下越界
+    MayExceed,
+    /// 任何具体值下都越界
+    OutOfBounds,
+}
+
+/// 访问 [base + offset, base + offset + size) 是否落在长度为 limit 的区域 [0, limit) 内
+///
+/// base 是相对区域起点的无符号偏移（如包指针减去 data），offset 按有符号数处理，
+/// 与内核检查 `off + size > limit` 的方式相同；地址的范围由两个 tnum 的区间端点相加得到，
+/// 在 i128 上计算因此不会回绕。bottom 视为不可达，返回 `InBounds`。
+pub fn check_access(base: Tnum, offset: Tnum, size: u64, limit: u64) -> AccessVerdict {
+    if base.is_bottom() || offset.is_bottom() {
+        return AccessVerdict::InBounds;
+    }
+    let lo = base.umin_value() as i128 + offset.smin_value() as i128;
+    let hi = base.umax_value() as i128 + offset.smax_value() as i128;
+    let (size, limit) = (size as i128, limit as i128);
+    if lo >= 0 && hi + size <= limit {
+        AccessVerdict::InBounds
+    } else if hi < 0 || lo + size > limit || size > limit {
+        AccessVerdict::OutOfBounds
+    } else {
+        AccessVerdict::MayExceed
+    }
+}
+
+/// BPF_ATOMIC 指令（BPF_DW）的运算
+///
+/// `Fetch*` 即带 BPF_FETCH 的形式；`Cmpxchg` 带着 r0 的值，内存等于 r0 时才写入源操作数。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BpfAtomicOp {
+    Add,
+    FetchAdd,
+    And,
+    FetchAnd,
+    Or,
+    FetchOr,
+    Xor,
+    FetchXor,
+    Xchg,
+    Cmpxchg(Tnum),
+}
+
+impl BpfAtomicOp {
+    pub fn name(self) -> &'static str {
+        match self {
+            BpfAtomicOp::Add => "add",
+            BpfAtomicOp::FetchAdd => "fetch_add",
+            BpfAtomicOp::And => "and",
+            BpfAtomicOp::FetchAnd => "fetch_and",
+            BpfAtomicOp::Or => "or",
+            BpfAtomicOp::FetchOr => "fetch_or",
+            BpfAtomicOp::Xor => "xor",
+            BpfAtomicOp::FetchXor => "fetch_xor",
+            BpfAtomicOp::Xchg => "xchg",
+            BpfAtomicOp::Cmpxchg(_) => "cmpxchg",
+        }
+    }
+}
+
+/// 原子指令的转移函数：返回（内存的新值，指令返回的值）
+///
+/// 带 FETCH 的形式与 XCHG 把内存的旧值写回源寄存器，CMPXCHG 写回 r0；不带 FETCH 的形式
+/// 不改变寄存器，返回的值即 src。内存可能等于也可能不等于 r0 时，CMPXCHG 的新值是两种结果的 join。
+pub fn abstract_atomic(op: BpfAtomicOp, mem: Tnum, src: Tnum) -> (Tnum, Tnum) {
+    match op {
+        BpfAtomicOp::Add => (mem.add(src), src),
+        BpfAtomicOp::FetchAdd => (mem.add(src), mem),
+        BpfAtomicOp::And => (mem.bit_and(src), src),
+        BpfAtomicOp::FetchAnd => (mem.bit_and(src), mem),
+        BpfAtomicOp::Or => (mem.bit_or(src), src),
+        BpfAtomicOp::FetchOr => (mem.bit_or(src), mem),
+        BpfAtomicOp::Xor => (mem.xor(src), src),
+        BpfAtomicOp::FetchXor => (mem.xor(src), mem),
+        BpfAtomicOp::Xchg => (src, mem),
+        BpfAtomicOp::Cmpxchg(r0) => {
+            let equal = mem.meet(&r0);
+            let new = if equal.is_bottom() {
+                mem
+            } else if mem.is_singleton() && r0.is_singleton() {
+                src
+            } else {
+                mem.or(&src)
+            };
+            (new, mem)
+        }
+    }
+}
+
+/// 原子指令的具体语义：返回（内存的新值，指令返回的值）
+pub fn concrete_atomic(op: BpfAtomicOp, mem: u64, src: u64, r0: u64) -> (u64, u64) {
+    match op {
+        BpfAtomicOp::Add => (mem.wrapping_add(src), src),
+        BpfAtomicOp::FetchAdd => (mem.wrapping_add(src), mem),
+        BpfAtomicOp::And => (mem & src, src),
+        BpfAtomicOp::FetchAnd => (mem & src, mem),
+        BpfAtomicOp::Or => (mem | src, src),
+        BpfAtomicOp::FetchOr => (mem | src, mem),
+        BpfAtomicOp::Xor => (mem ^ src, src),
+        BpfAtomicOp::FetchXor => (mem ^ src, mem),
+        BpfAtomicOp::Xchg => (src, mem),
+        BpfAtomicOp::Cmpxchg(_) => (if mem == r0 { src } else { mem }, mem),
+    }
+}
+
+/// 全部原子运算，用于逐条验证；CMPXCHG 的 r0 在验证时另取
+pub const ATOMIC_OPS: &[BpfAtomicOp] = &[
+    BpfAtomicOp::Add,
+    BpfAtomicOp::FetchAdd,
+    BpfAtomicOp::And,
+    BpfAtomicOp::FetchAnd,
+    BpfAtomicOp::Or,
+    BpfAtomicOp::FetchOr,
+    BpfAtomicOp::Xor,
+    BpfAtomicOp::FetchXor,
+    BpfAtomicOp::Xchg,
+    BpfAtomicOp::Cmpxchg(Tnum::top()),
+];
+
+/// [`check_atomic`] 的结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AtomicCheck {
+    /// 检查的具体值组数
+    pub checked: u64,
+    /// 第一个新内存值或返回值不在抽象结果中的具体值组：内存、src、r0
+    pub example: Option<(u64, u64, u64)>,
+}
+
+/// 在缩减位宽下穷举验证一个原子运算的转移函数；CMPXCHG 的 r0 取全部 tnum
+pub fn check_atomic(op: BpfAtomicOp, bits: u32) -> AtomicCheck {
+    let tnums = tnum_space(bits);
+    let wide = |t: &Tnum| t.sign_extend(bits as u8);
+    let r0s: &[Tnum] = match op {
+        BpfAtomicOp::Cmpxchg(_) => &tnums,
+        _ => &[Tnum::new(0, 0)],
+    };
+    let mut check = AtomicCheck::default();
+    for r0 in r0s {
+        let op = match op {
+            BpfAtomicOp::Cmpxchg(_) => BpfAtomicOp::Cmpxchg(wide(r0)),
+            op => op,
+        };
+        for a in &tnums {
+            for b in &tnums {
+                let (new, ret) = abstract_atomic(op, wide(a), wide(b));
+                for z in r0.iter_concrete() {
+                    for x in a.iter_concrete() {
+                        for y in b.iter_concrete() {
+                            let (x, y, z) = (sext(x, bits), sext(y, bits), sext(z, bits));
+                            let (m, r) = concrete_atomic(op, x, y, z);
+                            check.checked += 1;
+                            if !new.contains_value(m) || !ret.contains_value(r) {
+                                check.example.get_or_insert((x, y, z));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    check
+}
+
+/// 在缩减位宽下逐个验证原子指令的转移函数，见 [`check_atomic`]
+pub fn verify_atomic(bits: u32) {
+    println!("=== 穷举验证原子指令（{} 位，符号扩展）===", bits);
+    for &op in ATOMIC_OPS {
+        let check = check_atomic(op, bits);
+        println!(
+            "{:<10} 具体值组 {:>10}，{}",
+            op.name(),
+            check.checked,
+            if check.example.is_some() {
+                "不可靠"
+            } else {
+                "可靠"
+            }
+        );
+        if let Some((x, y, z)) = check.example {
+            println!("    反例: mem=0x{:x}, src=0x{:x}, r0=0x{:x}", x, y, z);
+        }
+    }
+}
+
+/// 全部条件跳转的比较，用于逐条验证
+pub const JMP_OPS: &[BpfJmpOp] = &[
+    BpfJmpOp::Jeq,
+    BpfJmpOp::Jne,
+    BpfJmpOp::Jgt,
+    BpfJmpOp::Jge,
+    BpfJmpOp::Jlt,
+    BpfJmpOp::Jle,
+    BpfJmpOp::Jsgt,
+    BpfJmpOp::Jsge,
+    BpfJmpOp::Jslt,
+    BpfJmpOp::Jsle,
+    BpfJmpOp::Jset,
+];
+
+/// [`check_assume`] 的结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AssumeCheck {
+    /// 满足条件的具体值对数
+    pub checked: u64,
+    /// 细化后状态有变化的次数
+    pub refined: u64,
+    /// 第一个被细化错误排除的具体值对：r1、r2 与条件的方向
+    pub example: Option<(u64, u64, bool)>,
+}
+
+/// 在缩减位宽下穷举验证一个比较的 [`RegState::assume`]：条件取 taken 的每对具体值都仍在
+/// 细化后的寄存器中
+///
+/// r1、r2 取所有 bits 位 tnum 的符号扩展，两个方向都检查。
+pub fn check_assume(op: BpfJmpOp, bitness: Bitness, bits: u32) -> AssumeCheck {
+    let tnums = tnum_space(bits);
+    let mut check = AssumeCheck::default();
+    for a in &tnums {
+        for b in &tnums {
+            let mut before = RegState::top();
+            before.regs[1] = RegValue::from_tnum(a.sign_extend(bits as u8));
+            before.regs[2] = RegValue::from_tnum(b.sign_extend(bits as u8));
+            for taken in [true, false] {
+                let mut after = before;
+                after
+                    .assume(op, bitness, 1, Src::Reg(2), taken)
+                    .expect("寄存器编号有效");
+                if after != before {
+                    check.refined += 1;
+                }
+                for x in a.iter_concrete() {
+                    for y in b.iter_concrete() {
+                        let (x, y) = (sext(x, bits), sext(y, bits));
+                        if op.holds(bitness, x, y) != taken {
+                            continue;
+                        }
+                        check.checked += 1;
+                        if !after.regs[1].contains_value(x) || !after.regs[2].contains_value(y) {
+                            check.example.get_or_insert((x, y, taken));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    check
+}
+
+/// 在缩减位宽下逐个验证条件跳转的细化，见 [`check_assume`]
+pub fn verify_assume(bits: u32) {
+    println!("=== 穷举验证条件跳转的细化（{} 位，符号扩展）===", bits);
+    for bitness in [Bitness::B64, Bitness::B32] {
+        for &op in JMP_OPS {
+            let check = check_assume(op, bitness, bits);
+            println!(
+                "{:<6} {:<5} 满足条件的具体值对 {:>8}，有细化 {:>6}，{}",
+                if bitness == Bitness::B64 {
+                    "jmp64"
+                } else {
+                    "jmp32"
+                },
+                op.name(),
+                check.checked,
+                check.refined,
+                if check.example.is_some() {
+                    "不可靠"
+                } else {
+                    "可靠"
+                }
+            );
+            if let Some((x, y, taken)) = check.example {
+                println!(
+                    "    反例: r1=0x{:x}, r2=0x{:x}，{} 后被排除",
+                    x,
+                    y,
+                    if taken { "跳转" } else { "不跳转" }
+                );
+            }
+        }
+    }
+}
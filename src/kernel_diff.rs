@@ -0,0 +1,237 @@
+//! 与 Linux 内核 kernel/bpf/tnum.c 的差分测试
+//!
+//! build.rs 把内核的 tnum.c 编译进来，[`cross_check`] 在穷举的小 tnum 与随机 tnum 上
+//! 逐位比较两边的结果。Solana 的验证器不应在这些运算上悄悄偏离上游语义。
+use crate::tnum::Tnum;
+
+/// 与内核 `struct tnum` 相同的内存布局
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct KernelTnum {
+    value: u64,
+    mask: u64,
+}
+
+impl From<Tnum> for KernelTnum {
+    fn from(t: Tnum) -> Self {
+        Self {
+            value: t.value,
+            mask: t.mask,
+        }
+    }
+}
+
+impl From<KernelTnum> for Tnum {
+    fn from(t: KernelTnum) -> Self {
+        Tnum::new(t.value, t.mask)
+    }
+}
+
+mod ffi {
+    use super::KernelTnum;
+
+    extern "C" {
+        pub fn tnum_lshift(a: KernelTnum, shift: u8) -> KernelTnum;
+        pub fn tnum_rshift(a: KernelTnum, shift: u8) -> KernelTnum;
+        pub fn tnum_arshift(a: KernelTnum, min_shift: u8, insn_bitness: u8) -> KernelTnum;
+        pub fn tnum_add(a: KernelTnum, b: KernelTnum) -> KernelTnum;
+        pub fn tnum_sub(a: KernelTnum, b: KernelTnum) -> KernelTnum;
+        pub fn tnum_and(a: KernelTnum, b: KernelTnum) -> KernelTnum;
+        pub fn tnum_or(a: KernelTnum, b: KernelTnum) -> KernelTnum;
+        pub fn tnum_xor(a: KernelTnum, b: KernelTnum) -> KernelTnum;
+        pub fn tnum_mul(a: KernelTnum, b: KernelTnum) -> KernelTnum;
+        pub fn tnum_intersect(a: KernelTnum, b: KernelTnum) -> KernelTnum;
+        pub fn tnum_cast(a: KernelTnum, size: u8) -> KernelTnum;
+    }
+}
+
+/// 一次比较：由 (a, b, 参数) 算出（内核结果, 本 crate 结果）
+///
+/// 参数是移位量或字节数，不用参数的二元运算忽略它，不用 b 的运算忽略 b。
+pub type DiffOp = fn(Tnum, Tnum, u8) -> (Tnum, Tnum);
+
+/// 参与比较的运算与各自的参数取值
+pub struct KernelOp {
+    pub name: &'static str,
+    pub diff: DiffOp,
+    /// 为空表示二元运算，否则对 a 与每个参数比较
+    pub params: &'static [u8],
+}
+
+const SHIFTS64: &[u8] = &[
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+    26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49,
+    50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63,
+];
+
+macro_rules! binary {
+    ($name:literal, $kernel:ident, $ours:expr) => {
+        KernelOp {
+            name: $name,
+            diff: |a, b, _| {
+                // SAFETY: 内核的这些函数是纯函数，只读取按值传入的结构体
+                let k = unsafe { ffi::$kernel(a.into(), b.into()) };
+                (k.into(), $ours(&a, b))
+            },
+            params: &[],
+        }
+    };
+}
+
+/// 与内核逐位比较的运算
+///
+/// 内核的 tnum_intersect 对应这里已弃用的 `intersect`，比较的正是它。
+#[allow(deprecated)]
+pub const KERNEL_OPS: &[KernelOp] = &[
+    binary!("add", tnum_add, Tnum::add),
+    binary!("sub", tnum_sub, Tnum::sub),
+    binary!("mul", tnum_mul, Tnum::mul),
+    binary!("and", tnum_and, Tnum::bit_and),
+    binary!("or", tnum_or, Tnum::bit_or),
+    binary!("xor", tnum_xor, Tnum::xor),
+    binary!("intersect", tnum_intersect, Tnum::intersect),
+    KernelOp {
+        name: "lshift",
+        diff: |a, _, k| {
+            // SAFETY: 同上；移位量小于 64
+            let r = unsafe { ffi::tnum_lshift(a.into(), k) };
+            (r.into(), a.tnum_lshift(k))
+        },
+        params: SHIFTS64,
+    },
+    KernelOp {
+        name: "rshift",
+        diff: |a, _, k| {
+            // SAFETY: 同上
+            let r = unsafe { ffi::tnum_rshift(a.into(), k) };
+            (r.into(), a.tnum_rshift(k))
+        },
+        params: SHIFTS64,
+    },
+    KernelOp {
+        name: "arshift",
+        diff: |a, _, k| {
+            // SAFETY: 同上
+            let r = unsafe { ffi::tnum_arshift(a.into(), k, 64) };
+            (r.into(), a.ashr_const(k as u64))
+        },
+        params: SHIFTS64,
+    },
+    KernelOp {
+        name: "arshift32",
+        diff: |a, _, k| {
+            // SAFETY: 同上；32 位时移位量小于 32
+            let r = unsafe { ffi::tnum_arshift(a.into(), k, 32) };
+            (r.into(), a.alu32(a, |x, _| x.ashr_const(k as u64)))
+        },
+        params: SHIFTS64.split_at(32).0,
+    },
+    KernelOp {
+        name: "cast",
+        diff: |a, _, size| {
+            // SAFETY: 同上；字节数取 1、2、4
+            let r = unsafe { ffi::tnum_cast(a.into(), size) };
+            (r.into(), a.cast(size))
+        },
+        // size 为 8 时内核计算 1ULL << 64，是 C 的未定义行为，不参与比较
+        params: &[1, 2, 4],
+    },
+];
+
+/// 一处不一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mismatch {
+    pub a: Tnum,
+    pub b: Tnum,
+    pub param: u8,
+    pub kernel: Tnum,
+    pub ours: Tnum,
+}
+
+/// 一个运算的比较结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpDiff {
+    pub name: &'static str,
+    pub checked: u64,
+    pub mismatches: u64,
+    /// 第一处不一致
+    pub example: Option<Mismatch>,
+}
+
+/// 穷举的小 tnum：value、mask 都在低 `SMALL_BITS` 位，另把每个循环右移 2 位以覆盖符号位
+const SMALL_BITS: u32 = 4;
+
+fn inputs(samples: usize) -> Vec<Tnum> {
+    let limit = 1u64 << SMALL_BITS;
+    let mut out: Vec<Tnum> = (0..limit)
+        .flat_map(|v| (0..limit).map(move |m| (v, m)))
+        .filter(|&(v, m)| v & m == 0)
+        .flat_map(|(v, m)| {
+            [
+                Tnum::new(v, m),
+                Tnum::new(v.rotate_right(2), m.rotate_right(2)),
+            ]
+        })
+        .collect();
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    for i in 0..samples {
+        // 轮流生成稀疏、稠密与单点的 mask
+        let mask = match i % 3 {
+            0 => next() & next() & next(),
+            1 => next(),
+            _ => 0,
+        };
+        out.push(Tnum::new(next() & !mask, mask));
+    }
+    out
+}
+
+/// 在穷举的小 tnum 与 samples 个随机 tnum 上比较 [`KERNEL_OPS`] 中的全部运算
+///
+/// 二元运算比较全部 tnum 对，带参数的运算对每个 tnum 比较全部参数。
+pub fn cross_check(samples: usize) -> Vec<OpDiff> {
+    let tnums = inputs(samples);
+    KERNEL_OPS
+        .iter()
+        .map(|op| {
+            let mut diff = OpDiff {
+                name: op.name,
+                checked: 0,
+                mismatches: 0,
+                example: None,
+            };
+            let mut compare = |a: Tnum, b: Tnum, param: u8| {
+                let (kernel, ours) = (op.diff)(a, b, param);
+                diff.checked += 1;
+                if kernel != ours {
+                    diff.mismatches += 1;
+                    diff.example.get_or_insert(Mismatch {
+                        a,
+                        b,
+                        param,
+                        kernel,
+                        ours,
+                    });
+                }
+            };
+            for &a in &tnums {
+                if op.params.is_empty() {
+                    for &b in &tnums {
+                        compare(a, b, 0);
+                    }
+                } else {
+                    for &param in op.params {
+                        compare(a, a, param);
+                    }
+                }
+            }
+            diff
+        })
+        .collect()
+}
@@ -0,0 +1,336 @@
+//! LLVM KnownBits 算法的移植，用于与本 crate 的转移函数做差分比较
+//!
+//! tnum 中的 udiv、sdiv、urem、srem、umin、umax 大多照 llvm/lib/Support/KnownBits.cpp
+//! 改写而来。这里按 KnownBits 的 Zero/One 表示逐行重写这些算法（不带 `Exact`），
+//! [`differential`] 比较两边的精度，找出本 crate 严格更弱的输入。
+//!
+//! 语义差异：LLVM 中除以 0 是 UB、`INT_MIN / -1` 是 poison，移植保留了这些假设。
+//! 前者与 [`verify`] 的具体语义一致（无定义的输入不参与检查），后者不一致：
+//! 这里 `INT_MIN / -1` 回绕为 `INT_MIN`，因此同时检查移植在本 crate 语义下是否可靠。
+use crate::tnum::Tnum;
+use crate::verify::{self, AbstractOp, CompareConfig, ComparisonReport, SoundnessReport};
+
+const SIGN: u64 = 1 << 63;
+
+/// LLVM 的 KnownBits：`zero` 中的位已知为 0，`one` 中的位已知为 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KnownBits {
+    pub zero: u64,
+    pub one: u64,
+}
+
+impl From<Tnum> for KnownBits {
+    fn from(t: Tnum) -> Self {
+        Self {
+            zero: !(t.value | t.mask),
+            one: t.value,
+        }
+    }
+}
+
+impl From<KnownBits> for Tnum {
+    fn from(k: KnownBits) -> Self {
+        Tnum::new(k.one, !(k.zero | k.one))
+    }
+}
+
+/// 高 n 位为 1 的掩码，n 可以为 0 或 64
+fn high_bits(n: u32) -> u64 {
+    u64::MAX.checked_shl(64 - n).unwrap_or(0)
+}
+
+/// 低 n 位为 1 的掩码，n 可以为 0 或 64
+fn low_bits(n: u32) -> u64 {
+    u64::MAX.checked_shr(64 - n).unwrap_or(0)
+}
+
+impl KnownBits {
+    fn unknown() -> Self {
+        Self { zero: 0, one: 0 }
+    }
+
+    fn all_zero() -> Self {
+        Self {
+            zero: u64::MAX,
+            one: 0,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.zero == u64::MAX
+    }
+
+    fn is_constant(&self) -> bool {
+        self.zero | self.one == u64::MAX
+    }
+
+    fn is_non_negative(&self) -> bool {
+        self.zero & SIGN != 0
+    }
+
+    fn is_negative(&self) -> bool {
+        self.one & SIGN != 0
+    }
+
+    fn is_strictly_positive(&self) -> bool {
+        self.is_non_negative() && self.one != 0
+    }
+
+    fn is_non_zero(&self) -> bool {
+        self.one != 0
+    }
+
+    fn min_value(&self) -> u64 {
+        self.one
+    }
+
+    fn max_value(&self) -> u64 {
+        !self.zero
+    }
+
+    /// 符号位未知时取 1，其余未知位取 0
+    fn signed_min_value(&self) -> u64 {
+        self.one | (!self.zero & SIGN)
+    }
+
+    /// 符号位未知时取 0，其余未知位取 1
+    fn signed_max_value(&self) -> u64 {
+        (!self.zero & !SIGN) | (self.one & SIGN)
+    }
+
+    fn count_min_leading_zeros(&self) -> u32 {
+        self.zero.leading_ones()
+    }
+
+    fn count_min_leading_ones(&self) -> u32 {
+        self.one.leading_ones()
+    }
+
+    fn count_min_trailing_zeros(&self) -> u32 {
+        self.zero.trailing_ones()
+    }
+
+    /// 值不小于 val 时能推出的高位：从高位起 val 不超过本值的前缀里，val 为 1 的位必为 1
+    fn make_ge(&self, val: u64) -> Self {
+        let n = (self.zero | val).leading_ones();
+        Self {
+            zero: self.zero,
+            one: self.one | (val & high_bits(n)),
+        }
+    }
+
+    /// 两边都已知的位
+    fn intersect_with(&self, other: Self) -> Self {
+        Self {
+            zero: self.zero & other.zero,
+            one: self.one & other.one,
+        }
+    }
+
+    fn flip(self) -> Self {
+        Self {
+            zero: self.one,
+            one: self.zero,
+        }
+    }
+
+    /// KnownBits::umax
+    pub fn umax(lhs: Self, rhs: Self) -> Self {
+        if lhs.min_value() >= rhs.max_value() {
+            return lhs;
+        }
+        if rhs.min_value() >= lhs.max_value() {
+            return rhs;
+        }
+        let l = lhs.make_ge(rhs.min_value());
+        let r = rhs.make_ge(lhs.min_value());
+        l.intersect_with(r)
+    }
+
+    /// KnownBits::umin，交换 Zero 与 One 后求 umax
+    pub fn umin(lhs: Self, rhs: Self) -> Self {
+        Self::umax(lhs.flip(), rhs.flip()).flip()
+    }
+
+    /// KnownBits::udiv：商的前导零由 被除数最大值 / 除数最小值 给出
+    pub fn udiv(lhs: Self, rhs: Self) -> Self {
+        if lhs.is_zero() || rhs.is_zero() {
+            // 结果为 0 或 UB
+            return Self::all_zero();
+        }
+        let min_denom = rhs.min_value();
+        let max_num = lhs.max_value();
+        // 除数最小值为 0 时按 1 计
+        let max_res = max_num.checked_div(min_denom).unwrap_or(max_num);
+        let mut known = Self::unknown();
+        known.zero |= high_bits(max_res.leading_zeros());
+        known
+    }
+
+    /// KnownBits::sdiv：按符号估计商的一个端点，据此设置前导 0 或前导 1
+    pub fn sdiv(lhs: Self, rhs: Self) -> Self {
+        if lhs.is_non_negative() && rhs.is_non_negative() {
+            return Self::udiv(lhs, rhs);
+        }
+        if lhs.is_zero() || rhs.is_zero() {
+            return Self::all_zero();
+        }
+        let sdiv = |n: u64, d: u64| (n as i64).wrapping_div(d as i64) as u64;
+        let mut res = None;
+        if lhs.is_negative() && rhs.is_negative() {
+            // 结果非负；INT_MIN / -1 是 poison，按有符号最大值估计
+            let denom = rhs.signed_max_value();
+            let num = lhs.signed_min_value();
+            res = Some(if num == SIGN && denom == u64::MAX {
+                i64::MAX as u64
+            } else {
+                sdiv(num, denom)
+            });
+        } else if lhs.is_negative() && rhs.is_non_negative() {
+            // -LHS u>= RHS 时结果为负
+            if lhs.signed_max_value().wrapping_neg() >= rhs.signed_max_value() {
+                let denom = rhs.signed_min_value();
+                let num = lhs.signed_min_value();
+                res = Some(if denom == 0 { num } else { sdiv(num, denom) });
+            }
+        } else if lhs.is_strictly_positive() && rhs.is_negative() {
+            // LHS u>= -RHS 时结果为负
+            if lhs.signed_min_value() >= rhs.signed_min_value().wrapping_neg() {
+                let denom = rhs.signed_max_value();
+                let num = lhs.signed_max_value();
+                res = Some(sdiv(num, denom));
+            }
+        }
+        let mut known = Self::unknown();
+        match res {
+            Some(r) if r & SIGN == 0 => known.zero |= high_bits(r.leading_zeros()),
+            Some(r) => known.one |= high_bits(r.leading_ones()),
+            None => {}
+        }
+        known
+    }
+
+    /// remGetLowBits：除数低 N 位已知为 0 时余数保留被除数的低 N 位
+    fn rem_low_bits(lhs: Self, rhs: Self) -> Self {
+        let mut known = Self::unknown();
+        if !rhs.is_zero() && rhs.zero & 1 != 0 {
+            let mask = low_bits(rhs.count_min_trailing_zeros());
+            known.one |= lhs.one & mask;
+            known.zero |= lhs.zero & mask;
+        }
+        known
+    }
+
+    /// 除数为 2 的幂的常数时返回它
+    fn power_of_two(&self) -> Option<u64> {
+        (self.is_constant() && self.one.is_power_of_two()).then_some(self.one)
+    }
+
+    /// KnownBits::urem
+    pub fn urem(lhs: Self, rhs: Self) -> Self {
+        let mut known = Self::rem_low_bits(lhs, rhs);
+        if let Some(p) = rhs.power_of_two() {
+            known.zero |= !(p - 1);
+            return known;
+        }
+        // 余数不超过任一操作数，两边的前导零都保留
+        let leaders = lhs
+            .count_min_leading_zeros()
+            .max(rhs.count_min_leading_zeros());
+        known.zero |= high_bits(leaders);
+        known
+    }
+
+    /// KnownBits::srem
+    pub fn srem(lhs: Self, rhs: Self) -> Self {
+        let mut known = Self::rem_low_bits(lhs, rhs);
+        if let Some(p) = rhs.power_of_two() {
+            let low = p - 1;
+            // 被除数非负或低位全为 0 时高位全为 0
+            if lhs.is_non_negative() || low & !lhs.zero == 0 {
+                known.zero |= !low;
+            }
+            // 被除数为负且低位不全为 0 时高位全为 1
+            if lhs.is_negative() && low & lhs.one != 0 {
+                known.one |= !low;
+            }
+            return known;
+        }
+        // 余数为 0 或与被除数同号，且绝对值不超过被除数
+        if lhs.is_negative() && known.is_non_zero() {
+            known.one |= high_bits(lhs.count_min_leading_ones());
+        } else if lhs.is_non_negative() {
+            known.zero |= high_bits(lhs.count_min_leading_zeros());
+        }
+        known
+    }
+}
+
+/// 在 tnum 上调用 KnownBits 算法；KnownBits 表示不了 bottom，直接返回 bottom
+fn on_tnum(f: fn(KnownBits, KnownBits) -> KnownBits, a: &Tnum, b: Tnum) -> Tnum {
+    if a.is_bottom() || b.is_bottom() {
+        return Tnum::bottom();
+    }
+    f((*a).into(), b.into()).into()
+}
+
+/// 参与比较的运算：名称与 [`verify::BINARY_OPS`] 中本 crate 的运算相同
+pub const KNOWN_BITS_OPS: &[(&str, AbstractOp)] = &[
+    ("udiv", |a, b| on_tnum(KnownBits::udiv, a, b)),
+    ("sdiv", |a, b| on_tnum(KnownBits::sdiv, a, b)),
+    ("urem", |a, b| on_tnum(KnownBits::urem, a, b)),
+    ("srem", |a, b| on_tnum(KnownBits::srem, a, b)),
+    ("umin", |a, b| on_tnum(KnownBits::umin, a, b)),
+    ("umax", |a, b| on_tnum(KnownBits::umax, a, b)),
+];
+
+/// 按名称查找 KnownBits 的移植
+pub fn known_bits_op(name: &str) -> Option<AbstractOp> {
+    KNOWN_BITS_OPS
+        .iter()
+        .find(|&&(n, _)| n == name)
+        .map(|&(_, op)| op)
+}
+
+/// 一个运算的差分结果
+#[derive(Debug, Clone)]
+pub struct KnownBitsDiff {
+    pub name: &'static str,
+    /// 本 crate 为第一个运算、KnownBits 为第二个；`b_finer` 即本 crate 严格更弱
+    pub comparison: ComparisonReport,
+    /// 本 crate 的转移函数的可靠性，位宽为 [`verify::EXHAUSTIVE_WIDTH`]；不可靠时“更紧”不说明问题
+    pub ours_soundness: SoundnessReport,
+    /// KnownBits 的移植在本 crate 具体语义下的可靠性，位宽同上
+    pub port_soundness: SoundnessReport,
+}
+
+/// 在 `config` 的枚举范围上比较 [`KNOWN_BITS_OPS`] 中的全部运算
+///
+/// 除数恰为 0 时两边都没有定义，KnownBits 一侧按本 crate 的约定取 top，不计入差异；
+/// `config.skip_zero_rhs` 只对除法与取余生效。
+pub fn differential(config: &CompareConfig) -> Vec<KnownBitsDiff> {
+    KNOWN_BITS_OPS
+        .iter()
+        .map(|&(name, kb)| {
+            let ours = verify::binary_op(name).expect("KNOWN_BITS_OPS 的名称都已登记");
+            let divides = matches!(name, "udiv" | "sdiv" | "urem" | "srem");
+            let port = |a: Tnum, b: Tnum| {
+                if divides && b.is_zero() {
+                    Tnum::top()
+                } else {
+                    kb(&a, b)
+                }
+            };
+            let config = CompareConfig {
+                skip_zero_rhs: config.skip_zero_rhs && divides,
+                ..*config
+            };
+            KnownBitsDiff {
+                name,
+                comparison: verify::compare_ops(|a, b| (ours.abs)(&a, b), port, &config),
+                ours_soundness: verify::check_sound(ours.abs, ours.con, verify::EXHAUSTIVE_WIDTH),
+                port_soundness: verify::check_sound(kb, ours.con, verify::EXHAUSTIVE_WIDTH),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,16 @@
+//! 抽象域共用的格接口
+//!
+//! [`Tnum`](crate::tnum::Tnum)（含 `TnumU128`）、[`RegValue`](crate::regval::RegValue)
+//! 与 [`RegState`](crate::interp::RegState) 都实现了 [`Lattice`]，不动点迭代等通用算法只依赖这里的运算。
+
+/// 有 top 与 bottom 的格，`le` 为 γ 包含关系
+pub trait Lattice: Copy + PartialEq {
+    fn top() -> Self;
+    fn bottom() -> Self;
+    fn is_bottom(&self) -> bool;
+    /// 上界：γ(self) ∪ γ(other) ⊆ γ(join)
+    fn join(&self, other: &Self) -> Self;
+    /// 下界：γ(meet) ⊆ γ(self) ∩ γ(other)
+    fn meet(&self, other: &Self) -> Self;
+    fn le(&self, other: &Self) -> bool;
+}
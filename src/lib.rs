@@ -0,0 +1,54 @@
+//! This is a tnum implementation for Solana eBPF
+//!
+//! 抽象域在 [`tnum`] 中，两个转移函数的精度比较在 [`verify`] 中，任意位宽上的穷举检查在 [`width`] 中，可靠性的 SMT 编码在
+//! [`smt`] 中（求解需要 `z3` 特性），性质测试的生成器在 `testing` 中（需要 `testing` 特性）；
+//! 与内核 tnum.c 的差分测试在 `kernel_diff` 中（需要 `kernel-diff` 特性），与 LLVM KnownBits
+//! 的差分比较在 [`knownbits`] 中；
+//! eBPF ALU 指令的抽象解释在 [`interp`] 中，整段字节码的数据流分析在 [`driver`] 中；
+//! 供 C 验证器调用的 `extern "C"` 接口在 `ffi` 中（需要 `ffi` 特性）；
+//! Python 绑定在 `python` 中（需要 `pyo3` 特性），浏览器演示用的 wasm-bindgen 接口在 `wasm` 中
+//! （需要 `wasm` 特性）；
+//! 其余验证工具（枚举、Z3 合成等）在二进制目标中。
+//!
+//! 关闭默认的 `std` 特性时 crate 为 `#![no_std]`，只依赖 `core`：保留 [`tnum`]（`Tnum`、`TnumU128`
+//! 与全部转移函数）、[`regval`]、[`lattice`] 与 [`stats`]，其余模块与二进制目标需要 `std`。
+#![cfg_attr(not(feature = "std"), no_std)]
+#[cfg(feature = "std")]
+pub mod driver;
+#[cfg(feature = "std")]
+pub mod exact;
+#[cfg(feature = "std")]
+pub mod frame;
+// 两者导出同名的 tnum_* 符号，kernel-diff 会与本 crate 自己比较
+#[cfg(all(feature = "ffi", feature = "kernel-diff"))]
+compile_error!("`ffi` 与 `kernel-diff` 特性不能同时启用");
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod interp;
+#[cfg(feature = "kernel-diff")]
+pub mod kernel_diff;
+#[cfg(feature = "std")]
+pub mod knownbits;
+pub mod lattice;
+#[cfg(feature = "std")]
+pub mod pqr;
+#[cfg(feature = "pyo3")]
+pub mod python;
+pub mod regval;
+#[cfg(feature = "std")]
+pub mod smt;
+pub mod stats;
+#[cfg(feature = "serde")]
+pub mod table;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod tnum;
+#[cfg(feature = "std")]
+pub mod verify;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "std")]
+pub mod width;
+#[cfg(feature = "std")]
+pub mod witness;
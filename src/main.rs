@@ -1,1304 +1,1978 @@
-//! This is a tnum implementation for Solana eBPF
 //! Direct enumeration verification for fast_divide algorithm (without Z3)
+use clap::Parser;
+#[cfg(feature = "z3")]
 use fastdivide::DividerU64;
-use std::u64;
-
-fn testbit(val: u64, bit: u8) -> bool {
-    if bit >= 64 {
-        return false;
-    }
-    (val & (1u64 << bit)) != 0
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use std::collections::HashSet;
+#[cfg(feature = "serde")]
+use tnum_z3_verification::table;
+use tnum_z3_verification::tnum::{for_each_concrete, Tnum, Word};
+use tnum_z3_verification::width::{
+    unary_op, unary_op_u64, width_op, width_tnums, UnaryAbstract, UnaryConcrete, WIDTH_OPS,
+};
+use tnum_z3_verification::witness::{unknown_bit_witnesses, BitWitness};
+use tnum_z3_verification::{
+    driver, exact, frame, interp, knownbits, pqr, regval, smt, stats, verify, width,
+};
+
+mod repl;
+#[cfg(feature = "z3")]
+mod synth;
+
+/// 按名称查找抽象运算，见 [`verify::BINARY_OPS`]
+fn abstract_op(name: &str) -> Option<verify::AbstractOp> {
+    verify::binary_op(name).map(|op| op.abs)
 }
 
-/// 位操作 trait
-pub trait BitOps {
-    /// 清除低位（从最低位开始的 n 位）
-    fn clear_low_bits(&mut self, n: u32);
-    /// 清除高位（从最高位开始的 n 位）
-    fn clear_high_bits(&mut self, n: u32);
+/// 按名称查找对应的具体语义（除以 0 无定义）
+fn concrete_op(name: &str) -> Option<verify::ConcreteOp> {
+    verify::binary_op(name).map(|op| op.con)
 }
 
-impl BitOps for u64 {
-    fn clear_low_bits(&mut self, n: u32) {
-        if n >= 64 {
-            *self = 0;
-        } else {
-            *self &= (!0u64).wrapping_shl(n);
-        }
+fn parse_u64(s: &str) -> Option<u64> {
+    match s.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
     }
-
-    fn clear_high_bits(&mut self, n: u32) {
-        if n >= 64 {
-            *self = 0;
-        } else {
-            *self &= (1u64 << (64 - n)) - 1;
-        }
-    }
-}
-
-// This is for bit-level abstraction
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-/// tnum definition
-pub struct Tnum {
-    pub value: u64,
-    pub mask: u64,
-}
-
-pub struct TnumU128 {
-    pub value: u128,
-    pub mask: u128,
 }
 
-impl TnumU128 {
-    /// 创建实例
-    pub fn new(value: u128, mask: u128) -> Self {
-        Self { value, mask }
-    }
-    /// tnum 的加法操作
-    pub fn add(&self, other: Self) -> Self {
-        // 计算掩码之和 - 表示两个不确定数的掩码组合
-        let sm = self.mask.wrapping_add(other.mask);
-
-        // 计算确定值之和
-        let sv = self.value.wrapping_add(other.value);
-
-        // sigma = (a.mask + b.mask) + (a.value + b.value)
-        // 用于检测进位传播情况
-        let sigma = sm.wrapping_add(sv);
-
-        // chi = 进位传播位图
-        // 通过异或操作找出哪些位发生了进位
-        let chi = sigma ^ sv;
-
-        // mu = 最终的不确定位掩码
-        // 包括:
-        // 1. 进位产生的不确定性 (chi)
-        // 2. 原始输入的不确定位 (a.mask | b.mask)
-        let mu = chi | self.mask | other.mask;
-
-        // 返回结果:
-        // value: 确定值之和，但排除所有不确定位 (~mu)
-        // mask: 所有不确定位的掩码
-        Self::new(sv & !mu, mu)
-    }
-
-    /// tnum 的乘法操作
-    pub fn mul(&self, other: Self) -> Self {
-        let mut a = Self::new(self.value, self.mask);
-        let mut b = Self::new(other.value, other.mask);
-        let acc_v = a.value.wrapping_mul(b.value);
-        let mut acc_m: Self = Self::new(0, 0);
-        while (a.value != 0) || (a.mask != 0) {
-            // println!("acc_m.mask:{:?}, acc_m.value:{:?}", acc_m.mask, acc_m.value);
-            if (a.value & 1) != 0 {
-                acc_m = acc_m.add(Self::new(0, b.mask));
-            } else if (a.mask & 1) != 0 {
-                acc_m = acc_m.add(Self::new(0, b.value | b.mask));
+/// 见证模式：`witness <op> <a_value> <a_mask> <b_value> <b_mask>`
+fn run_witness_mode(args: &[String]) {
+    let usage = "用法: witness <op> <a_value> <a_mask> <b_value> <b_mask>";
+    if args.len() != 5 {
+        println!("{}", usage);
+        return;
+    }
+    let nums: Vec<u64> = match args[1..].iter().map(|s| parse_u64(s)).collect() {
+        Some(nums) => nums,
+        None => {
+            println!("{}", usage);
+            return;
+        }
+    };
+    let (abs, con) = match (abstract_op(&args[0]), concrete_op(&args[0])) {
+        (Some(abs), Some(con)) => (abs, con),
+        _ => {
+            println!("未知运算: {}", args[0]);
+            return;
+        }
+    };
+
+    let a = Tnum::new(nums[0], nums[1]);
+    let b = Tnum::new(nums[2], nums[3]);
+    let result = abs(&a, b);
+    println!("=== {} 未知位见证 ===", args[0]);
+    println!(
+        "a = (0x{:x}, 0x{:x}), b = (0x{:x}, 0x{:x})",
+        a.value, a.mask, b.value, b.mask
+    );
+    println!("抽象结果 = (0x{:x}, 0x{:x})", result.value, result.mask);
+    if let Some(f) = result.describe() {
+        println!(
+            "  无符号 [{}, {}]，有符号 [{}, {}]，对齐 {}，未知位 {}",
+            f.umin, f.umax, f.smin, f.smax, f.align, f.unknown_bits
+        );
+    }
+
+    let witnesses = match unknown_bit_witnesses(a, b, result, con, 1 << 24) {
+        Some(w) => w,
+        None => {
+            println!("具体值组合过多，超出枚举预算");
+            return;
+        }
+    };
+    let mut deficient = 0;
+    for w in &witnesses {
+        match *w {
+            BitWitness::Inherent { bit, zero, one } => println!(
+                "bit {:2}: 固有  0 <- ({}, {})  1 <- ({}, {})",
+                bit, zero.0, zero.1, one.0, one.1
+            ),
+            BitWitness::CouldBeKnown { bit, known } => {
+                deficient += 1;
+                println!("bit {:2}: 可确定为 {}（算法缺陷）", bit, known as u8);
             }
-            a.value = a.value.wrapping_shr(1);
-            a.mask = a.mask.wrapping_shr(1);
-            b.value = b.value.wrapping_shl(1);
-            b.mask = b.mask.wrapping_shl(1);
         }
-        Self::new(acc_v, 0).add(acc_m)
     }
+    println!(
+        "未知位: {}，固有: {}，可确定: {}",
+        witnesses.len(),
+        witnesses.len() - deficient,
+        deficient
+    );
 }
 
-impl Tnum {
-    /// 创建实例
-    pub fn new(value: u64, mask: u64) -> Self {
-        Self { value, mask }
-    }
-
-    /// 创建 bottom 元素
-    pub fn bottom() -> Self {
-        Self::new(0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF)
-    }
-
-    /// 创建 top 元素
-    pub fn top() -> Self {
-        Self::new(0, 0xFFFFFFFFFFFFFFFF)
-    }
-
-    /// 创建一个常数 tnum 实例
-    pub fn const_val(value: u64) -> Self {
-        Self::new(value, 0)
-    }
-
-    /// from integer interval to tnum
-    pub fn from_range(min: u64, max: u64) -> Self {
-        let chi = min ^ max;
-        //最高未知位
-        let bits = (64 - chi.leading_zeros()) as u64;
-        //超出范围则完全未知
-        if bits > 63 {
-            return Self::new(0, u64::MAX);
+/// 蒙特卡洛模式：`sampled <op> [tnums] [samples]`
+///
+/// 64 位下无法穷举，随机生成 tnums 个至多 32 个未知位的 tnum，对每一对抽取 samples 个具体输入，
+/// 检查具体结果是否落在抽象结果中。先检查抽样在 4 个未知位时是否均匀。
+fn run_sampled_mode(args: &[String]) {
+    let usage = "用法: sampled <op> [tnums] [samples]";
+    let op = match args.first() {
+        Some(op) => op.as_str(),
+        None => {
+            println!("{}", usage);
+            return;
+        }
+    };
+    let (abs, con) = match (abstract_op(op), concrete_op(op)) {
+        (Some(abs), Some(con)) => (abs, con),
+        _ => {
+            println!("未知运算: {}", op);
+            return;
+        }
+    };
+    let count = args.get(1).map(|s| s.parse::<usize>());
+    let samples = args.get(2).map(|s| s.parse::<usize>());
+    let (count, samples) = match (count.unwrap_or(Ok(64)), samples.unwrap_or(Ok(64))) {
+        (Ok(c), Ok(s)) => (c, s),
+        _ => {
+            println!("{}", usage);
+            return;
+        }
+    };
+
+    let mut state = 0x853c_49e6_748f_ea9bu64;
+    let mut rng = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    let probe = Tnum::new(0x100u64, 0x1e);
+    let mut hist = [0u64; 16];
+    for x in probe.sample_n(&mut rng, 160_000) {
+        hist[((x >> 1) & 0xf) as usize] += 1;
+    }
+    let (lo, hi) = (hist.iter().min().unwrap(), hist.iter().max().unwrap());
+    println!(
+        "=== {} 蒙特卡洛验证（{} 个 tnum，每对 {} 个样本）===",
+        op, count, samples
+    );
+    println!("抽样均匀性: 16 个值各出现 {}..{} 次（期望 10000）", lo, hi);
+
+    let tnums: Vec<Tnum> = (0..count)
+        .map(|_| {
+            let mask = rng() & rng();
+            Tnum::new(rng() & !mask, mask)
+        })
+        .collect();
+    let mut checked = 0u64;
+    let mut unsound = 0u64;
+    let mut example = None;
+    for a in &tnums {
+        for b in &tnums {
+            let res = abs(a, *b);
+            for (x, y) in a
+                .sample_n(&mut rng, samples)
+                .into_iter()
+                .zip(b.sample_n(&mut rng, samples))
+            {
+                if let Some(r) = con(x, y) {
+                    checked += 1;
+                    if res.is_bottom() || r & !res.mask != res.value {
+                        unsound += 1;
+                        example.get_or_insert((*a, *b, x, y, r, res));
+                    }
+                }
+            }
         }
-
-        //范围内的未知位
-        let delta = (1u64 << bits) - 1;
-        Self::new(min & !delta, delta)
-    }
-
-    /// 获取 value 字段
-    pub fn value(&self) -> u64 {
-        self.value
-    }
-
-    /// 获取 mask 字段
-    pub fn mask(&self) -> u64 {
-        self.mask
-    }
-
-    pub fn is_zero(&self) -> bool {
-        self.value == 0 && self.mask == 0
-    }
-    /// 判断是否为bottom（不可能的值）
-    pub fn is_bottom(&self) -> bool {
-        (self.value & self.mask) != 0
-    }
-
-    /// 判断是否为top（完全不确定的值）
-    pub fn is_top(&self) -> bool {
-        self.value == 0 && self.mask == u64::MAX
-    }
-
-    /// 判断是否为确定值（单点）
-    pub fn is_singleton(&self) -> bool {
-        self.mask == 0
-    }
-
-    /// 判断是否为非负数（最高位为0）
-    pub fn is_nonnegative(&self) -> bool {
-        (self.value & (1 << 63)) == 0 && (self.mask & (1 << 63)) == 0
-    }
-
-    /// 判断是否为负数（最高位为1）
-    pub fn is_negative(&self) -> bool {
-        (self.value & (1 << 63)) != 0 && (self.mask & (1 << 63)) == 0
-    }
-
-    /// 统计高位连续0的个数
-    pub fn countl_zero(&self) -> u32 {
-        self.value.leading_zeros()
-    }
-
-    /// 统计低位连续0的个数
-    pub fn countr_zero(&self) -> u32 {
-        self.value.trailing_zeros()
-    }
-
-    /// 统计最小的高位连续0的个数
-    pub fn count_min_leading_zeros(&self) -> u32 {
-        let max = self.value.wrapping_add(self.mask);
-        max.leading_zeros()
-    }
-
-    /// 统计最小的低位连续0的个数
-    pub fn count_min_trailing_zeros(&self) -> u32 {
-        let max = self.value.wrapping_add(self.mask);
-        max.trailing_zeros()
-    }
-
-    /// 统计最大的高位连续0的个数
-    pub fn count_max_leading_zeros(&self) -> u32 {
-        self.value.leading_zeros()
     }
-
-    /// 统计最大的低位连续0的个数
-    pub fn count_max_trailing_zeros(&self) -> u32 {
-        self.value.trailing_zeros()
+    println!("具体结果: {}，不可靠: {}", checked, unsound);
+    if let Some((a, b, x, y, r, res)) = example {
+        println!(
+            "反例: a=({:#x}, {:#x}), b=({:#x}, {:#x}), {:#x} {} {:#x} = {:#x} 不在 ({:#x}, {:#x}) 中",
+            a.value, a.mask, b.value, b.mask, x, op, y, r, res.value, res.mask
+        );
     }
+}
 
-    /// 清除高位
-    pub fn clear_high_bits(&mut self, n: u32) {
-        if n >= 64 {
-            self.value = 0;
-            self.mask = 0;
-        } else {
-            let mask = (1u64 << (64 - n)) - 1;
-            self.value &= mask;
-            self.mask &= mask;
+/// 在 `Tnum<T>` 上验证与 64 位相同的转移函数
+///
+/// 8 位时枚举所有至多 max_unknown 个未知位的 tnum 对；更宽的 tnum 对无法穷举，
+/// 改为用固定种子抽取 samples 个至多 max_unknown 个未知位的 tnum，两两组合。
+/// 每一对都枚举全部具体输入，因此可靠性和与最优抽象的精度比较都是精确的。
+fn verify_width<T: Word>(op: &str, max_unknown: u32, samples: usize) {
+    let (abs, con) = match width_op::<T>(op) {
+        Some(ops) => ops,
+        None => {
+            println!("未知运算: {}", op);
+            return;
         }
+    };
+    let tnums = width_tnums::<T>(max_unknown, samples);
+    println!(
+        "=== 验证 {} 在 {} 位 tnum 上的可靠性（至多 {} 个未知位，{} 个 tnum）===",
+        op,
+        T::BITS,
+        max_unknown,
+        tnums.len()
+    );
+
+    let total = width::check_width(&tnums, abs, con);
+    println!("tnum 对数: {}，具体结果数: {}", total.pairs, total.concrete);
+    println!("不可靠的具体结果: {}", total.unsound);
+    if total.defined > 0 {
+        println!(
+            "最优: {} / {} ({:.2}%)，平均多出未知位: {:.4}",
+            total.optimal,
+            total.defined,
+            total.optimal as f64 * 100.0 / total.defined as f64,
+            total.extra_unknown as f64 / total.defined as f64
+        );
+    }
+    if let Some(e) = total.example {
+        println!(
+            "反例: a=({:#x}, {:#x}), b=({:#x}, {:#x}), {:#x} {} {:#x} = {:#x} 不在 ({:#x}, {:#x}) 中",
+            e.a.value, e.a.mask, e.b.value, e.b.mask, e.x, op, e.y, e.r, e.result.value, e.result.mask
+        );
     }
+}
 
-    /// tnum 的左移操作
-    pub fn tnum_lshift(self: Tnum, shift: u8) -> Tnum {
-        Tnum::new(
-            self.value.wrapping_shl(shift as u32),
-            self.mask.wrapping_shl(shift as u32),
-        )
+/// 穷举模式：`exhaustive [op]`，在 8 位上枚举全部 tnum 对和全部具体输入，
+/// 不给 op 时检查 [`WIDTH_OPS`] 中的全部运算。tests/width.rs 在其中的子集上断言可靠性。
+fn run_exhaustive_mode(args: &[String]) {
+    match args.first() {
+        Some(op) if width_op::<u8>(op).is_some() => verify_width::<u8>(op, 8, 0),
+        Some(op) => println!("未知运算: {}", op),
+        None => {
+            for op in WIDTH_OPS {
+                verify_width::<u8>(op, 8, 0);
+            }
+        }
     }
+}
 
-    /// tnum 的右移操作
-    pub fn tnum_rshift(self: Tnum, shift: u8) -> Tnum {
-        Tnum::new(
-            self.value.wrapping_shr(shift as u32),
-            self.mask.wrapping_shr(shift as u32),
-        )
+/// 打印一元运算的可靠性与最优性，见 [`width::check_unary`]
+fn verify_unary<T: Word>(
+    op: &str,
+    abs: UnaryAbstract<T>,
+    con: UnaryConcrete<T>,
+    inputs: &[Tnum<T>],
+) {
+    println!(
+        "=== 验证一元运算 {}（{} 位，{} 个 tnum）===",
+        op,
+        T::BITS,
+        inputs.len()
+    );
+    let report = width::check_unary(inputs, abs, con);
+    println!(
+        "不可靠: {}，可靠但非最优: {}",
+        report.unsound, report.imprecise
+    );
+    if let Some(gap) = report.example.or(report.suboptimal) {
+        println!(
+            "示例: a=({:#x}, {:#x})，结果 ({:#x}, {:#x})，最优 ({:#x}, {:#x})",
+            gap.a.value,
+            gap.a.mask,
+            gap.result.value,
+            gap.result.mask,
+            gap.best.value,
+            gap.best.mask
+        );
     }
+}
 
-    /// tnum 算数右移的操作
-    pub fn tnum_arshift(self: Tnum, min_shift: u8, insn_bitness: u8) -> Tnum {
-        match insn_bitness {
-            32 => {
-                //32位模式
-                let value = ((self.value as i32) >> min_shift) as u32;
-                let mask = ((self.mask as i32) >> min_shift) as u32;
-                Tnum::new(value as u64, mask as u64)
-            }
-            _ => {
-                //64位模式
-                let value = ((self.value as i64) >> min_shift) as u64;
-                let mask = ((self.mask as i64) >> min_shift) as u64;
-                Tnum::new(value, mask)
-            }
+/// 精确求值模式：`exact <op> <width> <a_value> <a_mask> <b_value> <b_mask>`
+///
+/// 用位图计算精确结果集合及其最优抽象，与算法结果（截断到 width 位）比较。
+fn run_exact_mode(args: &[String]) {
+    let usage = "用法: exact <op> <width> <a_value> <a_mask> <b_value> <b_mask>，width 取 1..=16";
+    if args.len() != 6 {
+        println!("{}", usage);
+        return;
+    }
+    let width = match args[1].parse::<u32>() {
+        Ok(w @ 1..=exact::MAX_WIDTH) => w,
+        _ => {
+            println!("{}", usage);
+            return;
         }
-    }
-
-    pub fn shl(&self, x: &Tnum) -> Tnum {
-        if self.is_bottom() || x.is_bottom() {
-            return Tnum::bottom();
-        } else if self.is_top() || x.is_top() {
-            return Tnum::top();
+    };
+    let nums: Vec<u64> = match args[2..].iter().map(|s| parse_u64(s)).collect() {
+        Some(nums) => nums,
+        None => {
+            println!("{}", usage);
+            return;
         }
+    };
+    let (abs, con) = match (abstract_op(&args[0]), concrete_op(&args[0])) {
+        (Some(abs), Some(con)) => (abs, con),
+        _ => {
+            println!("未知运算: {}", args[0]);
+            return;
+        }
+    };
+
+    let m = (1u64 << width) - 1;
+    let a = Tnum::new(nums[0] & m, nums[1] & m);
+    let b = Tnum::new(nums[2] & m, nums[3] & m);
+    let exact = exact::exact_eval(&a, &b, width, con);
+    let best = exact.best_tnum();
+    let res = abs(&a, b);
+    let res = Tnum::new(res.value & m, res.mask & m);
+
+    println!("=== {} 精确求值（{} 位）===", args[0], width);
+    println!("具体结果个数: {}", exact.len());
+    println!("最优抽象 = (0x{:x}, 0x{:x})", best.value, best.mask);
+    println!("算法结果 = (0x{:x}, 0x{:x})", res.value, res.mask);
+    println!("可靠: {}，最优: {}", exact.is_covered_by(&res), res == best);
+}
 
-        if x.is_singleton() {
-            return self.shl_const(x.value);
-        } else {
-            let w = 64u8;
-            let mut res = Tnum::top();
-            let min_shift_amount = x.value;
-
-            if self.mask == u64::MAX {
-                res.value = res.value.wrapping_shl(min_shift_amount as u32);
-                res.mask = res.mask.wrapping_shl(min_shift_amount as u32);
-                return res;
-            }
-
-            let max_value = x.value.wrapping_add(x.mask);
-            let len = (self.value | self.mask).leading_zeros() as u64;
-            let mut max_res = Tnum::top();
-
-            if len > max_value {
-                max_res.mask.clear_high_bits((len - max_value) as u32);
-            }
-
-            let max_shift_amount = if max_value > w as u64 {
-                w as u64
-            } else {
-                max_value
-            };
-
-            if min_shift_amount == 0 && max_shift_amount == w as u64 {
-                println!("[Rust shl] Fast path: shift amount is unknown");
-                let min_trailing_zeros = self.count_min_trailing_zeros();
-                res.value.clear_low_bits(min_trailing_zeros);
-                res.mask.clear_low_bits(min_trailing_zeros);
-                return res;
-            }
-
-            res.mask = u64::MAX;
-            res.value = u64::MAX;
-            let mut join_count = 0;
-
-            for i in min_shift_amount..=max_shift_amount {
-                if x.value == ((!x.mask) & i) {
-                    continue;
-                }
-                join_count += 1;
-                let tmp = self.shl_const(i);
-                res = res.or(&tmp);
-                if join_count > 8 || res.is_top() {
-                    return Tnum::top();
-                }
+/// 可靠性模式：`verify-sound [width] [op]`，不给 op 时检查 [`verify::BINARY_OPS`] 中的全部运算
+fn run_sound_mode(args: &[String]) {
+    let usage = "用法: verify-sound [width] [op]，width 取 1..=64";
+    let width = match args.first().map(|s| s.parse::<u32>()).unwrap_or(Ok(4)) {
+        Ok(w @ 1..=64) => w,
+        _ => {
+            println!("{}", usage);
+            return;
+        }
+    };
+    let ops: Vec<&verify::BinaryOp> = match args.get(1) {
+        Some(name) => match verify::binary_op(name) {
+            Some(op) => vec![op],
+            None => {
+                println!("未知运算: {}", name);
+                return;
             }
-
-            if res.is_bottom() {
-                Tnum::top()
-            } else {
-                res
+        },
+        None => verify::BINARY_OPS.iter().collect(),
+    };
+    println!(
+        "=== {} 位操作数（零扩展与符号扩展）上的可靠性，{} ===",
+        width,
+        if width <= verify::EXHAUSTIVE_WIDTH {
+            "穷举全部 tnum"
+        } else {
+            "抽样 tnum"
+        }
+    );
+    for op in ops {
+        let report = verify::check_sound(op.abs, op.con, width);
+        println!(
+            "{:<20} tnum 对 {:>8}，具体结果 {:>10}，不可靠 {}",
+            op.name, report.pairs, report.concrete, report.unsound
+        );
+        for (label, e) in [("反例", report.example), ("缩小后", report.minimal)] {
+            if let Some(e) = e {
+                println!(
+                    "  {}: a=({:#x}, {:#x}), b=({:#x}, {:#x}), x={:#x}, y={:#x}, 结果 {:#x} 不在 ({:#x}, {:#x}) 中",
+                    label, e.a.value, e.a.mask, e.b.value, e.b.mask, e.x, e.y, e.r, e.result.value, e.result.mask
+                );
             }
         }
     }
+}
 
-    pub fn lshr(&self, x: &Tnum) -> Tnum {
-        if self.is_bottom() || x.is_bottom() {
-            return Tnum::bottom();
-        } else if self.is_top() || x.is_top() {
-            return Tnum::top();
+/// 与 LLVM KnownBits 的差分模式：`known-bits [max] [rhs_unknown_bits]`
+///
+/// 对 [`knownbits::KNOWN_BITS_OPS`] 中的每个运算比较本 crate 与 KnownBits 移植的精度，
+/// 列出本 crate 严格更弱的输入；任一方在本 crate 语义下不可靠时一并指出。
+/// 除法与取余跳过可能为 0 的除数：KnownBits 把除以 0 当作 UB，这里的差异不是移植的问题。
+fn run_known_bits_mode(args: &[String]) {
+    let usage = "用法: known-bits [max] [rhs_unknown_bits]";
+    let max = args.first().map(|s| parse_u64(s));
+    let bits = args.get(1).map(|s| s.parse::<u32>());
+    let (max, bits) = match (max.unwrap_or(Some(255)), bits.unwrap_or(Ok(2))) {
+        (Some(max), Ok(bits)) => (max, bits),
+        _ => {
+            println!("{}", usage);
+            return;
+        }
+    };
+    let config = verify::CompareConfig {
+        value_max: max,
+        mask_max: max,
+        rhs_unknown_bits: bits,
+        ..Default::default()
+    };
+    println!(
+        "=== 与 LLVM KnownBits 比较（左操作数 value、mask ≤ {}，右操作数至多 {} 个未知位）===",
+        max, bits
+    );
+    for diff in knownbits::differential(&config) {
+        let report = &diff.comparison;
+        println!(
+            "{:<5} 共 {:>9}，相同 {:>6.2}%，本 crate 更紧 {:>6.2}%，KnownBits 更紧 {:>6.2}%，不可比较 {:>6.2}%",
+            diff.name,
+            report.total,
+            report.percent(report.equal),
+            report.percent(report.a_finer),
+            report.percent(report.b_finer),
+            report.percent(report.incomparable)
+        );
+        if !diff.ours_soundness.is_sound() {
+            println!("  本 crate 的转移函数不可靠，它更紧的输入不说明精度更好");
         }
+        if let Some(ce) = diff.port_soundness.minimal {
+            println!(
+                "  KnownBits 在本 crate 语义下不可靠: a=({:#x}, {:#x}), b=({:#x}, {:#x}), {:#x} ∉ ({:#x}, {:#x})",
+                ce.a.value, ce.a.mask, ce.b.value, ce.b.mask, ce.r, ce.result.value, ce.result.mask
+            );
+        }
+        let ours = verify::binary_op(diff.name).expect("已登记");
+        let kb = knownbits::known_bits_op(diff.name).expect("已登记");
+        for &(a, b) in &report.b_finer_examples {
+            let (r_ours, r_kb) = ((ours.abs)(&a, b), kb(&a, b));
+            println!(
+                "  更弱: a=({:#x}, {:#x}), b=({:#x}, {:#x}): 本 crate ({:#x}, {:#x})，KnownBits ({:#x}, {:#x})",
+                a.value, a.mask, b.value, b.mask, r_ours.value, r_ours.mask, r_kb.value, r_kb.mask
+            );
+        }
+    }
+}
 
-        if x.is_singleton() {
-            return self.lshr_const(x.value);
-        } else {
-            let w = 64u8; // 假设 64 位
-            let mut res = Tnum::top();
-            let min_shift_amount = x.value;
-            let len = self.value.leading_zeros() as u64;
-            let max_value = x.value.wrapping_add(x.mask);
-            let max_shift_amount = if max_value > w as u64 {
-                w as u64
-            } else {
-                max_value
-            };
-            let mut max_res = Tnum::top();
-            if (len + x.value) >= w as u64 {
-                return Tnum::new(0, 0);
-            } else {
-                max_res.clear_high_bits((len + x.value) as u32);
+/// 分阶段验证模式：`pipeline [op]`，不给 op 时检查 [`verify::BINARY_OPS`] 中的全部运算
+///
+/// 先在小位宽下枚举，通过的运算再交给 Z3 在 64 位下证明，每个运算给出一个结论。
+fn run_pipeline_mode(args: &[String]) {
+    let pipeline = verify::Pipeline::default();
+    let reports = match args.first() {
+        Some(name) => match verify::binary_op(name) {
+            Some(op) => vec![pipeline.run(op)],
+            None => {
+                println!("未知运算: {}", name);
+                return;
             }
-
-            res = Tnum {
-                value: u64::MAX,
-                mask: u64::MAX,
-            };
-            // let mut join_count = 0;
-            for i in min_shift_amount..=max_shift_amount {
-                res = res.or(&self.lshr_const(i));
-                // join_count += 1;
-                if res.is_top() {
-                    return max_res;
+        },
+        None => pipeline.run_all(),
+    };
+    let widths: Vec<String> = pipeline
+        .widths
+        .iter()
+        .map(|w| format!("{} 位", w))
+        .collect();
+    println!(
+        "=== 分阶段验证：枚举 {}，再用 Z3 证明 64 位 ===",
+        widths.join("、")
+    );
+    for report in &reports {
+        let stages: Vec<String> = report
+            .enumeration
+            .iter()
+            .map(|(w, r)| format!("{}位{}", w, if r.is_sound() { "✓" } else { "✗" }))
+            .collect();
+        let verdict = match &report.verdict {
+            verify::PipelineVerdict::Proven => "可靠（Z3 证明）".to_string(),
+            verify::PipelineVerdict::Unsound { stage, .. } => match stage {
+                verify::Stage::Enumeration(w) => format!("不可靠（{} 位枚举）", w),
+                verify::Stage::Solver => "不可靠（Z3 反例）".to_string(),
+            },
+            verify::PipelineVerdict::Survived(why) => format!(
+                "枚举通过，未证明：{}",
+                match why {
+                    verify::Unproven::NoEncoding => "没有符号编码，只有小位宽枚举",
+                    verify::Unproven::EncodingMismatch(_) => "编码与实现不一致",
+                    verify::Unproven::SolverUnknown => "Z3 无法判定",
+                    verify::Unproven::SolverDisabled => "未启用 z3 特性",
                 }
+            ),
+        };
+        println!("{:<20} {:<12} {}", report.op, stages.join(" "), verdict);
+        match &report.verdict {
+            verify::PipelineVerdict::Unsound {
+                counterexample: e, ..
+            } => println!(
+                "  反例: a=({:#x}, {:#x}), b=({:#x}, {:#x}), x={:#x}, y={:#x}, 结果 {:#x} 不在 ({:#x}, {:#x}) 中",
+                e.a.value, e.a.mask, e.b.value, e.b.mask, e.x, e.y, e.r, e.result.value, e.result.mask
+            ),
+            verify::PipelineVerdict::Survived(verify::Unproven::EncodingMismatch(e)) => {
+                println!("  {}", e)
             }
-            if res.is_bottom() {
-                max_res
-            } else {
-                res
-            }
+            _ => {}
         }
     }
+    let proven = reports
+        .iter()
+        .filter(|r| r.verdict == verify::PipelineVerdict::Proven)
+        .count();
+    let unsound = reports
+        .iter()
+        .filter(|r| matches!(r.verdict, verify::PipelineVerdict::Unsound { .. }))
+        .count();
+    let no_encoding = reports
+        .iter()
+        .filter(|r| r.verdict == verify::PipelineVerdict::Survived(verify::Unproven::NoEncoding))
+        .count();
+    println!(
+        "共 {} 个运算：证明可靠 {}，不可靠 {}，未证明 {}（其中 {} 个没有符号编码）",
+        reports.len(),
+        proven,
+        unsound,
+        reports.len() - proven - unsound,
+        no_encoding
+    );
+}
 
-    /// tnum 的加法操作
-    pub fn add(&self, other: Self) -> Self {
-        // 计算掩码之和 - 表示两个不确定数的掩码组合
-        let sm = self.mask.wrapping_add(other.mask);
-
-        // 计算确定值之和
-        let sv = self.value.wrapping_add(other.value);
-
-        // sigma = (a.mask + b.mask) + (a.value + b.value)
-        // 用于检测进位传播情况
-        let sigma = sm.wrapping_add(sv);
-
-        // chi = 进位传播位图
-        // 通过异或操作找出哪些位发生了进位
-        let chi = sigma ^ sv;
-
-        // mu = 最终的不确定位掩码
-        // 包括:
-        // 1. 进位产生的不确定性 (chi)
-        // 2. 原始输入的不确定位 (a.mask | b.mask)
-        let mu = chi | self.mask | other.mask;
-
-        // 返回结果:
-        // value: 确定值之和，但排除所有不确定位 (~mu)
-        // mask: 所有不确定位的掩码
-        Self::new(sv & !mu, mu)
-    }
-
-    /// tnum 的减法操作
-    pub fn sub(&self, other: Self) -> Self {
-        if self.is_bottom() || other.is_bottom() {
-            return Self::bottom();
-        } else if self.is_top() || other.is_top() {
-            return Self::top();
-        }
-        let dv = self.value.wrapping_sub(other.value);
-        let alpha = dv.wrapping_add(self.mask);
-        let beta = dv.wrapping_sub(other.mask);
-        let chi = alpha ^ beta;
-        let mu = chi | self.mask | other.mask;
-        Self::new(dv & !mu, mu)
-    }
-
-    /// tnum 的按位异或操作
-    pub fn xor(&self, other: Self) -> Self {
-        if self.is_bottom() || other.is_bottom() {
-            return Self::bottom();
-        } else if self.is_top() || other.is_top() {
-            return Self::top();
+/// 内核差分模式：`kernel-diff [samples]`，与编译进来的内核 tnum.c 逐位比较
+#[cfg(feature = "kernel-diff")]
+fn run_kernel_diff_mode(args: &[String]) {
+    let samples = match args.first().map(|s| s.parse::<usize>()).unwrap_or(Ok(256)) {
+        Ok(n) => n,
+        Err(_) => {
+            println!("用法: kernel-diff [samples]");
+            return;
+        }
+    };
+    println!(
+        "=== 与内核 tnum.c 逐位比较（穷举小 tnum 与 {} 个随机 tnum）===",
+        samples
+    );
+    for diff in tnum_z3_verification::kernel_diff::cross_check(samples) {
+        println!(
+            "{:<10} 比较 {:>10}，不一致 {}",
+            diff.name, diff.checked, diff.mismatches
+        );
+        if let Some(m) = diff.example {
+            println!(
+                "  a=({:#x}, {:#x}), b=({:#x}, {:#x}), 参数 {}: 内核 ({:#x}, {:#x})，本 crate ({:#x}, {:#x})",
+                m.a.value, m.a.mask, m.b.value, m.b.mask, m.param, m.kernel.value, m.kernel.mask, m.ours.value, m.ours.mask
+            );
         }
-
-        let v = self.value ^ other.value;
-        let mu = self.mask | other.mask;
-
-        Self::new(v & !mu, mu)
     }
+}
 
-    /// tnum 的乘法操作
-    pub fn mul(&self, other: Self) -> Self {
-        if self.is_bottom() || other.is_bottom() {
-            return Self::bottom();
-        } else if self.is_top() || other.is_top() {
-            return Self::top();
-        }
-        let mut a = *self;
-        let mut b = other;
-        let acc_v = a.value.wrapping_mul(b.value);
-        let mut acc_m: Self = Self::new(0, 0);
-        while (a.value != 0) || (a.mask != 0) {
-            // println!("acc_m.mask:{:?}, acc_m.value:{:?}", acc_m.mask, acc_m.value);
-            if (a.value & 1) != 0 {
-                acc_m = acc_m.add(Tnum::new(0, b.mask));
-            } else if (a.mask & 1) != 0 {
-                acc_m = acc_m.add(Tnum::new(0, b.value | b.mask));
+/// 单调性模式：`verify-monotone [width] [op]`，不给 op 时检查 [`verify::BINARY_OPS`] 中的全部运算
+fn run_monotone_mode(args: &[String]) {
+    let usage = format!(
+        "用法: verify-monotone [width] [op]，width 取 1..={}",
+        verify::EXHAUSTIVE_WIDTH
+    );
+    let width = match args.first().map(|s| s.parse::<u32>()).unwrap_or(Ok(4)) {
+        Ok(w) if (1..=verify::EXHAUSTIVE_WIDTH).contains(&w) => w,
+        _ => {
+            println!("{}", usage);
+            return;
+        }
+    };
+    let ops: Vec<&verify::BinaryOp> = match args.get(1) {
+        Some(name) => match verify::binary_op(name) {
+            Some(op) => vec![op],
+            None => {
+                println!("未知运算: {}", name);
+                return;
+            }
+        },
+        None => verify::BINARY_OPS.iter().collect(),
+    };
+    println!("=== {} 位操作数（零扩展与符号扩展）上的单调性 ===", width);
+    for op in ops {
+        let report = verify::check_monotone(op.abs, width);
+        println!(
+            "{:<20} 检查 {:>10}，违反 {}",
+            op.name, report.checked, report.violations
+        );
+        for (label, v) in [("反例", report.example), ("缩小后", report.minimal)] {
+            if let Some(v) = v {
+                println!(
+                    "  {}: a=({:#x}, {:#x}) ⊑ ({:#x}, {:#x}), b=({:#x}, {:#x}) ⊑ ({:#x}, {:#x}), 结果 ({:#x}, {:#x}) ⋢ ({:#x}, {:#x})",
+                    label,
+                    v.a.value, v.a.mask, v.a2.value, v.a2.mask,
+                    v.b.value, v.b.mask, v.b2.value, v.b2.mask,
+                    v.lo.value, v.lo.mask, v.hi.value, v.hi.mask
+                );
             }
-            a = a.lshr_const(1);
-            b = b.shl_const(1);
         }
-        Tnum::new(acc_v, 0).add(acc_m)
     }
+}
 
-    /// tnum 的按位非操作
-    pub fn not(&self) -> Self {
-        if self.is_bottom() {
-            return Self::bottom();
-        } else if self.is_top() {
-            return Self::top();
+/// 精度回退模式：`regress <op> <table>`，用 `table` 模式写出的真值表作为基线，
+/// 比较当前实现与基线的精度
+#[cfg(feature = "serde")]
+fn run_regress_mode(args: &[String]) {
+    if args.len() != 2 {
+        println!("用法: regress <op> <table>");
+        return;
+    }
+    let op = match abstract_op(&args[0]) {
+        Some(op) => op,
+        None => {
+            println!("未知运算: {}", args[0]);
+            return;
         }
-        Self::new(!(self.value ^ self.mask), self.mask)
-    }
-
-    /// A constant-value optimization for tnum_mul
-    pub fn mul_opt(&self, other: Self) -> Self {
-        // 如果一个是常数
-        if self.mask == 0 && self.value.count_ones() == 1 {
-            // a.value = 2 ^ x
-            other.shl_const(self.value.trailing_zeros() as u64)
-        } else if other.mask == 0 && other.value.count_ones() == 1 {
-            // a.value = 2 ^ x
-            self.shl_const(other.value.trailing_zeros() as u64)
-        } else if (self.value | self.mask).count_ones() <= (other.value | other.mask).count_ones() {
-            self.mul(other)
-        } else {
-            other.mul(*self)
+    };
+    let reader = match table::TableReader::<_, table::TruthEntry>::open(&args[1]) {
+        Ok(reader) => reader,
+        Err(e) => {
+            println!("读取失败: {}", e);
+            return;
+        }
+    };
+    let header = reader.header().clone();
+    // precision-search 的语料本来就以另一个运算的结果为基线
+    if header.op != args[0] && header.kind != "corpus" {
+        println!("注意: 基线表的运算是 {}，与 {} 不同", header.op, args[0]);
+    }
+    let mut entries = Vec::new();
+    for entry in reader {
+        match entry {
+            Ok(e) => entries.push((e.a, e.b, e.result)),
+            Err(e) => {
+                println!("读取失败: {}", e);
+                return;
+            }
         }
     }
-
-    ///computes the w of the tnum domain.
-    pub fn join(&self, other: Self) -> Self {
-        let v = self.value ^ other.value;
-        let m = (self.mask | other.mask) | v;
-        Self::new((self.value | other.value) & (!m), m)
+    let max_examples = verify::CompareConfig::default().max_examples;
+    let report = verify::check_regression(op, entries, max_examples);
+    println!(
+        "=== {} 与基线 {}（{} 位）的精度 ===",
+        args[0], args[1], header.width
+    );
+    println!("总记录数: {}", report.total);
+    println!(
+        "相同: {} ({:.2}%)",
+        report.equal,
+        report.percent(report.equal)
+    );
+    println!(
+        "提升: {} ({:.2}%)，平均紧 {:.4} 位",
+        report.a_finer,
+        report.percent(report.a_finer),
+        report.average_a_finer_gap()
+    );
+    println!(
+        "回退: {} ({:.2}%)，平均松 {:.4} 位",
+        report.b_finer,
+        report.percent(report.b_finer),
+        report.average_b_finer_gap()
+    );
+    println!(
+        "不可比较: {} ({:.2}%)",
+        report.incomparable,
+        report.percent(report.incomparable)
+    );
+    for (label, examples) in [
+        ("回退", &report.b_finer_examples),
+        ("不可比较", &report.examples),
+    ] {
+        for (a, b) in examples {
+            let res = op(a, *b);
+            println!(
+                "  {}: a=({:#x}, {:#x}), b=({:#x}, {:#x}), 当前结果 ({:#x}, {:#x})",
+                label, a.value, a.mask, b.value, b.mask, res.value, res.mask
+            );
+        }
     }
+}
 
-    /// [split_at_mu] splits a tnum at the first unknow.
-    fn split_at_mu(&self) -> (Self, u32, Self) {
-        let i = self.mask.leading_ones();
-        let x1 = Self::new(self.value >> (i + 1), self.mask >> (i + 1));
-        let x2 = Self::new(self.value & ((1 << i) - 1), self.mask & ((1 << i) - 1));
-        (x1, i, x2)
-    }
+/// precision-search 每个除数最多加入语料的反例数
+#[cfg(feature = "serde")]
+const SEARCH_CASES_PER_DIVISOR: usize = 4;
+
+/// precision-search 在一个除数上的结果
+#[cfg(feature = "serde")]
+struct DivisorSearch {
+    divisor: u64,
+    /// 由 Z3 判定，而不是随机搜索
+    solved: bool,
+    /// 反例的被除数与 op_b 的结果
+    cases: Vec<(Tnum, Tnum)>,
+}
 
-    /// [tnum_mul_const] multiplies a constant [c] by the tnum [x]
-    /// which has [j] unknown bits and [n] is the fuel (Z.of_nat n = j).
-    fn mul_const(&self, c: u64, n: u64) -> Self {
-        if n == 0 {
-            Self::new(c.wrapping_mul(self.value), 0)
-        } else {
-            let (y1, i1, y2) = self.split_at_mu();
-            let p = y1.mul_const(c, n - 1);
-            let mc = Self::new(c.wrapping_mul(y2.mask), 0);
-            let mu0 = p.shl_const((i1 + 1) as u64).add(mc);
-            let mu1 = mu0.add(Self::new(c.wrapping_shl(i1 as u32), 0));
-            mu0.join(mu1)
+/// 反例引导的精度搜索：`precision-search <corpus> [op_a] [op_b] [samples]`
+///
+/// 对 1..=64 与 [`DIVIDER_EDGE_CASES`] 中的每个常数除数 c 寻找 `op_a(a, c) ⋢ op_b(a, c)`
+/// 的被除数（默认 fast_divide 相对 udiv）。两个运算都在 [`smt::CONST_DIV_OPS`] 中时先对拍编码，
+/// 再交给 Z3 判定；运算没有编码、Z3 无法判定或没有启用 z3 特性时，只在 samples 个随机的
+/// 64 位被除数上搜索，找不到反例不代表精化成立，这些除数单独列出。
+/// 找到的反例以 op_b 的结果为基线追加到语料（二进制结果表），之后可用
+/// `regress <op_a> <corpus>` 回归。
+#[cfg(feature = "serde")]
+fn run_precision_search_mode(args: &[String]) {
+    let usage = "用法: precision-search <corpus> [op_a] [op_b] [samples]";
+    let Some(path) = args.first() else {
+        println!("{}", usage);
+        return;
+    };
+    let op_a = args.get(1).map_or("fast_divide", String::as_str);
+    let op_b = args.get(2).map_or("udiv", String::as_str);
+    let samples = match args.get(3).map(|s| s.parse::<usize>()).unwrap_or(Ok(4096)) {
+        Ok(n) => n,
+        Err(_) => {
+            println!("{}", usage);
+            return;
         }
-    }
-
-    /// [xtnum_mul x i y j] computes the multiplication of
-    /// [x]  which has [i] unknown bits by
-    /// [y]  which has [j] unknown bits such (i <= j)
-    fn xtnum_mul(x: Self, i: u64, y: Self, j: u64) -> Self {
-        if i == 0 && j == 0 {
-            Self::new(x.value * y.value, 0)
-        } else {
-            let (y1, i1, y2) = y.split_at_mu(); // y = y1.mu.y2
-            let p = if i == j {
-                Self::xtnum_mul(y1, j - 1, x, i)
-            } else {
-                Self::xtnum_mul(x, i, y1, j - 1)
-            };
-            let mc = x.mul_const(y2.value, i);
-            let mu0 = p.shl_const((i1 + 1) as u64).add(mc);
-            let mu1 = mu0.add(x.shl_const(i1 as u64));
-            mu0.join(mu1)
+    };
+    let (fa, fb) = match (abstract_op(op_a), abstract_op(op_b)) {
+        (Some(fa), Some(fb)) => (fa, fb),
+        _ => {
+            println!("未知运算: {} 或 {}", op_a, op_b);
+            return;
         }
-    }
-
-    /// the top of the xtnum_mul
-    pub fn xtnum_mul_top(&self, other: Self) -> Self {
-        let i = 64 - self.mask.leading_zeros() as u64;
-        let j = 64 - other.mask.leading_zeros() as u64;
-        if i <= j {
-            Self::xtnum_mul(*self, i, other, j)
-        } else {
-            Self::xtnum_mul(other, j, *self, i)
+    };
+
+    // 已有语料：运算须与 op_b 一致，按 (a, b) 去重
+    let mut corpus: Vec<table::TruthEntry> = Vec::new();
+    if std::path::Path::new(path).exists() {
+        let reader = match table::TableReader::<_, table::TruthEntry>::open(path) {
+            Ok(reader) => reader,
+            Err(e) => {
+                println!("读取失败: {}", e);
+                return;
+            }
+        };
+        if reader.header().op != op_b {
+            println!("语料的基线运算是 {}，与 {} 不同", reader.header().op, op_b);
+            return;
         }
-    }
-
-    /// clear bit of a tnum
-    fn clear_bit(&self, pos: u8) -> Self {
-        Self::new(self.value & !(1 << pos), self.mask & !(1 << pos))
-    }
-
-    /// bit size of a tnum
-    fn size(&self) -> u8 {
-        let a = 64 - self.value.leading_zeros();
-        let b = 64 - self.mask.leading_zeros();
-        if a < b {
-            b as u8
-        } else {
-            a as u8
+        for entry in reader {
+            match entry {
+                Ok(e) => corpus.push(e),
+                Err(e) => {
+                    println!("读取失败: {}", e);
+                    return;
+                }
+            }
         }
     }
+    let known: HashSet<(Tnum, Tnum)> = corpus.iter().map(|e| (e.a, e.b)).collect();
 
-    /// max 64 of a tnum
-    fn max_val(&self) -> u64 {
-        self.value | self.mask
-    }
-
-    /// [xtnum_mul_high x y n] multiplies x by y
-    /// where n is the number of bits that are set in either x or y.
-    /// We also have that x <= y and 0 <= x and 0 <= y
-    fn xtnum_mul_high(&self, y: Self, n: u8) -> Self {
-        if self.mask == 0 && y.mask == 0 {
-            //if both are constants, perform normal multiplication
-            Self::new(self.value.wrapping_mul(y.value), 0)
-        } else if n == 0 {
-            //panic!("should not happen");
-            Self::new(0, 0) //should not happen
-        } else {
-            let b = y.size();
-            if b == 0 {
-                return Self::new(0, 0);
+    let encoded = smt::const_div_op(op_a).is_some() && smt::const_div_op(op_b).is_some();
+    if encoded {
+        for name in [op_a, op_b] {
+            if let Err(e) = smt::self_check(name, SMT_SELF_CHECK_SAMPLES) {
+                println!("{}: {}", name, e);
+                return;
             }
-            let ym = testbit(y.mask, b - 1);
-            let y_prime = y.clear_bit(b - 1); //clear the highest bit of y
-            let p = if y_prime.max_val() <= self.max_val() {
-                y_prime.xtnum_mul_high(*self, n - 1)
-            } else {
-                self.xtnum_mul_high(y_prime, n - 1)
+        }
+    } else {
+        println!(
+            "{} 或 {} 没有常数除数的编码，全部除数只做随机搜索",
+            op_a, op_b
+        );
+    }
+    if cfg!(not(feature = "z3")) {
+        println!("未启用 z3 特性，全部除数只做随机搜索");
+    }
+
+    let dividends: Vec<Tnum> = [4, 8, 16, 32]
+        .into_iter()
+        .flat_map(|k| width_tnums::<u64>(k, samples / 4))
+        .collect();
+    let divisors: Vec<u64> = (1..=64).chain(DIVIDER_EDGE_CASES.iter().copied()).collect();
+    println!(
+        "=== 搜索 {}(a, c) ⋢ {}(a, c)：{} 个除数 ===",
+        op_a,
+        op_b,
+        divisors.len()
+    );
+    let found: Vec<DivisorSearch> = divisors
+        .par_iter()
+        .map(|&c| {
+            #[cfg(feature = "z3")]
+            let solved = smt::refinement_query(op_a, op_b, c).map(|q| smt::solve_refinement(&q));
+            #[cfg(not(feature = "z3"))]
+            let solved: Option<smt::Refinement> = None;
+            let d = Tnum::const_val(c);
+            let cases = match solved {
+                Some(smt::Refinement::Refuted { a, rhs, .. }) => vec![(a, rhs)],
+                Some(smt::Refinement::Holds) => Vec::new(),
+                Some(smt::Refinement::Unknown) | None => dividends
+                    .iter()
+                    .map(|a| (*a, fa(a, d), fb(a, d)))
+                    .filter(|(_, lhs, rhs)| !lhs.le(rhs))
+                    .map(|(a, _, rhs)| (a, rhs))
+                    .take(SEARCH_CASES_PER_DIVISOR)
+                    .collect(),
             };
-            if ym {
-                p.add(self.shl_const((b - 1) as u64)).join(p)
-            } else {
-                p.add(self.shl_const((b - 1) as u64))
+            DivisorSearch {
+                divisor: c,
+                solved: matches!(
+                    solved,
+                    Some(smt::Refinement::Holds | smt::Refinement::Refuted { .. })
+                ),
+                cases,
             }
+        })
+        .collect();
+
+    let mut added = 0usize;
+    for kind in verify::DivisorKind::ALL {
+        let of_kind: Vec<_> = found
+            .iter()
+            .filter(|f| verify::DivisorKind::of(&Tnum::const_val(f.divisor)) == kind)
+            .collect();
+        if of_kind.is_empty() {
+            continue;
         }
-    }
-
-    /// the top level of xtnum_mul_high
-    pub fn xtnum_mul_high_top(&self, other: Self) -> Self {
-        self.xtnum_mul_high(
-            other,
-            ((self.value | self.mask).count_ones() + (other.value | other.mask).count_ones()) as u8,
-        )
-    }
-
-    /// aux function for tnum_mul_rec
-    fn decompose(&self) -> (Self, Self) {
-        (
-            Self::new(self.value >> 1, self.mask >> 1),
-            Self::new(self.value & 1, self.mask & 1),
-        )
-    }
-
-    /// A new tnum_mul proposed by frederic
-    pub fn mul_rec(&self, other: Self) -> Self {
-        if self.mask == 0 && other.mask == 0 {
-            // both are known
-            Self::new(self.value * other.value, 0)
-        } else if self.mask == u64::MAX && other.mask == u64::MAX {
-            //both are unknown
-            Self::new(0, u64::MAX)
-        } else if (self.value == 0 && self.mask == 0) || (other.value == 0 && other.mask == 0) {
-            // mult by 0
-            Self::new(0, 0)
-        } else if self.value == 1 && self.mask == 0 {
-            // mult by 1
-            other
-        } else if other.value == 1 && other.mask == 0 {
-            // mult by 1
-            *self
-        } else {
-            let (a_up, _a_low) = self.decompose();
-            let (b_up, _b_low) = other.decompose();
-            a_up.mul_rec(b_up)
-            //tnum_mul_rec(a_up, b_up) + tnum_mul_rec(a_up, b_low) + tnum_mul_rec(a_low, b_up) + tnum_mul_rec(a_low, b_low)
-            // TODO: this one is wrong, replace this line with the following impl
-            /* decompose the mask of am && bm
-            so that the last bits either 0s or 1s
-            In assembly, finding the rightmost 1 or 0 of a number is fast
-
-            let (a_up,a_low) = decompose a in
-            let (b_up,b_low) = decompose b in
-            // a_low and b_low are either 1s or 0s
-            (mul a_up b_up) + (mul a_up b_low) +
-            (mul a_low b_up) + (mul a_low b_low)
-            */
+        let solved = of_kind.iter().filter(|f| f.solved).count();
+        let refuted = of_kind.iter().filter(|f| !f.cases.is_empty()).count();
+        println!(
+            "{:<10} 除数 {:>4}，Z3 判定 {:>4}，只做随机搜索 {:>4}，找到反例 {:>4}",
+            kind.name(),
+            of_kind.len(),
+            solved,
+            of_kind.len() - solved,
+            refuted
+        );
+    }
+    let sampled: Vec<String> = found
+        .iter()
+        .filter(|f| !f.solved && f.cases.is_empty())
+        .map(|f| f.divisor.to_string())
+        .collect();
+    if !sampled.is_empty() {
+        println!(
+            "未经 Z3 判定且随机搜索没有找到反例，精化未证明: c = {}",
+            sampled.join(", ")
+        );
+    }
+    for f in &found {
+        let b = Tnum::const_val(f.divisor);
+        for &(a, result) in &f.cases {
+            if !known.contains(&(a, b)) {
+                corpus.push(table::TruthEntry { a, b, result });
+                added += 1;
+            }
         }
     }
 
-    /// tnum 的交集计算
-    pub fn intersect(&self, other: Self) -> Self {
-        let v = self.value | other.value;
-        let mu = self.mask & other.mask;
-        Self::new(v & !mu, mu)
-    }
-
-    /// tnum 用与截断到指定字节大小
-    pub fn cast(&self, size: u8) -> Self {
-        //处理溢出
-        let mut result = *self;
-        result.value &= (1u64 << (size * 8)) - 1;
-        result.mask &= (1u64 << (size * 8)) - 1;
-        result
-    }
-
-    pub fn is_aligned(&self, size: u64) -> bool {
-        (self.value | self.mask) & (size - 1) == (size - 1)
-    }
-
-    /// Checks if self contains other
-    pub fn contains(&self, other: Self) -> bool {
-        if self.is_bottom() {
-            false
-        } else if other.is_bottom() {
-            true
-        } else {
-            (self.value & !other.mask) == (other.value & !other.mask)
-                && (self.mask | other.mask) == self.mask
+    let header = table::Header {
+        kind: "corpus".to_string(),
+        op: op_b.to_string(),
+        width: 64,
+    };
+    // 先写临时文件再改名，写入中断时不破坏原有语料
+    let tmp = format!("{}.tmp", path);
+    let result = table::TableWriter::create(&tmp, &header).and_then(|mut w| {
+        for e in &corpus {
+            w.write(e)?;
         }
+        w.finish()?;
+        std::fs::rename(&tmp, path)
+    });
+    match result {
+        Ok(()) => println!(
+            "新增 {} 条，语料共 {} 条: {}（用 regress {} {} 回归）",
+            added,
+            corpus.len(),
+            path,
+            op_a,
+            path
+        ),
+        Err(e) => println!("写入失败: {}", e),
     }
+}
 
-    /// tnum转换为字符串
-    pub fn to_sbin(&self, size: usize) -> String {
-        let mut result = vec![0u8; size];
-        let mut a = *self;
-
-        // 从高位到低位处理每一位
-        for n in (1..=64).rev() {
-            if n < size {
-                result[n - 1] = match (a.mask & 1, a.value & 1) {
-                    (1, _) => b'x', // 不确定位
-                    (0, 1) => b'1', // 确定位 1
-                    (0, 0) => b'0', // 确定位 0
-                    _ => unreachable!(),
-                };
-            }
-            // 右移处理下一位
-            a.mask >>= 1;
-            a.value >>= 1;
+/// 求解前对拍编码与实现所用的随机输入个数
+const SMT_SELF_CHECK_SAMPLES: usize = 100_000;
+
+/// SMT 模式：`smt [op]`，不给 op 时检查 [`smt::SYMBOLIC_OPS`] 中的全部运算
+///
+/// 先对拍编码与实现，再用 Z3 在 64 位下对全部 tnum 对与具体值证明可靠性。
+fn run_smt_mode(args: &[String]) {
+    let names: Vec<&str> = match args.first() {
+        Some(name) if smt::symbolic_op(name).is_some() => vec![name.as_str()],
+        Some(name) => {
+            println!("{} 没有符号编码，可用 smt-pair 在给定的 tnum 上求解", name);
+            return;
         }
-
-        // 设置字符串结束位置
-        let end = std::cmp::min(size - 1, 64);
-        result[end] = 0;
-
-        // 转换为字符串
-        String::from_utf8(result[..end].to_vec()).unwrap_or_else(|_| String::new())
-    }
-
-    pub fn subreg(&self) -> Self {
-        self.cast(4)
-    }
-
-    pub fn clear_subreg(&self) -> Self {
-        self.lshr_const(32).shl_const(32)
-    }
-
-    pub fn with_subreg(&self, subreg: Self) -> Self {
-        self.clear_subreg().or(&subreg.subreg())
-    }
-
-    pub fn with_const_subreg(&self, value: u32) -> Self {
-        self.with_subreg(Self::const_val(value as u64))
-    }
-
-    /// 有符号取余操作（SRem）
-    pub fn srem(&self, other: Self) -> Self {
-        // 处理 bottom 和 top 情况
-        if self.is_bottom() || other.is_bottom() {
-            return Self::bottom();
-        } else if self.is_top() || other.is_top() {
-            return Self::top();
+        None => smt::SYMBOLIC_OPS.iter().map(|&(name, _)| name).collect(),
+    };
+    println!("=== 64 位全符号可靠性 ===");
+    for name in names {
+        if let Err(e) = smt::self_check(name, SMT_SELF_CHECK_SAMPLES) {
+            println!("{:<12} 编码与实现不一致: {}", name, e);
+            continue;
         }
+        let query = smt::soundness_query(name, 64).expect("符号编码已检查");
+        print_verdict(name, &query);
+    }
+}
 
-        // 处理单点值情况
-        if self.is_singleton() && other.is_singleton() {
-            let res_single = Tnum::new(
-                (self.value as i64).wrapping_rem(other.value as i64) as u64,
-                0,
-            );
-            return res_single;
+/// `smt-pair <op> <a.value> <a.mask> <b.value> <b.mask>`：F(a, b) 由实现算出，
+/// 用 Z3 检查 γ(a) × γ(b) 中全部 64 位具体值
+fn run_smt_pair_mode(args: &[String]) {
+    let usage = "用法: smt-pair <op> <a.value> <a.mask> <b.value> <b.mask>";
+    if args.len() != 5 {
+        println!("{}", usage);
+        return;
+    }
+    let nums: Option<Vec<u64>> = args[1..].iter().map(|s| parse_u64(s)).collect();
+    let nums = match nums {
+        Some(n) if n[0] & n[1] == 0 && n[2] & n[3] == 0 => n,
+        _ => {
+            println!("{}，value 与 mask 不能有公共位", usage);
+            return;
         }
+    };
+    let (a, b) = (Tnum::new(nums[0], nums[1]), Tnum::new(nums[2], nums[3]));
+    let name = args[0].as_str();
+    if let Err(e) = smt::self_check(name, SMT_SELF_CHECK_SAMPLES) {
+        println!("{}", e);
+        return;
+    }
+    let query = smt::pair_query(name, a, b).expect("具体语义已检查");
+    print_verdict(name, &query);
+}
 
-        // 处理除数为0的情况
-        if other.value == 0 {
-            return Self::top(); // top
-        } else {
-            let mut res = rem_get_low_bits(self, &other);
-            if other.mask == 0
-                && (other.value) & 1 == 0
-                && ((other.value.trailing_zeros() + other.value.leading_zeros() + 1) == 64)
-            {
-                let low_bits = other.value - 1;
-                if self.is_nonnegative()
-                    || (other.value.trailing_zeros() <= self.count_min_trailing_zeros())
-                {
-                    res.value = low_bits & res.value;
-                    res.mask = low_bits & res.mask;
-                }
-                if self.is_negative() && !(self.value & low_bits) == 0 {
-                    res.mask = low_bits & res.mask;
-                    res.value = (!low_bits) | res.value;
-                }
-                return res;
-            }
-            let leadingz = self.count_min_leading_zeros();
-            res.value.clear_high_bits(leadingz);
-            res.mask.clear_high_bits(leadingz);
-            return res;
+/// `emit-smt <op> [width] [file]`：把 op 的全符号可靠性查询导出为 SMT-LIB2 脚本，
+/// 不给 file 时写到标准输出；不需要 z3 特性
+fn run_emit_smt_mode(args: &[String]) {
+    let usage = "用法: emit-smt <op> [width] [file]，width 取 1..=64，默认 64";
+    let Some(name) = args.first() else {
+        println!("{}", usage);
+        return;
+    };
+    let width = match args.get(1).map(|s| s.parse::<u32>()).unwrap_or(Ok(64)) {
+        Ok(w @ 1..=64) => w,
+        _ => {
+            println!("{}", usage);
+            return;
         }
+    };
+    if let Err(e) = smt::self_check(name, SMT_SELF_CHECK_SAMPLES) {
+        println!("{}: {}", name, e);
+        return;
+    }
+    let Some(script) = smt::to_smtlib(name, width) else {
+        println!(
+            "{} 没有符号编码，可导出的运算: {:?}",
+            name,
+            smt::SYMBOLIC_OPS
+                .iter()
+                .map(|&(n, _)| n)
+                .collect::<Vec<_>>()
+        );
+        return;
+    };
+    match args.get(2) {
+        Some(path) => match std::fs::write(path, script) {
+            Ok(()) => eprintln!("已写入 {}", path),
+            Err(e) => eprintln!("写入 {} 失败: {}", path, e),
+        },
+        None => print!("{}", script),
     }
+}
 
-    /// 无符号取余操作（URem）
-    pub fn urem(&self, other: Self) -> Self {
-        // 处理 bottom 和 top 情况
-        if self.is_bottom() || other.is_bottom() {
-            return Self::bottom();
-        } else if self.is_top() || other.is_top() {
-            return Self::top();
-        }
-
-        // 处理除数为0的情况
-        if other.value == 0 {
-            return Self::top(); // 除以0返回top
+/// 读取并分析原始 eBPF 字节码，失败时输出原因
+fn analyze_file(
+    path: &str,
+    profile: interp::IsaProfile,
+) -> Option<(Vec<driver::Insn>, Vec<interp::RegState>)> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            println!("读取 {} 失败: {}", path, e);
+            return None;
         }
-
-        let mut res = rem_get_low_bits(self, &other);
-        // 处理低位
-        // 检查除数是否为 2 的幂
-        if other.mask == 0
-            && !((other.value >> 63) & 1 == 1)
-            && ((other.value.trailing_zeros() + other.value.leading_zeros() + 1) == 64)
-        {
-            // 除数是 2 的幂，直接用位掩码计算余数
-            let low_bits = other.value - 1; // 例如：8-1=7(0b111)，用于掩码
-            let res_value = low_bits & self.value;
-            let res_mask = low_bits & self.mask;
-            return Self::new(res_value, res_mask);
+    };
+    let result = driver::decode(&bytes).and_then(|insns| {
+        driver::analyze(profile, &insns, interp::RegState::top()).map(|facts| (insns, facts))
+    });
+    match result {
+        Ok(r) => Some(r),
+        Err(e) => {
+            println!("分析失败: {}", e);
+            None
         }
+    }
+}
 
-        // 一般情况：结果的精度有限
-        // 由于结果小于或等于任一操作数，因此操作数中的前导零在结果中也存在
-        let leading_zeros = self
-            .count_min_leading_zeros()
-            .max(other.count_min_leading_zeros());
-        res.clear_high_bits(leading_zeros);
-
-        res
+/// 分析原始 eBPF 字节码，以 Graphviz dot 格式输出带寄存器事实的控制流图
+fn run_emit_dot_mode(args: &[String]) {
+    let usage = "用法: emit-dot <file> [linux|sbf-v1|sbf-v2] [out]，file 为原始指令字节";
+    let profile = args
+        .get(1)
+        .map_or(Some(interp::IsaProfile::LinuxEbpf), |s| {
+            interp::IsaProfile::from_name(s)
+        });
+    let (Some(path), Some(profile)) = (args.first(), profile) else {
+        println!("{}", usage);
+        return;
+    };
+    let Some((insns, facts)) = analyze_file(path, profile) else {
+        return;
+    };
+    let dot = driver::to_dot(profile, &insns, &facts);
+    match args.get(2) {
+        Some(out) => match std::fs::write(out, dot) {
+            Ok(()) => eprintln!("已写入 {}", out),
+            Err(e) => eprintln!("写入 {} 失败: {}", out, e),
+        },
+        None => print!("{}", dot),
     }
+}
 
-    /// 有符号除法操作
-    pub fn signed_div(&self, other: Self) -> Self {
-        if self.is_bottom() || other.is_bottom() {
-            return Self::bottom();
+/// 对原始 eBPF 字节码做数据流分析，逐条输出执行前已知的寄存器；
+/// `--report <json>` 另外把每条指令的全部寄存器事实写成 JSON
+fn run_analyze_mode(args: &[String]) {
+    let usage = "用法: analyze <file> [linux|sbf-v1|sbf-v2] [--report <json>]，file 为原始指令字节";
+    let mut args = args.to_vec();
+    let report_path = match args.iter().position(|a| a == "--report") {
+        Some(i) if i + 1 < args.len() => Some(args.drain(i..i + 2).nth(1).unwrap_or_default()),
+        Some(_) => {
+            println!("{}", usage);
+            return;
         }
-
-        let w = 64;
-
-        if self.is_singleton() && other.is_singleton() {
-            return Tnum::new(self.value.wrapping_div(other.value), 0);
+        None => None,
+    };
+    #[cfg(not(feature = "serde"))]
+    if report_path.is_some() {
+        println!("JSON 报告需要启用 serde 特性");
+        return;
+    }
+    let profile = args
+        .get(1)
+        .map_or(Some(interp::IsaProfile::LinuxEbpf), |s| {
+            interp::IsaProfile::from_name(s)
+        });
+    let (Some(path), Some(profile)) = (args.first(), profile) else {
+        println!("{}", usage);
+        return;
+    };
+    let Some((insns, facts)) = analyze_file(path, profile) else {
+        return;
+    };
+    #[cfg(feature = "serde")]
+    if let Some(out) = &report_path {
+        let report = driver::Report::new(profile, &insns, &facts);
+        let written = std::fs::File::create(out)
+            .map_err(serde_json::Error::io)
+            .and_then(|file| serde_json::to_writer_pretty(std::io::BufWriter::new(file), &report));
+        match written {
+            Ok(()) => eprintln!("已写入 {}", out),
+            Err(e) => eprintln!("写入 {} 失败: {}", out, e),
         }
-
-        if self.is_nonnegative() && other.is_nonnegative() {
-            return self.udiv(other);
+    }
+    println!(
+        "=== {}，{} 条指令（{}）===",
+        path,
+        insns.len(),
+        profile.name()
+    );
+    for (pc, (insn, state)) in insns.iter().zip(&facts).enumerate() {
+        print!(
+            "{:>4}: {:02x} r{} r{} {:+} {:#x}",
+            pc, insn.opcode, insn.dst, insn.src, insn.off, insn.imm
+        );
+        if state.is_bottom() {
+            println!("  不可达");
+            continue;
         }
-
-        let mut result = Self::top();
-        let mut tmp: i64 = 0;
-
-        if self.is_negative() && other.is_negative() {
-            if self.value == i64::MIN as u64 && other.is_singleton() && other.value == u64::MAX {
-                return Self::top();
-            }
-
-            let denom = other.get_signed_max_value();
-            let num = self.get_signed_min_value();
-
-            if !(num == i64::MIN as u64 && denom == i64::MAX as u64) {
-                tmp = (num as i64).wrapping_div(denom as i64);
-            } else {
-                tmp = i64::MAX;
-            }
-        } else if self.is_negative() && other.is_nonnegative() {
-            // Result is negative if -LHS u>= RHS
-            let neg_lhs_max: i64 = (self.get_signed_max_value() as i64).wrapping_neg();
-            if neg_lhs_max >= other.get_signed_max_value() as i64 {
-                let denom = other.get_signed_min_value();
-                let num = self.get_signed_min_value();
-                tmp = (num as i64).wrapping_div(denom as i64);
-            }
-        } else if self.is_nonnegative() && other.is_negative() {
-            // Result is negative if LHS u>= -RHS
-            let neg_rhs_min = (other.get_signed_min_value() as i64).wrapping_neg();
-            if self.get_signed_min_value() >= neg_rhs_min as u64 {
-                let denom = other.get_signed_max_value();
-                let num = self.get_signed_max_value();
-                tmp = (num as i64).wrapping_div(denom as i64);
+        println!();
+        for (r, v) in state.regs.iter().enumerate() {
+            if *v != regval::RegValue::top() {
+                println!(
+                    "        r{:<2} tnum=({:#x}, {:#x}) u=[{}, {}] s=[{}, {}]",
+                    r, v.tnum.value, v.tnum.mask, v.umin, v.umax, v.smin, v.smax
+                );
             }
         }
+    }
+}
 
-        if tmp != 0 {
-            if (tmp >> 63) & 1 == 0 {
-                let lead_zeros = tmp.leading_zeros();
-                result.clear_high_bits(lead_zeros);
-            } else {
-                let lead_ones = (!tmp).leading_zeros();
-                if lead_ones > 0 {
-                    let high_mask = u64::MAX << (64 - lead_ones);
-                    result.value |= high_mask;
-                    result.mask &= !high_mask;
-                }
+/// smt-divider 默认检查的除数：1..=256 以外的边界值
+const DIVIDER_EDGE_CASES: &[u64] = &[
+    641,
+    1_000_000_007,
+    u32::MAX as u64,
+    (u32::MAX as u64) + 2,
+    1 << 63,
+    (1 << 63) + 1,
+    u64::MAX - 1,
+    u64::MAX,
+];
+
+/// `smt-divider [d...]`：验证抽象除法依赖的 [`fastdivide::DividerU64`] 分解，
+/// 即 `divide(n) == n / d` 对全部 64 位被除数成立
+///
+/// 不给除数时检查 1..=256 与 [`DIVIDER_EDGE_CASES`]。先对拍编码与 fastdivide，再用 Z3 求解。
+fn run_smt_divider_mode(args: &[String]) {
+    let divisors: Vec<u64> = if args.is_empty() {
+        (1..=256)
+            .chain(DIVIDER_EDGE_CASES.iter().copied())
+            .collect()
+    } else {
+        match args
+            .iter()
+            .map(|s| parse_u64(s))
+            .collect::<Option<Vec<u64>>>()
+        {
+            Some(ds) if !ds.contains(&0) => ds,
+            _ => {
+                println!("用法: smt-divider [d...]，d 为非零的 64 位整数");
+                return;
             }
         }
-        result
+    };
+    println!("=== DividerU64 分解：{} 个除数 ===", divisors.len());
+    let results: Vec<(u64, Result<(), String>)> = divisors
+        .par_iter()
+        .map(|&d| (d, smt::divider_self_check(d, SMT_SELF_CHECK_SAMPLES)))
+        .collect();
+    let mut consistent = Vec::new();
+    for (d, r) in results {
+        match r {
+            Ok(()) => consistent.push(d),
+            Err(e) => println!("{}", e),
+        }
     }
+    print_divider_verdicts(&consistent);
+}
 
-    /// fast_divide
-    pub fn fast_divide(&self, other: Self) -> Self {
-        if other.mask == 0 && other.value == 0 {
-            return Tnum::top();
-        } else if other.mask == 0 && other.value == 1 {
-            return *self;
-        } else if other.mask == 0 {
-            let divider = DividerU64::divide_by(other.value);
-            match divider {
-                DividerU64::Fast { magic, shift } => {
-                    // 修正：使用64位magic number算法
-                    // 正确公式: ((dividend * magic) >> 64) >> shift
-                    // let product = (self.value as u128).wrapping_mul(magic.into());
-                    // let high_part = (product >> 64) as u64;  // 取高64位
-                    // let result_value = high_part >> shift;
-                    
-                    // // 对于mask的处理（保守估计）
-                    // let mask_product = (self.mask as u128).wrapping_mul(magic.into());
-                    // let mask_high = (mask_product >> 64) as u64;
-                    // let result_mask = mask_high >> shift;
-
-
-                    let Tnum_magic = TnumU128::new(magic as u128, 0);
-                    let self_u128 = TnumU128::new(self.value as u128, self.mask as u128);
-                    let temp = self_u128.mul(Tnum_magic);
-                    let result_value = (temp.value >> 64) as u64 >> shift;
-                    let result_mask = (temp.mask >> 64) as u64 >> shift;
-                    
-                    return Self::new(result_value, result_mask);
-                    // println!("  - Strategy: Fast Path");
-                    // println!("  - Magic (M): 0x{:X} ({})", magic, magic);
-                    // println!("  - Shift (s): {}", shift);
-                    // println!("  - Formula: ((n * M) >> 64) >> s");
-                }
-                DividerU64::BitShift(shift) => {
-                    return self.tnum_rshift(shift as u8);
-                    // println!("  - Strategy: BitShift (Power of 2)");
-                    // println!("  - No Magic number (M) needed.");
-                    // println!("  - Shift (s): {}", shift);
-                    // println!("  - Formula: n >> s");
-                }
-                DividerU64::General { magic_low, shift } => {
-                    // a/b
-                    // M = 2^n/b
-                    // a*(2^n/b)>>n==a/b
-                    let self_u128 = TnumU128::new(self.value as u128,self.mask as u128);
-                    let other_u128 = TnumU128::new(magic_low as u128,0);
-                    let temp = self_u128.mul(other_u128);
-                    let q = Self::new((temp.value >> 64) as u64, (temp.mask >> 64) as u64);
-                    let mut res = self.sub(q).tnum_rshift(1).add(q);
-                    res = res.tnum_rshift(shift as u8);
-                    return res;
-                    // println!("  - Strategy: General Path");
-                    // println!("  - Magic_low: 0x{:X} ({})", magic_low, magic_low);
-                    // println!("  - The effective Magic number is (2^64 + Magic_low)");
-                    // println!("  - Shift (s): {}", shift);
-                    // println!("  - Formula: A more complex calculation (see source)");
-                }
+#[cfg(feature = "z3")]
+fn print_divider_verdicts(divisors: &[u64]) {
+    // (Fast, BitShift, General) 三种策略下证明成立的个数
+    let mut correct = [0usize; 3];
+    let mut unknown = 0usize;
+    for &d in divisors {
+        let strategy = match DividerU64::divide_by(d) {
+            DividerU64::Fast { .. } => 0,
+            DividerU64::BitShift(_) => 1,
+            DividerU64::General { .. } => 2,
+        };
+        match smt::prove_divider(d).expect("除数非零") {
+            smt::DividerVerdict::Correct => correct[strategy] += 1,
+            smt::DividerVerdict::Unknown => unknown += 1,
+            smt::DividerVerdict::Wrong { n, got } => {
+                println!(
+                    "d={} 反例: n={:#x}, 分解得 {:#x}, 应为 {:#x}",
+                    d,
+                    n,
+                    got,
+                    n / d
+                )
             }
         }
-        self.sdiv(other)
     }
+    println!(
+        "成立（UNSAT）: Fast {}，BitShift {}，General {}；Z3 无法判定: {}",
+        correct[0], correct[1], correct[2], unknown
+    );
+}
 
-    /// 有符号除法操作
-    pub fn sdiv(&self, other: Self) -> Self {
-        if self.is_bottom() || other.is_bottom() {
-            return Self::bottom();
-        }
-        if self.is_top() || other.is_top() {
-            return Self::top();
-        }
-
-        let w = 64;
+#[cfg(not(feature = "z3"))]
+fn print_divider_verdicts(divisors: &[u64]) {
+    println!(
+        "{} 个除数的编码与 fastdivide 一致，求解需要启用 z3 特性",
+        divisors.len()
+    );
+}
 
-        if other.value == 0 {
-            return Self::top();
-        } else if (self.mask == 0 && other.mask == 0) {
-            return Self::new(self.value.wrapping_div(other.value), 0);
+/// `smt-refine [op_a] [op_b] [max_divisor] [search_max]`：对 1..=max_divisor 中的每个常数除数 c
+/// 判定 `op_a(a, c) ⊑ op_b(a, c)` 是否对全部 64 位被除数成立，按除数类别汇总
+///
+/// 默认检查 fast_divide 相对 udiv。反例先在 value、mask 不超过 search_max 的被除数上枚举，
+/// 找不到时能编码的除数交给 Z3。
+fn run_smt_refine_mode(args: &[String]) {
+    let usage = "用法: smt-refine [op_a] [op_b] [max_divisor] [search_max]";
+    let op_a = args.first().map_or("fast_divide", String::as_str);
+    let op_b = args.get(1).map_or("udiv", String::as_str);
+    let nums: Option<Vec<u64>> = args.iter().skip(2).map(|s| parse_u64(s)).collect();
+    let (max_divisor, search_max) = match nums.as_deref() {
+        Some([]) => (64, 64),
+        Some([d]) => (*d, 64),
+        Some([d, s]) => (*d, *s),
+        _ => {
+            println!("{}", usage);
+            return;
         }
-
-        let t0 = self.get_zero_circle();
-        let t1 = self.get_one_circle();
-        let x0 = other.get_zero_circle();
-        let x1 = other.get_one_circle();
-
-        let res00 = t0.signed_div(x0);
-        let res01 = t0.signed_div(x1);
-        let res10 = t1.signed_div(x0);
-        let res11 = t1.signed_div(x1);
-
-        res00.or(&res01).or(&res10).or(&res11)
-    }
-
-    fn get_signed_min_value(&self) -> u64 {
-        if (self.value >> 63) & 1 == 1 {
-            self.value | self.mask
-        } else {
-            self.value
+    };
+    for name in [op_a, op_b] {
+        if let Err(e) = smt::self_check(name, SMT_SELF_CHECK_SAMPLES) {
+            println!("{}: {}", name, e);
+            return;
         }
     }
-
-    fn get_signed_max_value(&self) -> u64 {
-        if (self.value >> 63) & 1 == 1 {
-            self.value
-        } else {
-            self.value | self.mask
+    println!(
+        "=== {}(a, c) ⊑ {}(a, c)，c ∈ 1..={} ===",
+        op_a, op_b, max_divisor
+    );
+    let verdicts: Vec<(u64, smt::Refinement)> = (1..=max_divisor)
+        .into_par_iter()
+        .map(|c| {
+            let r = smt::prove_refinement(op_a, op_b, c, search_max);
+            (c, r.expect("运算已检查"))
+        })
+        .collect();
+    println!(
+        "{:<10} {:>8} {:>8} {:>8}  首个反例",
+        "类别", "成立", "反例", "未知"
+    );
+    for kind in verify::DivisorKind::ALL {
+        let of_kind: Vec<&(u64, smt::Refinement)> = verdicts
+            .iter()
+            .filter(|(c, _)| verify::DivisorKind::of(&Tnum::const_val(*c)) == kind)
+            .collect();
+        if of_kind.is_empty() {
+            continue;
         }
+        let count = |f: fn(&smt::Refinement) -> bool| of_kind.iter().filter(|(_, r)| f(r)).count();
+        let holds = count(|r| *r == smt::Refinement::Holds);
+        let refuted = count(|r| matches!(r, smt::Refinement::Refuted { .. }));
+        let unknown = count(|r| *r == smt::Refinement::Unknown);
+        let first = of_kind.iter().find_map(|(c, r)| match r {
+            smt::Refinement::Refuted { a, lhs, rhs } => Some(format!(
+                "c={}, a=({:#x}, {:#x}): ({:#x}, {:#x}) ⋢ ({:#x}, {:#x})",
+                c, a.value, a.mask, lhs.value, lhs.mask, rhs.value, rhs.mask
+            )),
+            _ => None,
+        });
+        println!(
+            "{:<10} {:>8} {:>8} {:>8}  {}",
+            kind.name(),
+            holds,
+            refuted,
+            unknown,
+            first.unwrap_or_default()
+        );
     }
+}
 
-    pub fn get_zero_circle(&self) -> Self {
-        let width = 64;
-        let sign_max = i64::MAX;
-        let value = self.value as i64;
-        let mask = self.mask as i64;
-        if value & (1i64 << 63) != 0 {
-            return Tnum::new(sign_max as u64, sign_max as u64);
-        } else if mask & (1i64 << 63) != 0 {
-            return Tnum::new(value as u64, (mask & sign_max) as u64);
-        } else {
-            return *self;
-        }
+#[cfg(feature = "z3")]
+fn print_verdict(name: &str, query: &smt::Query) {
+    match smt::solve(query) {
+        smt::Verdict::Sound => println!("{:<12} 可靠（UNSAT）", name),
+        smt::Verdict::Unknown => println!("{:<12} Z3 无法判定", name),
+        smt::Verdict::Unsound(e) => println!(
+            "{:<12} 反例: a=({:#x}, {:#x}), b=({:#x}, {:#x}), x={:#x}, y={:#x}, 结果 {:#x} 不在 ({:#x}, {:#x}) 中",
+            name, e.a.value, e.a.mask, e.b.value, e.b.mask, e.x, e.y, e.r, e.result.value, e.result.mask
+        ),
     }
+}
 
-    pub fn get_one_circle(&self) -> Self {
-        let value = self.value as i64;
-        let mask = self.mask as i64;
-        let width = 64;
-        let sign_max = i64::MAX;
-        let sign_min = i64::MIN;
-        let unsign_max = u64::MAX;
-        if value & (1i64 << 63) != 0 {
-            return *self;
-        } else if mask & (1i64 << 63) != 0 {
-            let mut value = value;
-            value |= (1i64 << 63);
-            let mut mask = mask;
-            mask &= !(1i64 << 63);
-            return Tnum::new(value as u64, mask as u64);
-        } else {
-            return Tnum::new(unsign_max, unsign_max);
-        }
-    }
+#[cfg(not(feature = "z3"))]
+fn print_verdict(name: &str, _query: &smt::Query) {
+    println!("{:<12} 编码与实现一致，求解需要启用 z3 特性", name);
+}
 
-    /// 无符号除法操作
-    pub fn udiv(&self, other: Self) -> Self {
-        // 处理 bottom 和 top 情况
-        if self.is_bottom() || other.is_bottom() {
-            return Self::bottom();
+/// 精度模式：`precision <op> [max_unknown] [tnums]`
+///
+/// 用固定种子抽取 tnums 个至多 max_unknown 个未知位的 64 位 tnum，对每一对用
+/// [`exact::best_abstract_partial`] 求最优抽象，统计算法结果等于最优的比例、多出的未知位个数，
+/// 以及不包含最优抽象（不可靠）的个数。
+fn run_precision_mode(args: &[String]) {
+    let usage = "用法: precision <op> [max_unknown] [tnums]，max_unknown 取 0..=8";
+    let op = match args.first() {
+        Some(op) => op.as_str(),
+        None => {
+            println!("{}", usage);
+            return;
         }
-        if self.is_top() || other.is_top() {
-            return Self::top();
+    };
+    let (abs, con) = match (abstract_op(op), concrete_op(op)) {
+        (Some(abs), Some(con)) => (abs, con),
+        _ => {
+            println!("未知运算: {}", op);
+            return;
         }
-
-        let w = 64;
-        let flag: bool = (other.value == 0);
-        if flag {
-            // 处理除数为0的情况
-            return Self::top();
-        } else {
-            let mut Res = Tnum::top();
-            let MaxRes = match (self.value + self.mask).checked_div(other.value) {
-                // 如果除法成功，返回包含结果的新 Tnum
-                Some(result) => result,
-                // 如果除以零，checked_div 返回 None，我们返回 top
-                None => return Self::top(),
-            };
-            let leadz = MaxRes.leading_zeros();
-            Res.value.clear_high_bits(leadz);
-            Res.mask.clear_high_bits(leadz);
-            // if (leadz == 64) {
-            //     return Res;
-            // }
-            // let result = self.div_compute_low_bit(Res, other);
-            return Res;
+    };
+    let max_unknown = args.get(1).map(|s| s.parse::<u32>());
+    let count = args.get(2).map(|s| s.parse::<usize>());
+    let (max_unknown, count) = match (max_unknown.unwrap_or(Ok(4)), count.unwrap_or(Ok(256))) {
+        (Ok(k @ 0..=8), Ok(c)) => (k, c),
+        _ => {
+            println!("{}", usage);
+            return;
         }
-    }
+    };
+
+    let tnums = width_tnums::<u64>(max_unknown, count);
+    println!(
+        "=== {} 相对最优抽象的精度（{} 个至多 {} 个未知位的 tnum）===",
+        op,
+        tnums.len(),
+        max_unknown
+    );
+    // (对数, 等于最优, 多出的未知位, 不可靠)
+    let c = tnums
+        .par_iter()
+        .map(|a| {
+            let mut n = [0u64; 4];
+            for b in &tnums {
+                let res = abs(a, *b);
+                let best =
+                    exact::best_abstract_partial(*a, *b, con, usize::MAX).expect("预算不受限");
+                n[0] += 1;
+                n[1] += (res == best) as u64;
+                if best.le(&res) {
+                    n[2] += (res.mask.count_ones() - best.mask.count_ones()) as u64;
+                } else {
+                    n[3] += 1;
+                }
+            }
+            n
+        })
+        .reduce(|| [0; 4], |p, q| std::array::from_fn(|i| p[i] + q[i]));
+    println!("tnum 对数: {}", c[0]);
+    println!(
+        "等于最优: {} ({:.2}%)",
+        c[1],
+        c[1] as f64 * 100.0 / c[0].max(1) as f64
+    );
+    println!(
+        "平均多出的未知位: {:.3}",
+        c[2] as f64 / (c[0] - c[3]).max(1) as f64
+    );
+    println!("不可靠: {}", c[3]);
+}
 
-    fn div_compute_low_bit(&self, mut result: Self, other: Self) -> Self {
-        // 奇数 / 奇数 -> 奇数
-        if (self.value & 1) != 0 && (self.mask & 1) != 0 {
-            result.value |= 1; // 设置最低位为1
-            result.mask &= !1;
+/// 真值表模式：`table <op> <bits> <out>`，把 bits 位下所有 tnum 对的结果写成二进制表
+#[cfg(feature = "serde")]
+fn run_table_mode(args: &[String]) {
+    let usage = "用法: table <op> <bits> <out>，bits 取 1..=16";
+    if args.len() != 3 {
+        println!("{}", usage);
+        return;
+    }
+    let (op, bits) = match (abstract_op(&args[0]), args[1].parse::<u32>()) {
+        (Some(op), Ok(bits @ 1..=16)) => (op, bits),
+        _ => {
+            println!("{}", usage);
+            return;
         }
-
-        let min_tz =
-            self.count_min_trailing_zeros() as i32 - other.count_max_trailing_zeros() as i32;
-        let max_tz =
-            self.count_max_trailing_zeros() as i32 - other.count_min_trailing_zeros() as i32;
-
-        if min_tz >= 0 {
-            result.value.clear_low_bits(min_tz as u32);
-            result.mask.clear_low_bits(min_tz as u32);
-
-            if min_tz == max_tz {
-                // 结果恰好有min_tz个尾随零
-                result.value |= 1u64 << min_tz; // 设置第min_tz位为1
-                result.mask &= !(1u64 << min_tz); // 清除第min_tz位的掩码
+    };
+
+    let header = table::Header {
+        kind: "truth-table".to_string(),
+        op: args[0].clone(),
+        width: bits,
+    };
+    let limit = 1u64 << bits;
+    let tnums: Vec<Tnum> = (0..limit)
+        .flat_map(|value| (0..limit).map(move |mask| Tnum::new(value, mask)))
+        .filter(|t| !t.is_bottom())
+        .collect();
+
+    let result = table::TableWriter::create(&args[2], &header).and_then(|mut w| {
+        for a in &tnums {
+            for b in &tnums {
+                w.write(&table::TruthEntry {
+                    a: *a,
+                    b: *b,
+                    result: op(a, *b),
+                })?;
             }
         }
-
-        // 检查结果是否为bottom
-        if result.is_bottom() {
-            return Self::top();
-        }
-
-        result
+        let count = w.count();
+        w.finish()?;
+        Ok(count)
+    });
+    match result {
+        Ok(count) => println!("已写入 {} 条记录到 {}", count, args[2]),
+        Err(e) => println!("写入失败: {}", e),
     }
+}
 
-    pub fn shl_const(&self, k: u64) -> Self {
-        // 处理特殊情况
-        if self.is_bottom() {
-            return *self;
+/// 读取二进制真值表并打印表头和记录数：`table-info <file>`
+#[cfg(feature = "serde")]
+fn run_table_info_mode(args: &[String]) {
+    let path = match args.first() {
+        Some(path) => path,
+        None => {
+            println!("用法: table-info <file>");
+            return;
         }
-        if self.is_top() {
-            return *self;
+    };
+    let reader = match table::TableReader::<_, table::TruthEntry>::open(path) {
+        Ok(reader) => reader,
+        Err(e) => {
+            println!("读取失败: {}", e);
+            return;
         }
+    };
+    let header = reader.header().clone();
+    let mut count = 0u64;
+    for entry in reader {
+        if let Err(e) = entry {
+            println!("读取失败: {}", e);
+            return;
+        }
+        count += 1;
+    }
+    println!(
+        "种类: {}，运算: {}，位宽: {}，记录数: {}",
+        header.kind, header.op, header.width, count
+    );
+}
+
+/// best-udiv 模式中枚举最优抽象的预算（具体值对个数）
+const BEST_UDIV_BUDGET: usize = 1 << 16;
+
+/// 比较 `best_udiv` 与其两个组成部分 `fast_divide`、`udiv` 的精度
+///
+/// 枚举 value|mask ≤ max 的被除数与至多 unknown_bits 个未知位的除数，
+/// 统计 meet 严格优于二者的情况，检查结果不为 bottom 且可靠，并与 [`Tnum::udiv_exact_small`]
+/// 给出的最优抽象比较。
+fn run_best_udiv_mode(max: u64, unknown_bits: u32) {
+    println!(
+        "=== best_udiv = fast_divide ⊓ udiv（被除数 ≤ {}，除数至多 {} 个未知位）===",
+        max, unknown_bits
+    );
+    let divisors = verify::rhs_space(&verify::CompareConfig {
+        value_max: max,
+        rhs_unknown_bits: unknown_bits,
+        ..Default::default()
+    });
+    // (总数, 与 fast_divide 相同, 与 udiv 相同, 严格优于二者, bottom, 不可靠, 最优, 参与最优比较)
+    let c = (0..=max)
+        .into_par_iter()
+        .map(|value_a| {
+            let mut n = [0u64; 8];
+            for mask_a in (0..=max).filter(|m| m & value_a == 0) {
+                let a = Tnum::new(value_a, mask_a);
+                for &b in &divisors {
+                    let (best, fast, udiv) = (a.best_udiv(b), a.fast_divide(b), a.udiv(b));
+                    n[0] += 1;
+                    n[1] += (best == fast) as u64;
+                    n[2] += (best == udiv) as u64;
+                    n[3] += (best != fast && best != udiv) as u64;
+                    n[4] += best.is_bottom() as u64;
+                    // 除数可能为 0 时 udiv 按约定放宽为 top，不与最优抽象比较
+                    if !b.contains_value(0) {
+                        if let Some(exact) = a.udiv_exact_small(b, BEST_UDIV_BUDGET) {
+                            n[6] += (best == exact) as u64;
+                            n[7] += 1;
+                        }
+                    }
+                    let mut sound = true;
+                    for_each_concrete(&a, |x| {
+                        for_each_concrete(&b, |y| {
+                            if y != 0 && !best.contains_value(x / y) {
+                                sound = false;
+                            }
+                        })
+                    });
+                    n[5] += !sound as u64;
+                }
+            }
+            n
+        })
+        .reduce(|| [0; 8], |p, q| std::array::from_fn(|i| p[i] + q[i]));
+    println!("总测试用例数: {}", c[0]);
+    println!(
+        "与 fast_divide 相同: {}，与 udiv 相同: {}，严格优于二者: {}",
+        c[1], c[2], c[3]
+    );
+    println!("bottom: {}，不可靠: {}", c[4], c[5]);
+    println!(
+        "除数非零时等于最优抽象: {} / {}（具体值对超过 {} 个的不计入）",
+        c[6], c[7], BEST_UDIV_BUDGET
+    );
+}
 
-        let width = 64; // 固定位宽
-        let shift = k % width as u64; // 确保移位值在范围内，模拟 wrapint(k, w)
+/// 打印两个运算的精度比较结果
+fn print_comparison(report: &verify::ComparisonReport, name_a: &str, name_b: &str) {
+    println!("=== 精度比较结果 ===");
+    println!("总测试用例数: {}", report.total);
+    println!(
+        "{} ⊆ {} ({} 更精确): {} ({:.2}%)",
+        name_a,
+        name_b,
+        name_a,
+        report.a_finer,
+        report.percent(report.a_finer)
+    );
+    println!(
+        "{} ⊆ {} ({} 更精确): {} ({:.2}%)",
+        name_b,
+        name_a,
+        name_b,
+        report.b_finer,
+        report.percent(report.b_finer)
+    );
+    println!(
+        "{} = {} (精度相同): {} ({:.2}%)",
+        name_a,
+        name_b,
+        report.equal,
+        report.percent(report.equal)
+    );
+    println!(
+        "不可比较的情况: {} ({:.2}%)",
+        report.incomparable,
+        report.percent(report.incomparable)
+    );
+    println!(
+        "平均 log2 基数差 (正数表示 {} 更紧): {:.4}",
+        name_a,
+        report.average_log2_gap()
+    );
+    println!(
+        "{} 更精确时平均紧 {:.4} 位，{} 更精确时平均紧 {:.4} 位",
+        name_a,
+        report.average_a_finer_gap(),
+        name_b,
+        report.average_b_finer_gap()
+    );
+    for (a, b) in &report.examples {
+        println!(
+            "  例: a=({:#x}, {:#x}), b=({:#x}, {:#x})",
+            a.value, a.mask, b.value, b.mask
+        );
+    }
+}
 
-        Self::new(
-            self.value.wrapping_shl(shift as u32),
-            self.mask.wrapping_shl(shift as u32),
-        )
+/// 按 fast_divide 对除数走的路径分类打印精度比较
+fn print_divisor_kinds(report: &verify::ComparisonReport, name_a: &str, name_b: &str) {
+    println!();
+    println!("=== 按除数类型 ===");
+    println!(
+        "{:<10} {:>8} {:>12} {:>18} {:>18} {:>10}",
+        "类型",
+        "除数",
+        "用例",
+        format!("{} 更精确", name_a),
+        format!("{} 更精确", name_b),
+        "不可比较"
+    );
+    for k in report.by_divisor_kind() {
+        let pct = |n: u64| n as f64 * 100.0 / k.total().max(1) as f64;
+        println!(
+            "{:<10} {:>8} {:>12} {:>9} ({:>5.2}%) {:>9} ({:>5.2}%) {:>10}",
+            k.kind.name(),
+            k.divisors,
+            k.total(),
+            k.a_finer,
+            pct(k.a_finer),
+            k.b_finer,
+            pct(k.b_finer),
+            k.incomparable
+        );
     }
+}
 
-    pub fn lshr_const(&self, k: u64) -> Self {
-        // 处理特殊情况
-        if self.is_bottom() {
-            return *self;
+/// 除法类运算：右操作数可能为 0 时只能返回 top，比较时跳过这样的右操作数
+fn is_division(name: &str) -> bool {
+    name.contains("div") || name.contains("rem")
+}
+
+/// 比较任意两个二元运算的精度：`compare-ops <op_a> <op_b> [max] [rhs_unknown_bits]`
+///
+/// 运算名与 [`abstract_op`] 相同；除法类运算跳过可能为 0 的右操作数。
+fn run_compare_ops_mode(args: &[String]) {
+    let usage = "用法: compare-ops <op_a> <op_b> [max] [rhs_unknown_bits]";
+    if args.len() < 2 {
+        println!("{}", usage);
+        return;
+    }
+    let (op_a, op_b) = match (abstract_op(&args[0]), abstract_op(&args[1])) {
+        (Some(a), Some(b)) => (a, b),
+        _ => {
+            println!("未知运算: {} 或 {}", args[0], args[1]);
+            return;
         }
-        if self.is_top() {
-            return *self;
+    };
+    let max = args.get(2).map(|s| parse_u64(s));
+    let bits = args.get(3).map(|s| s.parse::<u32>());
+    let (max, bits) = match (max.unwrap_or(Some(255)), bits.unwrap_or(Ok(0))) {
+        (Some(max), Ok(bits)) => (max, bits),
+        _ => {
+            println!("{}", usage);
+            return;
         }
+    };
+    let config = verify::CompareConfig {
+        value_max: max,
+        mask_max: max,
+        rhs_unknown_bits: bits,
+        skip_zero_rhs: is_division(&args[0]) || is_division(&args[1]),
+        ..Default::default()
+    };
+    println!(
+        "=== 比较 {} 与 {} 的精度（左操作数 value、mask ≤ {}，右操作数至多 {} 个未知位）===",
+        args[0], args[1], max, bits
+    );
+    let report = verify::compare_ops(|a, b| op_a(&a, b), |a, b| op_b(&a, b), &config);
+    print_comparison(&report, &args[0], &args[1]);
+}
 
-        let width = 64; // 固定位宽
-        let shift = k; // 确保移位值在范围内，模拟 wrapint(k, w)
+/// `compare` 模式的命令行参数
+#[derive(Debug, Parser)]
+#[command(name = "compare", about = "在枚举的操作数对上比较两个二元运算的精度")]
+struct CompareArgs {
+    /// 第一个运算，名称见 verify::BINARY_OPS
+    #[arg(long, default_value = "fast_divide")]
+    op_a: String,
+    /// 第二个运算
+    #[arg(long, default_value = "sdiv")]
+    op_b: String,
+    /// 左操作数 value 的上界，右操作数 value|mask 的上界
+    #[arg(long, default_value_t = 4096, value_parser = parse_u64_arg)]
+    value_max: u64,
+    /// 左操作数 mask 的上界
+    #[arg(long, default_value_t = 4096, value_parser = parse_u64_arg)]
+    mask_max: u64,
+    /// 右操作数至多有几个未知位，为 0 时只枚举常数
+    #[arg(long, default_value_t = 0)]
+    rhs_unknown_bits: u32,
+    /// 线程数，为 0 时使用 rayon 默认线程数
+    #[arg(long, default_value_t = 0)]
+    threads: usize,
+    /// 不在 stderr 上打印进度
+    #[arg(long)]
+    quiet: bool,
+    /// 按枚举顺序保留的不可比较例子个数
+    #[arg(long, default_value_t = 10)]
+    max_examples: usize,
+    /// 输出格式：给出 --out 时默认为 json，否则为 human
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+    /// 把 json 或 csv 格式的比较结果写入该文件，不给时写到 stdout
+    #[arg(long)]
+    out: Option<std::path::PathBuf>,
+    /// 定期把进度写入该断点文件
+    #[cfg(feature = "serde")]
+    #[arg(long)]
+    checkpoint: Option<std::path::PathBuf>,
+    /// 写断点的间隔秒数
+    #[cfg(feature = "serde")]
+    #[arg(long, default_value_t = 60)]
+    checkpoint_secs: u64,
+    /// 从断点文件继续，运算与枚举范围取自断点，之后继续写入同一文件
+    #[cfg(feature = "serde")]
+    #[arg(long, conflicts_with_all = ["op_a", "op_b", "value_max", "mask_max", "rhs_unknown_bits", "max_examples"])]
+    resume: Option<std::path::PathBuf>,
+}
 
-        Self::new(
-            self.value.wrapping_shr(shift as u32),
-            self.mask.wrapping_shr(shift as u32),
-        )
-    }
+/// 比较结果的输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum OutputFormat {
+    /// 给人看的摘要
+    Human,
+    /// 完整的 [`verify::ComparisonReport`]，包括每个右操作数的计数和例子
+    Json,
+    /// 每个右操作数一行，见 [`verify::ComparisonReport::write_csv`]
+    Csv,
+}
 
-    pub fn ashr_const(&self, k: u64) -> Self {
-        // 处理特殊情况
-        if self.is_bottom() {
-            return *self;
-        }
-        if self.is_top() {
-            return *self;
-        }
-
-        let width = 64; // 固定位宽
-        let shift = k % width as u64; // 确保移位值在范围内，模拟 wrapint(k, w)
-
-        // 获取符号位
-        let vsig = (self.value >> 63) & 1 == 1;
-        let msig = (self.mask >> 63) & 1 == 1;
-
-        // 根据符号位选择不同的移位策略
-        if !vsig && !msig {
-            // 都是非负数，使用逻辑右移
-            Self::new(
-                self.value.wrapping_shr(shift as u32),
-                self.mask.wrapping_shr(shift as u32),
-            )
-        } else if vsig && !msig {
-            // value 是负数但 mask 非负
-            Self::new(
-                ((self.value as i64).wrapping_shr(shift as u32)) as u64,
-                self.mask.wrapping_shr(shift as u32),
-            )
-        } else {
-            // 其他情况
-            Self::new(
-                self.value.wrapping_shr(shift as u32),
-                ((self.mask as i64).wrapping_shr(shift as u32)) as u64,
-            )
+/// 在 stderr 上定期打印枚举进度：完成比例、速度与预计剩余时间
+struct ProgressLine {
+    total: u64,
+    /// 本次运行开始时已完成的个数（从断点继续时不为 0）
+    initial: u64,
+    start: std::time::Instant,
+    last: std::time::Instant,
+}
+
+impl ProgressLine {
+    /// 两次打印之间至少间隔的秒数
+    const INTERVAL_SECS: u64 = 2;
+
+    fn new(total: u64, initial: u64) -> Self {
+        let now = std::time::Instant::now();
+        Self {
+            total,
+            initial,
+            start: now,
+            last: now,
         }
     }
 
-    pub fn le(&self, other: &Tnum) -> bool {
-        // 修改参数类型为 &Tnum
-        if other.is_top() || self.is_bottom() {
-            return true;
-        } else if other.is_bottom() || self.is_top() {
-            return false;
-        } else if self.value == other.value && self.mask == other.mask {
-            return true;
-        } else if (self.mask & (!other.mask)) != 0 {
-            // self[i] 未知但 other[i] 已知
-            return false;
-        } else {
-            return (self.value & (!other.mask)) == other.value;
+    fn update(&mut self, done: u64, finished: bool) {
+        if !finished && self.last.elapsed().as_secs() < Self::INTERVAL_SECS {
+            return;
         }
+        self.last = std::time::Instant::now();
+        let secs = self.start.elapsed().as_secs_f64();
+        let rate = (done - self.initial) as f64 / secs.max(1e-9);
+        let left = if rate > 0.0 {
+            (self.total.saturating_sub(done) as f64 / rate) as u64
+        } else {
+            0
+        };
+        eprintln!(
+            "进度: {} / {} ({:.2}%)，{:.0} 对/秒，已用 {}，预计剩余 {}",
+            done,
+            self.total,
+            done as f64 * 100.0 / self.total.max(1) as f64,
+            rate,
+            format_secs(secs as u64),
+            format_secs(left)
+        );
     }
+}
 
-    /// 等价关系判断（==）
-    pub fn eq(&self, other: &Tnum) -> bool {
-        // 修改参数类型为 &Tnum
-        self.le(other) && other.le(self)
-    }
+fn format_secs(secs: u64) -> String {
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
 
-    pub fn or(&self, other: &Tnum) -> Tnum {
-        if self.le(other) {
-            return *other;
-        } else if other.le(self) {
-            return *self;
-        } else {
-            let mu = self.mask | other.mask;
-            let this_know = self.value & (!mu);
-            let x_know = other.value & (!mu);
-            let disagree = this_know ^ x_know;
+fn parse_u64_arg(s: &str) -> Result<u64, String> {
+    parse_u64(s).ok_or_else(|| format!("无效的数: {}", s))
+}
 
-            Tnum::new(this_know & x_know, mu | disagree)
+/// 比较模式：`compare [--op-a <op>] [--op-b <op>] [--value-max N] [--mask-max N]
+/// [--rhs-unknown-bits K] [--threads T] [--quiet] [--max-examples N] [--format human|json|csv]
+/// [--out <file>] [--checkpoint <file>] [--resume <file>]`，默认比较 fast_divide 与 sdiv
+///
+/// 按 value_a 并行枚举，每行的结果按 value_a 顺序合并，输出与线程数无关。
+/// 从断点继续时比较结果与一次跑完相同，转移函数统计只包含本次运行的部分。
+fn run_compare_mode(args: &[String]) {
+    let cli = match CompareArgs::try_parse_from(args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            return;
         }
-    }
-
-    pub fn and(&self, other: &Tnum) -> Tnum {
-        if self.le(other) {
-            return *self;
-        } else if other.le(self) {
-            return *other;
+    };
+    let format = match (cli.format, &cli.out) {
+        (Some(OutputFormat::Human), Some(_)) => {
+            println!("human 格式只输出到 stdout，--out 需要 json 或 csv");
+            return;
         }
-
-        let mu1 = self.mask & other.mask;
-        let mu2 = self.mask | other.mask;
-        let this_known_v = self.value & (!mu2);
-        let x_known_v = other.value & (!mu2);
-        let disagree = this_known_v ^ x_known_v;
-
-        if disagree != 0 {
-            return Tnum::bottom();
+        (Some(format), _) => format,
+        (None, Some(_)) => OutputFormat::Json,
+        (None, None) => OutputFormat::Human,
+    };
+    #[cfg(not(feature = "serde"))]
+    if format == OutputFormat::Json {
+        println!("json 输出需要启用 serde 特性");
+        return;
+    }
+    let human = format == OutputFormat::Human;
+    let mut checkpoint = verify::Checkpoint {
+        config: verify::CompareConfig {
+            value_max: cli.value_max,
+            mask_max: cli.mask_max,
+            rhs_unknown_bits: cli.rhs_unknown_bits,
+            skip_zero_rhs: is_division(&cli.op_a) || is_division(&cli.op_b),
+            max_examples: cli.max_examples,
+            ..Default::default()
+        },
+        op_a: cli.op_a.clone(),
+        op_b: cli.op_b.clone(),
+        progress: verify::CompareProgress::default(),
+    };
+    #[cfg(feature = "serde")]
+    if let Some(path) = &cli.resume {
+        match verify::Checkpoint::load(path) {
+            Ok(c) => checkpoint = c,
+            Err(e) => {
+                println!("读取断点失败: {}", e);
+                return;
+            }
         }
-
-        Tnum::new((self.value | other.value) & (!mu1), mu1)
     }
-}
-
-pub fn rem_get_low_bits(lhs: &Tnum, rhs: &Tnum) -> Tnum {
-    let w = 64u8; // 固定位宽为64
-
-    if !rhs.is_zero() && (rhs.value & 1) == 0 && (rhs.mask & 1) == 0 {
-        let qzero = rhs.count_min_trailing_zeros();
-
-        if qzero == 0 {
-            return Tnum::top();
+    checkpoint.config.threads = cli.threads;
+    let (name_a, name_b) = (checkpoint.op_a.clone(), checkpoint.op_b.clone());
+    let config = checkpoint.config;
+    let (op_a, op_b) = match (abstract_op(&name_a), abstract_op(&name_b)) {
+        (Some(a), Some(b)) => (a, b),
+        _ => {
+            println!("未知运算: {} 或 {}", name_a, name_b);
+            return;
         }
-
-        /// mask源代码看起来有点问题？
-        let mut mask = if qzero > 1 { (1u64 << qzero) - 1 } else { 0u64 };
-        // mask = 0xFFFFFFFFFFFFFFFF;
-
-        let res_value = lhs.value & mask;
-        let res_mask = lhs.mask & mask;
-        let res = Tnum::new(res_value, res_mask);
-
-        return res;
+    };
+    if human {
+        println!("=== 比较 {} 与 {} 的精度 ===", name_a, name_b);
+        println!("比较思路：");
+        println!(
+            "1. 枚举左操作数 Tnum_a(value_a, mask_a)，value_a ≤ {}，mask_a ≤ {}，value_a & mask_a == 0",
+            config.value_max, config.mask_max
+        );
+        println!(
+            "2. 枚举右操作数 Tnum_b(value_b, mask_b)，至多 {} 个未知位",
+            config.rhs_unknown_bits
+        );
+        println!("3. 分别计算 {} 和 {} 的结果", name_a, name_b);
+        println!("4. 使用 le 和 eq 函数比较两种算法的精度关系");
+        println!();
+        if checkpoint.progress.next_value > 0 || checkpoint.progress.done {
+            println!("从 value_a = {} 继续", checkpoint.progress.next_value);
+        }
+        println!("右操作数个数: {}", verify::rhs_space(&config).len());
     }
 
-    Tnum::top()
-}
-
-/// 比较 fast_divide 与 sdiv 的精度
-fn compare_fast_divide_with_sdiv() {
-    println!("=== 比较 fast_divide 与 sdiv 的精度 ===");
-    println!("比较思路：");
-    println!("1. 枚举被除数 Tnum_a(value_a, mask_a)，其中 value_a & mask_a == 0");
-    println!("2. 枚举除数 Tnum_b(value_b, 0) - 常数");
-    println!("3. 分别计算 fast_divide 和 sdiv 的结果");
-    println!("4. 使用 le 和 eq 函数比较两种算法的精度关系");
-    println!();
+    stats::reset();
+    stats::enable();
 
-    let mut total_cases = 0;
-    let mut fast_le_sdiv = 0;    // fast_divide ⊆ sdiv (fast_divide 更精确)
-    let mut sdiv_le_fast = 0;    // sdiv ⊆ fast_divide (sdiv 更精确)
-    let mut equal_cases = 0;     // fast_divide = sdiv (精度相同)
-    let mut incomparable = 0;    // 不可比较 (两个结果有重叠但不包含)
-
-    // 枚举被除数 Tnum (缩小范围以便观察)
-    for value_a in 0..=4096u64 {
-        for mask_a in 0..=4096u64 {
-            // 约束：value_a & mask_a == 0
-            if value_a & mask_a != 0 {
-                continue;
+    #[cfg(feature = "serde")]
+    let save_to = cli.checkpoint.clone().or_else(|| cli.resume.clone());
+    #[cfg(feature = "serde")]
+    let mut last_save = std::time::Instant::now();
+    let mut progress_line = (!cli.quiet).then(|| {
+        ProgressLine::new(
+            verify::pair_count(&config),
+            checkpoint.progress.report.total,
+        )
+    });
+    let report = verify::compare_ops_from(
+        |a, b| op_a(&a, b),
+        |a, b| op_b(&a, b),
+        &config,
+        checkpoint.progress.clone(),
+        |progress| {
+            if let Some(line) = &mut progress_line {
+                line.update(progress.report.total, progress.done);
             }
-
-            // 枚举除数（常数）
-            for value_b in 0..=4096u64 { // 除数范围缩小以便观察
-                let tnum_a = Tnum::new(value_a, mask_a);
-                let tnum_b = Tnum::const_val(value_b);
-                
-                // 计算 fast_divide 和 sdiv 结果
-                let fast_result = tnum_a.fast_divide(tnum_b);
-                let sdiv_result = tnum_a.sdiv(tnum_b);
-                
-                total_cases += 1;
-                
-                // 使用 le 和 eq 函数进行比较
-                let fast_le_sdiv_bool = fast_result.le(&sdiv_result);
-                let sdiv_le_fast_bool = sdiv_result.le(&fast_result);
-                let equal_bool = fast_result.eq(&sdiv_result);
-                
-                if equal_bool {
-                    equal_cases += 1;
-                } else if fast_le_sdiv_bool && !sdiv_le_fast_bool {
-                    fast_le_sdiv += 1;
-                } else if sdiv_le_fast_bool && !fast_le_sdiv_bool {
-                    sdiv_le_fast += 1;
-                } else if fast_le_sdiv_bool && sdiv_le_fast_bool {
-                    // 这种情况应该就是 equal_bool，但以防万一
-                    equal_cases += 1;
-                } else {
-                    incomparable += 1;
+            #[cfg(feature = "serde")]
+            if let Some(path) = &save_to {
+                let due = last_save.elapsed().as_secs() >= cli.checkpoint_secs;
+                if due || progress.done {
+                    checkpoint.progress = progress.clone();
+                    if let Err(e) = checkpoint.save(path) {
+                        eprintln!("写入断点失败: {}", e);
+                    }
+                    last_save = std::time::Instant::now();
                 }
             }
+        },
+    );
+    let snapshot = stats::snapshot();
+    stats::disable();
+    if human {
+        print_comparison(&report, &name_a, &name_b);
+        if is_division(&name_a) || is_division(&name_b) {
+            print_divisor_kinds(&report, &name_a, &name_b);
         }
+        println!();
+        println!("=== 转移函数统计 ===");
+        for (counter, count) in snapshot.iter() {
+            println!("{}: {}", counter.name(), count);
+        }
+        return;
     }
 
-    println!("=== 精度比较结果 ===");
-    println!("总测试用例数: {}", total_cases);
-    println!("fast_divide ⊆ sdiv (fast_divide 更精确): {} ({:.2}%)", 
-             fast_le_sdiv, (fast_le_sdiv as f64 / total_cases as f64) * 100.0);
-    println!("sdiv ⊆ fast_divide (sdiv 更精确): {} ({:.2}%)", 
-             sdiv_le_fast, (sdiv_le_fast as f64 / total_cases as f64) * 100.0);
-    println!("fast_divide = sdiv (精度相同): {} ({:.2}%)", 
-             equal_cases, (equal_cases as f64 / total_cases as f64) * 100.0);
-    println!("不可比较的情况: {} ({:.2}%)", 
-             incomparable, (incomparable as f64 / total_cases as f64) * 100.0);
+    let sink: Box<dyn std::io::Write> = match &cli.out {
+        Some(out) => match std::fs::File::create(out) {
+            Ok(file) => Box::new(std::io::BufWriter::new(file)),
+            Err(e) => {
+                println!("写入失败: {}", e);
+                return;
+            }
+        },
+        None => Box::new(std::io::stdout().lock()),
+    };
+    let written = match format {
+        #[cfg(feature = "serde")]
+        OutputFormat::Json => serde_json::to_writer_pretty(sink, &report).map_err(Into::into),
+        _ => report.write_csv(sink),
+    };
+    match (written, &cli.out) {
+        (Ok(()), Some(out)) => eprintln!("已写入 {}", out.display()),
+        (Ok(()), None) => {}
+        (Err(e), _) => eprintln!("写入失败: {}", e),
+    }
 }
 
+/// main 分派的全部模式，未知模式时列出
+const MODES: &[&str] = &[
+    "witness", "synth", "pipeline", "known-bits", "kernel-diff", "smt", "smt-pair", "smt-refine",
+    "smt-divider", "emit-smt", "verify-pqr", "verify-interp", "verify-regval", "analyze", "repl",
+    "emit-dot", "frames", "verify-width", "exhaustive", "verify-unary", "sampled", "best-udiv",
+    "exact", "precision", "verify-sound", "verify-monotone", "compare-ops", "table", "table-info",
+    "regress", "precision-search", "compare",
+];
+
 fn main() {
-    compare_fast_divide_with_sdiv();
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("witness") => run_witness_mode(&args[2..]),
+        #[cfg(feature = "z3")]
+        Some("synth") => synth::run_synth_mode(&args[2..]),
+        #[cfg(not(feature = "z3"))]
+        Some("synth") => println!("synth 需要启用 z3 特性"),
+        Some("pipeline") => run_pipeline_mode(&args[2..]),
+        Some("known-bits") => run_known_bits_mode(&args[2..]),
+        #[cfg(feature = "kernel-diff")]
+        Some("kernel-diff") => run_kernel_diff_mode(&args[2..]),
+        #[cfg(not(feature = "kernel-diff"))]
+        Some("kernel-diff") => println!("kernel-diff 需要启用 kernel-diff 特性并设置 KERNEL_SRC"),
+        Some("smt") => run_smt_mode(&args[2..]),
+        Some("smt-pair") => run_smt_pair_mode(&args[2..]),
+        Some("smt-refine") => run_smt_refine_mode(&args[2..]),
+        Some("smt-divider") => run_smt_divider_mode(&args[2..]),
+        Some("emit-smt") => run_emit_smt_mode(&args[2..]),
+        Some("verify-pqr") => match args.get(2).map(|s| s.parse::<u32>()).unwrap_or(Ok(4)) {
+            Ok(bits @ 1..=6) => pqr::verify_soundness(bits),
+            _ => println!("用法: verify-pqr [bits]，bits 取 1..=6"),
+        },
+        Some("verify-interp") => {
+            let profile = args
+                .get(3)
+                .map_or(Some(interp::IsaProfile::LinuxEbpf), |s| {
+                    interp::IsaProfile::from_name(s)
+                });
+            match (
+                args.get(2).map(|s| s.parse::<u32>()).unwrap_or(Ok(4)),
+                profile,
+            ) {
+                (Ok(bits @ 1..=6), Some(profile)) => {
+                    interp::verify_soundness(profile, bits);
+                    interp::verify_assume(bits);
+                    interp::verify_atomic(bits);
+                }
+                _ => println!("用法: verify-interp [bits] [linux|sbf-v1|sbf-v2]，bits 取 1..=6"),
+            }
+        }
+        Some("verify-regval") => match args.get(2).map(|s| s.parse::<usize>()).unwrap_or(Ok(128)) {
+            Ok(samples @ 1..=1024) => regval::verify_soundness(samples),
+            _ => println!("用法: verify-regval [samples]，samples 取 1..=1024"),
+        },
+        Some("analyze") => run_analyze_mode(&args[2..]),
+        Some("repl") => repl::run_repl_mode(),
+        Some("emit-dot") => run_emit_dot_mode(&args[2..]),
+        Some("frames") => match args.get(2).map(String::as_str) {
+            Some("v1") => frame::run_frames_mode(frame::SbpfVersion::V1 { gaps: false }),
+            Some("v1-gaps") => frame::run_frames_mode(frame::SbpfVersion::V1 { gaps: true }),
+            Some("v2") | None => frame::run_frames_mode(frame::SbpfVersion::V2),
+            _ => println!("用法: frames [v1|v1-gaps|v2]"),
+        },
+        Some("verify-width") => {
+            let op = args.get(3).map(String::as_str).unwrap_or("add");
+            let max_unknown = args.get(4).map(|s| s.parse::<u32>());
+            match (args.get(2).map(String::as_str), max_unknown) {
+                (Some("8"), None) => verify_width::<u8>(op, 8, 0),
+                (Some("8"), Some(Ok(k))) => verify_width::<u8>(op, k, 0),
+                (Some("16"), None) => verify_width::<u16>(op, 4, 2048),
+                (Some("16"), Some(Ok(k))) => verify_width::<u16>(op, k, 2048),
+                (Some("32"), None) => verify_width::<u32>(op, 4, 1024),
+                (Some("32"), Some(Ok(k))) => verify_width::<u32>(op, k, 1024),
+                (Some("128"), None) => verify_width::<u128>(op, 4, 512),
+                (Some("128"), Some(Ok(k))) => verify_width::<u128>(op, k, 512),
+                _ => println!("用法: verify-width <8|16|32|128> <op> [max_unknown]"),
+            }
+        }
+        Some("exhaustive") => run_exhaustive_mode(&args[2..]),
+        Some("verify-unary") => {
+            let op = args.get(2).map(String::as_str).unwrap_or("neg");
+            if let Some((abs, con)) = unary_op::<u8>(op) {
+                verify_unary(op, abs, con, &width_tnums::<u8>(8, 0));
+            } else if let Some((abs, con)) = unary_op_u64(op) {
+                verify_unary(op, abs, con, &width_tnums::<u64>(4, 4096));
+            } else {
+                println!("未知运算: {}", op);
+            }
+        }
+        Some("sampled") => run_sampled_mode(&args[2..]),
+        Some("best-udiv") => {
+            let max = args.get(2).map(|s| parse_u64(s));
+            let unknown_bits = args.get(3).map(|s| s.parse::<u32>());
+            match (max.unwrap_or(Some(255)), unknown_bits.unwrap_or(Ok(2))) {
+                (Some(max), Ok(bits)) => run_best_udiv_mode(max, bits),
+                _ => println!("用法: best-udiv [max] [divisor_unknown_bits]"),
+            }
+        }
+        Some("exact") => run_exact_mode(&args[2..]),
+        Some("precision") => run_precision_mode(&args[2..]),
+        Some("verify-sound") => run_sound_mode(&args[2..]),
+        Some("verify-monotone") => run_monotone_mode(&args[2..]),
+        Some("compare-ops") => run_compare_ops_mode(&args[2..]),
+        #[cfg(feature = "serde")]
+        Some("table") => run_table_mode(&args[2..]),
+        #[cfg(feature = "serde")]
+        Some("table-info") => run_table_info_mode(&args[2..]),
+        #[cfg(feature = "serde")]
+        Some("regress") => run_regress_mode(&args[2..]),
+        #[cfg(feature = "serde")]
+        Some("precision-search") => run_precision_search_mode(&args[2..]),
+        #[cfg(not(feature = "serde"))]
+        Some("table" | "table-info" | "regress" | "precision-search") => {
+            println!("真值表需要启用 serde 特性")
+        }
+        Some("compare") => run_compare_mode(&args[1..]),
+        None => run_compare_mode(&[]),
+        Some(mode) => {
+            eprintln!("未知的模式 {}", mode);
+            eprintln!("用法: tnum_z3_verification [模式] [参数...]，不带模式时运行默认的 compare");
+            eprintln!("可用的模式: {}", MODES.join(" "));
+            std::process::exit(2);
+        }
+    }
 }
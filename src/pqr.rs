@@ -0,0 +1,234 @@
+//! sBPF v2 的 PQR 指令类（乘法、高位乘法、除法与取余）
+//!
+//! 操作码布局：低 3 位为类 `0x06`，`0x08` 位选择寄存器源操作数，`0x10` 位选择 64 位形式，
+//! 高 3 位为运算。UHMUL / SHMUL 只有 64 位形式。
+//!
+//! [`transfer`] 给定目的寄存器和源操作数的 tnum，返回目的寄存器的新值；
+//! [`crate::driver`] 在 sBPF v2 下对 PQR 类的指令调用它。
+use crate::tnum::{DivZeroPolicy, Tnum};
+use crate::verify::{Counterexample, SoundnessReport};
+
+pub const BPF_PQR: u8 = 0x06;
+const BPF_X: u8 = 0x08;
+const BPF_B: u8 = 0x10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PqrOp {
+    Lmul,
+    Uhmul,
+    Shmul,
+    Udiv,
+    Urem,
+    Sdiv,
+    Srem,
+}
+
+impl PqrOp {
+    pub fn name(self) -> &'static str {
+        match self {
+            PqrOp::Lmul => "lmul",
+            PqrOp::Uhmul => "uhmul",
+            PqrOp::Shmul => "shmul",
+            PqrOp::Udiv => "udiv",
+            PqrOp::Urem => "urem",
+            PqrOp::Sdiv => "sdiv",
+            PqrOp::Srem => "srem",
+        }
+    }
+}
+
+/// 解码后的 PQR 指令
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PqrInsn {
+    pub op: PqrOp,
+    /// 64 位形式；否则为 32 位形式
+    pub is64: bool,
+    /// 源操作数为寄存器；否则为立即数
+    pub reg_src: bool,
+}
+
+impl PqrInsn {
+    pub fn mnemonic(&self) -> String {
+        format!(
+            "{}{}_{}",
+            self.op.name(),
+            if self.is64 { 64 } else { 32 },
+            if self.reg_src { "reg" } else { "imm" }
+        )
+    }
+}
+
+/// 解码操作码，不属于 PQR 类或不存在的组合返回 `None`
+pub fn decode(opcode: u8) -> Option<PqrInsn> {
+    if opcode & 0x07 != BPF_PQR {
+        return None;
+    }
+    let is64 = opcode & BPF_B != 0;
+    let op = match opcode & 0xe0 {
+        0x80 => PqrOp::Lmul,
+        0x20 if is64 => PqrOp::Uhmul,
+        0xa0 if is64 => PqrOp::Shmul,
+        0x40 => PqrOp::Udiv,
+        0x60 => PqrOp::Urem,
+        0xc0 => PqrOp::Sdiv,
+        0xe0 => PqrOp::Srem,
+        _ => return None,
+    };
+    Some(PqrInsn {
+        op,
+        is64,
+        reg_src: opcode & BPF_X != 0,
+    })
+}
+
+/// 立即数源操作数：32 位立即数符号扩展到 64 位
+pub fn imm_operand(imm: i32) -> Tnum {
+    Tnum::const_val(imm as i64 as u64)
+}
+
+fn low32(t: Tnum) -> Tnum {
+    t.zext(32)
+}
+
+fn sext32(t: Tnum) -> Tnum {
+    t.sign_extend(32)
+}
+
+/// PQR 指令的转移函数
+///
+/// 32 位形式只看操作数的低 32 位；无符号运算的结果零扩展，LMUL32 与有符号运算的结果符号扩展。
+/// 除数为 0 或 SDIV 溢出在 sBPF 中是运行时错误，这些输入不产生结果，除数为 0 按
+/// [`DivZeroPolicy::Bottom`] 处理。
+pub fn transfer(insn: PqrInsn, dst: Tnum, src: Tnum) -> Tnum {
+    let abort = DivZeroPolicy::Bottom;
+    if insn.is64 {
+        match insn.op {
+            PqrOp::Lmul => dst.mul(src),
+            PqrOp::Uhmul => dst.umulh(src),
+            PqrOp::Shmul => dst.smulh(src),
+            PqrOp::Udiv => dst.udiv_with(src, abort),
+            PqrOp::Urem => dst.urem_with(src, abort),
+            PqrOp::Sdiv => dst.sdiv_with(src, abort),
+            PqrOp::Srem => dst.srem_with(src, abort),
+        }
+    } else {
+        match insn.op {
+            PqrOp::Lmul => sext32(dst.mul(src)),
+            PqrOp::Udiv => low32(low32(dst).udiv_with(low32(src), abort)),
+            PqrOp::Urem => low32(low32(dst).urem_with(low32(src), abort)),
+            PqrOp::Sdiv => sext32(sext32(dst).sdiv_with(sext32(src), abort)),
+            PqrOp::Srem => sext32(sext32(dst).srem_with(sext32(src), abort)),
+            PqrOp::Uhmul | PqrOp::Shmul => unreachable!("高位乘法只有 64 位形式"),
+        }
+    }
+}
+
+/// PQR 指令的具体语义，运行时错误返回 `None`
+pub fn concrete(insn: PqrInsn, dst: u64, src: u64) -> Option<u64> {
+    if insn.is64 {
+        let (a, b) = (dst as i64, src as i64);
+        match insn.op {
+            PqrOp::Lmul => Some(dst.wrapping_mul(src)),
+            PqrOp::Uhmul => Some(((dst as u128 * src as u128) >> 64) as u64),
+            PqrOp::Shmul => Some(((a as i128 * b as i128) >> 64) as u64),
+            PqrOp::Udiv => dst.checked_div(src),
+            PqrOp::Urem => dst.checked_rem(src),
+            PqrOp::Sdiv => a.checked_div(b).map(|q| q as u64),
+            PqrOp::Srem => a.checked_rem(b).map(|r| r as u64),
+        }
+    } else {
+        let (x, y) = (dst as u32, src as u32);
+        let (a, b) = (x as i32, y as i32);
+        match insn.op {
+            PqrOp::Lmul => Some(a.wrapping_mul(b) as i64 as u64),
+            PqrOp::Udiv => x.checked_div(y).map(u64::from),
+            PqrOp::Urem => x.checked_rem(y).map(u64::from),
+            PqrOp::Sdiv => a.checked_div(b).map(|q| q as i64 as u64),
+            PqrOp::Srem => a.checked_rem(b).map(|r| r as i64 as u64),
+            PqrOp::Uhmul | PqrOp::Shmul => None,
+        }
+    }
+}
+
+fn sext_value(x: u64, bits: u32) -> u64 {
+    let shift = 64 - bits;
+    (((x << shift) as i64) >> shift) as u64
+}
+
+/// 在缩减位宽下穷举验证一条 PQR 指令的可靠性
+///
+/// 枚举所有 bits 位的 tnum 对并符号扩展到 64 位，这样负数、符号未知和 32 位截断都能覆盖到；
+/// 要求每个有定义的具体结果都落在抽象结果中。立即数形式的源操作数只取常数。
+pub fn check_soundness(insn: PqrInsn, bits: u32) -> SoundnessReport {
+    let limit = 1u64 << bits;
+    let mut tnums = Vec::new();
+    for value in 0..limit {
+        for mask in 0..limit {
+            if value & mask == 0 {
+                tnums.push(Tnum::new(value, mask));
+            }
+        }
+    }
+    let consts: Vec<Tnum> = (0..limit).map(|v| Tnum::new(v, 0)).collect();
+    let srcs = if insn.reg_src { &tnums } else { &consts };
+    let mut report = SoundnessReport::default();
+    for a in &tnums {
+        for b in srcs {
+            let dst = a.sign_extend(bits as u8);
+            let src = if insn.reg_src {
+                b.sign_extend(bits as u8)
+            } else {
+                imm_operand(sext_value(b.value, bits) as i32)
+            };
+            let res = transfer(insn, dst, src);
+            report.pairs += 1;
+            crate::tnum::for_each_concrete(a, |x| {
+                crate::tnum::for_each_concrete(b, |y| {
+                    let (x, y) = (sext_value(x, bits), sext_value(y, bits));
+                    if let Some(r) = concrete(insn, x, y) {
+                        report.concrete += 1;
+                        if !res.contains_value(r) {
+                            report.unsound += 1;
+                            report.example.get_or_insert(Counterexample {
+                                a: dst,
+                                b: src,
+                                x,
+                                y,
+                                r,
+                                result: res,
+                            });
+                        }
+                    }
+                })
+            });
+        }
+    }
+    report
+}
+
+/// 在缩减位宽下逐个操作码验证转移函数的可靠性，见 [`check_soundness`]
+pub fn verify_soundness(bits: u32) {
+    println!(
+        "=== 穷举验证 sBPF v2 PQR 转移函数（{} 位，符号扩展）===",
+        bits
+    );
+    for opcode in 0..=u8::MAX {
+        let Some(insn) = decode(opcode) else {
+            continue;
+        };
+        let report = check_soundness(insn, bits);
+        println!(
+            "0x{:02x} {:<12} 具体结果 {:>8}，不可靠 {:>8}",
+            opcode,
+            insn.mnemonic(),
+            report.concrete,
+            report.unsound
+        );
+        if let Some(ce) = report.example {
+            println!(
+                "    反例: 0x{:x}, 0x{:x} -> 0x{:x} 不在 (0x{:x}, 0x{:x}) 中",
+                ce.x, ce.y, ce.r, ce.result.value, ce.result.mask
+            );
+        }
+    }
+}
@@ -0,0 +1,510 @@
+//! Python 绑定（需要 `pyo3` 特性）
+//!
+//! 导出名为 `fastdivide_z3` 的 Python 模块：`Tnum` 类与全部转移函数，[`verify`] 中的可靠性、
+//! 单调性检查与精度比较，以及 [`driver`] 的字节码数据流分析；各种报告以只读对象返回。
+//! 用 maturin 构建（pyproject.toml 中已配置模块名与特性）：`maturin develop --release`。
+//!
+//! ```python
+//! from fastdivide_z3 import Tnum, compare
+//! a = Tnum.parse("0b1x0x")
+//! print(a.fast_divide(Tnum.const(3)))
+//! r = compare("fast_divide", "udiv", value_max=255, mask_max=255)
+//! print(r.a_finer, r.b_finer, r.average_log2_gap)
+//! ```
+//!
+//! 二元运算名见 [`verify::BINARY_OPS`]，既可以 `a.apply("udiv", b)`，也可以直接 `a.udiv(b)`；
+//! 一元运算见 [`UNARY_OPS`]。枚举与分析期间释放 GIL。
+// pyo3 0.22 的宏展开会对 PyResult 做一次 `.into()`
+#![allow(clippy::useless_conversion)]
+use pyo3::exceptions::{PyAttributeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyCFunction, PyDict, PyTuple};
+
+use crate::driver;
+use crate::interp::{IsaProfile, RegState};
+use crate::regval;
+use crate::tnum::Tnum;
+use crate::verify::{self, CompareConfig};
+
+type UnaryOp = fn(&Tnum) -> Tnum;
+
+/// 可用的一元运算
+pub const UNARY_OPS: &[(&str, UnaryOp)] = &[
+    ("not", Tnum::not),
+    ("neg", Tnum::neg),
+    ("popcount", Tnum::popcount),
+    ("bswap16", Tnum::bswap16),
+    ("bswap32", Tnum::bswap32),
+    ("bswap64", Tnum::bswap64),
+];
+
+fn binary_op(name: &str) -> PyResult<&'static verify::BinaryOp> {
+    verify::binary_op(name).ok_or_else(|| PyValueError::new_err(format!("未知的二元运算 {}", name)))
+}
+
+fn unary_op(name: &str) -> Option<UnaryOp> {
+    UNARY_OPS.iter().find(|(n, _)| *n == name).map(|op| op.1)
+}
+
+/// Python 中的 `Tnum`
+#[pyclass(name = "Tnum", module = "fastdivide_z3", frozen, eq, hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PyTnum(pub Tnum);
+
+impl From<Tnum> for PyTnum {
+    fn from(t: Tnum) -> Self {
+        Self(t)
+    }
+}
+
+#[pymethods]
+impl PyTnum {
+    /// value 与 mask 有重叠的位时抛出 ValueError
+    #[new]
+    fn new(value: u64, mask: u64) -> PyResult<Self> {
+        if value & mask != 0 {
+            return Err(PyValueError::new_err("value 与 mask 有重叠的位"));
+        }
+        Ok(Tnum::new(value, mask).into())
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "const")]
+    fn const_val(value: u64) -> Self {
+        Tnum::const_val(value).into()
+    }
+
+    #[staticmethod]
+    fn top() -> Self {
+        Tnum::top().into()
+    }
+
+    #[staticmethod]
+    fn bottom() -> Self {
+        Tnum::bottom().into()
+    }
+
+    /// 包含 [min, max] 的最小 tnum
+    #[staticmethod]
+    fn from_range(min: u64, max: u64) -> Self {
+        Tnum::from_range(min, max).into()
+    }
+
+    /// 解析 x 记法，如 `0b1x0x`
+    #[staticmethod]
+    fn parse(s: &str) -> PyResult<Self> {
+        s.parse::<Tnum>()
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(format!("无法解析的 x 记法 {}: {:?}", s, e)))
+    }
+
+    #[getter]
+    fn value(&self) -> u64 {
+        self.0.value
+    }
+
+    #[getter]
+    fn mask(&self) -> u64 {
+        self.0.mask
+    }
+
+    fn is_bottom(&self) -> bool {
+        self.0.is_bottom()
+    }
+
+    fn is_singleton(&self) -> bool {
+        self.0.is_singleton()
+    }
+
+    /// γ(self) 中具体值的个数
+    fn cardinality(&self) -> u128 {
+        self.0.cardinality()
+    }
+
+    fn contains(&self, x: u64) -> bool {
+        self.0.contains_value(x)
+    }
+
+    /// 格上的偏序 self ⊑ other
+    fn le(&self, other: PyTnum) -> bool {
+        self.0.le(&other.0)
+    }
+
+    fn join(&self, other: PyTnum) -> Self {
+        self.0.or(&other.0).into()
+    }
+
+    fn meet(&self, other: PyTnum) -> Self {
+        self.0.meet(&other.0).into()
+    }
+
+    /// 按名字调用转移函数，other 为 None 时调用一元运算
+    #[pyo3(signature = (op, other = None))]
+    fn apply(&self, op: &str, other: Option<PyTnum>) -> PyResult<Self> {
+        match other {
+            Some(other) => Ok((binary_op(op)?.abs)(&self.0, other.0).into()),
+            None => unary_op(op)
+                .map(|f| f(&self.0).into())
+                .ok_or_else(|| PyValueError::new_err(format!("未知的一元运算 {}", op))),
+        }
+    }
+
+    /// 把转移函数暴露为方法：`a.fast_divide(b)`、`a.popcount()`
+    fn __getattr__<'py>(&self, py: Python<'py>, name: &str) -> PyResult<Bound<'py, PyCFunction>> {
+        let t = self.0;
+        if let Some(op) = verify::binary_op(name) {
+            let abs = op.abs;
+            PyCFunction::new_closure_bound(
+                py,
+                None,
+                None,
+                move |args: &Bound<'_, PyTuple>, _: Option<&Bound<'_, PyDict>>| {
+                    let (other,) = args.extract::<(PyTnum,)>()?;
+                    PyResult::Ok(PyTnum(abs(&t, other.0)))
+                },
+            )
+        } else if let Some(f) = unary_op(name) {
+            PyCFunction::new_closure_bound(
+                py,
+                None,
+                None,
+                move |_: &Bound<'_, PyTuple>, _: Option<&Bound<'_, PyDict>>| PyTnum(f(&t)),
+            )
+        } else {
+            Err(PyAttributeError::new_err(format!(
+                "Tnum 没有属性或运算 {}",
+                name
+            )))
+        }
+    }
+
+    fn __add__(&self, other: PyTnum) -> Self {
+        self.0.add(other.0).into()
+    }
+
+    fn __sub__(&self, other: PyTnum) -> Self {
+        self.0.sub(other.0).into()
+    }
+
+    fn __mul__(&self, other: PyTnum) -> Self {
+        self.0.mul(other.0).into()
+    }
+
+    fn __and__(&self, other: PyTnum) -> Self {
+        self.0.bit_and(other.0).into()
+    }
+
+    fn __or__(&self, other: PyTnum) -> Self {
+        self.0.bit_or(other.0).into()
+    }
+
+    fn __xor__(&self, other: PyTnum) -> Self {
+        self.0.xor(other.0).into()
+    }
+
+    fn __neg__(&self) -> Self {
+        self.0.neg().into()
+    }
+
+    fn __invert__(&self) -> Self {
+        self.0.not().into()
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Tnum(value={:#x}, mask={:#x})", self.0.value, self.0.mask)
+    }
+}
+
+/// 一个可靠性反例
+#[pyclass(module = "fastdivide_z3", get_all, frozen)]
+#[derive(Debug, Clone)]
+pub struct Counterexample {
+    a: PyTnum,
+    b: PyTnum,
+    x: u64,
+    y: u64,
+    r: u64,
+    result: PyTnum,
+}
+
+impl From<verify::Counterexample> for Counterexample {
+    fn from(ce: verify::Counterexample) -> Self {
+        Self {
+            a: ce.a.into(),
+            b: ce.b.into(),
+            x: ce.x,
+            y: ce.y,
+            r: ce.r,
+            result: ce.result.into(),
+        }
+    }
+}
+
+/// [`verify::SoundnessReport`]
+#[pyclass(module = "fastdivide_z3", get_all, frozen)]
+#[derive(Debug, Clone)]
+pub struct SoundnessReport {
+    pairs: u64,
+    concrete: u64,
+    unsound: u64,
+    example: Option<Counterexample>,
+    minimal: Option<Counterexample>,
+}
+
+impl From<verify::SoundnessReport> for SoundnessReport {
+    fn from(r: verify::SoundnessReport) -> Self {
+        Self {
+            pairs: r.pairs,
+            concrete: r.concrete,
+            unsound: r.unsound,
+            example: r.example.map(Into::into),
+            minimal: r.minimal.map(Into::into),
+        }
+    }
+}
+
+/// 单调性反例：a ⊑ a2、b ⊑ b2，但 lo = op(a, b) ⋢ hi = op(a2, b2)
+#[pyclass(module = "fastdivide_z3", get_all, frozen)]
+#[derive(Debug, Clone)]
+pub struct MonotoneViolation {
+    a: PyTnum,
+    a2: PyTnum,
+    b: PyTnum,
+    b2: PyTnum,
+    lo: PyTnum,
+    hi: PyTnum,
+}
+
+impl From<verify::MonotoneViolation> for MonotoneViolation {
+    fn from(v: verify::MonotoneViolation) -> Self {
+        Self {
+            a: v.a.into(),
+            a2: v.a2.into(),
+            b: v.b.into(),
+            b2: v.b2.into(),
+            lo: v.lo.into(),
+            hi: v.hi.into(),
+        }
+    }
+}
+
+/// [`verify::MonotoneReport`]
+#[pyclass(module = "fastdivide_z3", get_all, frozen)]
+#[derive(Debug, Clone)]
+pub struct MonotoneReport {
+    checked: u64,
+    violations: u64,
+    example: Option<MonotoneViolation>,
+    minimal: Option<MonotoneViolation>,
+}
+
+impl From<verify::MonotoneReport> for MonotoneReport {
+    fn from(r: verify::MonotoneReport) -> Self {
+        Self {
+            checked: r.checked,
+            violations: r.violations,
+            example: r.example.map(Into::into),
+            minimal: r.minimal.map(Into::into),
+        }
+    }
+}
+
+/// [`verify::ComparisonReport`]，`by_rhs` 的每项为 (b, equal, a_finer, b_finer, incomparable)
+#[pyclass(module = "fastdivide_z3", frozen)]
+#[derive(Debug, Clone)]
+pub struct ComparisonReport(verify::ComparisonReport);
+
+fn pairs(v: &[(Tnum, Tnum)]) -> Vec<(PyTnum, PyTnum)> {
+    v.iter().map(|&(a, b)| (a.into(), b.into())).collect()
+}
+
+#[pymethods]
+impl ComparisonReport {
+    #[getter]
+    fn total(&self) -> u64 {
+        self.0.total
+    }
+
+    #[getter]
+    fn equal(&self) -> u64 {
+        self.0.equal
+    }
+
+    #[getter]
+    fn a_finer(&self) -> u64 {
+        self.0.a_finer
+    }
+
+    #[getter]
+    fn b_finer(&self) -> u64 {
+        self.0.b_finer
+    }
+
+    #[getter]
+    fn incomparable(&self) -> u64 {
+        self.0.incomparable
+    }
+
+    #[getter]
+    fn examples(&self) -> Vec<(PyTnum, PyTnum)> {
+        pairs(&self.0.examples)
+    }
+
+    #[getter]
+    fn b_finer_examples(&self) -> Vec<(PyTnum, PyTnum)> {
+        pairs(&self.0.b_finer_examples)
+    }
+
+    #[getter]
+    fn by_rhs(&self) -> Vec<(PyTnum, u64, u64, u64, u64)> {
+        self.0
+            .by_rhs
+            .iter()
+            .map(|c| (c.b.into(), c.equal, c.a_finer, c.b_finer, c.incomparable))
+            .collect()
+    }
+
+    #[getter]
+    fn average_log2_gap(&self) -> f64 {
+        self.0.average_log2_gap()
+    }
+
+    #[getter]
+    fn average_a_finer_gap(&self) -> f64 {
+        self.0.average_a_finer_gap()
+    }
+
+    #[getter]
+    fn average_b_finer_gap(&self) -> f64 {
+        self.0.average_b_finer_gap()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ComparisonReport(total={}, equal={}, a_finer={}, b_finer={}, incomparable={})",
+            self.0.total, self.0.equal, self.0.a_finer, self.0.b_finer, self.0.incomparable
+        )
+    }
+}
+
+/// 分析得到的一个寄存器值
+#[pyclass(module = "fastdivide_z3", get_all, frozen)]
+#[derive(Debug, Clone)]
+pub struct RegValue {
+    tnum: PyTnum,
+    umin: u64,
+    umax: u64,
+    smin: i64,
+    smax: i64,
+}
+
+impl From<&regval::RegValue> for RegValue {
+    fn from(v: &regval::RegValue) -> Self {
+        Self {
+            tnum: v.tnum.into(),
+            umin: v.umin,
+            umax: v.umax,
+            smin: v.smin,
+            smax: v.smax,
+        }
+    }
+}
+
+/// 二元与一元运算的名字
+#[pyfunction]
+fn ops() -> (Vec<&'static str>, Vec<&'static str>) {
+    (
+        verify::BINARY_OPS.iter().map(|op| op.name).collect(),
+        UNARY_OPS.iter().map(|op| op.0).collect(),
+    )
+}
+
+/// 在低 width 位上检查转移函数的可靠性
+#[pyfunction]
+#[pyo3(signature = (op, width = verify::EXHAUSTIVE_WIDTH))]
+fn check_sound(py: Python<'_>, op: &str, width: u32) -> PyResult<SoundnessReport> {
+    let op = binary_op(op)?;
+    Ok(py
+        .allow_threads(|| verify::check_sound(op.abs, op.con, width))
+        .into())
+}
+
+/// 在低 width 位上检查转移函数的单调性
+#[pyfunction]
+#[pyo3(signature = (op, width = verify::EXHAUSTIVE_WIDTH))]
+fn check_monotone(py: Python<'_>, op: &str, width: u32) -> PyResult<MonotoneReport> {
+    let op = binary_op(op)?;
+    Ok(py
+        .allow_threads(|| verify::check_monotone(op.abs, width))
+        .into())
+}
+
+/// 比较两个转移函数的精度，未给出的参数取 [`CompareConfig::default`]
+#[pyfunction]
+#[pyo3(signature = (
+    op_a, op_b, *, value_max = None, mask_max = None, rhs_unknown_bits = None,
+    skip_zero_rhs = None, threads = None, max_examples = None
+))]
+#[allow(clippy::too_many_arguments)]
+fn compare(
+    py: Python<'_>,
+    op_a: &str,
+    op_b: &str,
+    value_max: Option<u64>,
+    mask_max: Option<u64>,
+    rhs_unknown_bits: Option<u32>,
+    skip_zero_rhs: Option<bool>,
+    threads: Option<usize>,
+    max_examples: Option<usize>,
+) -> PyResult<ComparisonReport> {
+    let (a, b) = (binary_op(op_a)?.abs, binary_op(op_b)?.abs);
+    let default = CompareConfig::default();
+    let config = CompareConfig {
+        value_max: value_max.unwrap_or(default.value_max),
+        mask_max: mask_max.unwrap_or(default.mask_max),
+        rhs_unknown_bits: rhs_unknown_bits.unwrap_or(default.rhs_unknown_bits),
+        skip_zero_rhs: skip_zero_rhs.unwrap_or(default.skip_zero_rhs),
+        threads: threads.unwrap_or(default.threads),
+        max_examples: max_examples.unwrap_or(default.max_examples),
+    };
+    let report =
+        py.allow_threads(|| verify::compare_ops(|x, y| a(&x, y), |x, y| b(&x, y), &config));
+    Ok(ComparisonReport(report))
+}
+
+/// 分析原始 eBPF 字节码，返回每条指令入口处 r0–r10 的值，不可达的指令为 None
+#[pyfunction]
+#[pyo3(signature = (code, profile = "linux"))]
+fn analyze(py: Python<'_>, code: &[u8], profile: &str) -> PyResult<Vec<Option<Vec<RegValue>>>> {
+    let profile = IsaProfile::from_name(profile)
+        .ok_or_else(|| PyValueError::new_err(format!("未知的指令集 {}", profile)))?;
+    let insns = driver::decode(code).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let facts = py
+        .allow_threads(|| driver::analyze(profile, &insns, RegState::top()))
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(facts
+        .iter()
+        .map(|state| (!state.is_bottom()).then(|| state.regs.iter().map(Into::into).collect()))
+        .collect())
+}
+
+#[pymodule]
+fn fastdivide_z3(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyTnum>()?;
+    m.add_class::<Counterexample>()?;
+    m.add_class::<SoundnessReport>()?;
+    m.add_class::<MonotoneViolation>()?;
+    m.add_class::<MonotoneReport>()?;
+    m.add_class::<ComparisonReport>()?;
+    m.add_class::<RegValue>()?;
+    m.add_function(wrap_pyfunction!(ops, m)?)?;
+    m.add_function(wrap_pyfunction!(check_sound, m)?)?;
+    m.add_function(wrap_pyfunction!(check_monotone, m)?)?;
+    m.add_function(wrap_pyfunction!(compare, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze, m)?)?;
+    Ok(())
+}
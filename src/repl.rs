@@ -0,0 +1,272 @@
+//! 交互式 tnum 计算器（`repl` 模式）
+//!
+//! 每行可含多条以 `;` 分隔的语句：`name = expr` 绑定变量，单独的表达式输出结果。
+//! 表达式可以是 x 记法字面量（`0b1x00`）、常数（`12`、`0xff` 或 `const 12`）、`top`、`bottom`、
+//! `(value, mask)`、变量名，以及方法调用 `e.op(e)` 或 `e.op()`，可以链式调用；
+//! 二元运算为 [`verify::BINARY_OPS`] 与 `join`、`meet`，一元运算见 [`UNARY_OPS`]。
+//! 结果按 x 记法、十六进制 value/mask 与具体值个数输出。`:vars` 列出变量，`:ops` 列出运算，`:q` 退出。
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use tnum_z3_verification::tnum::Tnum;
+use tnum_z3_verification::verify;
+
+type UnaryOp = fn(&Tnum) -> Tnum;
+
+/// 可用的一元运算
+const UNARY_OPS: &[(&str, UnaryOp)] = &[
+    ("not", Tnum::not),
+    ("neg", Tnum::neg),
+    ("popcount", Tnum::popcount),
+    ("bswap16", Tnum::bswap16),
+    ("bswap32", Tnum::bswap32),
+    ("bswap64", Tnum::bswap64),
+];
+
+/// BINARY_OPS 之外的格运算
+const LATTICE_OPS: &[(&str, verify::AbstractOp)] =
+    &[("join", |a, b| a.or(&b)), ("meet", |a, b| a.meet(&b))];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    /// 以数字开头的词：`0b` 开头为 x 记法，否则为常数
+    Word(String),
+    LParen,
+    RParen,
+    Comma,
+    Dot,
+    Eq,
+}
+
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            ',' => Token::Comma,
+            '.' => Token::Dot,
+            '=' => Token::Eq,
+            c if c.is_ascii_alphanumeric() || c == '_' => {
+                let mut word = String::new();
+                while let Some(&c) = chars
+                    .peek()
+                    .filter(|c| c.is_ascii_alphanumeric() || **c == '_')
+                {
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(if c.is_ascii_digit() {
+                    Token::Word(word)
+                } else {
+                    Token::Ident(word)
+                });
+                continue;
+            }
+            c => return Err(format!("无法识别的字符 '{}'", c)),
+        };
+        chars.next();
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn parse_number(word: &str) -> Result<u64, String> {
+    let word = word.replace('_', "");
+    let parsed = match word.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => word.parse(),
+    };
+    parsed.map_err(|_| format!("无法解析的常数 {}", word))
+}
+
+fn parse_literal(word: &str) -> Result<Tnum, String> {
+    if word.starts_with("0b") {
+        word.parse()
+            .map_err(|e| format!("无法解析的 x 记法 {}: {:?}", word, e))
+    } else {
+        parse_number(word).map(Tnum::const_val)
+    }
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    vars: &'a BTreeMap<String, Tnum>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), String> {
+        match self.next() {
+            Some(t) if t == token => Ok(()),
+            Some(t) => Err(format!("应为 {:?}，实际为 {:?}", token, t)),
+            None => Err(format!("应为 {:?}，实际已到行尾", token)),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Tnum, String> {
+        let mut value = self.atom()?;
+        while self.peek() == Some(&Token::Dot) {
+            self.next();
+            let name = match self.next() {
+                Some(Token::Ident(name)) => name,
+                t => return Err(format!("'.' 之后应为运算名，实际为 {:?}", t)),
+            };
+            self.expect(Token::LParen)?;
+            if self.peek() == Some(&Token::RParen) {
+                self.next();
+                let op = UNARY_OPS
+                    .iter()
+                    .find(|(n, _)| *n == name)
+                    .ok_or_else(|| format!("未知的一元运算 {}", name))?;
+                value = (op.1)(&value);
+            } else {
+                let rhs = self.expr()?;
+                self.expect(Token::RParen)?;
+                let op = verify::binary_op(&name)
+                    .map(|op| op.abs)
+                    .or_else(|| LATTICE_OPS.iter().find(|(n, _)| *n == name).map(|op| op.1))
+                    .ok_or_else(|| format!("未知的二元运算 {}", name))?;
+                value = op(&value, rhs);
+            }
+        }
+        Ok(value)
+    }
+
+    fn atom(&mut self) -> Result<Tnum, String> {
+        match self.next() {
+            Some(Token::Word(word)) => parse_literal(&word),
+            Some(Token::Ident(name)) if name == "const" => match self.next() {
+                Some(Token::Word(word)) => parse_number(&word).map(Tnum::const_val),
+                t => Err(format!("const 之后应为常数，实际为 {:?}", t)),
+            },
+            Some(Token::Ident(name)) if name == "top" => Ok(Tnum::top()),
+            Some(Token::Ident(name)) if name == "bottom" => Ok(Tnum::bottom()),
+            Some(Token::Ident(name)) => self
+                .vars
+                .get(&name)
+                .copied()
+                .ok_or_else(|| format!("未定义的变量 {}", name)),
+            Some(Token::LParen) => {
+                let value = self.expr()?;
+                if self.peek() == Some(&Token::RParen) {
+                    self.next();
+                    return Ok(value);
+                }
+                self.expect(Token::Comma)?;
+                let mask = self.expr()?;
+                self.expect(Token::RParen)?;
+                if !value.is_singleton() || !mask.is_singleton() {
+                    return Err("(value, mask) 的两项都应为常数".to_string());
+                } else if value.value & mask.value != 0 {
+                    return Err("value 与 mask 有重叠的位".to_string());
+                }
+                Ok(Tnum::new(value.value, mask.value))
+            }
+            t => Err(format!("应为表达式，实际为 {:?}", t)),
+        }
+    }
+}
+
+/// x 记法（省去前导的已知 0 位）、十六进制 value/mask 与具体值个数
+fn describe(t: &Tnum) -> String {
+    if t.is_bottom() {
+        return "⊥，0 个具体值".to_string();
+    }
+    let width = 64 - (t.value | t.mask).leading_zeros().min(63);
+    format!(
+        "{:w$}  (value {:#x}, mask {:#x})，{} 个具体值",
+        t,
+        t.value,
+        t.mask,
+        t.cardinality(),
+        w = width as usize
+    )
+}
+
+/// 执行一条语句，表达式语句返回要输出的结果
+fn run_statement(stmt: &str, vars: &mut BTreeMap<String, Tnum>) -> Result<Option<String>, String> {
+    let tokens = tokenize(stmt)?;
+    let (target, expr) = match tokens.as_slice() {
+        [] => return Ok(None),
+        [Token::Ident(name), Token::Eq, rest @ ..] => (Some(name.clone()), rest),
+        _ => (None, tokens.as_slice()),
+    };
+    let mut parser = Parser {
+        tokens: expr,
+        pos: 0,
+        vars,
+    };
+    let value = parser.expr()?;
+    if let Some(t) = parser.peek() {
+        return Err(format!("多余的 {:?}", t));
+    }
+    Ok(match target {
+        Some(name) => {
+            vars.insert(name, value);
+            None
+        }
+        None => Some(describe(&value)),
+    })
+}
+
+pub fn run_repl_mode() {
+    println!("tnum 计算器：`a = 0b1x00; b = const 12; a.fast_divide(b)`，:ops 列出运算，:q 退出");
+    let mut vars = BTreeMap::new();
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        let _ = std::io::stdout().flush();
+        let Some(Ok(line)) = lines.next() else {
+            println!();
+            break;
+        };
+        match line.trim() {
+            ":q" | ":quit" => break,
+            ":vars" => {
+                for (name, value) in &vars {
+                    println!("{} = {}", name, describe(value));
+                }
+                continue;
+            }
+            ":ops" => {
+                let binary: Vec<&str> = verify::BINARY_OPS
+                    .iter()
+                    .map(|op| op.name)
+                    .chain(LATTICE_OPS.iter().map(|op| op.0))
+                    .collect();
+                let unary: Vec<&str> = UNARY_OPS.iter().map(|op| op.0).collect();
+                println!("二元: {}", binary.join(" "));
+                println!("一元: {}", unary.join(" "));
+                continue;
+            }
+            _ => {}
+        }
+        for stmt in line.split(';') {
+            match run_statement(stmt, &mut vars) {
+                Ok(Some(result)) => println!("{}", result),
+                Ok(None) => {}
+                Err(e) => {
+                    println!("错误: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
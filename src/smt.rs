@@ -0,0 +1,1557 @@
+//! 转移函数可靠性的 SMT 编码
+//!
+//! tnum 编码为一对位向量 (value, mask)。[`soundness_query`] 对 a、b、x、y 全部符号化，
+//! 断言 `x ∈ γ(a) ∧ y ∈ γ(b) ∧ f(x, y) ∉ γ(F(a, b))`：不可满足即 F 在该位宽下可靠，
+//! 可满足时模型就是反例。只有抽象转移函数能写成位向量公式的运算（[`SYMBOLIC_OPS`]，
+//! sdiv、fast_divide 等不在其中）支持全符号查询；其余运算只能用 [`pair_query`] 在给定的 a、b 上
+//! 对全部 64 位具体值求解，没有对全部 tnum 的证明。
+//!
+//! [`divider_query`] 验证抽象除法所依赖的 fastdivide 分解本身：`divide(n) == n / d`。
+//! 除数为常数时，[`prove_refinement`] 判定两个除法之间的精化 `F_a(a, c) ⊑ F_b(a, c)`。
+//!
+//! 编码先写成与求解器无关的 [`Term`] / [`Formula`]，[`self_check`] 用 [`Term::eval`]
+//! 把编码与 Rust 实现对拍；启用 `z3` 特性后由 [`solve`] 交给 Z3，
+//! 也可以用 [`to_smtlib`] 导出 SMT-LIB2 脚本交给其他求解器，不需要 z3 特性。
+use crate::tnum::Tnum;
+use crate::verify::{self, Counterexample};
+use fastdivide::DividerU64;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// 查询中的变量
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Var {
+    AValue,
+    AMask,
+    BValue,
+    BMask,
+    X,
+    Y,
+}
+
+impl Var {
+    pub const ALL: [Var; 6] = [
+        Var::AValue,
+        Var::AMask,
+        Var::BValue,
+        Var::BMask,
+        Var::X,
+        Var::Y,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Var::AValue => "av",
+            Var::AMask => "am",
+            Var::BValue => "bv",
+            Var::BMask => "bm",
+            Var::X => "x",
+            Var::Y => "y",
+        }
+    }
+}
+
+/// 变量的取值，按 [`Var::ALL`] 的顺序
+pub type Env = [u64; 6];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    And,
+    Or,
+    Xor,
+    Add,
+    Sub,
+    Mul,
+    Udiv,
+    Urem,
+    Sdiv,
+    Srem,
+    Shl,
+    Lshr,
+    Ashr,
+    /// 无符号乘积的高 width 位
+    Umulh,
+}
+
+impl BinOp {
+    /// SMT-LIB 中的函数名
+    pub fn smtlib_name(self) -> &'static str {
+        match self {
+            BinOp::And => "bvand",
+            BinOp::Or => "bvor",
+            BinOp::Xor => "bvxor",
+            BinOp::Add => "bvadd",
+            BinOp::Sub => "bvsub",
+            BinOp::Mul => "bvmul",
+            BinOp::Udiv => "bvudiv",
+            BinOp::Urem => "bvurem",
+            BinOp::Sdiv => "bvsdiv",
+            BinOp::Srem => "bvsrem",
+            BinOp::Shl => "bvshl",
+            BinOp::Lshr => "bvlshr",
+            BinOp::Ashr => "bvashr",
+            // SMT-LIB 没有对应的函数，由 Term::to_smtlib 展开
+            BinOp::Umulh => "umulh",
+        }
+    }
+}
+
+/// 位向量项，位宽由所在的查询决定；除法与移位采用 SMT-LIB 的语义
+///
+/// 子项用 `Rc` 共享：展开循环得到的编码是 DAG，求值、导出与交给 Z3 时每个子项只处理一次。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Var(Var),
+    Const(u64),
+    Not(Rc<Term>),
+    Neg(Rc<Term>),
+    Bin(BinOp, Rc<Term>, Rc<Term>),
+    Ite(Rc<Formula>, Rc<Term>, Rc<Term>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Formula {
+    True,
+    Eq(Term, Term),
+    Ult(Term, Term),
+    Slt(Term, Term),
+    Not(Rc<Formula>),
+    And(Vec<Formula>),
+    Or(Vec<Formula>),
+}
+
+fn width_mask(width: u32) -> u64 {
+    u64::MAX >> (64 - width)
+}
+
+impl Term {
+    pub fn var(v: Var) -> Self {
+        Term::Var(v)
+    }
+
+    pub fn bin(op: BinOp, a: Term, b: Term) -> Self {
+        Term::Bin(op, Rc::new(a), Rc::new(b))
+    }
+
+    pub fn ite(c: impl Into<Rc<Formula>>, a: Term, b: Term) -> Self {
+        Term::Ite(c.into(), Rc::new(a), Rc::new(b))
+    }
+
+    pub fn eq(self, other: Term) -> Formula {
+        Formula::Eq(self, other)
+    }
+
+    pub fn ult(self, other: Term) -> Formula {
+        Formula::Ult(self, other)
+    }
+
+    pub fn slt(self, other: Term) -> Formula {
+        Formula::Slt(self, other)
+    }
+
+    /// 在 width 位下求值，结果只保留低 width 位
+    pub fn eval(&self, env: &Env, width: u32) -> u64 {
+        self.eval_in(env, width, &mut Evaluator::default())
+    }
+
+    fn eval_in(&self, env: &Env, width: u32, memo: &mut Evaluator) -> u64 {
+        let m = width_mask(width);
+        let sign = 1u64 << (width - 1);
+        // 把低 width 位按有符号数扩展到 i64
+        let signed = |v: u64| ((v << (64 - width)) as i64) >> (64 - width);
+        let key = self as *const Term;
+        if let Some(&r) = memo.terms.get(&key) {
+            return r;
+        }
+        let r = match self {
+            Term::Var(v) => return env[*v as usize] & m,
+            Term::Const(c) => return c & m,
+            Term::Not(a) => !a.eval_in(env, width, memo),
+            Term::Neg(a) => a.eval_in(env, width, memo).wrapping_neg(),
+            Term::Ite(c, a, b) => {
+                if c.eval_in(env, width, memo) {
+                    a.eval_in(env, width, memo)
+                } else {
+                    b.eval_in(env, width, memo)
+                }
+            }
+            Term::Bin(op, a, b) => {
+                let (x, y) = (a.eval_in(env, width, memo), b.eval_in(env, width, memo));
+                match op {
+                    BinOp::And => x & y,
+                    BinOp::Or => x | y,
+                    BinOp::Xor => x ^ y,
+                    BinOp::Add => x.wrapping_add(y),
+                    BinOp::Sub => x.wrapping_sub(y),
+                    BinOp::Mul => x.wrapping_mul(y),
+                    BinOp::Udiv => x.checked_div(y).unwrap_or(m),
+                    BinOp::Urem => x.checked_rem(y).unwrap_or(x),
+                    BinOp::Sdiv => match (signed(x), signed(y)) {
+                        (sx, 0) if sx < 0 => 1,
+                        (_, 0) => m,
+                        (sx, sy) => sx.wrapping_div(sy) as u64,
+                    },
+                    BinOp::Srem => match (signed(x), signed(y)) {
+                        (_, 0) => x,
+                        (sx, sy) => sx.wrapping_rem(sy) as u64,
+                    },
+                    BinOp::Shl if y >= width as u64 => 0,
+                    BinOp::Shl => x << y,
+                    BinOp::Lshr if y >= width as u64 => 0,
+                    BinOp::Lshr => x >> y,
+                    BinOp::Ashr if y >= width as u64 => {
+                        if x & sign != 0 {
+                            m
+                        } else {
+                            0
+                        }
+                    }
+                    BinOp::Ashr => (signed(x) >> y) as u64,
+                    BinOp::Umulh => ((x as u128 * y as u128) >> width) as u64,
+                }
+            }
+        } & m;
+        memo.terms.insert(key, r);
+        r
+    }
+}
+
+impl Formula {
+    pub fn eval(&self, env: &Env, width: u32) -> bool {
+        self.eval_in(env, width, &mut Evaluator::default())
+    }
+
+    fn eval_in(&self, env: &Env, width: u32, memo: &mut Evaluator) -> bool {
+        let signed = |v: u64| ((v << (64 - width)) as i64) >> (64 - width);
+        let key = self as *const Formula;
+        if let Some(&r) = memo.formulas.get(&key) {
+            return r;
+        }
+        let mut bv = |t: &Term| t.eval_in(env, width, memo);
+        let r = match self {
+            Formula::True => true,
+            Formula::Eq(a, b) => bv(a) == bv(b),
+            Formula::Ult(a, b) => bv(a) < bv(b),
+            Formula::Slt(a, b) => signed(bv(a)) < signed(bv(b)),
+            Formula::Not(f) => !f.eval_in(env, width, memo),
+            Formula::And(fs) => fs.iter().all(|f| f.eval_in(env, width, memo)),
+            Formula::Or(fs) => fs.iter().any(|f| f.eval_in(env, width, memo)),
+        };
+        memo.formulas.insert(key, r);
+        r
+    }
+}
+
+/// 一次求值中各节点的结果，按节点地址缓存，共享的子项只求值一次
+#[derive(Default)]
+struct Evaluator {
+    terms: HashMap<*const Term, u64>,
+    formulas: HashMap<*const Formula, bool>,
+}
+
+macro_rules! term_ops {
+    ($($trait:ident $method:ident $op:ident),*) => {$(
+        impl std::ops::$trait for Term {
+            type Output = Term;
+            fn $method(self, rhs: Term) -> Term {
+                Term::bin(BinOp::$op, self, rhs)
+            }
+        }
+    )*};
+}
+
+term_ops!(BitAnd bitand And, BitOr bitor Or, BitXor bitxor Xor, Add add Add, Sub sub Sub, Mul mul Mul);
+
+impl std::ops::Not for Term {
+    type Output = Term;
+    fn not(self) -> Term {
+        Term::Not(Rc::new(self))
+    }
+}
+
+/// 写成 SMT-LIB：被引用不止一次的子项先用 define-fun 定义为 `t<n>`，DAG 不会被展开成树
+struct Printer {
+    width: u32,
+    /// 每个节点被引用的次数
+    term_refs: HashMap<*const Term, usize>,
+    formula_refs: HashMap<*const Formula, usize>,
+    term_names: HashMap<*const Term, String>,
+    formula_names: HashMap<*const Formula, String>,
+    /// 按依赖顺序排列的 define-fun
+    defs: Vec<String>,
+}
+
+impl Printer {
+    fn new(width: u32, assertions: &[Formula]) -> Self {
+        let mut p = Self {
+            width,
+            term_refs: HashMap::new(),
+            formula_refs: HashMap::new(),
+            term_names: HashMap::new(),
+            formula_names: HashMap::new(),
+            defs: Vec::new(),
+        };
+        for f in assertions {
+            p.count_formula(f);
+        }
+        p
+    }
+
+    fn count_term(&mut self, t: &Term) {
+        let n = self.term_refs.entry(t).or_insert(0);
+        *n += 1;
+        if *n > 1 {
+            return;
+        }
+        match t {
+            Term::Var(_) | Term::Const(_) => {}
+            Term::Not(a) | Term::Neg(a) => self.count_term(a),
+            Term::Bin(_, a, b) => {
+                self.count_term(a);
+                self.count_term(b);
+            }
+            Term::Ite(c, a, b) => {
+                self.count_formula(c);
+                self.count_term(a);
+                self.count_term(b);
+            }
+        }
+    }
+
+    fn count_formula(&mut self, f: &Formula) {
+        let n = self.formula_refs.entry(f).or_insert(0);
+        *n += 1;
+        if *n > 1 {
+            return;
+        }
+        match f {
+            Formula::True => {}
+            Formula::Eq(a, b) | Formula::Ult(a, b) | Formula::Slt(a, b) => {
+                self.count_term(a);
+                self.count_term(b);
+            }
+            Formula::Not(g) => self.count_formula(g),
+            Formula::And(fs) | Formula::Or(fs) => fs.iter().for_each(|g| self.count_formula(g)),
+        }
+    }
+
+    /// 把共享的节点写成 define-fun，返回其名字
+    fn define(&mut self, sort: String, body: String) -> String {
+        let name = format!("t{}", self.defs.len());
+        self.defs
+            .push(format!("(define-fun {} () {} {})", name, sort, body));
+        name
+    }
+
+    fn term(&mut self, t: &Term) -> String {
+        let key = t as *const Term;
+        if let Some(name) = self.term_names.get(&key) {
+            return name.clone();
+        }
+        let w = self.width;
+        let body = match t {
+            Term::Var(v) => return v.name().to_string(),
+            Term::Const(c) => return format!("(_ bv{} {})", c & width_mask(w), w),
+            Term::Not(a) => format!("(bvnot {})", self.term(a)),
+            Term::Neg(a) => format!("(bvneg {})", self.term(a)),
+            Term::Bin(BinOp::Umulh, a, b) => format!(
+                "((_ extract {} {}) (bvmul ((_ zero_extend {}) {}) ((_ zero_extend {}) {})))",
+                2 * w - 1,
+                w,
+                w,
+                self.term(a),
+                w,
+                self.term(b)
+            ),
+            Term::Bin(op, a, b) => {
+                format!("({} {} {})", op.smtlib_name(), self.term(a), self.term(b))
+            }
+            Term::Ite(c, a, b) => format!(
+                "(ite {} {} {})",
+                self.formula(c),
+                self.term(a),
+                self.term(b)
+            ),
+        };
+        if self.term_refs.get(&key).is_some_and(|&n| n > 1) {
+            let name = self.define(format!("(_ BitVec {})", w), body);
+            self.term_names.insert(key, name.clone());
+            return name;
+        }
+        body
+    }
+
+    fn formula(&mut self, f: &Formula) -> String {
+        let key = f as *const Formula;
+        if let Some(name) = self.formula_names.get(&key) {
+            return name.clone();
+        }
+        let all = |p: &mut Self, op: &str, fs: &[Formula], empty: &str| {
+            if fs.is_empty() {
+                return empty.to_string();
+            }
+            let args: Vec<String> = fs.iter().map(|g| p.formula(g)).collect();
+            format!("({} {})", op, args.join(" "))
+        };
+        let body = match f {
+            Formula::True => return "true".to_string(),
+            Formula::Eq(a, b) => format!("(= {} {})", self.term(a), self.term(b)),
+            Formula::Ult(a, b) => format!("(bvult {} {})", self.term(a), self.term(b)),
+            Formula::Slt(a, b) => format!("(bvslt {} {})", self.term(a), self.term(b)),
+            Formula::Not(g) => format!("(not {})", self.formula(g)),
+            Formula::And(fs) => all(self, "and", fs, "true"),
+            Formula::Or(fs) => all(self, "or", fs, "false"),
+        };
+        if self.formula_refs.get(&key).is_some_and(|&n| n > 1) {
+            let name = self.define("Bool".to_string(), body);
+            self.formula_names.insert(key, name.clone());
+            return name;
+        }
+        body
+    }
+}
+
+impl std::ops::Not for Formula {
+    type Output = Formula;
+    fn not(self) -> Formula {
+        Formula::Not(Rc::new(self))
+    }
+}
+
+/// 符号化的 tnum
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymTnum {
+    pub value: Term,
+    pub mask: Term,
+}
+
+impl SymTnum {
+    pub fn new(value: Term, mask: Term) -> Self {
+        Self { value, mask }
+    }
+
+    pub fn constant(t: Tnum) -> Self {
+        Self::new(Term::Const(t.value), Term::Const(t.mask))
+    }
+
+    /// value & mask == 0，即不是 bottom
+    pub fn well_formed(&self) -> Formula {
+        (self.value.clone() & self.mask.clone()).eq(Term::Const(0))
+    }
+
+    /// x ∈ γ(self)
+    pub fn contains(&self, x: Term) -> Formula {
+        (x & !self.mask.clone()).eq(self.value.clone())
+    }
+
+    /// γ(self) ⊆ γ(other)，两者都须良构
+    pub fn le(&self, other: &SymTnum) -> Formula {
+        let outside = !other.mask.clone();
+        Formula::And(vec![
+            (self.mask.clone() & outside.clone()).eq(Term::Const(0)),
+            (self.value.clone() & outside).eq(other.value.clone()),
+        ])
+    }
+
+    /// width 位全部未知
+    pub fn is_top(&self) -> Formula {
+        Formula::And(vec![
+            self.value.clone().eq(Term::Const(0)),
+            self.mask.clone().eq(Term::Const(u64::MAX)),
+        ])
+    }
+
+    pub fn top() -> Self {
+        Self::constant(Tnum::top())
+    }
+
+    /// c 成立时取 then，否则取 otherwise
+    pub fn select(c: Formula, then: SymTnum, otherwise: SymTnum) -> Self {
+        let c = Rc::new(c);
+        Self::new(
+            Term::ite(c.clone(), then.value, otherwise.value),
+            Term::ite(c, then.mask, otherwise.mask),
+        )
+    }
+
+    fn eval(&self, env: &Env, width: u32) -> Tnum {
+        let mut memo = Evaluator::default();
+        Tnum::new(
+            self.value.eval_in(env, width, &mut memo),
+            self.mask.eval_in(env, width, &mut memo),
+        )
+    }
+}
+
+/// 抽象转移函数的位向量编码，width 为查询的位宽（展开循环与枚举移位量时用到）
+pub type SymbolicOp = fn(&SymTnum, &SymTnum, u32) -> SymTnum;
+
+/// 抽象转移函数可以直接写成位向量公式的运算，与 [`Tnum`] 中的实现逐行对应
+///
+/// mul 的循环按位宽展开，移位对全部 width 个移位量求 join，除法与取余的分支写成 ite。
+/// 不在其中的运算没有 64 位的全符号证明，只有小位宽枚举与 [`pair_query`]：sdiv 按符号拆分后
+/// 对端点做有符号除法，fast_divide 与 best_udiv 对除数的具体值分情况枚举，mul_opt 等乘法变体、
+/// 循环移位、饱和与溢出检查、最值也都没有编码。
+pub const SYMBOLIC_OPS: &[(&str, SymbolicOp)] = &[
+    ("add", sym_add),
+    ("sub", sym_sub),
+    ("mul", sym_mul),
+    ("xor", sym_xor),
+    ("and", sym_and),
+    ("or", sym_or),
+    ("udiv", sym_udiv),
+    ("urem", sym_urem),
+    ("srem", sym_srem),
+    ("shl", |a, b, w| sym_shift(a, b, w, BinOp::Shl)),
+    ("lshr", |a, b, w| sym_shift(a, b, w, BinOp::Lshr)),
+    ("ashr", |a, b, w| sym_shift(a, b, w, BinOp::Ashr)),
+];
+
+fn sym_add(a: &SymTnum, b: &SymTnum, _width: u32) -> SymTnum {
+    let sm = a.mask.clone() + b.mask.clone();
+    let sv = a.value.clone() + b.value.clone();
+    let sigma = sm + sv.clone();
+    let chi = sigma ^ sv.clone();
+    let mu = chi | a.mask.clone() | b.mask.clone();
+    SymTnum::new(sv & !mu.clone(), mu)
+}
+
+fn sym_sub(a: &SymTnum, b: &SymTnum, _width: u32) -> SymTnum {
+    let dv = a.value.clone() - b.value.clone();
+    let alpha = dv.clone() + a.mask.clone();
+    let beta = dv.clone() - b.mask.clone();
+    let chi = alpha ^ beta;
+    let mu = chi | a.mask.clone() | b.mask.clone();
+    SymTnum::new(dv & !mu.clone(), mu)
+}
+
+/// 循环展开 width 步：第 i 步 a 已右移 i 位、b 已左移 i 位；a 的该位已知为 0 时加上 0，
+/// 与实现中跳过该位的结果相同
+fn sym_mul(a: &SymTnum, b: &SymTnum, width: u32) -> SymTnum {
+    let acc_v = a.value.clone() * b.value.clone();
+    let mut acc_m = SymTnum::constant(Tnum::const_val(0));
+    for i in 0..width {
+        let bit = |t: &Term| nonzero(t.clone() & Term::Const(1 << i));
+        let shl = |t: Term| Term::bin(BinOp::Shl, t, Term::Const(i as u64));
+        let m = Term::ite(
+            bit(&a.value),
+            shl(b.mask.clone()),
+            Term::ite(
+                bit(&a.mask),
+                shl(b.value.clone() | b.mask.clone()),
+                Term::Const(0),
+            ),
+        );
+        acc_m = sym_add(&acc_m, &SymTnum::new(Term::Const(0), m), width);
+    }
+    let res = sym_add(&SymTnum::new(acc_v, Term::Const(0)), &acc_m, width);
+    SymTnum::select(
+        Formula::Or(vec![a.is_top(), b.is_top()]),
+        SymTnum::top(),
+        res,
+    )
+}
+
+fn sym_xor(a: &SymTnum, b: &SymTnum, _width: u32) -> SymTnum {
+    let v = a.value.clone() ^ b.value.clone();
+    let mu = a.mask.clone() | b.mask.clone();
+    SymTnum::new(v & !mu.clone(), mu)
+}
+
+fn sym_and(a: &SymTnum, b: &SymTnum, _width: u32) -> SymTnum {
+    let alpha = a.value.clone() | a.mask.clone();
+    let beta = b.value.clone() | b.mask.clone();
+    let v = a.value.clone() & b.value.clone();
+    SymTnum::new(v.clone(), alpha & beta & !v)
+}
+
+fn sym_or(a: &SymTnum, b: &SymTnum, _width: u32) -> SymTnum {
+    let v = a.value.clone() | b.value.clone();
+    let mu = a.mask.clone() | b.mask.clone();
+    SymTnum::new(v.clone(), mu & !v)
+}
+
+fn nonzero(t: Term) -> Formula {
+    !t.eq(Term::Const(0))
+}
+
+/// t 恰有一位为 1
+fn is_pow2(t: &Term) -> Formula {
+    Formula::And(vec![
+        nonzero(t.clone()),
+        (t.clone() & (t.clone() - Term::Const(1))).eq(Term::Const(0)),
+    ])
+}
+
+/// 除数恰为 2^k 时商即 a >> k，写成除以 2^k；否则商落在被除数的最小值除以除数的最大值与
+/// 被除数的最大值除以除数的最小值之间，取这个区间的 tnum
+fn sym_udiv(a: &SymTnum, b: &SymTnum, width: u32) -> SymTnum {
+    let div = |t: Term| Term::bin(BinOp::Udiv, t, b.value.clone());
+    let pow2 = Formula::And(vec![b.mask.clone().eq(Term::Const(0)), is_pow2(&b.value)]);
+    let shifted = SymTnum::new(div(a.value.clone()), div(a.mask.clone()));
+    let min = Term::bin(
+        BinOp::Udiv,
+        a.value.clone(),
+        b.value.clone() + b.mask.clone(),
+    );
+    let max = div(a.value.clone() + a.mask.clone());
+    // 除数为 top 时 value 也为 0
+    let bounded = SymTnum::select(
+        b.value.clone().eq(Term::Const(0)),
+        SymTnum::top(),
+        sym_from_range(min, max, width),
+    );
+    SymTnum::select(pow2, shifted, bounded)
+}
+
+/// [`Tnum::from_range`]：min 与 max 的公共前缀已知，第一个不同的位及以下未知
+fn sym_from_range(min: Term, max: Term, width: u32) -> SymTnum {
+    let delta = smear(min.clone() ^ max, width);
+    SymTnum::new(min & !delta.clone(), delta)
+}
+
+/// [`rem_get_low_bits`](crate::tnum::rem_get_low_bits)：除数的低 k 位已知为 0 时，
+/// 余数的这些位与被除数相同，其余位未知
+fn sym_rem_low_bits(a: &SymTnum, b: &SymTnum) -> SymTnum {
+    let bits = b.value.clone() + b.mask.clone();
+    let low = (bits.clone() & Term::Neg(Rc::new(bits))) - Term::Const(1);
+    let even = |t: &Term| (t.clone() & Term::Const(1)).eq(Term::Const(0));
+    let applies = Formula::And(vec![
+        !Formula::And(vec![
+            b.value.clone().eq(Term::Const(0)),
+            b.mask.clone().eq(Term::Const(0)),
+        ]),
+        even(&b.value),
+        even(&b.mask),
+    ]);
+    let known = SymTnum::new(
+        a.value.clone() & low.clone(),
+        (a.mask.clone() & low.clone()) | !low,
+    );
+    SymTnum::select(applies, known, SymTnum::top())
+}
+
+fn sym_urem(a: &SymTnum, b: &SymTnum, width: u32) -> SymTnum {
+    let sign = Term::Const(1 << (width - 1));
+    let res = sym_rem_low_bits(a, b);
+    let pow2 = Formula::And(vec![
+        b.mask.clone().eq(Term::Const(0)),
+        (b.value.clone() & sign).eq(Term::Const(0)),
+        is_pow2(&b.value),
+    ]);
+    let low_bits = b.value.clone() - Term::Const(1);
+    let masked = SymTnum::new(
+        a.value.clone() & low_bits.clone(),
+        a.mask.clone() & low_bits,
+    );
+    // 余数不超过任一操作数：两者最大值的前导零取较多者
+    let keep = smear(a.value.clone() + a.mask.clone(), width)
+        & smear(b.value.clone() + b.mask.clone(), width);
+    let general = SymTnum::new(res.value & keep.clone(), res.mask & keep);
+    SymTnum::select(
+        Formula::Or(vec![
+            a.is_top(),
+            b.is_top(),
+            b.value.clone().eq(Term::Const(0)),
+        ]),
+        SymTnum::top(),
+        SymTnum::select(pow2, masked, general),
+    )
+}
+
+fn sym_srem(a: &SymTnum, b: &SymTnum, width: u32) -> SymTnum {
+    let zero = || Term::Const(0);
+    let sign = || Term::Const(1 << (width - 1));
+    let exact = SymTnum::new(
+        Term::bin(BinOp::Srem, a.value.clone(), b.value.clone()),
+        zero(),
+    );
+    let singleton = Formula::And(vec![
+        a.mask.clone().eq(zero()),
+        b.mask.clone().eq(zero()),
+        nonzero(b.value.clone()),
+    ]);
+    let res = sym_rem_low_bits(a, b);
+    let pow2 = Formula::And(vec![
+        b.mask.clone().eq(zero()),
+        (b.value.clone() & Term::Const(1)).eq(zero()),
+        is_pow2(&b.value),
+    ]);
+    let low_bits = b.value.clone() - Term::Const(1);
+    let max = a.value.clone() + a.mask.clone();
+    let nonnegative = ((a.value.clone() | a.mask.clone()) & sign()).eq(zero());
+    let negative = Formula::And(vec![
+        nonzero(a.value.clone() & sign()),
+        (a.mask.clone() & sign()).eq(zero()),
+    ]);
+    // 除数的尾零不多于被除数最大值的尾零
+    let aligned = (max.clone() & low_bits.clone()).eq(zero());
+    let r1 = SymTnum::select(
+        Formula::Or(vec![nonnegative, aligned]),
+        SymTnum::new(
+            res.value.clone() & low_bits.clone(),
+            res.mask.clone() & low_bits.clone(),
+        ),
+        res.clone(),
+    );
+    let r2 = SymTnum::select(
+        Formula::And(vec![
+            negative,
+            (!(a.value.clone() & low_bits.clone())).eq(zero()),
+        ]),
+        SymTnum::new(
+            r1.value.clone() | !low_bits.clone(),
+            r1.mask.clone() & low_bits,
+        ),
+        r1,
+    );
+    let keep = smear(max, width);
+    let general = SymTnum::new(res.value & keep.clone(), res.mask & keep);
+    let body = SymTnum::select(
+        b.value.clone().eq(zero()),
+        SymTnum::top(),
+        SymTnum::select(pow2, r2, general),
+    );
+    SymTnum::select(
+        Formula::Or(vec![a.is_top(), b.is_top()]),
+        SymTnum::top(),
+        SymTnum::select(singleton, exact, body),
+    )
+}
+
+/// b 的每个可能的移位量 k（按位宽取模）下常数移位结果的 join
+///
+/// 良构的 a 的 value 与 mask 不会同时有符号位，两者各自算术右移即实现中按符号位分的三种情形。
+fn sym_shift(a: &SymTnum, b: &SymTnum, width: u32, op: BinOp) -> SymTnum {
+    let low = Term::Const(width as u64 - 1);
+    // 全部结果的 value 之与为已知的 1，value | mask 之或以外为已知的 0
+    let (mut ones, mut any) = (Term::Const(u64::MAX), Term::Const(0));
+    for k in 0..width as u64 {
+        let possible = Rc::new(
+            ((b.value.clone() ^ Term::Const(k)) & !b.mask.clone() & low.clone()).eq(Term::Const(0)),
+        );
+        let shift = |t: &Term| Term::bin(op, t.clone(), Term::Const(k));
+        let (v, m) = (shift(&a.value), shift(&a.mask));
+        ones = ones & Term::ite(possible.clone(), v.clone(), Term::Const(u64::MAX));
+        any = any | Term::ite(possible, v | m, Term::Const(0));
+    }
+    SymTnum::new(ones.clone(), any & !ones)
+}
+
+pub fn symbolic_op(name: &str) -> Option<SymbolicOp> {
+    SYMBOLIC_OPS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|&(_, op)| op)
+}
+
+/// 具体语义的编码：结果与有定义的条件，与 [`verify::BINARY_OPS`] 中的具体语义一致
+pub fn concrete_term(name: &str, x: Term, y: Term, width: u32) -> Option<(Term, Formula)> {
+    let zero = || Term::Const(0);
+    let nonzero = |y: &Term| !y.clone().eq(zero());
+    // 移位量按位宽取模
+    let amount = |y: Term| y & Term::Const(width as u64 - 1);
+    let r = match name {
+        "add" => (x + y, Formula::True),
+        "sub" => (x - y, Formula::True),
+        "mul" | "mul_opt" | "mul_rec" | "xtnum_mul_top" | "xtnum_mul_high_top" => {
+            (x * y, Formula::True)
+        }
+        "umulh" => (Term::bin(BinOp::Umulh, x, y), Formula::True),
+        "xor" => (x ^ y, Formula::True),
+        "and" => (x & y, Formula::True),
+        "or" => (x | y, Formula::True),
+        "sat_add" => {
+            let s = x.clone() + y;
+            (
+                Term::ite(s.clone().ult(x), Term::Const(width_mask(width)), s),
+                Formula::True,
+            )
+        }
+        "sat_sub" => (
+            Term::ite(x.clone().ult(y.clone()), zero(), x - y),
+            Formula::True,
+        ),
+        "udiv" | "fast_divide" | "best_udiv" => {
+            let ok = nonzero(&y);
+            (Term::bin(BinOp::Udiv, x, y), ok)
+        }
+        "urem" => {
+            let ok = nonzero(&y);
+            (Term::bin(BinOp::Urem, x, y), ok)
+        }
+        "sdiv" | "fast_sdivide" => {
+            let ok = nonzero(&y);
+            (Term::bin(BinOp::Sdiv, x, y), ok)
+        }
+        "srem" => {
+            let ok = nonzero(&y);
+            (Term::bin(BinOp::Srem, x, y), ok)
+        }
+        "shl" => (Term::bin(BinOp::Shl, x, amount(y)), Formula::True),
+        "lshr" => (Term::bin(BinOp::Lshr, x, amount(y)), Formula::True),
+        "ashr" => (Term::bin(BinOp::Ashr, x, amount(y)), Formula::True),
+        "umax" => (Term::ite(x.clone().ult(y.clone()), y, x), Formula::True),
+        "umin" => (Term::ite(x.clone().ult(y.clone()), x, y), Formula::True),
+        "smax" => (Term::ite(x.clone().slt(y.clone()), y, x), Formula::True),
+        "smin" => (Term::ite(x.clone().slt(y.clone()), x, y), Formula::True),
+        _ => return None,
+    };
+    Some(r)
+}
+
+/// 一个可靠性查询：全部断言同时可满足当且仅当存在反例
+#[derive(Debug, Clone)]
+pub struct Query {
+    pub width: u32,
+    pub a: SymTnum,
+    pub b: SymTnum,
+    pub x: Term,
+    pub y: Term,
+    /// f(x, y)
+    pub r: Term,
+    /// F(a, b)
+    pub result: SymTnum,
+    pub assertions: Vec<Formula>,
+}
+
+impl Query {
+    fn new(width: u32, a: SymTnum, b: SymTnum, result: SymTnum, r: Term, defined: Formula) -> Self {
+        let (x, y) = (Term::var(Var::X), Term::var(Var::Y));
+        let assertions = vec![
+            a.well_formed(),
+            b.well_formed(),
+            a.contains(x.clone()),
+            b.contains(y.clone()),
+            defined,
+            !result.contains(r.clone()),
+        ];
+        Self {
+            width,
+            a,
+            b,
+            x,
+            y,
+            r,
+            result,
+            assertions,
+        }
+    }
+
+    /// 由变量取值（例如求解器的模型）还原反例
+    pub fn counterexample(&self, env: &Env) -> Counterexample {
+        let w = self.width;
+        Counterexample {
+            a: self.a.eval(env, w),
+            b: self.b.eval(env, w),
+            x: self.x.eval(env, w),
+            y: self.y.eval(env, w),
+            r: self.r.eval(env, w),
+            result: self.result.eval(env, w),
+        }
+    }
+
+    /// 写成独立的 SMT-LIB2 脚本，可直接交给 cvc5、bitwuzla 等求解器：unsat 即可靠
+    pub fn to_smtlib(&self, comment: &str) -> String {
+        let mut out = String::new();
+        for line in comment.lines() {
+            out += &format!("; {}\n", line);
+        }
+        out += "(set-logic QF_BV)\n";
+        for v in Var::ALL {
+            out += &format!("(declare-const {} (_ BitVec {}))\n", v.name(), self.width);
+        }
+        let mut printer = Printer::new(self.width, &self.assertions);
+        let asserts: Vec<String> = self.assertions.iter().map(|f| printer.formula(f)).collect();
+        for def in &printer.defs {
+            out += &format!("{}\n", def);
+        }
+        for f in asserts {
+            out += &format!("(assert {})\n", f);
+        }
+        out += "(check-sat)\n(get-model)\n";
+        out
+    }
+}
+
+/// op 的全符号可靠性查询，op 须在 [`SYMBOLIC_OPS`] 中
+pub fn soundness_query(name: &str, width: u32) -> Option<Query> {
+    let abs = symbolic_op(name)?;
+    let a = SymTnum::new(Term::var(Var::AValue), Term::var(Var::AMask));
+    let b = SymTnum::new(Term::var(Var::BValue), Term::var(Var::BMask));
+    let (r, defined) = concrete_term(name, Term::var(Var::X), Term::var(Var::Y), width)?;
+    let result = abs(&a, &b, width);
+    Some(Query::new(width, a, b, result, r, defined))
+}
+
+/// 固定 a、b 的 64 位查询：F(a, b) 由 Rust 实现算出，只有 x、y 是符号
+///
+/// 适用于 [`verify::BINARY_OPS`] 中具体语义能编码的全部运算，包括各种除法。
+pub fn pair_query(name: &str, a: Tnum, b: Tnum) -> Option<Query> {
+    let op = verify::binary_op(name)?;
+    let (r, defined) = concrete_term(name, Term::var(Var::X), Term::var(Var::Y), 64)?;
+    let result = SymTnum::constant((op.abs)(&a, b));
+    Some(Query::new(
+        64,
+        SymTnum::constant(a),
+        SymTnum::constant(b),
+        result,
+        r,
+        defined,
+    ))
+}
+
+/// op 在 width 位下的全符号可靠性查询写成的 SMT-LIB2 脚本，op 须在 [`SYMBOLIC_OPS`] 中
+pub fn to_smtlib(name: &str, width: u32) -> Option<String> {
+    let query = soundness_query(name, width)?;
+    let comment = format!(
+        "{} 在 {} 位下的可靠性：unsat 即可靠，sat 时模型中 av/am、bv/bm 为 a、b，x、y 为具体值",
+        name, width
+    );
+    Some(query.to_smtlib(&comment))
+}
+
+/// 用边界输入与固定种子的随机输入对拍编码与 Rust 实现
+///
+/// 检查 64 位下符号编码的抽象结果与 [`Tnum`] 的实现相同（只对 [`SYMBOLIC_OPS`]），
+/// 以及具体语义的编码与 [`verify::BINARY_OPS`] 相同。不一致时返回说明。
+pub fn self_check(name: &str, samples: usize) -> Result<(), String> {
+    let op = verify::binary_op(name).ok_or_else(|| format!("未知运算: {}", name))?;
+    let (x, y) = (Term::var(Var::X), Term::var(Var::Y));
+    let (r, defined) =
+        concrete_term(name, x, y, 64).ok_or_else(|| format!("{} 的具体语义无法编码", name))?;
+    let query = soundness_query(name, 64);
+    let const_div = const_div_op(name);
+    let a_sym = SymTnum::new(Term::var(Var::AValue), Term::var(Var::AMask));
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    // 先对拍边界 tnum（top、0、1、符号位等）的两两组合，再对拍随机输入
+    let edges = [
+        Tnum::top(),
+        Tnum::const_val(0),
+        Tnum::const_val(1),
+        Tnum::const_val(2),
+        Tnum::const_val(u64::MAX),
+        Tnum::const_val(1 << 63),
+        Tnum::new(0, 1 << 63),
+        Tnum::new(1 << 63, 1),
+        Tnum::new(0, 3),
+    ];
+    let edge_pairs: Vec<(Tnum, Tnum)> = edges
+        .iter()
+        .flat_map(|&a| edges.iter().map(move |&b| (a, b)))
+        .collect();
+    for i in 0..edge_pairs.len() + samples {
+        // 一半用稀疏的 mask 与小数值，覆盖常数、小除数等情形
+        let sparse = i % 2 == 0;
+        let mut tnum = || {
+            let mask = if sparse {
+                next() & next() & next()
+            } else {
+                next()
+            };
+            let value = if sparse { next() % 64 } else { next() };
+            Tnum::new(value & !mask, mask)
+        };
+        let (a, b) = match edge_pairs.get(i) {
+            Some(&pair) => pair,
+            None => (tnum(), tnum()),
+        };
+        let (cx, cy) = (a.value | (next() & a.mask), b.value | (next() & b.mask));
+        let env: Env = [a.value, a.mask, b.value, b.mask, cx, cy];
+        let expected = (op.con)(cx, cy);
+        let encoded = defined.eval(&env, 64).then(|| r.eval(&env, 64));
+        if encoded != expected {
+            return Err(format!(
+                "具体语义不一致: x={:#x}, y={:#x}, 编码 {:?}, 实现 {:?}",
+                cx, cy, encoded, expected
+            ));
+        }
+        if let Some(q) = &query {
+            let (got, want) = (q.result.eval(&env, 64), (op.abs)(&a, b));
+            if got != want {
+                return Err(format!(
+                    "抽象结果不一致: a=({:#x}, {:#x}), b=({:#x}, {:#x}), 编码 ({:#x}, {:#x}), 实现 ({:#x}, {:#x})",
+                    a.value, a.mask, b.value, b.mask, got.value, got.mask, want.value, want.mask
+                ));
+            }
+        }
+        // 常数除数的编码：把 b 的 value 当作除数
+        if let Some(div) = const_div {
+            let c = b.value;
+            if let Some(sym) = div(&a_sym, c) {
+                let (got, want) = (sym.eval(&env, 64), (op.abs)(&a, Tnum::const_val(c)));
+                if got != want {
+                    return Err(format!(
+                        "常数除数编码不一致: a=({:#x}, {:#x}), c={:#x}, 编码 ({:#x}, {:#x}), 实现 ({:#x}, {:#x})",
+                        a.value, a.mask, c, got.value, got.mask, want.value, want.mask
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 常数除数下抽象除法的位向量编码，被除数是符号；除数走的路径无法编码时返回 `None`
+pub type ConstDivOp = fn(&SymTnum, u64) -> Option<SymTnum>;
+
+/// 除数为常数时能写成位向量公式的除法
+///
+/// fast_divide 的 Fast 与 General 路径用到 128 位的 tnum 乘法，128 位的位向量写成高、低两个
+/// 64 位项，查询仍是 64 位的。
+pub const CONST_DIV_OPS: &[(&str, ConstDivOp)] =
+    &[("udiv", const_udiv), ("fast_divide", const_fast_divide)];
+
+fn sym_rshift(a: &SymTnum, k: u32) -> SymTnum {
+    let k = Term::Const(k as u64);
+    SymTnum::new(
+        Term::bin(BinOp::Lshr, a.value.clone(), k.clone()),
+        Term::bin(BinOp::Lshr, a.mask.clone(), k),
+    )
+}
+
+/// 把 width 位中最高的 1 位以下全部置 1，即 `MAX >> t.leading_zeros()`
+fn smear(t: Term, width: u32) -> Term {
+    let mut t = Rc::new(t);
+    for s in [1, 2, 4, 8, 16, 32].into_iter().take_while(|&s| s < width) {
+        let shifted = Term::Bin(BinOp::Lshr, t.clone(), Rc::new(Term::Const(s as u64)));
+        t = Rc::new(Term::Bin(BinOp::Or, t, Rc::new(shifted)));
+    }
+    (*t).clone()
+}
+
+fn const_udiv(a: &SymTnum, c: u64) -> Option<SymTnum> {
+    if c == 0 {
+        return Some(SymTnum::constant(Tnum::top()));
+    }
+    if c.is_power_of_two() {
+        return Some(sym_rshift(a, c.trailing_zeros()));
+    }
+    let div = |t: Term| Term::bin(BinOp::Udiv, t, Term::Const(c));
+    let max = div(a.value.clone() + a.mask.clone());
+    Some(sym_from_range(div(a.value.clone()), max, 64))
+}
+
+fn const_fast_divide(a: &SymTnum, c: u64) -> Option<SymTnum> {
+    let q = match c {
+        0 => SymTnum::constant(Tnum::top()),
+        1 => a.clone(),
+        _ => match DividerU64::divide_by(c) {
+            // umulh(a, magic) >> shift
+            DividerU64::Fast { magic, shift } => {
+                sym_rshift(&wide_mul_const(a, magic).high(), shift as u32)
+            }
+            DividerU64::BitShift(s) => sym_rshift(a, s as u32),
+            // (a·magic_low >> 1) + (a << 63)，再右移 64 + shift 位，同 Tnum::general_divide
+            DividerU64::General { magic_low, shift } => {
+                let half = wide_mul_const(a, magic_low).lshr_const(1);
+                half.add(&WideTnum::zext(a).shl_const(63))
+                    .lshr_const(64 + shift as u32)
+                    .low()
+            }
+        },
+    };
+    Some(q)
+}
+
+/// 128 位的位向量，由高、低两个 64 位项表示
+#[derive(Debug, Clone)]
+struct Wide {
+    hi: Term,
+    lo: Term,
+}
+
+impl Wide {
+    fn constant(c: u128) -> Self {
+        Self {
+            hi: Term::Const((c >> 64) as u64),
+            lo: Term::Const(c as u64),
+        }
+    }
+
+    fn map(&self, other: &Wide, f: fn(Term, Term) -> Term) -> Self {
+        Self {
+            hi: f(self.hi.clone(), other.hi.clone()),
+            lo: f(self.lo.clone(), other.lo.clone()),
+        }
+    }
+
+    /// 低半部分的进位加到高半部分
+    fn add(&self, other: &Wide) -> Self {
+        let lo = self.lo.clone() + other.lo.clone();
+        let carry = Term::ite(
+            lo.clone().ult(self.lo.clone()),
+            Term::Const(1),
+            Term::Const(0),
+        );
+        Self {
+            hi: self.hi.clone() + other.hi.clone() + carry,
+            lo,
+        }
+    }
+
+    fn not(&self) -> Self {
+        Self {
+            hi: !self.hi.clone(),
+            lo: !self.lo.clone(),
+        }
+    }
+
+    fn shl(&self, k: u32) -> Self {
+        let shl = |t: &Term, k: u32| Term::bin(BinOp::Shl, t.clone(), Term::Const(k as u64));
+        let lshr = |t: &Term, k: u32| Term::bin(BinOp::Lshr, t.clone(), Term::Const(k as u64));
+        match k {
+            0 => self.clone(),
+            1..=63 => Self {
+                hi: shl(&self.hi, k) | lshr(&self.lo, 64 - k),
+                lo: shl(&self.lo, k),
+            },
+            _ => Self {
+                hi: shl(&self.lo, k - 64),
+                lo: Term::Const(0),
+            },
+        }
+    }
+
+    fn lshr(&self, k: u32) -> Self {
+        let shl = |t: &Term, k: u32| Term::bin(BinOp::Shl, t.clone(), Term::Const(k as u64));
+        let lshr = |t: &Term, k: u32| Term::bin(BinOp::Lshr, t.clone(), Term::Const(k as u64));
+        match k {
+            0 => self.clone(),
+            1..=63 => Self {
+                hi: lshr(&self.hi, k),
+                lo: lshr(&self.lo, k) | shl(&self.hi, 64 - k),
+            },
+            _ => Self {
+                hi: Term::Const(0),
+                lo: lshr(&self.hi, k - 64),
+            },
+        }
+    }
+}
+
+/// 符号化的 128 位 tnum，与 [`TnumU128`](crate::tnum::TnumU128) 的运算逐行对应
+#[derive(Debug, Clone)]
+struct WideTnum {
+    value: Wide,
+    mask: Wide,
+}
+
+impl WideTnum {
+    /// 64 位 tnum 零扩展，即 `Tnum::widen_u128`
+    fn zext(a: &SymTnum) -> Self {
+        let zext = |t: &Term| Wide {
+            hi: Term::Const(0),
+            lo: t.clone(),
+        };
+        Self {
+            value: zext(&a.value),
+            mask: zext(&a.mask),
+        }
+    }
+
+    fn add(&self, other: &WideTnum) -> Self {
+        let sm = self.mask.add(&other.mask);
+        let sv = self.value.add(&other.value);
+        let sigma = sm.add(&sv);
+        let chi = sigma.map(&sv, |x, y| x ^ y);
+        let mu = chi
+            .map(&self.mask, |x, y| x | y)
+            .map(&other.mask, |x, y| x | y);
+        Self {
+            value: sv.map(&mu.not(), |x, y| x & y),
+            mask: mu,
+        }
+    }
+
+    /// 常数移位
+    fn shl_const(&self, k: u32) -> Self {
+        Self {
+            value: self.value.shl(k),
+            mask: self.mask.shl(k),
+        }
+    }
+
+    fn lshr_const(&self, k: u32) -> Self {
+        Self {
+            value: self.value.lshr(k),
+            mask: self.mask.lshr(k),
+        }
+    }
+
+    /// 高 64 位，即 `TnumU128::high`
+    fn high(&self) -> SymTnum {
+        SymTnum::new(self.value.hi.clone(), self.mask.hi.clone())
+    }
+
+    /// 低 64 位，即 `TnumU128::low`
+    fn low(&self) -> SymTnum {
+        SymTnum::new(self.value.lo.clone(), self.mask.lo.clone())
+    }
+}
+
+/// `a.widen_u128().mul(TnumU128::const_val(c))`
+///
+/// a 零扩展后高 64 位已知为 0，循环 64 步后 a 为 0；第 i 步 a 的该位未知时加上 c << i，
+/// 已知时加 0（常数 c 的 mask 为 0），与实现中跳过已知为 0 的位相同。两个操作数都不是 top。
+fn wide_mul_const(a: &SymTnum, c: u64) -> WideTnum {
+    let acc_v = Wide {
+        hi: Term::bin(BinOp::Umulh, a.value.clone(), Term::Const(c)),
+        lo: a.value.clone() * Term::Const(c),
+    };
+    let zero = || Wide::constant(0);
+    let mut acc_m = WideTnum {
+        value: zero(),
+        mask: zero(),
+    };
+    for i in 0..64 {
+        let bit = |t: &Term| nonzero(t.clone() & Term::Const(1 << i));
+        let unknown = Rc::new(Formula::And(vec![!bit(&a.value), bit(&a.mask)]));
+        let shifted = Wide::constant((c as u128) << i);
+        let m = Wide {
+            hi: Term::ite(unknown.clone(), shifted.hi, Term::Const(0)),
+            lo: Term::ite(unknown, shifted.lo, Term::Const(0)),
+        };
+        acc_m = acc_m.add(&WideTnum {
+            value: zero(),
+            mask: m,
+        });
+    }
+    WideTnum {
+        value: acc_v,
+        mask: zero(),
+    }
+    .add(&acc_m)
+}
+
+pub fn const_div_op(name: &str) -> Option<ConstDivOp> {
+    CONST_DIV_OPS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|&(_, op)| op)
+}
+
+/// 常数除数 c 下的精化查询：a 符号化，断言 `F_a(a, c) ⋢ F_b(a, c)`，64 位
+#[derive(Debug, Clone)]
+pub struct RefinementQuery {
+    pub op_a: String,
+    pub op_b: String,
+    pub c: u64,
+    pub a: SymTnum,
+    pub lhs: SymTnum,
+    pub rhs: SymTnum,
+    pub assertions: Vec<Formula>,
+}
+
+/// 两个运算都在 [`CONST_DIV_OPS`] 中且 c 走的路径都能编码时才有查询
+pub fn refinement_query(op_a: &str, op_b: &str, c: u64) -> Option<RefinementQuery> {
+    let a = SymTnum::new(Term::var(Var::AValue), Term::var(Var::AMask));
+    let lhs = const_div_op(op_a)?(&a, c)?;
+    let rhs = const_div_op(op_b)?(&a, c)?;
+    let assertions = vec![a.well_formed(), !lhs.le(&rhs)];
+    Some(RefinementQuery {
+        op_a: op_a.to_string(),
+        op_b: op_b.to_string(),
+        c,
+        a,
+        lhs,
+        rhs,
+        assertions,
+    })
+}
+
+/// 精化的判定结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Refinement {
+    /// 对全部被除数成立（求解器证明）
+    Holds,
+    /// 反例：`lhs = F_a(a, c)` 不包含于 `rhs = F_b(a, c)`
+    Refuted { a: Tnum, lhs: Tnum, rhs: Tnum },
+    /// 没有找到反例，也无法证明
+    Unknown,
+}
+
+impl RefinementQuery {
+    /// 由变量取值还原被除数，两个结果由 Rust 实现算出；不构成反例时返回 `None`
+    pub fn refutation(&self, env: &Env) -> Option<Refinement> {
+        refutes(&self.op_a, &self.op_b, self.a.eval(env, 64), self.c)
+    }
+}
+
+/// a 在常数除数 c 下是否满足 `op_a(a, c) ⋢ op_b(a, c)`
+fn refutes(op_a: &str, op_b: &str, a: Tnum, c: u64) -> Option<Refinement> {
+    let (fa, fb) = (verify::binary_op(op_a)?, verify::binary_op(op_b)?);
+    let divisor = Tnum::const_val(c);
+    let (lhs, rhs) = ((fa.abs)(&a, divisor), (fb.abs)(&a, divisor));
+    (!lhs.le(&rhs)).then_some(Refinement::Refuted { a, lhs, rhs })
+}
+
+/// 判定常数除数 c 下 `op_a(a, c) ⊑ op_b(a, c)` 是否对全部被除数 a 成立
+///
+/// 先在 value、mask 都不超过 `search_max` 的被除数上枚举反例；找不到时，
+/// 能编码的除数交给 Z3 证明或给出模型。运算不在 [`verify::BINARY_OPS`] 中时返回 `None`。
+pub fn prove_refinement(op_a: &str, op_b: &str, c: u64, search_max: u64) -> Option<Refinement> {
+    verify::binary_op(op_a)?;
+    verify::binary_op(op_b)?;
+    for value in 0..=search_max {
+        for mask in (0..=search_max).filter(|m| m & value == 0) {
+            if let Some(r) = refutes(op_a, op_b, Tnum::new(value, mask), c) {
+                return Some(r);
+            }
+        }
+    }
+    let Some(query) = refinement_query(op_a, op_b, c) else {
+        return Some(Refinement::Unknown);
+    };
+    #[cfg(feature = "z3")]
+    {
+        Some(solve_refinement(&query))
+    }
+    #[cfg(not(feature = "z3"))]
+    {
+        let _ = query;
+        Some(Refinement::Unknown)
+    }
+}
+
+/// 用 Z3 求解精化查询
+#[cfg(feature = "z3")]
+pub fn solve_refinement(query: &RefinementQuery) -> Refinement {
+    match check(&query.assertions, 64) {
+        Outcome::Unsat => Refinement::Holds,
+        Outcome::Unknown => Refinement::Unknown,
+        Outcome::Sat(env) => query.refutation(&env).expect("模型应是 Rust 实现下的反例"),
+    }
+}
+
+/// fastdivide 中 [`DividerU64::divide`] 的位向量编码，64 位
+///
+/// General 路径的 magic 有 65 位，只存低 64 位，按 fastdivide 的做法补回最高位。
+pub fn divider_term(divider: DividerU64, n: Term) -> Term {
+    let shr = |t: Term, s: u8| Term::bin(BinOp::Lshr, t, Term::Const(s as u64));
+    match divider {
+        DividerU64::Fast { magic, shift } => {
+            shr(Term::bin(BinOp::Umulh, Term::Const(magic), n), shift)
+        }
+        DividerU64::BitShift(shift) => shr(n, shift),
+        DividerU64::General { magic_low, shift } => {
+            let q = Term::bin(BinOp::Umulh, Term::Const(magic_low), n.clone());
+            shr(shr(n - q.clone(), 1) + q, shift)
+        }
+    }
+}
+
+/// 除数 d 的 [`DividerU64`] 分解的正确性查询：被除数为 x，断言分解的结果不等于 `x / d`
+///
+/// 不可满足即对全部 64 位被除数成立。d 为 0 时没有分解，返回 `None`。
+pub fn divider_query(d: u64) -> Option<Formula> {
+    if d == 0 {
+        return None;
+    }
+    let n = Term::var(Var::X);
+    let quotient = Term::bin(BinOp::Udiv, n.clone(), Term::Const(d));
+    Some(!divider_term(DividerU64::divide_by(d), n).eq(quotient))
+}
+
+/// 对拍 [`divider_term`] 与 [`DividerU64::divide`]：边界被除数加上固定种子的随机被除数
+pub fn divider_self_check(d: u64, samples: usize) -> Result<(), String> {
+    let divider = DividerU64::divide_by(d);
+    let term = divider_term(divider, Term::var(Var::X));
+    let mut state = 0x2545_f491_4f6c_dd1du64 ^ d;
+    let boundary = [0, 1, d - 1, d, d.wrapping_add(1), u64::MAX - 1, u64::MAX];
+    let random = (0..samples).map(|_| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    });
+    for n in boundary.into_iter().chain(random) {
+        let env: Env = [0, 0, 0, 0, n, 0];
+        let (got, want) = (term.eval(&env, 64), divider.divide(n));
+        if got != want {
+            return Err(format!(
+                "d={} 的分解编码不一致: n={:#x}, 编码 {:#x}, 实现 {:#x}",
+                d, n, got, want
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 分解的判定结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DividerVerdict {
+    /// 对全部被除数都等于 n / d
+    Correct,
+    /// 反例：分解的结果 got 不等于 n / d
+    Wrong {
+        n: u64,
+        got: u64,
+    },
+    Unknown,
+}
+
+/// 用 Z3 证明 d 的分解对全部 64 位被除数正确
+#[cfg(feature = "z3")]
+pub fn prove_divider(d: u64) -> Option<DividerVerdict> {
+    let query = divider_query(d)?;
+    Some(match check(&[query], 64) {
+        Outcome::Unsat => DividerVerdict::Correct,
+        Outcome::Unknown => DividerVerdict::Unknown,
+        Outcome::Sat(env) => {
+            let n = env[Var::X as usize];
+            DividerVerdict::Wrong {
+                n,
+                got: DividerU64::divide_by(d).divide(n),
+            }
+        }
+    })
+}
+
+/// 求解结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// 不可满足：没有反例
+    Sound,
+    Unsound(Counterexample),
+    /// 求解器无法判定
+    Unknown,
+}
+
+/// 用 Z3 求解查询
+#[cfg(feature = "z3")]
+pub fn solve(query: &Query) -> Verdict {
+    match check(&query.assertions, query.width) {
+        Outcome::Unsat => Verdict::Sound,
+        Outcome::Unknown => Verdict::Unknown,
+        Outcome::Sat(env) => Verdict::Unsound(query.counterexample(&env)),
+    }
+}
+
+#[cfg(feature = "z3")]
+enum Outcome {
+    Unsat,
+    Sat(Env),
+    Unknown,
+}
+
+#[cfg(feature = "z3")]
+fn check(assertions: &[Formula], width: u32) -> Outcome {
+    use z3::ast::BV;
+    use z3::{Config, Context, SatResult, Solver};
+
+    let cfg = Config::new();
+    let ctx = Context::new(&cfg);
+    let solver = Solver::new(&ctx);
+    let mut encoder = Z3Encoder {
+        ctx: &ctx,
+        width,
+        terms: HashMap::new(),
+        formulas: HashMap::new(),
+    };
+    for f in assertions {
+        solver.assert(&encoder.boolean(f));
+    }
+    match solver.check() {
+        SatResult::Unsat => Outcome::Unsat,
+        SatResult::Unknown => Outcome::Unknown,
+        SatResult::Sat => {
+            let model = solver.get_model().expect("sat 时应有模型");
+            let mut env: Env = [0; 6];
+            for (slot, v) in env.iter_mut().zip(Var::ALL) {
+                let var = BV::new_const(&ctx, v.name(), width);
+                *slot = model.eval(&var, true).and_then(|v| v.as_u64()).unwrap_or(0);
+            }
+            Outcome::Sat(env)
+        }
+    }
+}
+
+/// 把 [`Term`] / [`Formula`] 转成 Z3 的 AST，共享的子项只转换一次
+#[cfg(feature = "z3")]
+struct Z3Encoder<'c> {
+    ctx: &'c z3::Context,
+    width: u32,
+    terms: HashMap<*const Term, z3::ast::BV<'c>>,
+    formulas: HashMap<*const Formula, z3::ast::Bool<'c>>,
+}
+
+#[cfg(feature = "z3")]
+impl<'c> Z3Encoder<'c> {
+    fn bv(&mut self, t: &Term) -> z3::ast::BV<'c> {
+        use z3::ast::BV;
+
+        let key = t as *const Term;
+        if let Some(r) = self.terms.get(&key) {
+            return r.clone();
+        }
+        let (ctx, width) = (self.ctx, self.width);
+        let r = match t {
+            Term::Var(v) => BV::new_const(ctx, v.name(), width),
+            Term::Const(c) => BV::from_u64(ctx, c & width_mask(width), width),
+            Term::Not(a) => self.bv(a).bvnot(),
+            Term::Neg(a) => self.bv(a).bvneg(),
+            Term::Ite(c, a, b) => {
+                let c = self.boolean(c);
+                c.ite(&self.bv(a), &self.bv(b))
+            }
+            Term::Bin(op, a, b) => {
+                let (a, b) = (self.bv(a), self.bv(b));
+                match op {
+                    BinOp::And => a.bvand(&b),
+                    BinOp::Or => a.bvor(&b),
+                    BinOp::Xor => a.bvxor(&b),
+                    BinOp::Add => a.bvadd(&b),
+                    BinOp::Sub => a.bvsub(&b),
+                    BinOp::Mul => a.bvmul(&b),
+                    BinOp::Udiv => a.bvudiv(&b),
+                    BinOp::Urem => a.bvurem(&b),
+                    BinOp::Sdiv => a.bvsdiv(&b),
+                    BinOp::Srem => a.bvsrem(&b),
+                    BinOp::Shl => a.bvshl(&b),
+                    BinOp::Lshr => a.bvlshr(&b),
+                    BinOp::Ashr => a.bvashr(&b),
+                    BinOp::Umulh => a
+                        .zero_ext(width)
+                        .bvmul(&b.zero_ext(width))
+                        .extract(2 * width - 1, width),
+                }
+            }
+        };
+        self.terms.insert(key, r.clone());
+        r
+    }
+
+    fn boolean(&mut self, f: &Formula) -> z3::ast::Bool<'c> {
+        use z3::ast::{Ast, Bool};
+
+        let key = f as *const Formula;
+        if let Some(r) = self.formulas.get(&key) {
+            return r.clone();
+        }
+        let ctx = self.ctx;
+        let r = match f {
+            Formula::True => Bool::from_bool(ctx, true),
+            Formula::Eq(a, b) => self.bv(a)._eq(&self.bv(b)),
+            Formula::Ult(a, b) => self.bv(a).bvult(&self.bv(b)),
+            Formula::Slt(a, b) => self.bv(a).bvslt(&self.bv(b)),
+            Formula::Not(g) => self.boolean(g).not(),
+            Formula::And(fs) => {
+                let v: Vec<Bool<'c>> = fs.iter().map(|g| self.boolean(g)).collect();
+                Bool::and(ctx, &v.iter().collect::<Vec<_>>())
+            }
+            Formula::Or(fs) => {
+                let v: Vec<Bool<'c>> = fs.iter().map(|g| self.boolean(g)).collect();
+                Bool::or(ctx, &v.iter().collect::<Vec<_>>())
+            }
+        };
+        self.formulas.insert(key, r.clone());
+        r
+    }
+}
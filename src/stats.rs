@@ -0,0 +1,111 @@
+//! 转移函数的全局统计
+//!
+//! 默认关闭，调用 [`enable`] 后各转移函数才会计数。计数器是原子变量，
+//! 可在多线程枚举中共享；报告时用 [`snapshot`] 读取，用 [`reset`] 清零。
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// 计数项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Counter {
+    /// fast_divide 走 magic 乘法（Fast）分支
+    FastDivideFast,
+    /// fast_divide 走 2 的幂（BitShift）分支
+    FastDivideBitShift,
+    /// fast_divide 走 General 分支
+    FastDivideGeneral,
+    /// fast_divide 对少量未知位的除数按具体值分情况求 join
+    FastDivideSplit,
+    /// fast_divide 因除数不是常数回退到 sdiv
+    FastDivideFallback,
+    /// 除数可能为 0，除法/取余直接返回 top
+    DivisorZeroTop,
+    /// 具体值枚举或分情况的组合数超出预算，改走较不精确的路径
+    BudgetExceeded,
+    /// 转移函数放弃精度直接返回 top（含操作数为 top 的提前返回）
+    TopProduced,
+}
+
+impl Counter {
+    pub const ALL: [Counter; 8] = [
+        Counter::FastDivideFast,
+        Counter::FastDivideBitShift,
+        Counter::FastDivideGeneral,
+        Counter::FastDivideSplit,
+        Counter::FastDivideFallback,
+        Counter::DivisorZeroTop,
+        Counter::BudgetExceeded,
+        Counter::TopProduced,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Counter::FastDivideFast => "fast_divide/fast",
+            Counter::FastDivideBitShift => "fast_divide/bitshift",
+            Counter::FastDivideGeneral => "fast_divide/general",
+            Counter::FastDivideSplit => "fast_divide/split",
+            Counter::FastDivideFallback => "fast_divide/fallback",
+            Counter::DivisorZeroTop => "div/divisor_zero_top",
+            Counter::BudgetExceeded => "budget_exceeded",
+            Counter::TopProduced => "top_produced",
+        }
+    }
+}
+
+const COUNTERS: usize = Counter::ALL.len();
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static COUNTS: [AtomicU64; COUNTERS] = [const { AtomicU64::new(0) }; COUNTERS];
+
+/// 开启统计
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// 关闭统计（已有计数保留）
+pub fn disable() {
+    ENABLED.store(false, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// 计数一次；统计关闭时只有一次原子读的开销
+pub fn record(counter: Counter) {
+    if is_enabled() {
+        COUNTS[counter as usize].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// 所有计数器清零
+pub fn reset() {
+    for count in &COUNTS {
+        count.store(0, Ordering::Relaxed);
+    }
+}
+
+/// 某一时刻的计数快照
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    counts: [u64; COUNTERS],
+}
+
+impl Snapshot {
+    pub fn get(&self, counter: Counter) -> u64 {
+        self.counts[counter as usize]
+    }
+
+    /// 按 `Counter::ALL` 的顺序迭代 (计数项, 计数)
+    pub fn iter(&self) -> impl Iterator<Item = (Counter, u64)> + '_ {
+        Counter::ALL.iter().map(move |&c| (c, self.get(c)))
+    }
+}
+
+pub fn snapshot() -> Snapshot {
+    let mut counts = [0u64; COUNTERS];
+    for (dst, src) in counts.iter_mut().zip(&COUNTS) {
+        *dst = src.load(Ordering::Relaxed);
+    }
+    Snapshot { counts }
+}
@@ -0,0 +1,471 @@
+//! 基于模板的转移函数综合（实验性）
+//!
+//! 在 8 位宽度下自底向上枚举由 value/mask 组成的位运算表达式，
+//! 把表达式为 1 的结果位当作额外的已知位叠加到现有转移函数上。
+//! 先用样本快速过滤，再用 Z3 证明可靠性，最后输出 `Tnum<u8>` 上的候选 Rust 代码供人工审查。
+//!
+//! 现有转移函数按 8 位语义比较：有符号运算的操作数符号扩展，结果截断到 8 位。
+//! 只证明可靠性；「更精确」指与现有结果取 meet 后不会更差，且样本中有具体的输入多确定了位，
+//! 并不是对全部输入都更精确。
+use tnum_z3_verification::tnum::{for_each_concrete, Tnum};
+use std::collections::HashSet;
+use z3::ast::{Ast, Bool, BV};
+use z3::{Config, Context, SatResult, Solver};
+
+const WIDTH: u32 = 8;
+const WMASK: u64 = 0xff;
+const SAMPLES: usize = 512;
+
+/// 可综合的目标运算
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SynthOp {
+    Urem,
+    Srem,
+    Udiv,
+}
+
+impl SynthOp {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "urem" => Some(SynthOp::Urem),
+            "srem" => Some(SynthOp::Srem),
+            "udiv" => Some(SynthOp::Udiv),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SynthOp::Urem => "urem",
+            SynthOp::Srem => "srem",
+            SynthOp::Udiv => "udiv",
+        }
+    }
+
+    /// 8 位具体语义，除以 0 无定义
+    fn concrete(self, x: u64, y: u64) -> Option<u64> {
+        if y & WMASK == 0 {
+            return None;
+        }
+        let (x8, y8) = (x as u8, y as u8);
+        let r = match self {
+            SynthOp::Urem => x8 % y8,
+            SynthOp::Srem => (x8 as i8).wrapping_rem(y8 as i8) as u8,
+            SynthOp::Udiv => x8 / y8,
+        };
+        Some(r as u64)
+    }
+
+    /// 现有的 64 位转移函数按 8 位语义求值：无符号运算零扩展、有符号运算符号扩展，结果截断到 8 位
+    fn current(self, a: Tnum, b: Tnum) -> Tnum {
+        let r = match self {
+            SynthOp::Urem => a.urem(b),
+            SynthOp::Srem => a.sign_extend(8).srem(b.sign_extend(8)),
+            SynthOp::Udiv => a.udiv(b),
+        };
+        Tnum::new(r.value & WMASK, r.mask & WMASK)
+    }
+
+    /// 与 [`SynthOp::current`] 相同的计算，`widen` 为扩展到 64 位的闭包
+    fn current_rust(self) -> String {
+        let widen = match self {
+            SynthOp::Srem => "Tnum::new(t.value as u64, t.mask as u64).sign_extend(8)",
+            SynthOp::Urem | SynthOp::Udiv => "Tnum::new(t.value as u64, t.mask as u64)",
+        };
+        format!(
+            "let widen = |t: &Self| {};\n    let res = widen(self).{}(widen(&other));\n    let res = Self::new(res.value as u8, res.mask as u8);",
+            widen,
+            self.name()
+        )
+    }
+
+    fn encode<'c>(self, x: &BV<'c>, y: &BV<'c>) -> BV<'c> {
+        match self {
+            SynthOp::Urem => x.bvurem(y),
+            SynthOp::Srem => x.bvsrem(y),
+            SynthOp::Udiv => x.bvudiv(y),
+        }
+    }
+}
+
+/// 候选表达式声称的已知位种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Claim {
+    /// 表达式为 1 的位在结果中恒为 0
+    Zero,
+    /// 表达式为 1 的位在结果中恒为 1
+    One,
+}
+
+/// 模板空间中的表达式
+#[derive(Debug, Clone)]
+enum Expr {
+    AValue,
+    AMask,
+    BValue,
+    BMask,
+    Not(Box<Expr>),
+    Shl(Box<Expr>, u32),
+    Lshr(Box<Expr>, u32),
+    /// 把最高位的 1 向右填满
+    Smear(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+}
+
+fn smear(mut x: u64) -> u64 {
+    x |= x >> 1;
+    x |= x >> 2;
+    x |= x >> 4;
+    x
+}
+
+impl Expr {
+    fn eval(&self, a: Tnum, b: Tnum) -> u64 {
+        let r = match self {
+            Expr::AValue => a.value,
+            Expr::AMask => a.mask,
+            Expr::BValue => b.value,
+            Expr::BMask => b.mask,
+            Expr::Not(e) => !e.eval(a, b),
+            Expr::Shl(e, s) => e.eval(a, b) << s,
+            Expr::Lshr(e, s) => e.eval(a, b) >> s,
+            Expr::Smear(e) => smear(e.eval(a, b)),
+            Expr::And(l, r) => l.eval(a, b) & r.eval(a, b),
+            Expr::Or(l, r) => l.eval(a, b) | r.eval(a, b),
+            Expr::Add(l, r) => l.eval(a, b).wrapping_add(r.eval(a, b)),
+            Expr::Sub(l, r) => l.eval(a, b).wrapping_sub(r.eval(a, b)),
+        };
+        r & WMASK
+    }
+
+    fn to_z3<'c>(&self, ctx: &'c Context, v: &[BV<'c>; 4]) -> BV<'c> {
+        match self {
+            Expr::AValue => v[0].clone(),
+            Expr::AMask => v[1].clone(),
+            Expr::BValue => v[2].clone(),
+            Expr::BMask => v[3].clone(),
+            Expr::Not(e) => e.to_z3(ctx, v).bvnot(),
+            Expr::Shl(e, s) => e.to_z3(ctx, v).bvshl(&BV::from_u64(ctx, *s as u64, WIDTH)),
+            Expr::Lshr(e, s) => e.to_z3(ctx, v).bvlshr(&BV::from_u64(ctx, *s as u64, WIDTH)),
+            Expr::Smear(e) => {
+                let mut x = e.to_z3(ctx, v);
+                for s in [1u64, 2, 4] {
+                    x = x.bvor(&x.bvlshr(&BV::from_u64(ctx, s, WIDTH)));
+                }
+                x
+            }
+            Expr::And(l, r) => l.to_z3(ctx, v).bvand(&r.to_z3(ctx, v)),
+            Expr::Or(l, r) => l.to_z3(ctx, v).bvor(&r.to_z3(ctx, v)),
+            Expr::Add(l, r) => l.to_z3(ctx, v).bvadd(&r.to_z3(ctx, v)),
+            Expr::Sub(l, r) => l.to_z3(ctx, v).bvsub(&r.to_z3(ctx, v)),
+        }
+    }
+
+    fn to_rust(&self) -> String {
+        match self {
+            Expr::AValue => "self.value".to_string(),
+            Expr::AMask => "self.mask".to_string(),
+            Expr::BValue => "other.value".to_string(),
+            Expr::BMask => "other.mask".to_string(),
+            Expr::Not(e) => format!("(!{})", e.to_rust()),
+            Expr::Shl(e, s) => format!("({} << {})", e.to_rust(), s),
+            Expr::Lshr(e, s) => format!("({} >> {})", e.to_rust(), s),
+            Expr::Smear(e) => format!(
+                "u8::MAX.checked_shr({}.leading_zeros()).unwrap_or(0)",
+                e.to_rust()
+            ),
+            Expr::And(l, r) => format!("({} & {})", l.to_rust(), r.to_rust()),
+            Expr::Or(l, r) => format!("({} | {})", l.to_rust(), r.to_rust()),
+            Expr::Add(l, r) => format!("{}.wrapping_add({})", l.to_rust(), r.to_rust()),
+            Expr::Sub(l, r) => format!("{}.wrapping_sub({})", l.to_rust(), r.to_rust()),
+        }
+    }
+}
+
+/// 一个 8 位样本：输入 tnum 对（除数不可能为 0）、所有具体结果中恒为 0/1 的位，以及现有算法的结果
+struct Sample {
+    a: Tnum,
+    b: Tnum,
+    always_zero: u64,
+    always_one: u64,
+    current: Tnum,
+}
+
+/// 简单的 xorshift 伪随机数，保证每次运行的样本一致
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn tnum8(&mut self) -> Tnum {
+        let mask = self.next() & self.next() & WMASK;
+        Tnum::new(self.next() & !mask & WMASK, mask)
+    }
+}
+
+fn build_samples(op: SynthOp) -> Vec<Sample> {
+    let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+    let mut samples = Vec::with_capacity(SAMPLES);
+    while samples.len() < SAMPLES {
+        let (a, b) = (rng.tnum8(), rng.tnum8());
+        // 候选代码在除数可能为 0 时不做细化，这样的样本不能体现改进
+        if b.value == 0 {
+            continue;
+        }
+        let mut always_zero = WMASK;
+        let mut always_one = WMASK;
+        for_each_concrete(&a, |x| {
+            for_each_concrete(&b, |y| {
+                if let Some(r) = op.concrete(x, y) {
+                    always_zero &= !r;
+                    always_one &= r;
+                }
+            })
+        });
+        let current = op.current(a, b);
+        samples.push(Sample {
+            a,
+            b,
+            always_zero,
+            always_one,
+            current,
+        });
+    }
+    samples
+}
+
+/// 把候选声称的已知位叠加到现有结果上，与 [`emit_rust`] 输出的代码相同
+fn apply_claim(res: Tnum, known: u64, claim: Claim) -> Tnum {
+    match claim {
+        Claim::Zero => Tnum::new(res.value & !known, res.mask & !known),
+        Claim::One => Tnum::new(res.value | known, res.mask & !known),
+    }
+}
+
+/// 在样本上评估候选：样本中不可靠返回 `None`，否则返回改进的样本数
+fn score(e: &Expr, claim: Claim, samples: &[Sample]) -> Option<usize> {
+    let mut improved = 0;
+    for s in samples {
+        let k = e.eval(s.a, s.b);
+        let allowed = match claim {
+            Claim::Zero => s.always_zero,
+            Claim::One => s.always_one,
+        };
+        if k & !allowed != 0 {
+            return None;
+        }
+        if k & s.current.mask != 0 {
+            improved += 1;
+        }
+    }
+    Some(improved)
+}
+
+/// 自底向上枚举表达式，按样本上的取值去重（观察等价）
+fn enumerate(max_size: usize, samples: &[Sample]) -> Vec<Expr> {
+    let mut by_size: Vec<Vec<Expr>> = vec![Vec::new(); max_size + 1];
+    let mut seen: HashSet<Vec<u8>> = HashSet::new();
+    let mut push = |e: Expr, bucket: &mut Vec<Expr>| {
+        let sig: Vec<u8> = samples.iter().map(|s| e.eval(s.a, s.b) as u8).collect();
+        if seen.insert(sig) {
+            bucket.push(e);
+        }
+    };
+
+    for leaf in [Expr::AValue, Expr::AMask, Expr::BValue, Expr::BMask] {
+        push(leaf, &mut by_size[1]);
+    }
+    for size in 2..=max_size {
+        let mut bucket = Vec::new();
+        for e in by_size[size - 1].clone() {
+            push(Expr::Not(Box::new(e.clone())), &mut bucket);
+            push(Expr::Smear(Box::new(e.clone())), &mut bucket);
+            for s in 1..WIDTH {
+                push(Expr::Shl(Box::new(e.clone()), s), &mut bucket);
+                push(Expr::Lshr(Box::new(e.clone()), s), &mut bucket);
+            }
+        }
+        for ls in 1..size - 1 {
+            let rs = size - 1 - ls;
+            for (i, l) in by_size[ls].clone().iter().enumerate() {
+                for (j, r) in by_size[rs].clone().iter().enumerate() {
+                    let (l, r) = (Box::new(l.clone()), Box::new(r.clone()));
+                    push(Expr::Sub(l.clone(), r.clone()), &mut bucket);
+                    // 交换律运算只保留一种顺序
+                    if ls < rs || (ls == rs && i <= j) {
+                        push(Expr::And(l.clone(), r.clone()), &mut bucket);
+                        push(Expr::Or(l.clone(), r.clone()), &mut bucket);
+                        push(Expr::Add(l, r), &mut bucket);
+                    }
+                }
+            }
+        }
+        by_size[size] = bucket;
+    }
+    by_size.into_iter().flatten().collect()
+}
+
+/// 用 Z3 证明候选在全部 8 位输入上可靠；不可靠时返回反例 (a, b, x, y)
+fn prove(op: SynthOp, e: &Expr, claim: Claim) -> Result<(), (Tnum, Tnum, u64, u64)> {
+    let cfg = Config::new();
+    let ctx = Context::new(&cfg);
+    let solver = Solver::new(&ctx);
+    let bv = |name: &str| BV::new_const(&ctx, name, WIDTH);
+    let zero = BV::from_u64(&ctx, 0, WIDTH);
+    let vars = [bv("av"), bv("am"), bv("bv"), bv("bm")];
+    let (x, y) = (bv("x"), bv("y"));
+
+    // x ∈ γ(a), y ∈ γ(b), y ≠ 0
+    let well_formed = Bool::and(
+        &ctx,
+        &[
+            &vars[0].bvand(&vars[1])._eq(&zero),
+            &vars[2].bvand(&vars[3])._eq(&zero),
+            &x.bvand(&vars[1].bvnot())._eq(&vars[0]),
+            &y.bvand(&vars[3].bvnot())._eq(&vars[2]),
+            &y._eq(&zero).not(),
+        ],
+    );
+    solver.assert(&well_formed);
+
+    let r = op.encode(&x, &y);
+    let k = e.to_z3(&ctx, &vars);
+    let holds = match claim {
+        Claim::Zero => r.bvand(&k)._eq(&zero),
+        Claim::One => r.bvand(&k)._eq(&k),
+    };
+    solver.assert(&holds.not());
+
+    match solver.check() {
+        SatResult::Unsat => Ok(()),
+        _ => {
+            let model = solver.get_model().expect("sat 时应有模型");
+            let get = |v: &BV| model.eval(v, true).and_then(|v| v.as_u64()).unwrap_or(0);
+            Err((
+                Tnum::new(get(&vars[0]), get(&vars[1])),
+                Tnum::new(get(&vars[2]), get(&vars[3])),
+                get(&x),
+                get(&y),
+            ))
+        }
+    }
+}
+
+/// 输出放在 `impl Tnum<u8>` 中的候选：与 Z3 证明的是同一个 8 位函数，除数可能为 0 时不做细化
+fn emit_rust(op: SynthOp, e: &Expr, claim: Claim, index: usize) -> String {
+    let apply = match claim {
+        Claim::Zero => "Self::new(res.value & !known, res.mask & !known)",
+        Claim::One => "Self::new(res.value | known, res.mask & !known)",
+    };
+    format!(
+        "pub fn {op}_synth_{index}(&self, other: Self) -> Self {{\n    {current}\n    if other.value == 0 {{\n        return res;\n    }}\n    let known = {expr};\n    {apply}\n}}",
+        op = op.name(),
+        index = index,
+        current = op.current_rust(),
+        expr = e.to_rust(),
+        apply = apply
+    )
+}
+
+/// 候选在样本中第一个多确定了位的输入
+fn first_improved<'s>(e: &Expr, samples: &'s [Sample]) -> Option<&'s Sample> {
+    samples
+        .iter()
+        .find(|s| e.eval(s.a, s.b) & s.current.mask != 0)
+}
+
+/// 综合模式：`synth <urem|srem|udiv> [max_size]`
+pub fn run_synth_mode(args: &[String]) {
+    let usage = "用法: synth <urem|srem|udiv> [max_size]";
+    let op = match args.first().and_then(|s| SynthOp::from_name(s)) {
+        Some(op) => op,
+        None => {
+            println!("{}", usage);
+            return;
+        }
+    };
+    let max_size = match args.get(1).map(|s| s.parse::<usize>()) {
+        None => 5,
+        Some(Ok(n)) if n >= 1 => n,
+        Some(_) => {
+            println!("{}", usage);
+            return;
+        }
+    };
+
+    println!(
+        "=== 综合 {} 的改进转移函数（{} 位，表达式规模 ≤ {}）===",
+        op.name(),
+        WIDTH,
+        max_size
+    );
+    let samples = build_samples(op);
+    let exprs = enumerate(max_size, &samples);
+    println!("模板空间（去重后）: {} 个表达式", exprs.len());
+
+    let mut candidates: Vec<(usize, Claim, &Expr)> = Vec::new();
+    for e in &exprs {
+        for claim in [Claim::Zero, Claim::One] {
+            if let Some(improved) = score(e, claim, &samples) {
+                if improved > 0 {
+                    candidates.push((improved, claim, e));
+                }
+            }
+        }
+    }
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.0));
+    println!("样本过滤后剩余候选: {}", candidates.len());
+
+    // Z3 给出的反例会用于过滤后续候选
+    let mut counterexamples: Vec<(Tnum, Tnum, u64, u64)> = Vec::new();
+    let mut emitted = 0;
+    for (improved, claim, e) in candidates {
+        if emitted == 5 {
+            break;
+        }
+        let refuted = counterexamples.iter().any(|&(a, b, x, y)| {
+            let k = e.eval(a, b);
+            let r = op.concrete(x, y).unwrap_or(0);
+            match claim {
+                Claim::Zero => r & k != 0,
+                Claim::One => r & k != k,
+            }
+        });
+        if refuted {
+            continue;
+        }
+        match prove(op, e, claim) {
+            Ok(()) => {
+                emitted += 1;
+                let s = first_improved(e, &samples).expect("候选至少改进一个样本");
+                println!();
+                println!(
+                    "// 候选 {}: Z3 证明 8 位下可靠；样本中 {}/{} 对多确定了位（未证明），如 a={:8}, b={:8}: {:8} -> {:8}",
+                    emitted,
+                    improved,
+                    samples.len(),
+                    s.a,
+                    s.b,
+                    s.current,
+                    apply_claim(s.current, e.eval(s.a, s.b), claim)
+                );
+                println!("// impl Tnum<u8>");
+                println!("{}", emit_rust(op, e, claim, emitted));
+            }
+            Err(cex) => counterexamples.push(cex),
+        }
+    }
+    println!();
+    println!("Z3 反例数: {}", counterexamples.len());
+    if emitted == 0 {
+        println!("未找到比现有 {} 更精确的可靠候选", op.name());
+    }
+}
@@ -0,0 +1,147 @@
+//! 大规模结果表的紧凑二进制格式
+//!
+//! 文件是一个 gzip 压缩流，内容依次为 8 字节魔数、一个 [`Header`] 和若干条记录，
+//! 均使用 bincode 的变长整数编码。记录逐条写入、逐条读出，
+//! 不需要把整张表（如 16 位穷举的真值表）放进内存。
+use crate::tnum::Tnum;
+use bincode::Options;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"TNUMTBL1";
+
+/// 表头
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Header {
+    /// 记录种类，如 "truth-table"、"snapshot"、"checkpoint"
+    pub kind: String,
+    /// 运算名称
+    pub op: String,
+    /// 操作数位宽
+    pub width: u32,
+}
+
+/// 真值表中的一条记录：op(a, b) = result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TruthEntry {
+    pub a: Tnum,
+    pub b: Tnum,
+    pub result: Tnum,
+}
+
+fn options() -> impl Options {
+    bincode::DefaultOptions::new()
+}
+
+fn invalid_data(e: bincode::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// 流式写入器
+pub struct TableWriter<W: Write, T> {
+    enc: GzEncoder<W>,
+    count: u64,
+    record: PhantomData<T>,
+}
+
+impl<T: Serialize> TableWriter<BufWriter<File>, T> {
+    pub fn create<P: AsRef<Path>>(path: P, header: &Header) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), header)
+    }
+}
+
+impl<W: Write, T: Serialize> TableWriter<W, T> {
+    pub fn new(w: W, header: &Header) -> io::Result<Self> {
+        let mut enc = GzEncoder::new(w, Compression::default());
+        enc.write_all(MAGIC)?;
+        options()
+            .serialize_into(&mut enc, header)
+            .map_err(invalid_data)?;
+        Ok(Self {
+            enc,
+            count: 0,
+            record: PhantomData,
+        })
+    }
+
+    pub fn write(&mut self, record: &T) -> io::Result<()> {
+        options()
+            .serialize_into(&mut self.enc, record)
+            .map_err(invalid_data)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// 已写入的记录数
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// 结束压缩流并返回底层写入器
+    pub fn finish(self) -> io::Result<W> {
+        let mut w = self.enc.finish()?;
+        w.flush()?;
+        Ok(w)
+    }
+}
+
+/// 流式读取器，按顺序迭代所有记录
+pub struct TableReader<R: Read, T> {
+    dec: BufReader<GzDecoder<R>>,
+    header: Header,
+    record: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> TableReader<BufReader<File>, T> {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read, T: DeserializeOwned> TableReader<R, T> {
+    pub fn new(r: R) -> io::Result<Self> {
+        let mut dec = BufReader::new(GzDecoder::new(r));
+        let mut magic = [0u8; 8];
+        dec.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "不是 tnum 二进制结果表",
+            ));
+        }
+        let header = options().deserialize_from(&mut dec).map_err(invalid_data)?;
+        Ok(Self {
+            dec,
+            header,
+            record: PhantomData,
+        })
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+}
+
+impl<R: Read, T: DeserializeOwned> Iterator for TableReader<R, T> {
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.dec.fill_buf() {
+            Ok([]) => return None,
+            Ok(_) => {}
+            Err(e) => return Some(Err(e)),
+        }
+        Some(
+            options()
+                .deserialize_from(&mut self.dec)
+                .map_err(invalid_data),
+        )
+    }
+}
@@ -0,0 +1,39 @@
+//! 基于 proptest 的 tnum 生成器，供本仓库与下游 crate 的性质测试复用
+//!
+//! [`Tnum`] 的 [`Arbitrary`] 实现保证 `value & mask == 0`，并偏向生成单点、top、
+//! 只有一个未知位和低位连续未知的 tnum，这些是转移函数中特殊分支最多的情形。
+use crate::tnum::Tnum;
+use proptest::prelude::*;
+
+impl Arbitrary for Tnum {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Tnum>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        let make = |value: u64, mask: u64| Tnum::new(value & !mask, mask);
+        prop_oneof![
+            // 单点
+            2 => any::<u64>().prop_map(Tnum::const_val),
+            1 => Just(Tnum::top()),
+            // 一个未知位
+            2 => (any::<u64>(), 0..64u32).prop_map(move |(v, i)| make(v, 1 << i)),
+            // 低位连续未知，如对齐的指针或小范围的值
+            2 => (any::<u64>(), 1..64u32).prop_map(move |(v, k)| make(v, u64::MAX >> (64 - k))),
+            // 稀疏的 mask
+            3 => (any::<u64>(), any::<u64>(), any::<u64>())
+                .prop_map(move |(v, m1, m2)| make(v, m1 & m2)),
+            2 => (any::<u64>(), any::<u64>()).prop_map(move |(v, m)| make(v, m)),
+        ]
+        .boxed()
+    }
+}
+
+/// γ(t) 中的一个具体值
+pub fn member(t: Tnum) -> impl Strategy<Value = u64> {
+    any::<u64>().prop_map(move |r| t.value | (r & t.mask))
+}
+
+/// 一个 tnum 与它的一个具体值
+pub fn tnum_with_member() -> impl Strategy<Value = (Tnum, u64)> {
+    any::<Tnum>().prop_flat_map(|t| (Just(t), member(t)))
+}
@@ -0,0 +1,2822 @@
+//! tnum 抽象域：位宽泛型的 `Tnum<T>`（默认 64 位）与 `TnumU128` 及其转移函数
+use crate::lattice::Lattice;
+use crate::stats;
+use fastdivide::DividerU64;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::Hash;
+use core::ops::{Add, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, Mul, Not, Shl, Shr, Sub};
+use core::str::FromStr;
+
+pub(crate) fn testbit(val: u64, bit: u8) -> bool {
+    if bit >= 64 {
+        return false;
+    }
+    (val & (1u64 << bit)) != 0
+}
+
+/// 位操作 trait
+pub trait BitOps {
+    /// 清除低位（从最低位开始的 n 位）
+    fn clear_low_bits(&mut self, n: u32);
+    /// 清除高位（从最高位开始的 n 位）
+    fn clear_high_bits(&mut self, n: u32);
+}
+
+impl BitOps for u64 {
+    fn clear_low_bits(&mut self, n: u32) {
+        if n >= 64 {
+            *self = 0;
+        } else {
+            *self &= (!0u64).wrapping_shl(n);
+        }
+    }
+
+    fn clear_high_bits(&mut self, n: u32) {
+        if n >= 64 {
+            *self = 0;
+        } else {
+            *self &= u64::MAX >> n;
+        }
+    }
+}
+
+/// tnum 的底层无符号整数类型，决定位宽
+pub trait Word:
+    Copy
+    + Eq
+    + Ord
+    + Hash
+    + Send
+    + Sync
+    + fmt::Debug
+    + fmt::LowerHex
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + BitAndAssign
+    + BitOrAssign
+{
+    const BITS: u32;
+    const ZERO: Self;
+    const ONE: Self;
+    const MAX: Self;
+
+    fn wrapping_add(self, rhs: Self) -> Self;
+    fn wrapping_sub(self, rhs: Self) -> Self;
+    fn wrapping_mul(self, rhs: Self) -> Self;
+    fn wrapping_shl(self, rhs: u32) -> Self;
+    fn wrapping_shr(self, rhs: u32) -> Self;
+    fn rotate_left(self, n: u32) -> Self;
+    fn rotate_right(self, n: u32) -> Self;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn leading_zeros(self) -> u32;
+    fn trailing_zeros(self) -> u32;
+    fn count_ones(self) -> u32;
+    /// 截断到本类型的位宽
+    fn from_u64(v: u64) -> Self;
+    /// 截断到 64 位
+    fn to_u64(self) -> u64;
+
+    /// 最高位（符号位）
+    fn sign_bit() -> Self {
+        Self::ONE.wrapping_shl(Self::BITS - 1)
+    }
+}
+
+macro_rules! impl_word {
+    ($($t:ty),*) => {$(
+        impl Word for $t {
+            const BITS: u32 = <$t>::BITS;
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const MAX: Self = <$t>::MAX;
+
+            fn wrapping_add(self, rhs: Self) -> Self {
+                <$t>::wrapping_add(self, rhs)
+            }
+            fn wrapping_sub(self, rhs: Self) -> Self {
+                <$t>::wrapping_sub(self, rhs)
+            }
+            fn wrapping_mul(self, rhs: Self) -> Self {
+                <$t>::wrapping_mul(self, rhs)
+            }
+            fn wrapping_shl(self, rhs: u32) -> Self {
+                <$t>::wrapping_shl(self, rhs)
+            }
+            fn wrapping_shr(self, rhs: u32) -> Self {
+                <$t>::wrapping_shr(self, rhs)
+            }
+            fn rotate_left(self, n: u32) -> Self {
+                <$t>::rotate_left(self, n)
+            }
+            fn rotate_right(self, n: u32) -> Self {
+                <$t>::rotate_right(self, n)
+            }
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+            fn checked_div(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_div(self, rhs)
+            }
+            fn leading_zeros(self) -> u32 {
+                <$t>::leading_zeros(self)
+            }
+            fn trailing_zeros(self) -> u32 {
+                <$t>::trailing_zeros(self)
+            }
+            fn count_ones(self) -> u32 {
+                <$t>::count_ones(self)
+            }
+            fn from_u64(v: u64) -> Self {
+                v as $t
+            }
+            fn to_u64(self) -> u64 {
+                self as u64
+            }
+        }
+    )*};
+}
+
+impl_word!(u8, u16, u32, u64, u128);
+
+/// 移位量不小于位宽时的语义
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShiftSemantics {
+    /// 移位量按位宽取模，即 eBPF 的 `& 63`（ALU32 为 `& 31`），与 `wrapping_shl` 相同
+    #[default]
+    Masked,
+    /// 移位量不小于位宽时左移、逻辑右移得 0，算术右移得全部符号位
+    Saturating,
+}
+
+impl ShiftSemantics {
+    /// 位宽为 `T::BITS` 时的实际移位量，饱和语义下移出全部位时返回 `None`
+    fn amount<T: Word>(self, k: u64) -> Option<u32> {
+        match self {
+            ShiftSemantics::Masked => Some((k % T::BITS as u64) as u32),
+            ShiftSemantics::Saturating => (k < T::BITS as u64).then_some(k as u32),
+        }
+    }
+}
+
+/// 除数为 0 时的结果约定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DivZeroPolicy {
+    /// 结果为 0，即 eBPF 的 `x / 0 = 0`
+    ReturnZero,
+    /// 结果为被除数，即 eBPF 的 `x % 0 = x`
+    ReturnDividend,
+    /// 结果为 top，与不带策略的除法/取余相同
+    #[default]
+    Top,
+    /// 没有结果，如 Solana SBF 中除以 0 会中止执行
+    Bottom,
+}
+
+/// 对 γ 中全部具体值都成立、部分成立或都不成立的判断
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriState {
+    Definitely,
+    Maybe,
+    Never,
+}
+
+// This is for bit-level abstraction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/// tnum definition，位宽由 `T` 决定，默认 64 位
+///
+/// 格运算、按位运算、加减乘、移位和无符号除法/取余对任意位宽可用，
+/// 可在 8/16 位下穷举验证；有符号除法与 fast_divide 目前只有 64 位版本。
+pub struct Tnum<T: Word = u64> {
+    pub value: T,
+    pub mask: T,
+}
+
+/// 128 位 tnum，用于 magic 乘法的完整乘积
+pub type TnumU128 = Tnum<u128>;
+
+/// 三态的溢出标志：`Some(true)` 必然溢出，`Some(false)` 不可能溢出，`None` 可能溢出
+pub type MaybeOverflow = Option<bool>;
+
+/// 32 位 tnum，用于 ALU32 指令；与 64 位之间的转换见 [`Tnum::subreg32`] 与 [`Tnum::alu32`]
+pub type Tnum32 = Tnum<u32>;
+
+impl Tnum32 {
+    /// 零扩展到 64 位，即 ALU32 指令写回寄存器的结果
+    pub fn zext64(&self) -> Tnum {
+        if self.is_bottom() {
+            return Tnum::bottom();
+        }
+        Tnum::new(self.value as u64, self.mask as u64)
+    }
+
+    /// 符号扩展到 64 位
+    pub fn sext64(&self) -> Tnum {
+        self.zext64().sign_extend(32)
+    }
+
+    /// 32 位有符号除法：符号扩展后做 64 位 `sdiv` 再截断，INT_MIN / -1 同样回绕为 INT_MIN
+    ///
+    /// 不与 64 位的 `sdiv` 同名，以免 `Tnum::sdiv` 这样的路径产生歧义。
+    pub fn sdiv32(&self, other: Self) -> Self {
+        self.sext64().sdiv(other.sext64()).subreg32()
+    }
+
+    /// 32 位有符号取余，做法同 [`Tnum32::sdiv32`]
+    pub fn srem32(&self, other: Self) -> Self {
+        self.sext64().srem(other.sext64()).subreg32()
+    }
+}
+
+impl TnumU128 {
+    /// 高 64 位全部已知为 0 时无损地转换为 `Tnum`，否则返回 `None`
+    pub fn narrow_checked(&self) -> Option<Tnum> {
+        if (self.value | self.mask) >> 64 != 0 {
+            return None;
+        }
+        Some(Tnum::new(self.value as u64, self.mask as u64))
+    }
+
+    /// 低 64 位（按 2^64 取模），与具体值的截断精确对应
+    pub fn low(&self) -> Tnum {
+        Tnum::new(self.value as u64, self.mask as u64)
+    }
+
+    /// 高 64 位（即右移 64 位），与具体值的高半部分精确对应
+    pub fn high(&self) -> Tnum {
+        Tnum::new((self.value >> 64) as u64, (self.mask >> 64) as u64)
+    }
+}
+
+impl<T: Word> Tnum<T> {
+    /// 创建实例；value 与 mask 重叠时得到规范的 bottom
+    pub fn new(value: T, mask: T) -> Self {
+        Self { value, mask }.normalize()
+    }
+
+    /// value 与 mask 不重叠时创建实例，否则返回 `None`
+    pub fn try_new(value: T, mask: T) -> Option<Self> {
+        (value & mask == T::ZERO).then_some(Self { value, mask })
+    }
+
+    /// 把任意 bottom 表示换成规范的 bottom（value 与 mask 全为 1），其余原样返回
+    ///
+    /// 直接用结构体字面量或反序列化得到的值可能不规范。
+    pub fn normalize(self) -> Self {
+        if self.is_bottom() {
+            Self::bottom()
+        } else {
+            self
+        }
+    }
+
+    /// 创建 bottom 元素
+    pub const fn bottom() -> Self {
+        Self {
+            value: T::MAX,
+            mask: T::MAX,
+        }
+    }
+
+    /// 创建 top 元素
+    pub const fn top() -> Self {
+        Self {
+            value: T::ZERO,
+            mask: T::MAX,
+        }
+    }
+
+    /// 转移函数放弃精度、直接返回的 top，计入 [`stats::Counter::TopProduced`]
+    fn top_produced() -> Self {
+        stats::record(stats::Counter::TopProduced);
+        Self::top()
+    }
+
+    /// 创建一个常数 tnum 实例
+    pub const fn const_val(value: T) -> Self {
+        Self {
+            value,
+            mask: T::ZERO,
+        }
+    }
+
+    /// from integer interval to tnum
+    pub fn from_range(min: T, max: T) -> Self {
+        let chi = min ^ max;
+        //最高未知位
+        let bits = T::BITS - chi.leading_zeros();
+        //超出范围则完全未知
+        if bits > T::BITS - 1 {
+            return Self::new(T::ZERO, T::MAX);
+        }
+
+        //范围内的未知位
+        let delta = T::ONE.wrapping_shl(bits).wrapping_sub(T::ONE);
+        Self::new(min & !delta, delta)
+    }
+
+    /// 获取 value 字段
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    /// 获取 mask 字段
+    pub fn mask(&self) -> T {
+        self.mask
+    }
+
+    /// 按从小到大的顺序迭代 γ(self) 中的全部具体值，bottom 为空
+    pub fn iter_concrete(&self) -> Concretes<T> {
+        Concretes {
+            value: self.value,
+            mask: self.mask,
+            sub: T::ZERO,
+            done: self.is_bottom(),
+        }
+    }
+
+    /// 从 γ(self) 中均匀抽取一个具体值，bottom 返回 `None`
+    ///
+    /// `rng` 每次返回 64 个随机位；把随机位依次填入未知位，因此每个具体值的概率相同。
+    pub fn sample<R: FnMut() -> u64>(&self, rng: &mut R) -> Option<T> {
+        if self.is_bottom() {
+            return None;
+        }
+        let mut bits = T::from_u64(rng());
+        if T::BITS > 64 {
+            bits |= T::from_u64(rng()).wrapping_shl(64);
+        }
+        let mut x = self.value;
+        let mut rest = self.mask;
+        while rest != T::ZERO {
+            let i = rest.trailing_zeros();
+            if bits & T::ONE != T::ZERO {
+                x |= T::ONE.wrapping_shl(i);
+            }
+            bits = bits.wrapping_shr(1);
+            rest = rest & rest.wrapping_sub(T::ONE);
+        }
+        Some(x)
+    }
+
+    /// 独立均匀抽取 n 个具体值（可能重复），bottom 返回空
+    #[cfg(feature = "std")]
+    pub fn sample_n<R: FnMut() -> u64>(&self, rng: &mut R, n: usize) -> Vec<T> {
+        (0..n).map_while(|_| self.sample(rng)).collect()
+    }
+
+    /// 具体值的个数 2^(未知位数)，bottom 为 0；128 位全部未知时饱和为 `u128::MAX`
+    pub fn cardinality(&self) -> u128 {
+        if self.is_bottom() {
+            return 0;
+        }
+        1u128
+            .checked_shl(self.mask.count_ones())
+            .unwrap_or(u128::MAX)
+    }
+
+    /// 无符号最小值：未知位全取 0
+    pub fn umin_value(&self) -> T {
+        self.value
+    }
+
+    /// 无符号最大值：未知位全取 1
+    pub fn umax_value(&self) -> T {
+        self.value | self.mask
+    }
+
+    /// 包含 γ(self) 的最紧无符号区间，bottom 返回空区间 (MAX, 0)
+    pub fn to_unsigned_range(&self) -> (T, T) {
+        if self.is_bottom() {
+            return (T::MAX, T::ZERO);
+        }
+        (self.umin_value(), self.umax_value())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value == T::ZERO && self.mask == T::ZERO
+    }
+
+    /// 判断是否为bottom（不可能的值）
+    pub fn is_bottom(&self) -> bool {
+        (self.value & self.mask) != T::ZERO
+    }
+
+    /// 判断是否为top（完全不确定的值）
+    pub fn is_top(&self) -> bool {
+        self.value == T::ZERO && self.mask == T::MAX
+    }
+
+    /// 判断是否为确定值（单点）
+    pub fn is_singleton(&self) -> bool {
+        self.mask == T::ZERO
+    }
+
+    /// 判断是否为非负数（最高位为0）
+    pub fn is_nonnegative(&self) -> bool {
+        (self.value & T::sign_bit()) == T::ZERO && (self.mask & T::sign_bit()) == T::ZERO
+    }
+
+    /// 判断是否为负数（最高位为1）
+    pub fn is_negative(&self) -> bool {
+        (self.value & T::sign_bit()) != T::ZERO && (self.mask & T::sign_bit()) == T::ZERO
+    }
+
+    /// 统计高位连续0的个数
+    pub fn countl_zero(&self) -> u32 {
+        self.value.leading_zeros()
+    }
+
+    /// 统计低位连续0的个数
+    pub fn countr_zero(&self) -> u32 {
+        self.value.trailing_zeros()
+    }
+
+    /// 统计最小的高位连续0的个数
+    pub fn count_min_leading_zeros(&self) -> u32 {
+        let max = self.value.wrapping_add(self.mask);
+        max.leading_zeros()
+    }
+
+    /// 统计最小的低位连续0的个数
+    pub fn count_min_trailing_zeros(&self) -> u32 {
+        let max = self.value.wrapping_add(self.mask);
+        max.trailing_zeros()
+    }
+
+    /// 统计最大的高位连续0的个数
+    pub fn count_max_leading_zeros(&self) -> u32 {
+        self.value.leading_zeros()
+    }
+
+    /// 统计最大的低位连续0的个数
+    pub fn count_max_trailing_zeros(&self) -> u32 {
+        self.value.trailing_zeros()
+    }
+
+    /// 清除高位
+    pub fn clear_high_bits(&mut self, n: u32) {
+        if n >= T::BITS {
+            self.value = T::ZERO;
+            self.mask = T::ZERO;
+        } else {
+            let mask = T::MAX.wrapping_shr(n);
+            self.value &= mask;
+            self.mask &= mask;
+        }
+    }
+
+    /// tnum 的左移操作
+    pub fn tnum_lshift(self, shift: u8) -> Self {
+        Self::new(
+            self.value.wrapping_shl(shift as u32),
+            self.mask.wrapping_shl(shift as u32),
+        )
+    }
+
+    /// tnum 的右移操作
+    pub fn tnum_rshift(self, shift: u8) -> Self {
+        Self::new(
+            self.value.wrapping_shr(shift as u32),
+            self.mask.wrapping_shr(shift as u32),
+        )
+    }
+
+    /// tnum 的加法操作
+    pub fn add(&self, other: Self) -> Self {
+        // 计算掩码之和 - 表示两个不确定数的掩码组合
+        let sm = self.mask.wrapping_add(other.mask);
+
+        // 计算确定值之和
+        let sv = self.value.wrapping_add(other.value);
+
+        // sigma = (a.mask + b.mask) + (a.value + b.value)
+        // 用于检测进位传播情况
+        let sigma = sm.wrapping_add(sv);
+
+        // chi = 进位传播位图
+        // 通过异或操作找出哪些位发生了进位
+        let chi = sigma ^ sv;
+
+        // mu = 最终的不确定位掩码
+        // 包括:
+        // 1. 进位产生的不确定性 (chi)
+        // 2. 原始输入的不确定位 (a.mask | b.mask)
+        let mu = chi | self.mask | other.mask;
+
+        // 返回结果:
+        // value: 确定值之和，但排除所有不确定位 (~mu)
+        // mask: 所有不确定位的掩码
+        Self::new(sv & !mu, mu)
+    }
+
+    /// tnum 的减法操作
+    pub fn sub(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        } else if self.is_top() || other.is_top() {
+            return Self::top_produced();
+        }
+        let dv = self.value.wrapping_sub(other.value);
+        let alpha = dv.wrapping_add(self.mask);
+        let beta = dv.wrapping_sub(other.mask);
+        let chi = alpha ^ beta;
+        let mu = chi | self.mask | other.mask;
+        Self::new(dv & !mu, mu)
+    }
+
+    /// tnum 的按位异或操作
+    pub fn xor(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        } else if self.is_top() || other.is_top() {
+            return Self::top_produced();
+        }
+
+        let v = self.value ^ other.value;
+        let mu = self.mask | other.mask;
+
+        Self::new(v & !mu, mu)
+    }
+
+    /// tnum 的按位与操作（`tnum_and`）；注意 [`Tnum::and`] 是格上的 meet
+    pub fn bit_and(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        }
+        let alpha = self.value | self.mask;
+        let beta = other.value | other.mask;
+        let v = self.value & other.value;
+        Self::new(v, alpha & beta & !v)
+    }
+
+    /// tnum 的按位或操作（`tnum_or`）；注意 [`Tnum::or`] 是格上的 join
+    pub fn bit_or(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        }
+        let v = self.value | other.value;
+        let mu = self.mask | other.mask;
+        Self::new(v, mu & !v)
+    }
+
+    /// tnum 的乘法操作
+    pub fn mul(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        } else if self.is_top() || other.is_top() {
+            return Self::top_produced();
+        }
+        let mut a = *self;
+        let mut b = other;
+        let acc_v = a.value.wrapping_mul(b.value);
+        let mut acc_m: Self = Self::new(T::ZERO, T::ZERO);
+        loop {
+            let bits = a.value | a.mask;
+            if bits == T::ZERO {
+                break;
+            }
+            // 已知为 0 的位对 acc_m 没有贡献，直接跳到下一个为 1 或未知的位
+            let k = bits.trailing_zeros() as u64;
+            if k != 0 {
+                a = a.lshr_const(k);
+                b = b.shl_const(k);
+            }
+            if (a.value & T::ONE) != T::ZERO {
+                acc_m = acc_m.add(Self::new(T::ZERO, b.mask));
+            } else {
+                acc_m = acc_m.add(Self::new(T::ZERO, b.value | b.mask));
+            }
+            a = a.lshr_const(1);
+            b = b.shl_const(1);
+        }
+        Self::new(acc_v, T::ZERO).add(acc_m)
+    }
+
+    /// 按 width 位模运算的乘法，用于缩减位宽的穷举验证
+    ///
+    /// 乘积的低 width 位只依赖操作数的低 width 位，截断后的 `mul` 就是 width 位上的乘法。
+    pub fn mul_width(&self, other: Self, width: u32) -> Self {
+        self.trunc(width).mul(other.trunc(width)).trunc(width)
+    }
+
+    /// 无符号加法的溢出检查（`checked_add`）
+    ///
+    /// 返回不溢出时的结果与溢出标志；结果用端点之和构成的区间收紧，必然溢出时为 bottom。
+    /// 溢出标志是精确的：最大值之和溢出时可能溢出，最小值之和溢出时必然溢出。
+    pub fn checked_add(&self, other: Self) -> (Self, MaybeOverflow) {
+        if self.is_bottom() || other.is_bottom() {
+            return (Self::bottom(), tri_state(false, true));
+        }
+        let lo = self.umin_value().checked_add(other.umin_value());
+        let hi = self.umax_value().checked_add(other.umax_value());
+        let res = match lo {
+            Some(lo) => self
+                .add(other)
+                .meet(&Self::from_range(lo, hi.unwrap_or(T::MAX))),
+            None => Self::bottom(),
+        };
+        (res, tri_state(hi.is_none(), lo.is_none()))
+    }
+
+    /// 无符号减法的借位检查（`checked_sub`），做法同 [`Tnum::checked_add`]
+    pub fn checked_sub(&self, other: Self) -> (Self, MaybeOverflow) {
+        if self.is_bottom() || other.is_bottom() {
+            return (Self::bottom(), tri_state(false, true));
+        }
+        let lo = self.umin_value().checked_sub(other.umax_value());
+        let hi = self.umax_value().checked_sub(other.umin_value());
+        let res = match hi {
+            Some(hi) => self
+                .sub(other)
+                .meet(&Self::from_range(lo.unwrap_or(T::ZERO), hi)),
+            None => Self::bottom(),
+        };
+        (res, tri_state(lo.is_none(), hi.is_none()))
+    }
+
+    /// 无符号乘法的溢出检查（`checked_mul`），做法同 [`Tnum::checked_add`]
+    pub fn checked_mul(&self, other: Self) -> (Self, MaybeOverflow) {
+        if self.is_bottom() || other.is_bottom() {
+            return (Self::bottom(), tri_state(false, true));
+        }
+        let lo = self.umin_value().checked_mul(other.umin_value());
+        let hi = self.umax_value().checked_mul(other.umax_value());
+        let res = match lo {
+            Some(lo) => self
+                .mul(other)
+                .meet(&Self::from_range(lo, hi.unwrap_or(T::MAX))),
+            None => Self::bottom(),
+        };
+        (res, tri_state(hi.is_none(), lo.is_none()))
+    }
+
+    /// 无符号饱和加法（`saturating_add`）：不溢出部分取 `checked_add` 的结果，可能溢出时再并上 MAX
+    pub fn sat_add(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        }
+        let (res, overflow) = self.checked_add(other);
+        saturate(res, overflow, T::MAX)
+    }
+
+    /// 无符号饱和减法（`saturating_sub`），借位时结果为 0
+    pub fn sat_sub(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        }
+        let (res, overflow) = self.checked_sub(other);
+        saturate(res, overflow, T::ZERO)
+    }
+
+    /// 无符号饱和乘法（`saturating_mul`）
+    pub fn sat_mul(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        }
+        let (res, overflow) = self.checked_mul(other);
+        saturate(res, overflow, T::MAX)
+    }
+
+    /// tnum 的按位非操作
+    pub fn not(&self) -> Self {
+        if self.is_bottom() {
+            return Self::bottom();
+        } else if self.is_top() {
+            return Self::top_produced();
+        }
+        Self::new(!(self.value ^ self.mask), self.mask)
+    }
+
+    /// tnum 的取负操作（BPF_NEG），即 0 - x 的最优抽象
+    ///
+    /// -x 的第 i 位等于 x_i 异或「x 的低 i 位中有 1」：最低的可能为 1 的位 p 保持不变，
+    /// p 之上直到最低的确定为 1 的位 q（含 q）全部未知，q 之上的位按位取反。
+    pub fn neg(&self) -> Self {
+        if self.is_bottom() {
+            return Self::bottom();
+        }
+        let any = self.value | self.mask;
+        if any == T::ZERO {
+            return *self;
+        }
+        let p = any.trailing_zeros();
+        let q = self.value.trailing_zeros();
+        let above = |i: u32| {
+            if i + 1 >= T::BITS {
+                T::ZERO
+            } else {
+                T::MAX.wrapping_shl(i + 1)
+            }
+        };
+        let bit_p = T::ONE.wrapping_shl(p);
+        let flipped = above(q);
+        let unknown = above(p) & !flipped;
+        Self::new(
+            (self.value & bit_p) | (!any & flipped),
+            (self.mask & bit_p) | unknown | (self.mask & flipped),
+        )
+    }
+
+    ///computes the w of the tnum domain.
+    pub fn join(&self, other: Self) -> Self {
+        let v = self.value ^ other.value;
+        let m = (self.mask | other.mask) | v;
+        Self::new((self.value | other.value) & (!m), m)
+    }
+
+    /// tnum 的交集计算
+    ///
+    /// 已知位冲突时结果的 value 与 mask 会重叠，或者直接丢掉冲突；应使用 [`Tnum::meet`]。
+    #[deprecated(note = "已知位冲突时结果不正确，请使用 `meet`")]
+    pub fn intersect(&self, other: Self) -> Self {
+        let v = self.value | other.value;
+        let mu = self.mask & other.mask;
+        Self::new(v & !mu, mu)
+    }
+
+    /// 具体值 x 是否属于 γ(self)，即 `x & !mask == value`；bottom 不包含任何值
+    pub fn contains_value(&self, x: T) -> bool {
+        !self.is_bottom() && (x & !self.mask) == self.value
+    }
+
+    /// Checks if self contains other, i.e. γ(other) ⊆ γ(self)
+    ///
+    /// 与 `other.le(self)` 等价：other 的未知位必须在 self 中也未知，
+    /// 且 other 在 self 已知位上的取值与 self 一致。
+    pub fn contains(&self, other: Self) -> bool {
+        if other.is_bottom() {
+            true
+        } else if self.is_bottom() {
+            false
+        } else {
+            (other.value & !self.mask) == self.value && (self.mask | other.mask) == self.mask
+        }
+    }
+
+    /// 无符号取余操作（URem）
+    pub fn urem(&self, other: Self) -> Self {
+        // 处理 bottom 和 top 情况
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        } else if self.is_top() || other.is_top() {
+            return Self::top_produced();
+        }
+
+        // 处理除数为0的情况
+        if other.value == T::ZERO {
+            stats::record(stats::Counter::DivisorZeroTop);
+            return Self::top_produced(); // 除以0返回top
+        }
+
+        let mut res = rem_get_low_bits(self, &other);
+        // 处理低位
+        // 检查除数是否为 2 的幂
+        if other.mask == T::ZERO
+            && (other.value & T::sign_bit()) == T::ZERO
+            && ((other.value.trailing_zeros() + other.value.leading_zeros() + 1) == T::BITS)
+        {
+            // 除数是 2 的幂，直接用位掩码计算余数
+            let low_bits = other.value.wrapping_sub(T::ONE); // 例如：8-1=7(0b111)，用于掩码
+            let res_value = low_bits & self.value;
+            let res_mask = low_bits & self.mask;
+            return Self::new(res_value, res_mask);
+        }
+
+        // 一般情况：结果的精度有限
+        // 由于结果小于或等于任一操作数，因此操作数中的前导零在结果中也存在
+        let leading_zeros = self
+            .count_min_leading_zeros()
+            .max(other.count_min_leading_zeros());
+        res.clear_high_bits(leading_zeros);
+
+        res
+    }
+
+    /// 按 `policy` 处理除数为 0 的无符号除法
+    pub fn udiv_with(&self, other: Self, policy: DivZeroPolicy) -> Self {
+        // 除数非零时商不超过被除数；除数为 {0, 2^k} 时非零部分就是右移
+        self.div_zero_with(other, policy, Self::udiv, || match other.pow2_amount() {
+            Some(amount) => self.lshr(&amount),
+            None => {
+                let mut res = Self::top();
+                res.clear_high_bits(self.count_min_leading_zeros());
+                res
+            }
+        })
+    }
+
+    /// 按 `policy` 处理除数为 0 的无符号取余
+    pub fn urem_with(&self, other: Self, policy: DivZeroPolicy) -> Self {
+        // 除数非零时余数既不超过被除数，也小于除数
+        self.div_zero_with(other, policy, Self::urem, || {
+            let mut res = Self::top();
+            res.clear_high_bits(
+                self.count_min_leading_zeros()
+                    .max(other.count_min_leading_zeros()),
+            );
+            res
+        })
+    }
+
+    /// 除法/取余的除数为 0 时按 `policy` 求值，除数不可能为 0 时直接调用 `op`
+    ///
+    /// 除数恰为 0 时结果只由策略决定；除数可能为 0 时把策略给出的结果与 `nonzero`
+    /// （除数非零部分的上近似）求 join。`Top` 直接调用 `op`，与原有行为一致。
+    fn div_zero_with(
+        &self,
+        other: Self,
+        policy: DivZeroPolicy,
+        op: fn(&Self, Self) -> Self,
+        nonzero: impl FnOnce() -> Self,
+    ) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        } else if !other.contains_value(T::ZERO) {
+            return op(self, other);
+        }
+        let zero = match policy {
+            DivZeroPolicy::ReturnZero => Self::const_val(T::ZERO),
+            DivZeroPolicy::ReturnDividend => *self,
+            DivZeroPolicy::Top => return op(self, other),
+            DivZeroPolicy::Bottom => Self::bottom(),
+        };
+        if other.is_singleton() {
+            zero
+        } else {
+            nonzero().or(&zero)
+        }
+    }
+
+    /// 全部非零具体值都等于同一个 2^k 时返回 k，即 `value | mask` 恰有一位为 1
+    ///
+    /// 此时 γ 为 {2^k} 或 {0, 2^k}，除以它可以退化为右移。
+    pub fn pow2_shift(&self) -> Option<u32> {
+        let bits = self.value | self.mask;
+        if self.is_bottom() || bits.count_ones() != 1 {
+            return None;
+        }
+        Some(bits.trailing_zeros())
+    }
+
+    /// [`Tnum::pow2_shift`] 给出的移位量 tnum，除以除数的非零部分即按它右移
+    ///
+    /// 两个不同的 2 的幂不会单独出现在同一个 tnum 中（它们的和也在 γ 里），所以移位量总是单点。
+    pub fn pow2_amount(&self) -> Option<Self> {
+        self.pow2_shift()
+            .map(|k| Self::const_val(T::from_u64(k as u64)))
+    }
+
+    /// 无符号除法操作
+    pub fn udiv(&self, other: Self) -> Self {
+        // 处理 bottom 和 top 情况
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        }
+        // 除数的非零具体值都是 2^k（value | mask 只有一位）时商就是按移位量右移，被除数的已知位
+        // （包括尾零）平移到商的低位。γ 为 {0, 2^k} 时除数可能为 0，这里仍取 top，
+        // 非零部分的右移见 udiv_with
+        if let Some(amount) = other.pow2_amount() {
+            if other.is_singleton() {
+                return self.lshr(&amount);
+            }
+        }
+        // 除数为 top 时可能为 0 或 1，商没有任何已知位；被除数为 top 时商的高位仍可能已知，
+        // 不提前返回
+        if other.is_top() {
+            return Self::top_produced();
+        }
+
+        if other.value == T::ZERO {
+            // 处理除数为0的情况
+            stats::record(stats::Counter::DivisorZeroTop);
+            Self::top_produced()
+        } else {
+            let mut res = Self::top();
+            let max_res = match self.value.wrapping_add(self.mask).checked_div(other.value) {
+                // 如果除法成功，返回包含结果的新 Tnum
+                Some(result) => result,
+                // 如果除以零，checked_div 返回 None，我们返回 top
+                None => return Self::top_produced(),
+            };
+            let leadz = max_res.leading_zeros();
+            res.clear_high_bits(leadz);
+            self.div_compute_low_bit(res, other, max_res)
+        }
+    }
+
+    /// 用商的区间细化无符号除法的结果，`max_res` 为商的上界
+    ///
+    /// 除数非零时商落在 [min(x) / max(y), max(x) / min(y)] 中，两端的公共前缀都是已知位，
+    /// 区间窄时低位也随之确定（如 [100, 103] / 7 = 14）。LLVM 中“奇数 / 奇数 -> 奇数”与按尾零
+    /// 个数推出商的尾零只对整除成立（如 7 / 3 = 2、8 / 3 = 2），对向下取整的 udiv 不可靠，不采用。
+    fn div_compute_low_bit(&self, result: Self, other: Self, max_res: T) -> Self {
+        let min_res = self.umin_value().checked_div(other.umax_value()).expect("除数非零");
+        result.meet(&Self::from_range(min_res, max_res))
+    }
+
+    /// 具体值对不超过 `budget` 个时枚举全部商并求 join，得到最优的 tnum；超出预算返回 `None`
+    ///
+    /// 除数为 0 的具体输入被跳过，不像 udiv 那样把结果放宽为 top；除数恰为 0 时结果为 bottom。
+    pub fn udiv_exact_small(&self, other: Self, budget: usize) -> Option<Self> {
+        if self.cardinality().saturating_mul(other.cardinality()) > budget as u128 {
+            stats::record(stats::Counter::BudgetExceeded);
+            return None;
+        }
+        let divisors = || other.iter_concrete().filter(|&y| y != T::ZERO);
+        if self.is_bottom() || divisors().next().is_none() {
+            return Some(Self::bottom());
+        }
+        // 全部商的按位与为已知的 1，按位或之外为已知的 0
+        let (mut ones, mut any) = (T::MAX, T::ZERO);
+        for x in self.iter_concrete() {
+            for y in divisors() {
+                let q = x.checked_div(y).expect("除数非零");
+                ones &= q;
+                any |= q;
+            }
+        }
+        Some(Self::new(ones, any ^ ones))
+    }
+
+    /// 除数不可能为 0 且具体值对不超过 `budget` 个时取最优结果，否则同 [`Tnum::udiv`]
+    pub fn udiv_or_exact(&self, other: Self, budget: usize) -> Self {
+        if other.contains_value(T::ZERO) {
+            return self.udiv(other);
+        }
+        self.udiv_exact_small(other, budget)
+            .unwrap_or_else(|| self.udiv(other))
+    }
+
+    /// 左移常数位，移位量按位宽取模
+    pub fn shl_const(&self, k: u64) -> Self {
+        self.shl_const_with(k, ShiftSemantics::Masked)
+    }
+
+    /// 逻辑右移常数位，移位量按位宽取模
+    pub fn lshr_const(&self, k: u64) -> Self {
+        self.lshr_const_with(k, ShiftSemantics::Masked)
+    }
+
+    /// 算术右移常数位，移位量按位宽取模
+    pub fn ashr_const(&self, k: u64) -> Self {
+        self.ashr_const_with(k, ShiftSemantics::Masked)
+    }
+
+    pub fn shl_const_with(&self, k: u64, sem: ShiftSemantics) -> Self {
+        if self.is_bottom() {
+            return *self;
+        }
+        match sem.amount::<T>(k) {
+            Some(shift) => Self::new(
+                self.value.wrapping_shl(shift),
+                self.mask.wrapping_shl(shift),
+            ),
+            None => Self::const_val(T::ZERO),
+        }
+    }
+
+    pub fn lshr_const_with(&self, k: u64, sem: ShiftSemantics) -> Self {
+        if self.is_bottom() {
+            return *self;
+        }
+        match sem.amount::<T>(k) {
+            Some(shift) => Self::new(
+                self.value.wrapping_shr(shift),
+                self.mask.wrapping_shr(shift),
+            ),
+            None => Self::const_val(T::ZERO),
+        }
+    }
+
+    /// 饱和语义下移位量不小于位宽时，结果的每一位都等于符号位
+    pub fn ashr_const_with(&self, k: u64, sem: ShiftSemantics) -> Self {
+        if self.is_bottom() {
+            return *self;
+        }
+        let shift = sem.amount::<T>(k).unwrap_or(T::BITS - 1);
+
+        // 获取符号位
+        let vsig = self.value & T::sign_bit() != T::ZERO;
+        let msig = self.mask & T::sign_bit() != T::ZERO;
+        // 算术右移：符号位为 1 时高位补 1
+        let sar = |x: T| {
+            let shifted = x.wrapping_shr(shift);
+            if x & T::sign_bit() != T::ZERO {
+                shifted | !T::MAX.wrapping_shr(shift)
+            } else {
+                shifted
+            }
+        };
+
+        // 根据符号位选择不同的移位策略
+        if !vsig && !msig {
+            // 都是非负数，使用逻辑右移
+            Self::new(
+                self.value.wrapping_shr(shift),
+                self.mask.wrapping_shr(shift),
+            )
+        } else if vsig && !msig {
+            // value 是负数但 mask 非负
+            Self::new(sar(self.value), self.mask.wrapping_shr(shift))
+        } else {
+            // 其他情况
+            Self::new(self.value.wrapping_shr(shift), sar(self.mask))
+        }
+    }
+
+    /// 左移 tnum 位，移位量按位宽取模（eBPF 语义）
+    pub fn shl(&self, x: &Self) -> Self {
+        self.shl_with(x, ShiftSemantics::Masked)
+    }
+
+    /// 逻辑右移 tnum 位，移位量按位宽取模（eBPF 语义）
+    pub fn lshr(&self, x: &Self) -> Self {
+        self.lshr_with(x, ShiftSemantics::Masked)
+    }
+
+    /// 算术右移 tnum 位（`BPF_ARSH` 的寄存器形式），移位量按位宽取模（eBPF 语义）
+    pub fn ashr(&self, x: &Self) -> Self {
+        self.ashr_with(x, ShiftSemantics::Masked)
+    }
+
+    /// 按给定语义左移 tnum 位：对 x 所有可能的有效移位量求 join
+    ///
+    /// 有效移位量至多位宽 + 1 个，逐个求常数移位再 join 即得最优结果。
+    pub fn shl_with(&self, x: &Self, sem: ShiftSemantics) -> Self {
+        self.shift_join(x, sem, Self::shl_const_with)
+    }
+
+    pub fn lshr_with(&self, x: &Self, sem: ShiftSemantics) -> Self {
+        self.shift_join(x, sem, Self::lshr_const_with)
+    }
+
+    pub fn ashr_with(&self, x: &Self, sem: ShiftSemantics) -> Self {
+        self.shift_join(x, sem, Self::ashr_const_with)
+    }
+
+    fn shift_join(
+        &self,
+        x: &Self,
+        sem: ShiftSemantics,
+        shift: fn(&Self, u64, ShiftSemantics) -> Self,
+    ) -> Self {
+        if self.is_bottom() || x.is_bottom() {
+            return Self::bottom();
+        } else if x.is_singleton() {
+            return shift(self, x.value.to_u64(), sem);
+        }
+        let w = T::BITS as u64;
+        match sem {
+            ShiftSemantics::Masked => {
+                Self::join_many(shift_amounts(x).map(|k| shift(self, k, sem)))
+            }
+            // 所有不小于位宽的移位量结果相同，用 w 代表
+            ShiftSemantics::Saturating => Self::join_many(
+                (0..w)
+                    .filter(|&k| x.contains_value(T::from_u64(k)))
+                    .chain((x.umax_value() >= T::from_u64(w)).then_some(w))
+                    .map(|k| shift(self, k, sem)),
+            ),
+        }
+    }
+
+    /// 无符号最大值，算法同 LLVM `KnownBits::umax`
+    ///
+    /// 一方的最小值不小于另一方的最大值时直接取该方；否则结果若取自 a，则必然不小于 b 的最小值，
+    /// 据此细化 a 的高位（`make_uge`），对 b 同理，最后求 join。
+    pub fn umax(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        }
+        if self.value >= other.value | other.mask {
+            return *self;
+        } else if other.value >= self.value | self.mask {
+            return other;
+        }
+        let a = self.make_uge(other.value);
+        let b = other.make_uge(self.value);
+        a.or(&b)
+    }
+
+    /// 无符号最小值：umin(a, b) = !umax(!a, !b)
+    pub fn umin(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        }
+        self.not().umax(other.not()).not()
+    }
+
+    /// 有符号最大值：按符号位把两个操作数拆成非负、负两部分分别讨论
+    ///
+    /// 同号时有符号序与无符号序一致，用 `umax`；异号时非负的一方更大。
+    pub fn smax(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        }
+        let (a0, a1) = self.sign_split();
+        let (b0, b1) = other.sign_split();
+        let bottom = Self::bottom();
+        Self::join_many([
+            a0.umax(b0),
+            a1.umax(b1),
+            if b1.is_bottom() { bottom } else { a0 },
+            if a1.is_bottom() { bottom } else { b0 },
+        ])
+    }
+
+    /// 有符号最小值：同号时用 `umin`，异号时负的一方更小
+    pub fn smin(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        }
+        let (a0, a1) = self.sign_split();
+        let (b0, b1) = other.sign_split();
+        let bottom = Self::bottom();
+        Self::join_many([
+            a0.umin(b0),
+            a1.umin(b1),
+            if b0.is_bottom() { bottom } else { a1 },
+            if a0.is_bottom() { bottom } else { b1 },
+        ])
+    }
+
+    /// 按符号位拆分为（非负部分，负部分），不存在的部分为 bottom
+    fn sign_split(&self) -> (Self, Self) {
+        let sign = T::sign_bit();
+        (
+            self.and(&Self::new(T::ZERO, !sign)),
+            self.and(&Self::new(sign, !sign)),
+        )
+    }
+
+    /// 在 x >= val 的前提下细化：从最高位起，x 在 val 为 0 的位上可能为 1 之前，
+    /// val 为 1 的位 x 也必须为 1
+    fn make_uge(&self, val: T) -> Self {
+        let known_zero = !(self.value | self.mask);
+        let n = (!(known_zero | val)).leading_zeros();
+        let forced = if n == 0 {
+            T::ZERO
+        } else {
+            val & !T::MAX.wrapping_shr(n)
+        };
+        Self::new(self.value | forced, self.mask & !forced)
+    }
+
+    /// 是否存在相等的具体值：两边已知位在共同已知的位置上一致
+    pub fn may_eq(&self, other: Self) -> bool {
+        !self.is_bottom()
+            && !other.is_bottom()
+            && (self.value ^ other.value) & !(self.mask | other.mask) == T::ZERO
+    }
+
+    /// 是否所有具体值都相等：两边都是同一个常数（bottom 时空真）
+    pub fn must_eq(&self, other: Self) -> bool {
+        self.is_bottom()
+            || other.is_bottom()
+            || (self.is_singleton() && other.is_singleton() && self.value == other.value)
+    }
+
+    /// 是否可能无符号小于：self 的最小值小于 other 的最大值
+    pub fn may_ult(&self, other: Self) -> bool {
+        !self.is_bottom() && !other.is_bottom() && self.value < other.value | other.mask
+    }
+
+    /// 是否必然无符号小于：self 的最大值小于 other 的最小值（bottom 时空真）
+    pub fn must_ult(&self, other: Self) -> bool {
+        self.is_bottom() || other.is_bottom() || self.value | self.mask < other.value
+    }
+
+    /// 是否可能有符号小于：翻转符号位后按无符号比较
+    pub fn may_slt(&self, other: Self) -> bool {
+        self.flip_sign().may_ult(other.flip_sign())
+    }
+
+    /// 是否必然有符号小于
+    pub fn must_slt(&self, other: Self) -> bool {
+        self.flip_sign().must_ult(other.flip_sign())
+    }
+
+    /// 三态相等比较：对所有具体值都成立为 `Some(true)`，都不成立为 `Some(false)`，否则 `None`
+    pub fn cmp_eq(&self, other: Self) -> Option<bool> {
+        tri_state(self.may_eq(other), self.must_eq(other))
+    }
+
+    /// 三态无符号小于比较
+    pub fn cmp_ult(&self, other: Self) -> Option<bool> {
+        tri_state(self.may_ult(other), self.must_ult(other))
+    }
+
+    /// 三态有符号小于比较
+    pub fn cmp_slt(&self, other: Self) -> Option<bool> {
+        tri_state(self.may_slt(other), self.must_slt(other))
+    }
+
+    /// 翻转符号位，把有符号序映射为无符号序
+    fn flip_sign(&self) -> Self {
+        if self.is_bottom() {
+            return *self;
+        }
+        Self::new(self.value ^ (T::sign_bit() & !self.mask), self.mask)
+    }
+
+    /// 分支 `x == other` 成立（JEQ）：两边取 meet
+    pub fn refine_eq(&self, other: Self) -> Self {
+        self.and(&other)
+    }
+
+    /// 分支 `x != other` 成立（JNE）
+    ///
+    /// 只有 other 为常数时才能排除一个值：self 恰好就是该常数时不可达，
+    /// self 只剩一个未知位且其中一个取值是该常数时，结果是另一个取值。
+    pub fn refine_ne(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        }
+        if !other.is_singleton() || !self.contains(other) {
+            return *self;
+        }
+        if self.is_singleton() {
+            Self::bottom()
+        } else if self.mask.count_ones() == 1 {
+            Self::const_val(other.value ^ self.mask)
+        } else {
+            *self
+        }
+    }
+
+    /// 分支 `x < c`（无符号，JLT）成立
+    ///
+    /// 逐个未知位判断：把该位固定为 1 后的最小值（value | bit）仍小于 c 时该位才可能为 1，
+    /// 否则已知为 0。结果是满足条件的具体值集合的最优抽象。
+    pub fn refine_lt_const(&self, c: T) -> Self {
+        if self.is_bottom() || self.value >= c {
+            return Self::bottom();
+        }
+        let mut mask = T::ZERO;
+        for i in 0..T::BITS {
+            let bit = T::ONE.wrapping_shl(i);
+            if self.mask & bit != T::ZERO && self.value | bit < c {
+                mask |= bit;
+            }
+        }
+        Self::new(self.value, mask)
+    }
+
+    /// 分支 `x >= c`（无符号，JGE）成立
+    ///
+    /// 与 `refine_lt_const` 对称：把某个未知位固定为 0 后的最大值小于 c 时，该位已知为 1。
+    pub fn refine_ge_const(&self, c: T) -> Self {
+        let max = self.value | self.mask;
+        if self.is_bottom() || max < c {
+            return Self::bottom();
+        }
+        let mut forced = T::ZERO;
+        for i in 0..T::BITS {
+            let bit = T::ONE.wrapping_shl(i);
+            if self.mask & bit != T::ZERO && max & !bit < c {
+                forced |= bit;
+            }
+        }
+        Self::new(self.value | forced, self.mask & !forced)
+    }
+
+    /// 分支 `x & mask != 0`（JSET）成立
+    ///
+    /// 可能为 1 的位与 mask 没有交集时不可达；只剩一个候选位且其余已知为 0 时，该位必为 1。
+    pub fn refine_jset(&self, mask: T) -> Self {
+        if self.is_bottom() {
+            return *self;
+        }
+        let candidates = (self.value | self.mask) & mask;
+        if candidates == T::ZERO {
+            Self::bottom()
+        } else if self.value & mask == T::ZERO && candidates.count_ones() == 1 {
+            Self::new(self.value | candidates, self.mask & !candidates)
+        } else {
+            *self
+        }
+    }
+
+    /// JSET 不成立，即 `x & mask == 0`：mask 中的位全部清零
+    pub fn refine_jset_not(&self, mask: T) -> Self {
+        if self.is_bottom() || self.value & mask != T::ZERO {
+            return Self::bottom();
+        }
+        Self::new(self.value, self.mask & !mask)
+    }
+
+    /// 截断到低 bits 位，高位清零；bits 取 1..=位宽
+    pub fn trunc(&self, bits: u32) -> Self {
+        assert!(
+            (1..=T::BITS).contains(&bits),
+            "截断位宽须在 1..={} 之间",
+            T::BITS
+        );
+        if self.is_bottom() {
+            return *self;
+        }
+        let low = T::MAX.wrapping_shr(T::BITS - bits);
+        Self::new(self.value & low, self.mask & low)
+    }
+
+    /// 把低 from_bits 位的值零扩展到整个字，与 `trunc(from_bits)` 相同
+    pub fn zext(&self, from_bits: u32) -> Self {
+        self.trunc(from_bits)
+    }
+
+    /// 从低 from_bits 位符号扩展到整个字（MOVSX）
+    ///
+    /// 符号位已知为 0 时高位清零，已知为 1 时高位置 1，未知时高位全部未知。
+    pub fn sign_extend(&self, from_bits: u8) -> Self {
+        assert!(from_bits > 0, "符号扩展的源位宽至少为 1");
+        let bits = from_bits as u32;
+        if self.is_bottom() || bits >= T::BITS {
+            return *self;
+        }
+        let low = T::MAX.wrapping_shr(T::BITS - bits);
+        let sign = T::ONE.wrapping_shl(bits - 1);
+        let t = Self::new(self.value & low, self.mask & low);
+        if t.mask & sign != T::ZERO {
+            Self::new(t.value, t.mask | !low)
+        } else if t.value & sign != T::ZERO {
+            Self::new(t.value | !low, t.mask)
+        } else {
+            t
+        }
+    }
+
+    /// 置位计数（popcount）：结果在 [value 的 1 的个数, (value|mask) 的 1 的个数] 中，
+    /// 区间内每个值都可取到，因此按区间取 tnum 即为最优
+    pub fn popcount(&self) -> Self {
+        if self.is_bottom() {
+            return *self;
+        }
+        Self::from_range(
+            T::from_u64(self.value.count_ones() as u64),
+            T::from_u64((self.value | self.mask).count_ones() as u64),
+        )
+    }
+
+    /// 循环左移常数位，移位量按位宽取模；位的置换是精确的
+    pub fn rotl_const(&self, k: u64) -> Self {
+        if self.is_bottom() {
+            return *self;
+        }
+        let k = (k % T::BITS as u64) as u32;
+        Self::new(self.value.rotate_left(k), self.mask.rotate_left(k))
+    }
+
+    /// 循环右移常数位，移位量按位宽取模
+    pub fn rotr_const(&self, k: u64) -> Self {
+        self.rotl_const(T::BITS as u64 - k % T::BITS as u64)
+    }
+
+    /// 循环左移 tnum 位：对 x 按位宽取模后所有可能的移位量求 join
+    pub fn rotl(&self, x: &Self) -> Self {
+        if self.is_bottom() || x.is_bottom() {
+            return Self::bottom();
+        }
+        Self::join_many(shift_amounts(x).map(|k| self.rotl_const(k)))
+    }
+
+    /// 循环右移 tnum 位
+    pub fn rotr(&self, x: &Self) -> Self {
+        if self.is_bottom() || x.is_bottom() {
+            return Self::bottom();
+        }
+        Self::join_many(shift_amounts(x).map(|k| self.rotr_const(k)))
+    }
+
+    pub fn le(&self, other: &Self) -> bool {
+        // 修改参数类型为 &Tnum
+        if other.is_top() || self.is_bottom() {
+            true
+        } else if other.is_bottom() || self.is_top() {
+            false
+        } else if self.value == other.value && self.mask == other.mask {
+            true
+        } else if (self.mask & (!other.mask)) != T::ZERO {
+            // self[i] 未知但 other[i] 已知
+            false
+        } else {
+            (self.value & (!other.mask)) == other.value
+        }
+    }
+
+    /// 等价关系判断（==）
+    #[allow(clippy::should_implement_trait)]
+    pub fn eq(&self, other: &Self) -> bool {
+        // 修改参数类型为 &Tnum
+        self.le(other) && other.le(self)
+    }
+
+    pub fn or(&self, other: &Self) -> Self {
+        if self.le(other) {
+            *other
+        } else if other.le(self) {
+            *self
+        } else {
+            let mu = self.mask | other.mask;
+            let this_know = self.value & (!mu);
+            let x_know = other.value & (!mu);
+            let disagree = this_know ^ x_know;
+
+            Self::new(this_know & x_know, mu | disagree)
+        }
+    }
+
+    /// 格上的 meet，与 [`Tnum::meet`] 相同
+    pub fn and(&self, other: &Self) -> Self {
+        self.meet(other)
+    }
+
+    /// 格上的 meet：γ(a) ∩ γ(b) 恰好可以用一个 tnum 表示
+    ///
+    /// 任一方为 bottom 或两边的已知位冲突时返回规范的 bottom。
+    pub fn meet(&self, other: &Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        }
+        let known = !(self.mask | other.mask);
+        if (self.value ^ other.value) & known != T::ZERO {
+            return Self::bottom();
+        }
+        Self::new(self.value | other.value, self.mask & other.mask)
+    }
+
+    /// 不动点迭代用的加宽：self 为上一轮结果，other 为本轮新值
+    ///
+    /// 先求 join；若有新变为未知的位，则从其中最低的一位起向上全部置为未知。
+    /// 计数器这类从低位向高位进位的值因此一步就稳定，而不是每轮只多出一位。
+    pub fn widen(&self, other: &Self) -> Self {
+        self.widen_with_thresholds(other, &[])
+    }
+
+    /// 带阈值的加宽：向上置为未知的范围不超过第一个覆盖 join 结果全部未知位的阈值 mask
+    ///
+    /// 阈值应按从小到大给出，例如 `[0xff, 0xffff, 0xffff_ffff]`；都不覆盖时同 `widen`。
+    pub fn widen_with_thresholds(&self, other: &Self, thresholds: &[T]) -> Self {
+        let joined = self.or(other);
+        if joined.le(self) {
+            return *self;
+        }
+        if self.is_bottom() {
+            return joined;
+        }
+        let changed = joined.mask & !self.mask;
+        let mut upward = T::MAX.wrapping_shl(changed.trailing_zeros());
+        if let Some(&t) = thresholds.iter().find(|&&t| joined.mask & !t == T::ZERO) {
+            upward &= t;
+        }
+        let mask = joined.mask | upward;
+        Self::new(joined.value & !mask, mask)
+    }
+
+    /// 加宽后的收窄：用新一轮的结果细化，只会把未知位变为已知，因此迭代必然终止
+    pub fn narrow(&self, other: &Self) -> Self {
+        self.meet(other)
+    }
+
+    /// 多个 tnum 的 join，结果为 top 时提前返回
+    pub fn join_many<I: IntoIterator<Item = Self>>(iter: I) -> Self {
+        let mut res = Self::bottom();
+        for t in iter {
+            res = res.or(&t);
+            if res.is_top() {
+                break;
+            }
+        }
+        res
+    }
+
+    /// 多个 tnum 的 meet，结果为 bottom 时提前返回
+    pub fn meet_many<I: IntoIterator<Item = Self>>(iter: I) -> Self {
+        let mut res = Self::top();
+        for t in iter {
+            res = res.and(&t);
+            if res.is_bottom() {
+                break;
+            }
+        }
+        res
+    }
+}
+
+/// 编译期可求值的构造与基本运算，用于在 `const` / `static` 中预先算好 tnum 查找表
+///
+/// 泛型的方法要调用 [`Word`] 的 trait 方法，不能是 `const fn`，这里对 64 位单独实现，
+/// 结果与对应的方法逐位相同；`top`、`bottom` 与 `const_val` 本身就是 `const fn`。
+impl Tnum {
+    /// 同 [`Tnum::new`]
+    pub const fn const_new(value: u64, mask: u64) -> Self {
+        if value & mask != 0 {
+            Self::bottom()
+        } else {
+            Self { value, mask }
+        }
+    }
+
+    /// 同 [`Tnum::from_range`]
+    pub const fn const_from_range(min: u64, max: u64) -> Self {
+        let bits = 64 - (min ^ max).leading_zeros();
+        if bits > 63 {
+            return Self::top();
+        }
+        let delta = (1u64 << bits) - 1;
+        Self::const_new(min & !delta, delta)
+    }
+
+    /// 同 [`Tnum::add`]
+    pub const fn const_add(self, other: Self) -> Self {
+        let sm = self.mask.wrapping_add(other.mask);
+        let sv = self.value.wrapping_add(other.value);
+        let chi = sm.wrapping_add(sv) ^ sv;
+        let mu = chi | self.mask | other.mask;
+        Self::const_new(sv & !mu, mu)
+    }
+
+    /// 同 [`Tnum::bit_and`]
+    pub const fn const_bit_and(self, other: Self) -> Self {
+        if self.value & self.mask != 0 || other.value & other.mask != 0 {
+            return Self::bottom();
+        }
+        let v = self.value & other.value;
+        let alpha = self.value | self.mask;
+        let beta = other.value | other.mask;
+        Self::const_new(v, alpha & beta & !v)
+    }
+
+    /// 同 [`Tnum::bit_or`]
+    pub const fn const_bit_or(self, other: Self) -> Self {
+        if self.value & self.mask != 0 || other.value & other.mask != 0 {
+            return Self::bottom();
+        }
+        let v = self.value | other.value;
+        Self::const_new(v, (self.mask | other.mask) & !v)
+    }
+}
+
+impl Tnum {
+    /// 零扩展到 128 位，高 64 位为已知的 0
+    ///
+    /// 无损：结果的具体值集合恰好是 γ(self) 中每个值零扩展后的集合。
+    pub fn widen_u128(&self) -> TnumU128 {
+        TnumU128::new(self.value as u128, self.mask as u128)
+    }
+
+    /// 一次性计算各项派生事实，bottom 返回 `None`
+    pub fn describe(&self) -> Option<TnumFacts> {
+        if self.is_bottom() {
+            return None;
+        }
+        let sign = 1u64 << 63;
+        let known = |bit: u64| (self.mask & bit == 0).then_some(self.value & bit != 0);
+        Some(TnumFacts {
+            umin: self.umin_value(),
+            umax: self.umax_value(),
+            smin: self.smin_value(),
+            smax: self.smax_value(),
+            align: 1u64 << (self.value | self.mask).trailing_zeros().min(63),
+            odd: known(1),
+            negative: known(sign),
+            unknown_bits: self.mask.count_ones(),
+            singleton: self.is_singleton().then_some(self.value),
+        })
+    }
+
+    /// tnum 算数右移的操作
+    pub fn tnum_arshift(self: Tnum, min_shift: u8, insn_bitness: u8) -> Tnum {
+        match insn_bitness {
+            32 => {
+                //32位模式
+                let value = ((self.value as i32) >> min_shift) as u32;
+                let mask = ((self.mask as i32) >> min_shift) as u32;
+                Tnum::new(value as u64, mask as u64)
+            }
+            _ => {
+                //64位模式
+                let value = ((self.value as i64) >> min_shift) as u64;
+                let mask = ((self.mask as i64) >> min_shift) as u64;
+                Tnum::new(value, mask)
+            }
+        }
+    }
+
+    /// 两个操作数零扩展到 128 位后的完整乘积
+    pub fn mul_wide(&self, other: Self) -> TnumU128 {
+        self.widen_u128().mul(other.widen_u128())
+    }
+
+    /// 完整的 128 位无符号乘积，按 (低 64 位, 高 64 位) 拆分，对应 `u64::widening_mul`
+    pub fn widening_mul(&self, other: Self) -> (Self, Self) {
+        if self.is_bottom() || other.is_bottom() {
+            return (Self::bottom(), Self::bottom());
+        }
+        let p = self.mul_wide(other);
+        (p.low(), p.high())
+    }
+
+    /// 无符号乘法的高 64 位（UHMUL64），取 128 位乘积的高半部分
+    pub fn umulh(&self, other: Self) -> Self {
+        self.widening_mul(other).1
+    }
+
+    /// 有符号乘法的高 64 位（SHMUL64）
+    ///
+    /// 按两个操作数的符号位拆分：对补码有 smulh(a, b) = umulh(a, b) - [a < 0]·b - [b < 0]·a。
+    pub fn smulh(&self, other: Self) -> Self {
+        let (a_pos, a_neg) = self.sign_split();
+        let (b_pos, b_neg) = other.sign_split();
+        let mut res = Self::bottom();
+        for (x, x_neg) in [(a_pos, false), (a_neg, true)] {
+            for (y, y_neg) in [(b_pos, false), (b_neg, true)] {
+                if x.is_bottom() || y.is_bottom() {
+                    continue;
+                }
+                let mut r = x.umulh(y);
+                if x_neg {
+                    r = r.sub(y);
+                }
+                if y_neg {
+                    r = r.sub(x);
+                }
+                res = res.or(&r);
+            }
+        }
+        res
+    }
+
+    /// A constant-value optimization for tnum_mul
+    pub fn mul_opt(&self, other: Self) -> Self {
+        // 如果一个是常数
+        if self.mask == 0 && self.value.count_ones() == 1 {
+            // a.value = 2 ^ x
+            other.shl_const(self.value.trailing_zeros() as u64)
+        } else if other.mask == 0 && other.value.count_ones() == 1 {
+            // a.value = 2 ^ x
+            self.shl_const(other.value.trailing_zeros() as u64)
+        } else if (self.value | self.mask).count_ones() <= (other.value | other.mask).count_ones() {
+            self.mul(other)
+        } else {
+            other.mul(*self)
+        }
+    }
+
+    /// [split_at_mu] splits a tnum at the first unknow.
+    fn split_at_mu(&self) -> (Self, u32, Self) {
+        let i = self.mask.leading_ones();
+        let shr = |v: u64| v.checked_shr(i + 1).unwrap_or(0);
+        let low = 1u64.checked_shl(i).map_or(u64::MAX, |b| b - 1);
+        let x1 = Self::new(shr(self.value), shr(self.mask));
+        let x2 = Self::new(self.value & low, self.mask & low);
+        (x1, i, x2)
+    }
+
+    /// [tnum_mul_const] multiplies a constant [c] by the tnum [x]
+    /// which has [j] unknown bits and [n] is the fuel (Z.of_nat n = j).
+    fn mul_const(&self, c: u64, n: u64) -> Self {
+        if n == 0 {
+            Self::new(c.wrapping_mul(self.value), 0)
+        } else {
+            let (y1, i1, y2) = self.split_at_mu();
+            let p = y1.mul_const(c, n - 1);
+            let mc = Self::new(c.wrapping_mul(y2.mask), 0);
+            let mu0 = p.shl_const((i1 + 1) as u64).add(mc);
+            let mu1 = mu0.add(Self::new(c.wrapping_shl(i1), 0));
+            mu0.join(mu1)
+        }
+    }
+
+    /// [xtnum_mul x i y j] computes the multiplication of
+    /// [x]  which has [i] unknown bits by
+    /// [y]  which has [j] unknown bits such (i <= j)
+    fn xtnum_mul(x: Self, i: u64, y: Self, j: u64) -> Self {
+        if i == 0 && j == 0 {
+            Self::new(x.value.wrapping_mul(y.value), 0)
+        } else {
+            let (y1, i1, y2) = y.split_at_mu(); // y = y1.mu.y2
+            let p = if i == j {
+                Self::xtnum_mul(y1, j - 1, x, i)
+            } else {
+                Self::xtnum_mul(x, i, y1, j - 1)
+            };
+            let mc = x.mul_const(y2.value, i);
+            let mu0 = p.shl_const((i1 + 1) as u64).add(mc);
+            let mu1 = mu0.add(x.shl_const(i1 as u64));
+            mu0.join(mu1)
+        }
+    }
+
+    /// the top of the xtnum_mul
+    pub fn xtnum_mul_top(&self, other: Self) -> Self {
+        let i = 64 - self.mask.leading_zeros() as u64;
+        let j = 64 - other.mask.leading_zeros() as u64;
+        if i <= j {
+            Self::xtnum_mul(*self, i, other, j)
+        } else {
+            Self::xtnum_mul(other, j, *self, i)
+        }
+    }
+
+    /// clear bit of a tnum
+    fn clear_bit(&self, pos: u8) -> Self {
+        Self::new(self.value & !(1 << pos), self.mask & !(1 << pos))
+    }
+
+    /// bit size of a tnum
+    fn size(&self) -> u8 {
+        let a = 64 - self.value.leading_zeros();
+        let b = 64 - self.mask.leading_zeros();
+        if a < b {
+            b as u8
+        } else {
+            a as u8
+        }
+    }
+
+    /// max 64 of a tnum
+    /// [xtnum_mul_high x y n] multiplies x by y
+    /// where n is the number of bits that are set in either x or y.
+    /// We also have that x <= y and 0 <= x and 0 <= y
+    fn xtnum_mul_high(&self, y: Self, n: u8) -> Self {
+        if self.mask == 0 && y.mask == 0 {
+            //if both are constants, perform normal multiplication
+            Self::new(self.value.wrapping_mul(y.value), 0)
+        } else if n == 0 {
+            //panic!("should not happen");
+            Self::new(0, 0) //should not happen
+        } else {
+            let b = y.size();
+            if b == 0 {
+                return Self::new(0, 0);
+            }
+            let ym = testbit(y.mask, b - 1);
+            let y_prime = y.clear_bit(b - 1); //clear the highest bit of y
+            let p = if y_prime.umax_value() <= self.umax_value() {
+                y_prime.xtnum_mul_high(*self, n - 1)
+            } else {
+                self.xtnum_mul_high(y_prime, n - 1)
+            };
+            if ym {
+                p.add(self.shl_const((b - 1) as u64)).join(p)
+            } else {
+                p.add(self.shl_const((b - 1) as u64))
+            }
+        }
+    }
+
+    /// the top level of xtnum_mul_high
+    pub fn xtnum_mul_high_top(&self, other: Self) -> Self {
+        self.xtnum_mul_high(
+            other,
+            ((self.value | self.mask).count_ones() + (other.value | other.mask).count_ones()) as u8,
+        )
+    }
+
+    /// aux function for tnum_mul_rec
+    fn decompose(&self) -> (Self, Self) {
+        (
+            Self::new(self.value >> 1, self.mask >> 1),
+            Self::new(self.value & 1, self.mask & 1),
+        )
+    }
+
+    /// A new tnum_mul proposed by frederic
+    pub fn mul_rec(&self, other: Self) -> Self {
+        if self.mask == 0 && other.mask == 0 {
+            // both are known
+            Self::new(self.value.wrapping_mul(other.value), 0)
+        } else if self.mask == u64::MAX && other.mask == u64::MAX {
+            //both are unknown
+            Self::new(0, u64::MAX)
+        } else if (self.value == 0 && self.mask == 0) || (other.value == 0 && other.mask == 0) {
+            // mult by 0
+            Self::new(0, 0)
+        } else if self.value == 1 && self.mask == 0 {
+            // mult by 1
+            other
+        } else if other.value == 1 && other.mask == 0 {
+            // mult by 1
+            *self
+        } else {
+            let (a_up, _a_low) = self.decompose();
+            let (b_up, _b_low) = other.decompose();
+            a_up.mul_rec(b_up)
+            //tnum_mul_rec(a_up, b_up) + tnum_mul_rec(a_up, b_low) + tnum_mul_rec(a_low, b_up) + tnum_mul_rec(a_low, b_low)
+            // TODO: this one is wrong, replace this line with the following impl
+            /* decompose the mask of am && bm
+            so that the last bits either 0s or 1s
+            In assembly, finding the rightmost 1 or 0 of a number is fast
+
+            let (a_up,a_low) = decompose a in
+            let (b_up,b_low) = decompose b in
+            // a_low and b_low are either 1s or 0s
+            (mul a_up b_up) + (mul a_up b_low) +
+            (mul a_low b_up) + (mul a_low b_low)
+            */
+        }
+    }
+
+    /// tnum 用与截断到指定字节大小
+    /// 截断到低 size 个字节，size 取 1..=8
+    pub fn cast(&self, size: u8) -> Self {
+        self.trunc(size as u32 * 8)
+    }
+
+    #[deprecated(note = "判断的是低位都可能为 1 而不是对齐，请使用 `is_aligned_to`")]
+    pub fn is_aligned(&self, size: u64) -> bool {
+        (self.value | self.mask) & (size - 1) == (size - 1)
+    }
+
+    /// 是否按 align（2 的幂）对齐：低位全部已知为 0 时必然对齐，有已知的 1 时必然不对齐
+    pub fn is_aligned_to(&self, align: u64) -> TriState {
+        assert!(align.is_power_of_two(), "对齐量须为 2 的幂");
+        let low = align - 1;
+        if self.value & low != 0 {
+            TriState::Never
+        } else if self.mask & low == 0 {
+            TriState::Definitely
+        } else {
+            TriState::Maybe
+        }
+    }
+
+    /// 向下对齐到 align（2 的幂）：清除低位，是精确的
+    pub fn align_down(&self, align: u64) -> Self {
+        assert!(align.is_power_of_two(), "对齐量须为 2 的幂");
+        if self.is_bottom() {
+            return *self;
+        }
+        let high = !(align - 1);
+        Self::new(self.value & high, self.mask & high)
+    }
+
+    /// 向上对齐到 align（2 的幂）：(x + align - 1) & !(align - 1)，在 2^64 上回绕
+    pub fn align_up(&self, align: u64) -> Self {
+        assert!(align.is_power_of_two(), "对齐量须为 2 的幂");
+        self.add(Self::const_val(align - 1)).align_down(align)
+    }
+
+    pub fn subreg(&self) -> Self {
+        self.cast(4)
+    }
+
+    pub fn clear_subreg(&self) -> Self {
+        self.lshr_const(32).shl_const(32)
+    }
+
+    pub fn with_subreg(&self, subreg: Self) -> Self {
+        self.clear_subreg().bit_or(subreg.subreg())
+    }
+
+    /// 把 value 的低 size 个字节写入第 offset 个字节起的字节通道（小端），其余字节不变
+    ///
+    /// `with_subreg` 即 offset 为 0、size 为 4 的情形；offset + size 不超过 8。
+    pub fn store_bytes(&self, offset: u32, size: u32, value: Self) -> Self {
+        assert!(
+            (1..=8).contains(&size) && offset + size <= 8,
+            "字节通道须在 8 字节之内"
+        );
+        if self.is_bottom() || value.is_bottom() {
+            return Self::bottom();
+        }
+        let shift = offset * 8;
+        let lane = (u64::MAX >> (64 - size * 8)) << shift;
+        Self::new(
+            (self.value & !lane) | ((value.value << shift) & lane),
+            (self.mask & !lane) | ((value.mask << shift) & lane),
+        )
+    }
+
+    pub fn store8(&self, offset: u32, value: Self) -> Self {
+        self.store_bytes(offset, 1, value)
+    }
+
+    pub fn store16(&self, offset: u32, value: Self) -> Self {
+        self.store_bytes(offset, 2, value)
+    }
+
+    pub fn store32(&self, offset: u32, value: Self) -> Self {
+        self.store_bytes(offset, 4, value)
+    }
+
+    /// 读出第 offset 个字节起的 size 个字节，signed 为 true 时符号扩展，否则零扩展
+    pub fn load_bytes(&self, offset: u32, size: u32, signed: bool) -> Self {
+        assert!(
+            (1..=8).contains(&size) && offset + size <= 8,
+            "字节通道须在 8 字节之内"
+        );
+        let lane = self.lshr_const(offset as u64 * 8).trunc(size * 8);
+        if signed {
+            lane.sign_extend(size as u8 * 8)
+        } else {
+            lane
+        }
+    }
+
+    pub fn load8(&self, offset: u32, signed: bool) -> Self {
+        self.load_bytes(offset, 1, signed)
+    }
+
+    pub fn load16(&self, offset: u32, signed: bool) -> Self {
+        self.load_bytes(offset, 2, signed)
+    }
+
+    pub fn load32(&self, offset: u32, signed: bool) -> Self {
+        self.load_bytes(offset, 4, signed)
+    }
+
+    /// 低 32 位子寄存器，截断为 [`Tnum32`]
+    pub fn subreg32(&self) -> Tnum32 {
+        if self.is_bottom() {
+            return Tnum32::bottom();
+        }
+        Tnum32::new(self.value as u32, self.mask as u32)
+    }
+
+    /// ALU32 运算：在两个操作数的低 32 位上求值，结果零扩展写回，高 32 位清零
+    pub fn alu32<F: FnOnce(Tnum32, Tnum32) -> Tnum32>(&self, other: Self, op: F) -> Self {
+        op(self.subreg32(), other.subreg32()).zext64()
+    }
+
+    pub fn with_const_subreg(&self, value: u32) -> Self {
+        self.with_subreg(Self::const_val(value as u64))
+    }
+
+    /// BPF_END 16 位字节交换：取低 16 位交换字节，高位清零
+    pub fn bswap16(&self) -> Self {
+        if self.is_bottom() {
+            return *self;
+        }
+        Self::new(
+            (self.value as u16).swap_bytes() as u64,
+            (self.mask as u16).swap_bytes() as u64,
+        )
+    }
+
+    /// BPF_END 32 位字节交换：取低 32 位交换字节，高位清零
+    pub fn bswap32(&self) -> Self {
+        if self.is_bottom() {
+            return *self;
+        }
+        Self::new(
+            (self.value as u32).swap_bytes() as u64,
+            (self.mask as u32).swap_bytes() as u64,
+        )
+    }
+
+    /// BPF_END 64 位字节交换
+    pub fn bswap64(&self) -> Self {
+        if self.is_bottom() {
+            return *self;
+        }
+        Self::new(self.value.swap_bytes(), self.mask.swap_bytes())
+    }
+
+    /// 有符号取余操作（SRem）
+    pub fn srem(&self, other: Self) -> Self {
+        // 处理 bottom 和 top 情况
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        } else if self.is_top() || other.is_top() {
+            return Self::top_produced();
+        }
+
+        // 处理单点值情况（除数为 0 留给下面处理）
+        if self.is_singleton() && other.is_singleton() && other.value != 0 {
+            let res_single = Tnum::new(
+                (self.value as i64).wrapping_rem(other.value as i64) as u64,
+                0,
+            );
+            return res_single;
+        }
+
+        // 处理除数为0的情况
+        if other.value == 0 {
+            stats::record(stats::Counter::DivisorZeroTop);
+            Self::top_produced()
+        } else {
+            let mut res = rem_get_low_bits(self, &other);
+            if other.mask == 0
+                && (other.value) & 1 == 0
+                && ((other.value.trailing_zeros() + other.value.leading_zeros() + 1) == 64)
+            {
+                let low_bits = other.value - 1;
+                if self.is_nonnegative()
+                    || (other.value.trailing_zeros() <= self.count_min_trailing_zeros())
+                {
+                    res.value &= low_bits;
+                    res.mask &= low_bits;
+                }
+                if self.is_negative() && !(self.value & low_bits) == 0 {
+                    res.mask &= low_bits;
+                    res.value |= !low_bits;
+                }
+                return res;
+            }
+            let leadingz = self.count_min_leading_zeros();
+            res.value.clear_high_bits(leadingz);
+            res.mask.clear_high_bits(leadingz);
+            res
+        }
+    }
+
+    /// 有符号除法操作
+    pub fn signed_div(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        }
+
+        if self.is_singleton() && other.is_singleton() {
+            return match (self.value as i64).checked_div(other.value as i64) {
+                Some(q) => Tnum::new(q as u64, 0),
+                // i64::MIN / -1 回绕为 i64::MIN；除以 0 没有定义，取 top
+                None if other.value != 0 => Tnum::new(self.value, 0),
+                None => Self::top_produced(),
+            };
+        }
+
+        if self.is_nonnegative() && other.is_nonnegative() {
+            return self.udiv(other);
+        }
+
+        let mut result = Self::top();
+        let mut tmp: i64 = 0;
+
+        if self.is_negative() && other.is_negative() {
+            if self.value == i64::MIN as u64 && other.is_singleton() && other.value == u64::MAX {
+                return Self::top_produced();
+            }
+
+            let denom = other.get_signed_max_value();
+            let num = self.get_signed_min_value();
+
+            if !(num == i64::MIN as u64 && denom == i64::MAX as u64) {
+                tmp = (num as i64).wrapping_div(denom as i64);
+            } else {
+                tmp = i64::MAX;
+            }
+        } else if self.is_negative() && other.is_nonnegative() {
+            // Result is negative if -LHS u>= RHS
+            let neg_lhs_max: i64 = (self.get_signed_max_value() as i64).wrapping_neg();
+            if neg_lhs_max >= other.get_signed_max_value() as i64 {
+                let denom = other.get_signed_min_value();
+                let num = self.get_signed_min_value();
+                // 除数可能为 0 时不据此收紧结果
+                tmp = (num as i64).checked_div(denom as i64).unwrap_or(0);
+            }
+        } else if self.is_nonnegative() && other.is_negative() {
+            // Result is negative if LHS u>= -RHS
+            let neg_rhs_min = (other.get_signed_min_value() as i64).wrapping_neg();
+            if self.get_signed_min_value() >= neg_rhs_min as u64 {
+                let denom = other.get_signed_max_value();
+                let num = self.get_signed_max_value();
+                tmp = (num as i64).wrapping_div(denom as i64);
+            }
+        }
+
+        if tmp != 0 {
+            if (tmp >> 63) & 1 == 0 {
+                let lead_zeros = tmp.leading_zeros();
+                result.clear_high_bits(lead_zeros);
+            } else {
+                let lead_ones = (!tmp).leading_zeros();
+                if lead_ones > 0 {
+                    let high_mask = u64::MAX << (64 - lead_ones);
+                    result.value |= high_mask;
+                    result.mask &= !high_mask;
+                }
+            }
+        }
+        result
+    }
+
+    /// fast_divide
+    ///
+    /// 除数不是常数时，若未知位不超过 [`FAST_DIVIDE_SPLIT_BITS`]，对除数的每个非零具体值
+    /// 分别走常数路径、与商的区间求 meet 后再求 join（至多 8 次）；否则回退到 `sdiv`。
+    pub fn fast_divide(&self, other: Self) -> Self {
+        if other.mask == 0 && other.value == 0 {
+            stats::record(stats::Counter::DivisorZeroTop);
+            return Self::top_produced();
+        } else if other.mask == 0 && other.value == 1 {
+            return *self;
+        } else if other.mask == 0 {
+            let divider = DividerU64::divide_by(other.value);
+            match divider {
+                DividerU64::Fast { magic, shift } => {
+                    stats::record(stats::Counter::FastDivideFast);
+                    // ((n * magic) >> 64) >> shift
+                    let q = self.umulh(Tnum::const_val(magic));
+                    return q.tnum_rshift(shift);
+                }
+                DividerU64::BitShift(shift) => {
+                    stats::record(stats::Counter::FastDivideBitShift);
+                    return self.tnum_rshift(shift);
+                }
+                DividerU64::General { magic_low, shift } => {
+                    stats::record(stats::Counter::FastDivideGeneral);
+                    return self.general_divide(magic_low, shift as u32);
+                }
+            }
+        }
+        if other.mask.count_ones() <= FAST_DIVIDE_SPLIT_BITS {
+            stats::record(stats::Counter::FastDivideSplit);
+            // 除数为 0 没有结果，不参与 join；每个分支再与商的区间求 meet
+            let (lo, hi) = (self.umin_value(), self.umax_value());
+            return Self::join_many(other.iter_concrete().filter(|&d| d != 0).map(|d| {
+                self.fast_divide(Tnum::const_val(d))
+                    .meet(&Tnum::from_range(lo / d, hi / d))
+            }));
+        }
+        stats::record(stats::Counter::FastDivideFallback);
+        stats::record(stats::Counter::BudgetExceeded);
+        self.sdiv(other)
+    }
+
+    /// 按常数有符号除法的 magic 乘法序列（向零取整），除数不是常数时回退到 `sdiv`
+    ///
+    /// |d| 为 2 的幂时先给负数加上 2^k - 1 再算术右移；否则取 |d| 的 magic (M, s)，
+    /// 计算 smulh(n, M)，M 的符号位为 1 时再加 n，算术右移 s 位后商为负则加 1。
+    /// d 为负时最后取负。每一步都用对应的抽象转移函数。
+    pub fn fast_sdivide(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        } else if other.mask != 0 {
+            return self.sdiv(other);
+        }
+        let d = other.value as i64;
+        match d {
+            0 => {
+                stats::record(stats::Counter::DivisorZeroTop);
+                Self::top_produced()
+            }
+            1 => *self,
+            -1 => self.neg(),
+            _ => {
+                let abs_d = d.unsigned_abs();
+                let q = if abs_d.is_power_of_two() {
+                    let k = abs_d.trailing_zeros() as u64;
+                    let bias = self.ashr_const(63).lshr_const(64 - k);
+                    self.add(bias).ashr_const(k)
+                } else {
+                    let (magic, shift) = signed_magic(abs_d);
+                    let mut q = self.smulh(Tnum::const_val(magic));
+                    if (magic as i64) < 0 {
+                        q = q.add(*self);
+                    }
+                    q = q.ashr_const(shift as u64);
+                    q.add(q.lshr_const(63))
+                };
+                if d < 0 {
+                    q.neg()
+                } else {
+                    q
+                }
+            }
+        }
+    }
+
+    /// `DividerU64::General` 路径：实际 magic 为 2^64 + magic_low，需要 65 位
+    ///
+    /// 64 位序列 ((n - q) >> 1) + q 中的 `sub` 在 mask 重叠时丢失全部精度，这里改为在 128 位下
+    /// 直接计算 n·(2^64 + magic_low) >> (65 + shift)。乘积可能超过 128 位，所以先除以 2：
+    /// (n·2^64 + n·magic_low) >> 1 = (n << 63) + (n·magic_low >> 1)，两项之和小于 2^128。
+    fn general_divide(&self, magic_low: u64, shift: u32) -> Self {
+        let n = self.widen_u128();
+        let half = n.mul(TnumU128::const_val(magic_low as u128)).lshr_const(1);
+        half.add(n.shl_const(63))
+            .lshr_const(64 + shift as u64)
+            .low()
+    }
+
+    /// 无符号除法的组合入口：`fast_divide` 与 `udiv` 都是可靠的上近似，结果取二者的 meet
+    ///
+    /// 除数未知位过多时 `fast_divide` 回退到 `sdiv`，这条路径对无符号除法不可靠，此时只用 `udiv`。
+    pub fn best_udiv(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        }
+        let res = self.udiv(other);
+        if other.mask.count_ones() > FAST_DIVIDE_SPLIT_BITS {
+            stats::record(stats::Counter::BudgetExceeded);
+            return res;
+        }
+        let res = res.meet(&self.fast_divide(other));
+        debug_assert!(!res.is_bottom(), "best_udiv: fast_divide 与 udiv 不相交");
+        res
+    }
+
+    /// 按常数无符号取余：编译器生成的 x - (x / c) * c，商由 `fast_divide` 求得
+    ///
+    /// 结果再与区间 [0, c - 1] 取 meet；c 为 0 时返回 top。不与直接的 `urem` 取 meet，
+    /// 两条路径的可靠性见 tests/fast_rem.rs。
+    pub fn fast_urem_const(&self, c: u64) -> Self {
+        if self.is_bottom() {
+            return Self::bottom();
+        } else if c == 0 {
+            stats::record(stats::Counter::DivisorZeroTop);
+            return Self::top_produced();
+        }
+        let d = Tnum::const_val(c);
+        let composed = self.sub(self.fast_divide(d).mul(d));
+        composed.meet(&Tnum::from_range(0, c - 1))
+    }
+
+    /// 按常数有符号取余：x - (x / c) * c，商由 `fast_sdivide` 求得
+    ///
+    /// 与 `fast_urem_const` 一样不与直接的 `srem` 取 meet。
+    pub fn fast_srem_const(&self, c: i64) -> Self {
+        if self.is_bottom() {
+            return Self::bottom();
+        } else if c == 0 {
+            stats::record(stats::Counter::DivisorZeroTop);
+            return Self::top_produced();
+        }
+        let d = Tnum::const_val(c as u64);
+        self.sub(self.fast_sdivide(d).mul(d))
+    }
+
+    /// 按 `policy` 处理除数为 0 的有符号除法
+    ///
+    /// 除数可能为 0 时，除数为 {0, 2^k} 的非零部分按 2^k 计算，其余取 top。
+    pub fn sdiv_with(&self, other: Self, policy: DivZeroPolicy) -> Self {
+        self.div_zero_with(other, policy, Self::sdiv, || match other.pow2_shift() {
+            Some(k) => self.sdiv_pow2(k),
+            None => Self::top(),
+        })
+    }
+
+    /// 除以 2^k：向 0 取整，非负数即逻辑右移 k 位，负数先加上 2^k - 1 再算术右移 k 位
+    ///
+    /// 符号位未知时按符号位拆成两部分分别计算再求 join。2^63 作为有符号数是 i64::MIN，
+    /// 只有被除数也为 i64::MIN 时商为 1，其余为 0。
+    fn sdiv_pow2(&self, k: u32) -> Self {
+        let sign = 1u64 << 63;
+        if k == 63 {
+            return match (self.contains_value(sign), self.is_singleton()) {
+                (false, _) => Self::const_val(0),
+                (true, true) => Self::const_val(1),
+                (true, false) => Self::new(0, 1),
+            };
+        }
+        let amount = Self::const_val(k as u64);
+        let mut res = Self::bottom();
+        if self.value & sign == 0 {
+            res = Self::new(self.value, self.mask & !sign).lshr(&amount);
+        }
+        if (self.value | self.mask) & sign != 0 {
+            let neg = Self::new(self.value | sign, self.mask & !sign);
+            let bias = Self::const_val((1u64 << k) - 1);
+            res = res.or(&neg.add(bias).ashr(&amount));
+        }
+        res
+    }
+
+    /// 按 `policy` 处理除数为 0 的有符号取余
+    pub fn srem_with(&self, other: Self, policy: DivZeroPolicy) -> Self {
+        // 除数非零时余数与被除数同号且绝对值不超过被除数
+        self.div_zero_with(other, policy, Self::srem, || {
+            let mut res = Self::top();
+            if self.is_nonnegative() {
+                res.clear_high_bits(self.count_min_leading_zeros());
+            }
+            res
+        })
+    }
+
+    /// 按 `policy` 处理除数为 0 的 `best_udiv`
+    ///
+    /// 除数可能为 0 时，非零部分取 `udiv` 的非零部分，除数未知位不超过 [`FAST_DIVIDE_SPLIT_BITS`]
+    /// 时再与 `fast_divide`（分情况枚举时跳过 0）取 meet。
+    pub fn best_udiv_with(&self, other: Self, policy: DivZeroPolicy) -> Self {
+        self.div_zero_with(other, policy, Self::best_udiv, || {
+            let res = self.udiv_with(other, DivZeroPolicy::Bottom);
+            if other.mask.count_ones() > FAST_DIVIDE_SPLIT_BITS {
+                res
+            } else {
+                res.meet(&self.fast_divide(other))
+            }
+        })
+    }
+
+    /// 按 `policy` 处理除数为 0 的 `fast_divide`
+    ///
+    /// 除数至多 [`FAST_DIVIDE_SPLIT_BITS`] 个未知位时分情况枚举本就跳过 0，结果即为非零部分；
+    /// 未知位更多时 `fast_divide` 回退到有符号的 `sdiv`，对无符号除法不可靠，这里改用 `udiv_with`。
+    pub fn fast_divide_with(&self, other: Self, policy: DivZeroPolicy) -> Self {
+        if other.mask.count_ones() > FAST_DIVIDE_SPLIT_BITS {
+            return self.udiv_with(other, policy);
+        }
+        self.div_zero_with(other, policy, Self::fast_divide, || self.fast_divide(other))
+    }
+
+    /// 有符号除法操作
+    pub fn sdiv(&self, other: Self) -> Self {
+        if self.is_bottom() || other.is_bottom() {
+            return Self::bottom();
+        }
+        // 除数恰为 2^k 时退化为移位；γ 为 {0, 2^k} 时非零部分的移位见 sdiv_with
+        if other.is_singleton() {
+            if let Some(k) = other.pow2_shift() {
+                return self.sdiv_pow2(k);
+            }
+        }
+        if self.is_top() || other.is_top() {
+            return Self::top_produced();
+        }
+
+        if other.value == 0 {
+            stats::record(stats::Counter::DivisorZeroTop);
+            return Self::top_produced();
+        } else if self.mask == 0 && other.mask == 0 {
+            return Self::new(
+                (self.value as i64).wrapping_div(other.value as i64) as u64,
+                0,
+            );
+        }
+
+        let t0 = self.get_zero_circle();
+        let t1 = self.get_one_circle();
+        let x0 = other.get_zero_circle();
+        let x1 = other.get_one_circle();
+
+        let res00 = t0.signed_div(x0);
+        let res01 = t0.signed_div(x1);
+        let res10 = t1.signed_div(x0);
+        let res11 = t1.signed_div(x1);
+
+        Tnum::join_many([res00, res01, res10, res11])
+    }
+
+    /// 从有符号区间构造 tnum
+    ///
+    /// 区间不跨越 0 时与无符号区间相同；跨越 0 时区间同时包含 0 与 -1，每一位都可取 0 和 1，
+    /// top 就是最优抽象。
+    pub fn from_signed_range(min: i64, max: i64) -> Self {
+        if min < 0 && max >= 0 {
+            Self::top()
+        } else {
+            Self::from_range(min as u64, max as u64)
+        }
+    }
+
+    /// 有符号最小值
+    pub fn smin_value(&self) -> i64 {
+        self.get_signed_min_value() as i64
+    }
+
+    /// 有符号最大值
+    pub fn smax_value(&self) -> i64 {
+        self.get_signed_max_value() as i64
+    }
+
+    /// 包含 γ(self) 的最紧有符号区间，bottom 返回空区间 (i64::MAX, i64::MIN)
+    pub fn to_signed_range(&self) -> (i64, i64) {
+        if self.is_bottom() {
+            return (i64::MAX, i64::MIN);
+        }
+        (self.smin_value(), self.smax_value())
+    }
+
+    /// 加法及其进位、有符号溢出标志，两个标志都是精确的
+    pub fn add_with_flags(&self, other: Self) -> (Self, ArithFlags) {
+        let (_, carry) = self.checked_add(other);
+        let flags = arith_flags(self, &other, carry, |x, y| x + y);
+        (self.add(other), flags)
+    }
+
+    /// 减法及其借位、有符号溢出标志，两个标志都是精确的
+    pub fn sub_with_flags(&self, other: Self) -> (Self, ArithFlags) {
+        let (_, carry) = self.checked_sub(other);
+        let flags = arith_flags(self, &other, carry, |x, y| x - y);
+        (self.sub(other), flags)
+    }
+
+    /// 乘法及其进位、有符号溢出标志，两个标志都是精确的
+    pub fn mul_with_flags(&self, other: Self) -> (Self, ArithFlags) {
+        let (_, carry) = self.checked_mul(other);
+        let flags = arith_flags(self, &other, carry, |x, y| x * y);
+        (self.mul(other), flags)
+    }
+
+    /// 有符号最小值的位模式：符号位未知时取 1，其余未知位取 0
+    fn get_signed_min_value(&self) -> u64 {
+        self.value | (self.mask & (1u64 << 63))
+    }
+
+    /// 有符号最大值的位模式：符号位未知时取 0，其余未知位取 1
+    fn get_signed_max_value(&self) -> u64 {
+        self.value | (self.mask & !(1u64 << 63))
+    }
+
+    pub fn get_zero_circle(&self) -> Self {
+        let sign_max = i64::MAX;
+        let value = self.value as i64;
+        let mask = self.mask as i64;
+        if value & (1i64 << 63) != 0 {
+            Tnum::new(sign_max as u64, sign_max as u64)
+        } else if mask & (1i64 << 63) != 0 {
+            Tnum::new(value as u64, (mask & sign_max) as u64)
+        } else {
+            *self
+        }
+    }
+
+    pub fn get_one_circle(&self) -> Self {
+        let value = self.value as i64;
+        let mask = self.mask as i64;
+        let unsign_max = u64::MAX;
+        if value & (1i64 << 63) != 0 {
+            *self
+        } else if mask & (1i64 << 63) != 0 {
+            let mut value = value;
+            value |= 1i64 << 63;
+            let mut mask = mask;
+            mask &= !(1i64 << 63);
+            Tnum::new(value as u64, mask as u64)
+        } else {
+            Tnum::new(unsign_max, unsign_max)
+        }
+    }
+}
+
+/// 一个 tnum 可推出的各项事实
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TnumFacts {
+    /// 无符号范围
+    pub umin: u64,
+    pub umax: u64,
+    /// 有符号范围
+    pub smin: i64,
+    pub smax: i64,
+    /// 保证的对齐：所有具体值都是它的倍数（2 的幂，最大 2^63）
+    pub align: u64,
+    /// 奇偶性：`Some(true)` 表示必为奇数
+    pub odd: Option<bool>,
+    /// 符号：`Some(true)` 表示必为负数
+    pub negative: Option<bool>,
+    /// 未知位个数
+    pub unknown_bits: u32,
+    /// 单点值
+    pub singleton: Option<u64>,
+}
+
+/// `add_with_flags` 等返回的三态标志
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ArithFlags {
+    /// 无符号进位（减法为借位）：精确结果超出 [0, 2^64)
+    pub carry: MaybeOverflow,
+    /// 有符号溢出：精确结果超出 [i64::MIN, i64::MAX]
+    pub overflow: MaybeOverflow,
+}
+
+/// 格上的偏序：`a <= b` 当且仅当 γ(a) ⊆ γ(b)，不可比较时返回 `None`
+///
+/// 不同表示的 bottom 之间互不可比较，以保持与 `PartialEq` 一致。
+impl<T: Word> PartialOrd for Tnum<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            Some(Ordering::Equal)
+        } else if self.is_bottom() && other.is_bottom() {
+            None
+        } else if Tnum::le(self, other) {
+            Some(Ordering::Less)
+        } else if Tnum::le(other, self) {
+            Some(Ordering::Greater)
+        } else {
+            None
+        }
+    }
+}
+
+/// 用运算符语法调用转移函数的二元运算符
+macro_rules! impl_binary_op {
+    ($($trait:ident, $method:ident => $transfer:ident;)*) => {$(
+        impl<T: Word> $trait for Tnum<T> {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                Tnum::$transfer(&self, rhs)
+            }
+        }
+    )*};
+}
+
+// `&`、`|` 是按位运算，格上的 meet/join 仍需显式调用 `and`/`or`
+impl_binary_op! {
+    Add, add => add;
+    Sub, sub => sub;
+    Mul, mul => mul;
+    BitAnd, bitand => bit_and;
+    BitOr, bitor => bit_or;
+    BitXor, bitxor => xor;
+}
+
+impl<T: Word> Not for Tnum<T> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Tnum::not(&self)
+    }
+}
+
+/// 左移常数位，移位量按位宽取模
+impl<T: Word> Shl<u8> for Tnum<T> {
+    type Output = Self;
+
+    fn shl(self, k: u8) -> Self {
+        self.shl_const(k as u64)
+    }
+}
+
+/// 逻辑右移常数位
+impl<T: Word> Shr<u8> for Tnum<T> {
+    type Output = Self;
+
+    fn shr(self, k: u8) -> Self {
+        self.lshr_const(k as u64)
+    }
+}
+
+impl<T: Word> Tnum<T> {
+    /// 低 bits 位的 x 记法，高位在前，`x` 为未知位；bits 超过位宽时取位宽
+    fn sbin_digits(&self, bits: usize) -> impl Iterator<Item = char> + '_ {
+        (0..bits.min(T::BITS as usize)).rev().map(|i| {
+            let bit = |w: T| w.wrapping_shr(i as u32) & T::ONE != T::ZERO;
+            match (bit(self.mask), bit(self.value)) {
+                (true, _) => 'x',
+                (false, true) => '1',
+                (false, false) => '0',
+            }
+        })
+    }
+
+    /// 低 bits 位的 x 记法字符串（高位在前，`x` 为未知位），bits 超过位宽时取位宽；
+    /// bottom 为 `⊥`
+    #[cfg(feature = "std")]
+    pub fn to_sbin(&self, bits: usize) -> String {
+        if self.is_bottom() {
+            return "⊥".to_string();
+        }
+        self.sbin_digits(bits).collect()
+    }
+}
+
+/// 以 `0b1x0x` 的形式输出，宽度参数指定输出的低位个数（默认为完整位宽），
+/// 如 `format!("{:8}", t)` 只输出低 8 位
+impl<T: Word> fmt::Display for Tnum<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bits = f.width().unwrap_or(T::BITS as usize);
+        if self.is_bottom() {
+            f.write_str("⊥")
+        } else {
+            f.write_str("0b")?;
+            self.sbin_digits(bits)
+                .try_for_each(|c| fmt::Write::write_char(f, c))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseTnumError {
+    /// 没有任何位
+    Empty,
+    /// 出现 `0`、`1`、`x`、`_` 以外的字符
+    InvalidDigit(char),
+    /// 位数超过位宽
+    TooLong,
+}
+
+impl fmt::Display for ParseTnumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseTnumError::Empty => f.write_str("没有任何位"),
+            ParseTnumError::InvalidDigit(c) => write!(f, "非法字符 {:?}", c),
+            ParseTnumError::TooLong => f.write_str("位数超过位宽"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseTnumError {}
+
+/// 解析 [`Display`](fmt::Display) 输出的 x 记法：`0b` 前缀可省略，可用 `_` 分隔，
+/// 省略的高位为已知的 0；`⊥` 解析为 bottom
+impl<T: Word> FromStr for Tnum<T> {
+    type Err = ParseTnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "⊥" {
+            return Ok(Self::bottom());
+        }
+        let digits = s.strip_prefix("0b").unwrap_or(s);
+        let mut t = Self::const_val(T::ZERO);
+        let mut bits = 0;
+        for c in digits.chars().filter(|&c| c != '_') {
+            if bits == T::BITS {
+                return Err(ParseTnumError::TooLong);
+            }
+            t.value = t.value.wrapping_shl(1);
+            t.mask = t.mask.wrapping_shl(1);
+            match c {
+                '0' => {}
+                '1' => t.value |= T::ONE,
+                'x' | 'X' => t.mask |= T::ONE,
+                _ => return Err(ParseTnumError::InvalidDigit(c)),
+            }
+            bits += 1;
+        }
+        if bits == 0 {
+            return Err(ParseTnumError::Empty);
+        }
+        Ok(t)
+    }
+}
+
+/// join 为 [`Tnum::or`]，meet 为 [`Tnum::meet`]
+impl<T: Word> Lattice for Tnum<T> {
+    fn top() -> Self {
+        Tnum::top()
+    }
+
+    fn bottom() -> Self {
+        Tnum::bottom()
+    }
+
+    fn is_bottom(&self) -> bool {
+        Tnum::is_bottom(self)
+    }
+
+    fn join(&self, other: &Self) -> Self {
+        self.or(other)
+    }
+
+    fn meet(&self, other: &Self) -> Self {
+        Tnum::meet(self, other)
+    }
+
+    fn le(&self, other: &Self) -> bool {
+        Tnum::le(self, other)
+    }
+}
+
+impl<T: Word> Tnum<T> {
+    /// 按 (value, mask) 的字典序比较，是一个全序，可用于排序或作为有序集合的键
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        (self.value, self.mask).cmp(&(other.value, other.mask))
+    }
+}
+
+pub fn rem_get_low_bits<T: Word>(lhs: &Tnum<T>, rhs: &Tnum<T>) -> Tnum<T> {
+    if !rhs.is_zero() && (rhs.value & T::ONE) == T::ZERO && (rhs.mask & T::ONE) == T::ZERO {
+        let qzero = rhs.count_min_trailing_zeros();
+
+        // 除数的低 qzero 位全为 0（0 < qzero < 位宽）：余数的这些位与被除数相同，其余位未知
+        let low = T::MAX.wrapping_shr(T::BITS - qzero);
+        return Tnum::new(lhs.value & low, (lhs.mask & low) | !low);
+    }
+
+    Tnum::top()
+}
+
+/// 饱和运算的结果：可能溢出时在不溢出部分的结果上并上饱和值 limit
+fn saturate<T: Word>(res: Tnum<T>, overflow: MaybeOverflow, limit: T) -> Tnum<T> {
+    match overflow {
+        Some(false) => res,
+        _ => res.or(&Tnum::const_val(limit)),
+    }
+}
+
+/// 由无符号进位与有符号端点组合得到标志
+///
+/// 两个操作数先按符号拆开：每对符号部分内，精确结果（i128）的极值在端点组合处取到且可达，
+/// 乘积的符号也固定，因此「可能溢出」与「必然溢出」都可逐对精确判断后合并。
+/// bottom 时两个标志都空真地为 `Some(true)`。
+fn arith_flags(a: &Tnum, b: &Tnum, carry: MaybeOverflow, f: fn(i128, i128) -> i128) -> ArithFlags {
+    let (min, max) = (i64::MIN as i128, i64::MAX as i128);
+    let (a_pos, a_neg) = a.sign_split();
+    let (b_pos, b_neg) = b.sign_split();
+    let (mut may, mut must) = (false, true);
+    for x in [a_pos, a_neg].iter().filter(|t| !t.is_bottom()) {
+        for y in [b_pos, b_neg].iter().filter(|t| !t.is_bottom()) {
+            let (x_lo, x_hi) = x.to_signed_range();
+            let (y_lo, y_hi) = y.to_signed_range();
+            let corners = [(x_lo, y_lo), (x_lo, y_hi), (x_hi, y_lo), (x_hi, y_hi)]
+                .map(|(p, q)| f(p as i128, q as i128));
+            let lo = corners.iter().copied().min().unwrap();
+            let hi = corners.iter().copied().max().unwrap();
+            may |= lo < min || hi > max;
+            must &= lo > max || hi < min;
+        }
+    }
+    ArithFlags {
+        carry,
+        overflow: tri_state(may, must),
+    }
+}
+
+/// 有符号除以 d（d 为 |除数|，不小于 3 且不是 2 的幂）的 magic 数与移位量
+///
+/// 算法见 Hacker's Delight 10-1 节：找最小的 p ≥ 64 使 2^p > nc·(d - 2^p mod d)，
+/// 其中 nc 是最大的满足 nc mod d = d - 1 的正数；M = (2^p + d - 2^p mod d) / d。
+/// M 按 64 位补码返回，可能为负。
+fn signed_magic(d: u64) -> (u64, u32) {
+    let two63 = 1u64 << 63;
+    let anc = two63 - 1 - two63 % d;
+    let mut p = 63;
+    let (mut q1, mut r1) = (two63 / anc, two63 % anc);
+    let (mut q2, mut r2) = (two63 / d, two63 % d);
+    loop {
+        p += 1;
+        q1 = q1.wrapping_mul(2);
+        r1 = r1.wrapping_mul(2);
+        if r1 >= anc {
+            q1 = q1.wrapping_add(1);
+            r1 = r1.wrapping_sub(anc);
+        }
+        q2 = q2.wrapping_mul(2);
+        r2 = r2.wrapping_mul(2);
+        if r2 >= d {
+            q2 = q2.wrapping_add(1);
+            r2 = r2.wrapping_sub(d);
+        }
+        let delta = d - r2;
+        if !(q1 < delta || (q1 == delta && r1 == 0)) {
+            break;
+        }
+    }
+    (q2.wrapping_add(1), p - 64)
+}
+
+/// 由「可能成立」与「必然成立」得到三态结果；bottom 时 must 空真，结果为 `Some(true)`
+fn tri_state(may: bool, must: bool) -> Option<bool> {
+    if must {
+        Some(true)
+    } else if !may {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// `fast_divide` 对除数做分情况枚举时允许的最多未知位数
+pub const FAST_DIVIDE_SPLIT_BITS: u32 = 3;
+
+/// γ(x) 中的值按位宽取模后可能的移位量（位宽是 2 的幂，只看低 log2(位宽) 位）
+fn shift_amounts<T: Word>(x: &Tnum<T>) -> impl Iterator<Item = u64> {
+    let low = T::BITS as u64 - 1;
+    let (value, mask) = (x.value.to_u64() & low, x.mask.to_u64() & low);
+    (0..T::BITS as u64).filter(move |k| k & !mask == value)
+}
+
+/// 遍历 tnum 的全部具体值（枚举 mask 的所有子集），bottom 不产生任何值
+pub fn for_each_concrete<T: Word, F: FnMut(T)>(t: &Tnum<T>, f: F) {
+    t.iter_concrete().for_each(f)
+}
+
+/// [`Tnum::iter_concrete`] 返回的迭代器，按从小到大的顺序产生 γ(t) 中的值
+#[derive(Debug, Clone)]
+pub struct Concretes<T: Word> {
+    value: T,
+    mask: T,
+    /// 下一个要产生的 mask 子集
+    sub: T,
+    done: bool,
+}
+
+impl<T: Word> Iterator for Concretes<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+        let x = self.value | self.sub;
+        if self.sub == self.mask {
+            self.done = true;
+        } else {
+            // 按数值递增枚举 mask 的下一个子集
+            self.sub = self.sub.wrapping_sub(self.mask) & self.mask;
+        }
+        Some(x)
+    }
+}
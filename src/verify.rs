@@ -0,0 +1,1176 @@
+//! 转移函数的可靠性检查与精度比较
+//!
+//! [`BINARY_OPS`] 登记了每个公开二元运算的抽象转移函数与具体语义，[`check_sound`]
+//! 在缩小的位宽下检查具体结果是否都落在抽象结果中。
+//!
+//! [`compare_ops`] 按 [`CompareConfig`] 枚举操作数对，用格上的序关系比较两个抽象结果，
+//! 得到与线程数无关的 [`ComparisonReport`]。比较 fast_divide 与 sdiv、mul 与 mul_opt
+//! 等都用同一套枚举与统计。长时间的比较可以用 [`compare_ops_from`] 分批进行并保存 [`Checkpoint`]。
+//!
+//! [`Pipeline`] 把小位宽的枚举与 [`smt`] 中 64 位的 Z3 证明串起来，对每个运算给出一个结论。
+use crate::smt;
+use crate::tnum::{Tnum, FAST_DIVIDE_SPLIT_BITS};
+use fastdivide::DividerU64;
+use rayon::prelude::*;
+
+/// 64 位抽象转移函数
+pub type AbstractOp = fn(&Tnum, Tnum) -> Tnum;
+/// 具体语义，`None` 表示该输入无定义（如除以 0），不参与检查
+pub type ConcreteOp = fn(u64, u64) -> Option<u64>;
+
+/// 一个二元运算的抽象转移函数及其具体语义
+#[derive(Debug, Clone, Copy)]
+pub struct BinaryOp {
+    pub name: &'static str,
+    pub abs: AbstractOp,
+    pub con: ConcreteOp,
+}
+
+const fn op(name: &'static str, abs: AbstractOp, con: ConcreteOp) -> BinaryOp {
+    BinaryOp { name, abs, con }
+}
+
+fn sdiv_con(x: u64, y: u64) -> Option<u64> {
+    (y != 0).then(|| (x as i64).wrapping_div(y as i64) as u64)
+}
+
+fn srem_con(x: u64, y: u64) -> Option<u64> {
+    (y != 0).then(|| (x as i64).wrapping_rem(y as i64) as u64)
+}
+
+/// 全部公开的 64 位二元转移函数；移位与循环移位的移位量按 64 取模
+///
+/// `signed_div` 是 sdiv 在按符号拆分后的操作数上调用的辅助函数，不单独登记。
+pub const BINARY_OPS: &[BinaryOp] = &[
+    op("add", Tnum::add, |x, y| Some(x.wrapping_add(y))),
+    op("sub", Tnum::sub, |x, y| Some(x.wrapping_sub(y))),
+    op("mul", Tnum::mul, |x, y| Some(x.wrapping_mul(y))),
+    op("mul_opt", Tnum::mul_opt, |x, y| Some(x.wrapping_mul(y))),
+    op("mul_rec", Tnum::mul_rec, |x, y| Some(x.wrapping_mul(y))),
+    op("xtnum_mul_top", Tnum::xtnum_mul_top, |x, y| {
+        Some(x.wrapping_mul(y))
+    }),
+    op("xtnum_mul_high_top", Tnum::xtnum_mul_high_top, |x, y| {
+        Some(x.wrapping_mul(y))
+    }),
+    op("umulh", Tnum::umulh, |x, y| {
+        Some(((x as u128 * y as u128) >> 64) as u64)
+    }),
+    op("smulh", Tnum::smulh, |x, y| {
+        Some(((x as i64 as i128 * y as i64 as i128) >> 64) as u64)
+    }),
+    op("xor", Tnum::xor, |x, y| Some(x ^ y)),
+    op("and", Tnum::bit_and, |x, y| Some(x & y)),
+    op("or", Tnum::bit_or, |x, y| Some(x | y)),
+    op("sat_add", Tnum::sat_add, |x, y| Some(x.saturating_add(y))),
+    op("sat_sub", Tnum::sat_sub, |x, y| Some(x.saturating_sub(y))),
+    op("sat_mul", Tnum::sat_mul, |x, y| Some(x.saturating_mul(y))),
+    op("checked_add", |a, b| a.checked_add(b).0, u64::checked_add),
+    op("checked_sub", |a, b| a.checked_sub(b).0, u64::checked_sub),
+    op("checked_mul", |a, b| a.checked_mul(b).0, u64::checked_mul),
+    op("udiv", Tnum::udiv, u64::checked_div),
+    op("urem", Tnum::urem, u64::checked_rem),
+    op("sdiv", Tnum::sdiv, sdiv_con),
+    op("srem", Tnum::srem, srem_con),
+    op("fast_divide", Tnum::fast_divide, u64::checked_div),
+    op("fast_sdivide", Tnum::fast_sdivide, sdiv_con),
+    op("best_udiv", Tnum::best_udiv, u64::checked_div),
+    op(
+        "shl",
+        |a, b| a.shl(&b),
+        |x, y| Some(x.wrapping_shl(y as u32)),
+    ),
+    op(
+        "lshr",
+        |a, b| a.lshr(&b),
+        |x, y| Some(x.wrapping_shr(y as u32)),
+    ),
+    op(
+        "ashr",
+        |a, b| a.ashr(&b),
+        |x, y| Some((x as i64).wrapping_shr(y as u32) as u64),
+    ),
+    op(
+        "rotl",
+        |a, b| a.rotl(&b),
+        |x, y| Some(x.rotate_left(y as u32 % 64)),
+    ),
+    op(
+        "rotr",
+        |a, b| a.rotr(&b),
+        |x, y| Some(x.rotate_right(y as u32 % 64)),
+    ),
+    op("umax", Tnum::umax, |x, y| Some(x.max(y))),
+    op("umin", Tnum::umin, |x, y| Some(x.min(y))),
+    op("smax", Tnum::smax, |x, y| {
+        Some((x as i64).max(y as i64) as u64)
+    }),
+    op("smin", Tnum::smin, |x, y| {
+        Some((x as i64).min(y as i64) as u64)
+    }),
+];
+
+/// 按名称查找 [`BINARY_OPS`] 中的运算
+pub fn binary_op(name: &str) -> Option<&'static BinaryOp> {
+    BINARY_OPS.iter().find(|op| op.name == name)
+}
+
+/// 不超过该位宽时穷举全部 tnum，更宽时按固定种子抽样
+pub const EXHAUSTIVE_WIDTH: u32 = 6;
+/// 抽样时的 tnum 个数，每个至多 [`EXHAUSTIVE_WIDTH`] 个未知位
+const SAMPLED_TNUMS: usize = 256;
+
+/// 一个可靠性反例：输入 tnum、具体输入、具体结果与抽象结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Counterexample {
+    pub a: Tnum,
+    pub b: Tnum,
+    pub x: u64,
+    pub y: u64,
+    pub r: u64,
+    pub result: Tnum,
+}
+
+/// 可靠性检查的计数
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SoundnessReport {
+    /// 检查的 tnum 对数
+    pub pairs: u64,
+    /// 有定义的具体输入对数
+    pub concrete: u64,
+    /// 不在抽象结果中的具体结果个数
+    pub unsound: u64,
+    /// 按枚举顺序的第一个反例
+    pub example: Option<Counterexample>,
+    /// 第一个反例经 [`shrink`] 缩小后的结果
+    pub minimal: Option<Counterexample>,
+}
+
+impl SoundnessReport {
+    pub fn is_sound(&self) -> bool {
+        self.unsound == 0
+    }
+
+    fn merge(&mut self, other: SoundnessReport) {
+        self.pairs += other.pairs;
+        self.concrete += other.concrete;
+        self.unsound += other.unsound;
+        if self.example.is_none() {
+            self.example = other.example;
+        }
+    }
+}
+
+/// width 位的操作数：低 width 位的 tnum 零扩展或符号扩展到 64 位
+///
+/// 符号位未知时扩展后的 tnum 有 64 - width + 1 个未知位，具体值按低 width 位枚举后再扩展。
+#[derive(Debug, Clone, Copy)]
+struct Operand {
+    narrow: Tnum,
+    wide: Tnum,
+    signed: bool,
+}
+
+impl Operand {
+    fn concretes(&self, width: u32) -> impl Iterator<Item = u64> + '_ {
+        let shift = 64 - width;
+        let signed = self.signed;
+        self.narrow.iter_concrete().map(move |x| {
+            if signed {
+                ((x << shift) as i64 >> shift) as u64
+            } else {
+                x
+            }
+        })
+    }
+}
+
+/// width 位的全部 tnum（或抽样），每个都零扩展一次；符号位可能为 1 的再符号扩展一次
+fn operands(width: u32) -> Vec<Operand> {
+    let narrow: Vec<Tnum> = if width <= EXHAUSTIVE_WIDTH {
+        let limit = 1u64 << width;
+        (0..limit)
+            .flat_map(|v| (0..limit).map(move |m| (v, m)))
+            .filter(|&(v, m)| v & m == 0)
+            .map(|(v, m)| Tnum::new(v, m))
+            .collect()
+    } else {
+        let low = u64::MAX >> (64 - width);
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let mut tnums = Vec::with_capacity(SAMPLED_TNUMS);
+        while tnums.len() < SAMPLED_TNUMS {
+            let mask = next() & next() & next() & low;
+            if mask.count_ones() <= EXHAUSTIVE_WIDTH {
+                tnums.push(Tnum::new(next() & low & !mask, mask));
+            }
+        }
+        tnums
+    };
+    let sign = 1u64 << (width - 1);
+    let mut ops = Vec::with_capacity(narrow.len() * 2);
+    for t in narrow {
+        ops.push(Operand {
+            narrow: t,
+            wide: t,
+            signed: false,
+        });
+        if (t.value | t.mask) & sign != 0 && width < 64 {
+            ops.push(Operand {
+                narrow: t,
+                wide: t.sign_extend(width as u8),
+                signed: true,
+            });
+        }
+    }
+    ops
+}
+
+/// 检查 abs 相对 con 的可靠性：每个有定义的具体结果都在抽象结果中
+///
+/// 操作数为 width 位 tnum 的零扩展与符号扩展，因此有符号运算也会遇到负数；
+/// width 不超过 [`EXHAUSTIVE_WIDTH`] 时穷举全部 tnum，否则按固定种子抽样。
+/// 抽象运算本身仍在 64 位上计算。
+pub fn check_sound(abs: AbstractOp, con: ConcreteOp, width: u32) -> SoundnessReport {
+    assert!((1..=64).contains(&width), "位宽取 1..=64");
+    let ops = operands(width);
+    let rows: Vec<SoundnessReport> = ops
+        .par_iter()
+        .map(|a| {
+            let mut report = SoundnessReport::default();
+            for b in &ops {
+                report.pairs += 1;
+                let result = abs(&a.wide, b.wide);
+                for x in a.concretes(width) {
+                    for y in b.concretes(width) {
+                        let Some(r) = con(x, y) else { continue };
+                        report.concrete += 1;
+                        if !result.contains_value(r) {
+                            report.unsound += 1;
+                            report.example.get_or_insert(Counterexample {
+                                a: a.wide,
+                                b: b.wide,
+                                x,
+                                y,
+                                r,
+                                result,
+                            });
+                        }
+                    }
+                }
+            }
+            report
+        })
+        .collect();
+    let mut report = SoundnessReport::default();
+    for r in rows {
+        report.merge(r);
+    }
+    report.minimal = report.example.map(|e| shrink(abs, con, e));
+    report
+}
+
+impl Counterexample {
+    /// 用 abs、con 重新计算结果，仍不可靠时返回新的反例
+    fn recheck(abs: AbstractOp, con: ConcreteOp, a: Tnum, b: Tnum, x: u64, y: u64) -> Option<Self> {
+        let r = con(x, y)?;
+        let result = abs(&a, b);
+        (!result.contains_value(r)).then_some(Self {
+            a,
+            b,
+            x,
+            y,
+            r,
+            result,
+        })
+    }
+
+    /// 输入的规模：未知位、已知的 1 与具体输入中 1 的个数之和，每一步缩小都严格减小它
+    fn size(&self) -> u32 {
+        self.a.mask.count_ones()
+            + self.b.mask.count_ones()
+            + self.a.value.count_ones()
+            + self.b.value.count_ones()
+            + self.x.count_ones()
+            + self.y.count_ones()
+    }
+}
+
+/// 对一个操作数 t（具体输入 x ∈ γ(t)）的候选缩小：
+/// 把未知位定为 x 在该位的值；把已知的 1 连同 x 的该位清零；把 x 在未知位上的 1 改为 0
+fn shrink_candidates(t: Tnum, x: u64) -> Vec<(Tnum, u64)> {
+    let mut out = Vec::new();
+    for i in 0..64 {
+        let bit = 1u64 << i;
+        if t.mask & bit != 0 {
+            out.push((Tnum::new(t.value | (x & bit), t.mask & !bit), x));
+            if x & bit != 0 {
+                out.push((t, x & !bit));
+            }
+        } else if t.value & bit != 0 {
+            out.push((Tnum::new(t.value & !bit, t.mask), x & !bit));
+        }
+    }
+    out
+}
+
+/// 缩小可靠性反例：反复尝试减少未知位、清除已知的 1、减小具体输入，
+/// 只要新的输入仍然不可靠就接受，直到没有可接受的一步为止
+pub fn shrink(abs: AbstractOp, con: ConcreteOp, mut ce: Counterexample) -> Counterexample {
+    loop {
+        let lhs = shrink_candidates(ce.a, ce.x)
+            .into_iter()
+            .filter_map(|(a, x)| Counterexample::recheck(abs, con, a, ce.b, x, ce.y));
+        let rhs = shrink_candidates(ce.b, ce.y)
+            .into_iter()
+            .filter_map(|(b, y)| Counterexample::recheck(abs, con, ce.a, b, ce.x, y));
+        match lhs.chain(rhs).find(|next| next.size() < ce.size()) {
+            Some(next) => ce = next,
+            None => return ce,
+        }
+    }
+}
+
+/// 单调性反例：a ⊑ a2、b ⊑ b2，但 op(a, b) ⋢ op(a2, b2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MonotoneViolation {
+    pub a: Tnum,
+    pub a2: Tnum,
+    pub b: Tnum,
+    pub b2: Tnum,
+    /// op(a, b)
+    pub lo: Tnum,
+    /// op(a2, b2)
+    pub hi: Tnum,
+}
+
+impl MonotoneViolation {
+    /// 重新计算两个结果，仍违反单调性时返回新的反例
+    fn recheck(abs: AbstractOp, a: Tnum, a2: Tnum, b: Tnum, b2: Tnum) -> Option<Self> {
+        let (lo, hi) = (abs(&a, b), abs(&a2, b2));
+        (!lo.le(&hi)).then_some(Self {
+            a,
+            a2,
+            b,
+            b2,
+            lo,
+            hi,
+        })
+    }
+
+    fn size(&self) -> u32 {
+        [self.a, self.a2, self.b, self.b2]
+            .iter()
+            .map(|t| t.value.count_ones() + t.mask.count_ones())
+            .sum()
+    }
+}
+
+/// 单调性检查的计数
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MonotoneReport {
+    /// 检查的 (a, a2, b, b2) 组数
+    pub checked: u64,
+    pub violations: u64,
+    /// 按枚举顺序的第一个反例
+    pub example: Option<MonotoneViolation>,
+    /// 第一个反例经 [`shrink_monotone`] 缩小后的结果
+    pub minimal: Option<MonotoneViolation>,
+}
+
+/// t 的直接后继：把低 width 位中的一个已知位改为未知
+fn successors(t: Tnum, width: u32) -> impl Iterator<Item = Tnum> {
+    let low = u64::MAX >> (64 - width);
+    (0..width)
+        .map(|i| 1u64 << i)
+        .filter(move |bit| (t.mask | !low) & bit == 0)
+        .map(move |bit| Tnum::new(t.value & !bit, t.mask | bit))
+}
+
+/// 检查 abs 的单调性：a ⊑ a2 ∧ b ⊑ b2 ⇒ abs(a, b) ⊑ abs(a2, b2)
+///
+/// 在 width 位的全部非 bottom tnum 上，只需检查每个参数各自的直接后继（多走一步由传递性得到）。
+/// 两个操作数分别零扩展或符号扩展到 64 位（扩展是单调的），四种组合都检查。
+/// width 不超过 [`EXHAUSTIVE_WIDTH`]。
+pub fn check_monotone(abs: AbstractOp, width: u32) -> MonotoneReport {
+    assert!(
+        (1..=EXHAUSTIVE_WIDTH).contains(&width),
+        "位宽取 1..={}",
+        EXHAUSTIVE_WIDTH
+    );
+    let limit = 1u64 << width;
+    let narrow: Vec<Tnum> = (0..limit)
+        .flat_map(|v| (0..limit).map(move |m| (v, m)))
+        .filter(|&(v, m)| v & m == 0)
+        .map(|(v, m)| Tnum::new(v, m))
+        .collect();
+    let ext = |t: Tnum, signed: bool| {
+        if signed {
+            t.sign_extend(width as u8)
+        } else {
+            t
+        }
+    };
+    let rows: Vec<MonotoneReport> = narrow
+        .par_iter()
+        .map(|&a| {
+            let mut report = MonotoneReport::default();
+            for &b in &narrow {
+                for sa in [false, true] {
+                    for sb in [false, true] {
+                        let steps = successors(a, width)
+                            .map(|a2| (a2, b))
+                            .chain(successors(b, width).map(|b2| (a, b2)));
+                        for (a2, b2) in steps {
+                            report.checked += 1;
+                            let v = MonotoneViolation::recheck(
+                                abs,
+                                ext(a, sa),
+                                ext(a2, sa),
+                                ext(b, sb),
+                                ext(b2, sb),
+                            );
+                            if let Some(v) = v {
+                                report.violations += 1;
+                                report.example.get_or_insert(v);
+                            }
+                        }
+                    }
+                }
+            }
+            report
+        })
+        .collect();
+    let mut report = MonotoneReport::default();
+    for r in rows {
+        report.checked += r.checked;
+        report.violations += r.violations;
+        if report.example.is_none() {
+            report.example = r.example;
+        }
+    }
+    report.minimal = report.example.map(|v| shrink_monotone(abs, v));
+    report
+}
+
+/// 对 t ⊑ t2 同时修改同一位、保持 t ⊑ t2 的候选：
+/// 两边都已知为 1 的位清零；两边都未知的位定为 0 或 1
+fn shrink_pair_candidates(t: Tnum, t2: Tnum) -> Vec<(Tnum, Tnum)> {
+    let mut out = Vec::new();
+    for i in 0..64 {
+        let bit = 1u64 << i;
+        if t.value & t2.value & bit != 0 {
+            out.push((
+                Tnum::new(t.value & !bit, t.mask),
+                Tnum::new(t2.value & !bit, t2.mask),
+            ));
+        } else if t.mask & t2.mask & bit != 0 {
+            for v in [0, bit] {
+                out.push((
+                    Tnum::new(t.value | v, t.mask & !bit),
+                    Tnum::new(t2.value | v, t2.mask & !bit),
+                ));
+            }
+        }
+    }
+    out
+}
+
+/// 缩小单调性反例，做法同 [`shrink`]：只接受仍然违反单调性且规模更小的输入
+pub fn shrink_monotone(abs: AbstractOp, mut v: MonotoneViolation) -> MonotoneViolation {
+    loop {
+        let lhs = shrink_pair_candidates(v.a, v.a2)
+            .into_iter()
+            .filter_map(|(a, a2)| MonotoneViolation::recheck(abs, a, a2, v.b, v.b2));
+        let rhs = shrink_pair_candidates(v.b, v.b2)
+            .into_iter()
+            .filter_map(|(b, b2)| MonotoneViolation::recheck(abs, v.a, v.a2, b, b2));
+        match lhs.chain(rhs).find(|next| next.size() < v.size()) {
+            Some(next) => v = next,
+            None => return v,
+        }
+    }
+}
+
+/// 与记录的基线结果 `(a, b, 基线结果)` 比较精度
+///
+/// 报告中 `a_finer` 为精度提升，`b_finer` 为精度回退（例子见 `b_finer_examples`），
+/// `incomparable` 也应视为回退。
+pub fn check_regression<I>(abs: AbstractOp, baseline: I, max_examples: usize) -> ComparisonReport
+where
+    I: IntoIterator<Item = (Tnum, Tnum, Tnum)>,
+{
+    let mut report = ComparisonReport::default();
+    for (a, b, old) in baseline {
+        let new = abs(&a, b);
+        let precision = Precision::of(&new, &old);
+        report.record(a, b, precision, max_examples);
+        report.record_gap(&new, &old, precision);
+    }
+    report
+}
+
+/// 操作数的枚举范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompareConfig {
+    /// 左操作数的 value 不超过 value_max；右操作数取 value|mask ≤ value_max 的 tnum
+    pub value_max: u64,
+    /// 左操作数的 mask 不超过 mask_max
+    pub mask_max: u64,
+    /// 右操作数至多有几个未知位，为 0 时只枚举常数
+    pub rhs_unknown_bits: u32,
+    /// 跳过 value 为 0 且带未知位（可能为 0）的右操作数，除法比较时两边都只能返回 top
+    pub skip_zero_rhs: bool,
+    /// 线程数，为 0 时使用 rayon 默认线程数
+    pub threads: usize,
+    /// 按枚举顺序记录的不可比较例子个数上限
+    pub max_examples: usize,
+}
+
+impl Default for CompareConfig {
+    fn default() -> Self {
+        Self {
+            value_max: 4096,
+            mask_max: 4096,
+            rhs_unknown_bits: 0,
+            skip_zero_rhs: true,
+            threads: 0,
+            max_examples: 10,
+        }
+    }
+}
+
+/// 两个抽象结果在格上的关系
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    Equal,
+    /// 第一个运算的结果严格更精确
+    AFiner,
+    /// 第二个运算的结果严格更精确
+    BFiner,
+    Incomparable,
+}
+
+impl Precision {
+    pub fn of(a: &Tnum, b: &Tnum) -> Self {
+        match (a.le(b), b.le(a)) {
+            (true, true) => Precision::Equal,
+            (true, false) => Precision::AFiner,
+            (false, true) => Precision::BFiner,
+            (false, false) => Precision::Incomparable,
+        }
+    }
+}
+
+/// |γ(a)| / |γ(b)|，即 2^(a 的未知位数 − b 的未知位数)
+///
+/// 小于 1 时 a 更紧。bottom 的基数为 0：两边都是 bottom 时为 1，只有 b 是 bottom 时为无穷大。
+pub fn precision_ratio(a: Tnum, b: Tnum) -> f64 {
+    match (a.is_bottom(), b.is_bottom()) {
+        (true, true) => 1.0,
+        (true, false) => 0.0,
+        (false, true) => f64::INFINITY,
+        (false, false) => 2f64.powi(log2_gap(&a, &b)),
+    }
+}
+
+/// log2 |γ(a)| − log2 |γ(b)|，两边都不是 bottom 时有意义
+fn log2_gap(a: &Tnum, b: &Tnum) -> i32 {
+    a.mask.count_ones() as i32 - b.mask.count_ones() as i32
+}
+
+/// 比较结果的计数，按左操作数的 value 顺序合并
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComparisonReport {
+    pub total: u64,
+    pub equal: u64,
+    pub a_finer: u64,
+    pub b_finer: u64,
+    pub incomparable: u64,
+    /// 按枚举顺序记录的前若干个不可比较的操作数对
+    pub examples: Vec<(Tnum, Tnum)>,
+    /// 按枚举顺序记录的前若干个第二个运算严格更精确的操作数对
+    pub b_finer_examples: Vec<(Tnum, Tnum)>,
+    /// 每个右操作数上的计数，[`compare_ops`] 按 [`rhs_space`] 的顺序给出，其他来源为空
+    pub by_rhs: Vec<RhsCounts>,
+    /// 两个结果都不是 bottom 的对数
+    pub sized: u64,
+    /// 这些对上 log2 |γ(第二个结果)| − log2 |γ(第一个结果)| 之和，正数表示第一个运算更紧
+    pub log2_gap_sum: i64,
+    /// 第一个运算严格更精确时 log2 基数差之和（可比较时差不为 0）
+    pub a_finer_gap_sum: u64,
+    /// 第二个运算严格更精确时 log2 基数差之和
+    pub b_finer_gap_sum: u64,
+}
+
+/// 一个右操作数上的比较计数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RhsCounts {
+    pub b: Tnum,
+    pub equal: u64,
+    pub a_finer: u64,
+    pub b_finer: u64,
+    pub incomparable: u64,
+}
+
+impl RhsCounts {
+    pub fn new(b: Tnum) -> Self {
+        Self {
+            b,
+            equal: 0,
+            a_finer: 0,
+            b_finer: 0,
+            incomparable: 0,
+        }
+    }
+
+    pub fn add(&mut self, precision: Precision) {
+        match precision {
+            Precision::Equal => self.equal += 1,
+            Precision::AFiner => self.a_finer += 1,
+            Precision::BFiner => self.b_finer += 1,
+            Precision::Incomparable => self.incomparable += 1,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.equal + self.a_finer + self.b_finer + self.incomparable
+    }
+}
+
+impl ComparisonReport {
+    pub fn record(&mut self, a: Tnum, b: Tnum, precision: Precision, max_examples: usize) {
+        self.total += 1;
+        match precision {
+            Precision::Equal => self.equal += 1,
+            Precision::AFiner => self.a_finer += 1,
+            Precision::BFiner => {
+                self.b_finer += 1;
+                if self.b_finer_examples.len() < max_examples {
+                    self.b_finer_examples.push((a, b));
+                }
+            }
+            Precision::Incomparable => {
+                self.incomparable += 1;
+                if self.examples.len() < max_examples {
+                    self.examples.push((a, b));
+                }
+            }
+        }
+    }
+
+    /// 记录一对结果的基数差，与 [`Self::record`] 一起调用
+    pub fn record_gap(&mut self, ra: &Tnum, rb: &Tnum, precision: Precision) {
+        if ra.is_bottom() || rb.is_bottom() {
+            return;
+        }
+        let gap = log2_gap(rb, ra);
+        self.sized += 1;
+        self.log2_gap_sum += gap as i64;
+        match precision {
+            Precision::AFiner => self.a_finer_gap_sum += gap as u64,
+            Precision::BFiner => self.b_finer_gap_sum += (-gap) as u64,
+            _ => {}
+        }
+    }
+
+    /// 平均每对的 log2 基数差，正数表示第一个运算更紧
+    pub fn average_log2_gap(&self) -> f64 {
+        average(self.log2_gap_sum as f64, self.sized)
+    }
+
+    /// 第一个运算严格更精确时平均紧多少个未知位
+    pub fn average_a_finer_gap(&self) -> f64 {
+        average(self.a_finer_gap_sum as f64, self.a_finer)
+    }
+
+    /// 第二个运算严格更精确时平均紧多少个未知位
+    pub fn average_b_finer_gap(&self) -> f64 {
+        average(self.b_finer_gap_sum as f64, self.b_finer)
+    }
+
+    /// 把后面一段枚举的结果接到末尾，例子仍按枚举顺序保留前 max_examples 个
+    pub fn merge(&mut self, other: ComparisonReport, max_examples: usize) {
+        self.total += other.total;
+        self.equal += other.equal;
+        self.a_finer += other.a_finer;
+        self.b_finer += other.b_finer;
+        self.incomparable += other.incomparable;
+        self.sized += other.sized;
+        self.log2_gap_sum += other.log2_gap_sum;
+        self.a_finer_gap_sum += other.a_finer_gap_sum;
+        self.b_finer_gap_sum += other.b_finer_gap_sum;
+        let room = max_examples.saturating_sub(self.examples.len());
+        self.examples.extend(other.examples.into_iter().take(room));
+        let room = max_examples.saturating_sub(self.b_finer_examples.len());
+        self.b_finer_examples
+            .extend(other.b_finer_examples.into_iter().take(room));
+        if self.by_rhs.is_empty() {
+            self.by_rhs = other.by_rhs;
+        } else {
+            for (mine, theirs) in self.by_rhs.iter_mut().zip(&other.by_rhs) {
+                mine.equal += theirs.equal;
+                mine.a_finer += theirs.a_finer;
+                mine.b_finer += theirs.b_finer;
+                mine.incomparable += theirs.incomparable;
+            }
+        }
+    }
+
+    /// 以 CSV 写出：第一列 kind 为 total（总计）、rhs（每个右操作数）、
+    /// incomparable 或 b_finer（例子，只有操作数没有计数）
+    pub fn write_csv<W: std::io::Write>(&self, mut w: W) -> std::io::Result<()> {
+        writeln!(
+            w,
+            "kind,a_value,a_mask,b_value,b_mask,total,equal,a_finer,b_finer,incomparable"
+        )?;
+        writeln!(
+            w,
+            "total,,,,,{},{},{},{},{}",
+            self.total, self.equal, self.a_finer, self.b_finer, self.incomparable
+        )?;
+        for c in &self.by_rhs {
+            writeln!(
+                w,
+                "rhs,,,{},{},{},{},{},{},{}",
+                c.b.value,
+                c.b.mask,
+                c.total(),
+                c.equal,
+                c.a_finer,
+                c.b_finer,
+                c.incomparable
+            )?;
+        }
+        for (kind, examples) in [
+            ("incomparable", &self.examples),
+            ("b_finer", &self.b_finer_examples),
+        ] {
+            for (a, b) in examples {
+                writeln!(
+                    w,
+                    "{},{},{},{},{},,,,,",
+                    kind, a.value, a.mask, b.value, b.mask
+                )?;
+            }
+        }
+        w.flush()
+    }
+
+    /// n 占总数的百分比，总数为 0 时为 0
+    pub fn percent(&self, n: u64) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            n as f64 * 100.0 / self.total as f64
+        }
+    }
+}
+
+/// [`Tnum::fast_divide`] 对除数走的路径
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DivisorKind {
+    /// 常数 0，返回 top
+    Zero,
+    /// 常数 1，返回被除数
+    One,
+    /// `DividerU64::Fast`
+    Fast,
+    /// `DividerU64::BitShift`，2 的幂
+    BitShift,
+    /// `DividerU64::General`，magic 需要 65 位
+    General,
+    /// 未知位不超过 [`FAST_DIVIDE_SPLIT_BITS`]，按具体值拆分
+    Split,
+    /// 未知位更多，回退到 sdiv
+    Fallback,
+}
+
+impl DivisorKind {
+    pub const ALL: [DivisorKind; 7] = [
+        DivisorKind::Zero,
+        DivisorKind::One,
+        DivisorKind::Fast,
+        DivisorKind::BitShift,
+        DivisorKind::General,
+        DivisorKind::Split,
+        DivisorKind::Fallback,
+    ];
+
+    pub fn of(b: &Tnum) -> Self {
+        match (b.mask, b.value) {
+            (0, 0) => DivisorKind::Zero,
+            (0, 1) => DivisorKind::One,
+            (0, d) => match DividerU64::divide_by(d) {
+                DividerU64::Fast { .. } => DivisorKind::Fast,
+                DividerU64::BitShift(_) => DivisorKind::BitShift,
+                DividerU64::General { .. } => DivisorKind::General,
+            },
+            (m, _) if m.count_ones() <= FAST_DIVIDE_SPLIT_BITS => DivisorKind::Split,
+            _ => DivisorKind::Fallback,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DivisorKind::Zero => "zero",
+            DivisorKind::One => "one",
+            DivisorKind::Fast => "fast",
+            DivisorKind::BitShift => "bitshift",
+            DivisorKind::General => "general",
+            DivisorKind::Split => "split",
+            DivisorKind::Fallback => "fallback",
+        }
+    }
+}
+
+/// 一类除数上的比较计数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KindCounts {
+    pub kind: DivisorKind,
+    /// 这一类的右操作数个数
+    pub divisors: u64,
+    pub equal: u64,
+    pub a_finer: u64,
+    pub b_finer: u64,
+    pub incomparable: u64,
+}
+
+impl KindCounts {
+    pub fn total(&self) -> u64 {
+        self.equal + self.a_finer + self.b_finer + self.incomparable
+    }
+}
+
+impl ComparisonReport {
+    /// 把 [`Self::by_rhs`] 按 [`DivisorKind`] 归类，只列出出现过的类别
+    pub fn by_divisor_kind(&self) -> Vec<KindCounts> {
+        DivisorKind::ALL
+            .iter()
+            .filter_map(|&kind| {
+                let mut k = KindCounts {
+                    kind,
+                    divisors: 0,
+                    equal: 0,
+                    a_finer: 0,
+                    b_finer: 0,
+                    incomparable: 0,
+                };
+                for c in self.by_rhs.iter().filter(|c| DivisorKind::of(&c.b) == kind) {
+                    k.divisors += 1;
+                    k.equal += c.equal;
+                    k.a_finer += c.a_finer;
+                    k.b_finer += c.b_finer;
+                    k.incomparable += c.incomparable;
+                }
+                (k.divisors > 0).then_some(k)
+            })
+            .collect()
+    }
+}
+
+fn average(sum: f64, n: u64) -> f64 {
+    if n == 0 {
+        0.0
+    } else {
+        sum / n as f64
+    }
+}
+
+/// 右操作数：value|mask ≤ max 且至多 unknown_bits 个未知位，按 value 再按 mask 的顺序
+pub fn rhs_space(config: &CompareConfig) -> Vec<Tnum> {
+    let max = config.value_max;
+    let width_mask = u64::MAX.checked_shr(max.leading_zeros()).unwrap_or(0);
+    let mut rhs = Vec::new();
+    for value_b in 0..=max {
+        // 只在 value_b 为 0 的位上枚举 mask_b
+        let free = !value_b & width_mask;
+        let mut mask_b = 0u64;
+        loop {
+            let ok = mask_b.count_ones() <= config.rhs_unknown_bits
+                && value_b | mask_b <= max
+                && !(config.skip_zero_rhs && value_b == 0 && mask_b != 0);
+            if ok {
+                rhs.push(Tnum::new(value_b, mask_b));
+            }
+            if mask_b == free {
+                break;
+            }
+            mask_b = mask_b.wrapping_sub(free) & free;
+        }
+    }
+    rhs
+}
+
+/// 0..=max 中与 v 没有公共 1 位的数的个数
+fn disjoint_count(v: u64, max: u64) -> u64 {
+    let mut count = 0u64;
+    for i in (0..64).rev() {
+        let bit = 1u64 << i;
+        if max & bit == 0 {
+            continue;
+        }
+        // 这一位取 0，更低的位在 v 为 0 的位置上任取
+        count += 1u64 << (!v & (bit - 1)).count_ones();
+        if v & bit != 0 {
+            return count;
+        }
+    }
+    // max 本身与 v 不相交
+    count + 1
+}
+
+/// config 下 [`compare_ops`] 比较的操作数对总数
+pub fn pair_count(config: &CompareConfig) -> u64 {
+    let rhs = rhs_space(config).len() as u64;
+    (0..=config.value_max)
+        .map(|v| disjoint_count(v, config.mask_max) * rhs)
+        .sum()
+}
+
+/// 在 config 给出的操作数对上比较 op_a 与 op_b 的精度
+///
+/// 按左操作数的 value 并行枚举，每行的结果按 value 顺序合并，输出与线程数无关。
+pub fn compare_ops<A, B>(op_a: A, op_b: B, config: &CompareConfig) -> ComparisonReport
+where
+    A: Fn(Tnum, Tnum) -> Tnum + Sync,
+    B: Fn(Tnum, Tnum) -> Tnum + Sync,
+{
+    compare_ops_from(op_a, op_b, config, CompareProgress::default(), |_| {})
+}
+
+/// 每次并行枚举的左操作数 value 行数，每批结束后回调一次
+pub const CHUNK_ROWS: u64 = 64;
+
+/// 比较的进度：下一个要枚举的左操作数 value，以及此前各行累计的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompareProgress {
+    pub next_value: u64,
+    pub report: ComparisonReport,
+    /// value 已枚举完
+    pub done: bool,
+}
+
+/// 从 progress 继续 [`compare_ops`]，每批 [`CHUNK_ROWS`] 行合并后调用 on_chunk
+///
+/// 从断点继续与一次跑完的结果相同。
+pub fn compare_ops_from<A, B, F>(
+    op_a: A,
+    op_b: B,
+    config: &CompareConfig,
+    mut progress: CompareProgress,
+    mut on_chunk: F,
+) -> ComparisonReport
+where
+    A: Fn(Tnum, Tnum) -> Tnum + Sync,
+    B: Fn(Tnum, Tnum) -> Tnum + Sync,
+    F: FnMut(&CompareProgress),
+{
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(config.threads)
+        .build()
+        .expect("无法创建线程池");
+    let rhs = rhs_space(config);
+    let row = |value_a: u64| {
+        let mut report = ComparisonReport {
+            by_rhs: rhs.iter().copied().map(RhsCounts::new).collect(),
+            ..Default::default()
+        };
+        for mask_a in (0..=config.mask_max).filter(|m| m & value_a == 0) {
+            let a = Tnum::new(value_a, mask_a);
+            for (j, &b) in rhs.iter().enumerate() {
+                let (ra, rb) = (op_a(a, b), op_b(a, b));
+                let precision = Precision::of(&ra, &rb);
+                report.record(a, b, precision, config.max_examples);
+                report.record_gap(&ra, &rb, precision);
+                report.by_rhs[j].add(precision);
+            }
+        }
+        report
+    };
+    while !progress.done {
+        let start = progress.next_value;
+        let end = start.saturating_add(CHUNK_ROWS - 1).min(config.value_max);
+        let rows: Vec<ComparisonReport> =
+            pool.install(|| (start..=end).into_par_iter().map(row).collect());
+        for r in rows {
+            progress.report.merge(r, config.max_examples);
+        }
+        match end.checked_add(1) {
+            Some(next) if end < config.value_max => progress.next_value = next,
+            _ => progress.done = true,
+        }
+        on_chunk(&progress);
+    }
+    progress.report
+}
+
+/// 写入文件的断点：比较的两个运算、枚举范围与进度
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checkpoint {
+    pub op_a: String,
+    pub op_b: String,
+    pub config: CompareConfig,
+    pub progress: CompareProgress,
+}
+
+#[cfg(feature = "serde")]
+impl Checkpoint {
+    /// 以 JSON 写入 path：先写临时文件再改名，中途被杀不会留下不完整的断点
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&tmp)?);
+        serde_json::to_writer(&mut file, self)?;
+        std::io::Write::flush(&mut file)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        Ok(serde_json::from_reader(file)?)
+    }
+}
+
+/// [`Pipeline`] 中发现反例的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// 在该位宽下枚举
+    Enumeration(u32),
+    /// Z3 在 64 位下求解
+    Solver,
+}
+
+/// 枚举通过但没有 64 位证明的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unproven {
+    /// 抽象转移函数没有符号编码（不在 [`smt::SYMBOLIC_OPS`] 中）
+    NoEncoding,
+    /// 符号编码与实现对拍不一致
+    EncodingMismatch(String),
+    /// Z3 无法判定
+    SolverUnknown,
+    /// 没有启用 z3 特性，或 [`Pipeline::solver`] 为 false
+    SolverDisabled,
+}
+
+/// 一个运算的最终结论
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PipelineVerdict {
+    /// Z3 证明 64 位下可靠
+    Proven,
+    Unsound {
+        stage: Stage,
+        counterexample: Counterexample,
+    },
+    /// 枚举没有发现反例，但没有证明
+    Survived(Unproven),
+}
+
+/// 一个运算在 [`Pipeline`] 中的结果
+#[derive(Debug, Clone)]
+pub struct PipelineReport {
+    pub op: &'static str,
+    /// 依次执行的枚举阶段，发现反例后不再继续
+    pub enumeration: Vec<(u32, SoundnessReport)>,
+    pub verdict: PipelineVerdict,
+}
+
+/// 分阶段的可靠性验证：先在小位宽下枚举（快速失败），通过的运算再交给 Z3 在 64 位下证明
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pipeline {
+    /// 枚举的位宽，按顺序执行；不超过 [`EXHAUSTIVE_WIDTH`] 时穷举，否则抽样，与 [`check_sound`] 相同
+    pub widths: Vec<u32>,
+    /// 是否调用 Z3；没有启用 z3 特性时不起作用
+    pub solver: bool,
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self {
+            widths: vec![EXHAUSTIVE_WIDTH, 8],
+            solver: true,
+        }
+    }
+}
+
+/// 对拍符号编码时的随机输入个数
+const PIPELINE_SELF_CHECK_SAMPLES: usize = 100_000;
+
+impl Pipeline {
+    pub fn run(&self, op: &'static BinaryOp) -> PipelineReport {
+        let mut enumeration = Vec::with_capacity(self.widths.len());
+        for &width in &self.widths {
+            let report = check_sound(op.abs, op.con, width);
+            let example = report.minimal.or(report.example);
+            enumeration.push((width, report));
+            if let Some(counterexample) = example {
+                return PipelineReport {
+                    op: op.name,
+                    enumeration,
+                    verdict: PipelineVerdict::Unsound {
+                        stage: Stage::Enumeration(width),
+                        counterexample,
+                    },
+                };
+            }
+        }
+        PipelineReport {
+            op: op.name,
+            enumeration,
+            verdict: self.prove(op.name),
+        }
+    }
+
+    /// 依次验证 [`BINARY_OPS`] 中的全部运算
+    pub fn run_all(&self) -> Vec<PipelineReport> {
+        BINARY_OPS.iter().map(|op| self.run(op)).collect()
+    }
+
+    fn prove(&self, name: &str) -> PipelineVerdict {
+        if !self.solver {
+            return PipelineVerdict::Survived(Unproven::SolverDisabled);
+        }
+        let Some(query) = smt::soundness_query(name, 64) else {
+            return PipelineVerdict::Survived(Unproven::NoEncoding);
+        };
+        if let Err(e) = smt::self_check(name, PIPELINE_SELF_CHECK_SAMPLES) {
+            return PipelineVerdict::Survived(Unproven::EncodingMismatch(e));
+        }
+        solve(&query)
+    }
+}
+
+#[cfg(feature = "z3")]
+fn solve(query: &smt::Query) -> PipelineVerdict {
+    match smt::solve(query) {
+        smt::Verdict::Sound => PipelineVerdict::Proven,
+        smt::Verdict::Unknown => PipelineVerdict::Survived(Unproven::SolverUnknown),
+        smt::Verdict::Unsound(counterexample) => PipelineVerdict::Unsound {
+            stage: Stage::Solver,
+            counterexample,
+        },
+    }
+}
+
+#[cfg(not(feature = "z3"))]
+fn solve(_query: &smt::Query) -> PipelineVerdict {
+    PipelineVerdict::Survived(Unproven::SolverDisabled)
+}
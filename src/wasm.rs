@@ -0,0 +1,122 @@
+//! 浏览器中的 tnum 演示用的 wasm-bindgen 接口（需要 `wasm` 特性）
+//!
+//! JS 侧的 `Tnum` 只包一个 [`Tnum`]，value 与 mask 以 BigInt 传递；转移函数按
+//! [`verify::BINARY_OPS`] 中的名字调用，结果用 x 记法显示。构建：
+//!
+//! ```sh
+//! cargo rustc --release --lib --target wasm32-unknown-unknown --no-default-features \
+//!     --features wasm --crate-type cdylib
+//! wasm-bindgen --target web --out-dir www/pkg \
+//!     target/wasm32-unknown-unknown/release/tnum_z3_verification.wasm
+//! ```
+//!
+//! ```js
+//! import init, { tnum_of_string, tnum_const, op } from "./pkg/tnum_z3_verification.js";
+//! await init();
+//! const q = op(tnum_of_string("1x0x"), tnum_const(3n), "fast_divide");
+//! console.log(q.toString(), q.values(16));
+//! ```
+use wasm_bindgen::prelude::*;
+
+use crate::tnum::Tnum;
+use crate::verify;
+
+/// JS 中的 `Tnum`
+#[wasm_bindgen(js_name = Tnum)]
+#[derive(Debug, Clone, Copy)]
+pub struct JsTnum(Tnum);
+
+#[wasm_bindgen(js_class = Tnum)]
+impl JsTnum {
+    /// value 与 mask 有重叠的位时抛出异常
+    #[wasm_bindgen(constructor)]
+    pub fn new(value: u64, mask: u64) -> Result<JsTnum, JsError> {
+        if value & mask != 0 {
+            return Err(JsError::new("value 与 mask 有重叠的位"));
+        }
+        Ok(Self(Tnum::new(value, mask)))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn value(&self) -> u64 {
+        self.0.value
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn mask(&self) -> u64 {
+        self.0.mask
+    }
+
+    #[wasm_bindgen(js_name = isBottom)]
+    pub fn is_bottom(&self) -> bool {
+        self.0.is_bottom()
+    }
+
+    /// 未知位的个数，γ 中有 2^n 个具体值
+    #[wasm_bindgen(js_name = unknownBits)]
+    pub fn unknown_bits(&self) -> u32 {
+        self.0.mask.count_ones()
+    }
+
+    pub fn contains(&self, x: u64) -> bool {
+        self.0.contains_value(x)
+    }
+
+    /// 按从小到大的顺序列出至多 limit 个具体值
+    pub fn values(&self, limit: usize) -> Vec<u64> {
+        self.0.iter_concrete().take(limit).collect()
+    }
+
+    /// 64 位的 x 记法
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_x_notation(&self) -> String {
+        self.0.to_string()
+    }
+
+    /// 只显示低 width 位的 x 记法
+    #[wasm_bindgen(js_name = toStringWidth)]
+    pub fn to_x_notation_width(&self, width: usize) -> String {
+        format!("{:w$}", self.0, w = width.min(64))
+    }
+}
+
+/// 解析 x 记法，如 `0b1x0x` 或 `1x0x`；`⊥` 为 bottom
+#[wasm_bindgen]
+pub fn tnum_of_string(s: &str) -> Result<JsTnum, JsError> {
+    s.parse::<Tnum>()
+        .map(JsTnum)
+        .map_err(|e| JsError::new(&format!("无法解析的 x 记法 {}: {}", s, e)))
+}
+
+#[wasm_bindgen]
+pub fn tnum_const(value: u64) -> JsTnum {
+    JsTnum(Tnum::const_val(value))
+}
+
+/// 包含 [min, max] 的最小 tnum
+#[wasm_bindgen]
+pub fn tnum_range(min: u64, max: u64) -> JsTnum {
+    JsTnum(Tnum::from_range(min, max))
+}
+
+#[wasm_bindgen]
+pub fn tnum_top() -> JsTnum {
+    JsTnum(Tnum::top())
+}
+
+/// 按名字调用二元转移函数
+#[wasm_bindgen]
+pub fn op(a: &JsTnum, b: &JsTnum, name: &str) -> Result<JsTnum, JsError> {
+    let op =
+        verify::binary_op(name).ok_or_else(|| JsError::new(&format!("未知的二元运算 {}", name)))?;
+    Ok(JsTnum((op.abs)(&a.0, b.0)))
+}
+
+/// 可用的二元运算名
+#[wasm_bindgen]
+pub fn ops() -> Vec<String> {
+    verify::BINARY_OPS
+        .iter()
+        .map(|op| op.name.to_string())
+        .collect()
+}
@@ -0,0 +1,458 @@
+//! 任意位宽 `Tnum<T>` 上的可靠性与精度检查
+//!
+//! [`width_op`] 按名称给出与 64 位相同的转移函数在 `Tnum<T>` 上的版本及其具体语义，
+//! [`check_width`] 对一组 tnum 两两组合，枚举全部具体输入。8 位可以穷举全部 tnum，
+//! 更宽时由 [`width_tnums`] 抽样。一元运算由 [`unary_op`] 与 [`check_unary`] 同样检查，
+//! 并与最优抽象比较。
+use crate::tnum::{for_each_concrete, ShiftSemantics, Tnum, Tnum32, Word};
+use rayon::prelude::*;
+
+/// 任意位宽下的抽象运算
+pub type WidthAbstract<T> = fn(&Tnum<T>, Tnum<T>) -> Tnum<T>;
+/// 任意位宽下的具体语义，`None` 表示无定义
+pub type WidthConcrete<T> = fn(T, T) -> Option<T>;
+
+/// [`width_op`] 支持的全部运算
+pub const WIDTH_OPS: &[&str] = &[
+    "add",
+    "sub",
+    "mul",
+    "xor",
+    "and",
+    "or",
+    "udiv",
+    "sat_add",
+    "sat_sub",
+    "sat_mul",
+    "checked_add",
+    "checked_sub",
+    "checked_mul",
+    "urem",
+    "shl",
+    "lshr",
+    "shl-sat",
+    "lshr-sat",
+    "ashr-sat",
+    "ashr",
+    "umax",
+    "umin",
+    "smax",
+    "smin",
+    "rotl",
+    "rotr",
+];
+
+/// 按名称查找任意位宽下的抽象运算及其具体语义（除以 0 无定义）
+pub fn width_op<T: Word>(name: &str) -> Option<(WidthAbstract<T>, WidthConcrete<T>)> {
+    let ops: (WidthAbstract<T>, WidthConcrete<T>) = match name {
+        "add" => (Tnum::add, |x, y| Some(x.wrapping_add(y))),
+        "sub" => (Tnum::sub, |x, y| Some(x.wrapping_sub(y))),
+        "mul" => (Tnum::mul, |x, y| Some(x.wrapping_mul(y))),
+        "xor" => (Tnum::xor, |x, y| Some(x ^ y)),
+        "and" => (|a, b| *a & b, |x, y| Some(x & y)),
+        "or" => (|a, b| *a | b, |x, y| Some(x | y)),
+        "udiv" => (Tnum::udiv, |x, y| x.checked_div(y)),
+        "sat_add" => (Tnum::sat_add, |x, y| {
+            Some(x.checked_add(y).unwrap_or(T::MAX))
+        }),
+        "sat_sub" => (Tnum::sat_sub, |x, y| {
+            Some(x.checked_sub(y).unwrap_or(T::ZERO))
+        }),
+        "sat_mul" => (Tnum::sat_mul, |x, y| {
+            Some(x.checked_mul(y).unwrap_or(T::MAX))
+        }),
+        "checked_add" => (|a, b| a.checked_add(b).0, |x, y| x.checked_add(y)),
+        "checked_sub" => (|a, b| a.checked_sub(b).0, |x, y| x.checked_sub(y)),
+        "checked_mul" => (|a, b| a.checked_mul(b).0, |x, y| x.checked_mul(y)),
+        "urem" => (Tnum::urem, |x, y| {
+            x.checked_div(y).map(|q| x.wrapping_sub(q.wrapping_mul(y)))
+        }),
+        "shl" => (
+            |a, b| a.shl(&b),
+            |x, y| Some(x.wrapping_shl(y.to_u64() as u32)),
+        ),
+        "lshr" => (
+            |a, b| a.lshr(&b),
+            |x, y| Some(x.wrapping_shr(y.to_u64() as u32)),
+        ),
+        "shl-sat" => (
+            |a, b| a.shl_with(&b, ShiftSemantics::Saturating),
+            |x, y| {
+                Some(if y.to_u64() >= T::BITS as u64 {
+                    T::ZERO
+                } else {
+                    x.wrapping_shl(y.to_u64() as u32)
+                })
+            },
+        ),
+        "lshr-sat" => (
+            |a, b| a.lshr_with(&b, ShiftSemantics::Saturating),
+            |x, y| {
+                Some(if y.to_u64() >= T::BITS as u64 {
+                    T::ZERO
+                } else {
+                    x.wrapping_shr(y.to_u64() as u32)
+                })
+            },
+        ),
+        "ashr-sat" => (
+            |a, b| a.ashr_with(&b, ShiftSemantics::Saturating),
+            |x, y| {
+                let k = y.to_u64().min(T::BITS as u64 - 1) as u32;
+                let shifted = x.wrapping_shr(k);
+                if x & T::sign_bit() != T::ZERO {
+                    Some(shifted | !T::MAX.wrapping_shr(k))
+                } else {
+                    Some(shifted)
+                }
+            },
+        ),
+        "ashr" => (
+            |a, b| a.ashr(&b),
+            |x, y| {
+                let k = (y.to_u64() % T::BITS as u64) as u32;
+                let shifted = x.wrapping_shr(k);
+                if x & T::sign_bit() != T::ZERO {
+                    Some(shifted | !T::MAX.wrapping_shr(k))
+                } else {
+                    Some(shifted)
+                }
+            },
+        ),
+        "umax" => (Tnum::umax, |x, y| Some(x.max(y))),
+        "umin" => (Tnum::umin, |x, y| Some(x.min(y))),
+        "smax" => (Tnum::smax, |x, y| {
+            let flip = |v: T| v ^ T::sign_bit();
+            Some(flip(flip(x).max(flip(y))))
+        }),
+        "smin" => (Tnum::smin, |x, y| {
+            let flip = |v: T| v ^ T::sign_bit();
+            Some(flip(flip(x).min(flip(y))))
+        }),
+        "rotl" => (
+            |a, b| a.rotl(&b),
+            |x, y| Some(x.rotate_left((y.to_u64() % T::BITS as u64) as u32)),
+        ),
+        "rotr" => (
+            |a, b| a.rotr(&b),
+            |x, y| Some(x.rotate_right((y.to_u64() % T::BITS as u64) as u32)),
+        ),
+        _ => return None,
+    };
+    Some(ops)
+}
+
+/// 一个反例：输入 tnum、具体输入、具体结果与抽象结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WidthCounterexample<T: Word> {
+    pub a: Tnum<T>,
+    pub b: Tnum<T>,
+    pub x: T,
+    pub y: T,
+    pub r: T,
+    pub result: Tnum<T>,
+}
+
+/// 一组 tnum 对的可靠性与精度统计
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WidthReport<T: Word> {
+    /// 检查的 tnum 对数
+    pub pairs: u64,
+    /// 有定义的具体输入对数
+    pub concrete: u64,
+    /// 不在抽象结果中的具体结果个数
+    pub unsound: u64,
+    /// 至少有一个具体结果的 tnum 对数
+    pub defined: u64,
+    /// 结果等于最优抽象（全部具体结果的 join）的 tnum 对数
+    pub optimal: u64,
+    /// 结果比最优抽象多出的未知位总数
+    pub extra_unknown: u64,
+    /// 按枚举顺序的第一个反例
+    pub example: Option<WidthCounterexample<T>>,
+}
+
+impl<T: Word> WidthReport<T> {
+    fn new() -> Self {
+        Self {
+            pairs: 0,
+            concrete: 0,
+            unsound: 0,
+            defined: 0,
+            optimal: 0,
+            extra_unknown: 0,
+            example: None,
+        }
+    }
+
+    pub fn is_sound(&self) -> bool {
+        self.unsound == 0
+    }
+
+    fn merge(&mut self, other: WidthReport<T>) {
+        self.pairs += other.pairs;
+        self.concrete += other.concrete;
+        self.unsound += other.unsound;
+        self.defined += other.defined;
+        self.optimal += other.optimal;
+        self.extra_unknown += other.extra_unknown;
+        if self.example.is_none() {
+            self.example = other.example;
+        }
+    }
+}
+
+/// 检查 a 与每个 b 的抽象结果是否覆盖所有具体结果，并与最优抽象比较精度
+fn check_width_row<T: Word>(
+    a: Tnum<T>,
+    bs: &[Tnum<T>],
+    abs: WidthAbstract<T>,
+    con: WidthConcrete<T>,
+) -> WidthReport<T> {
+    let mut counts = WidthReport::new();
+    for b in bs {
+        counts.pairs += 1;
+        let res = abs(&a, *b);
+        let (mut all_ones, mut any_ones, mut any) = (T::MAX, T::ZERO, false);
+        for_each_concrete(&a, |x| {
+            for_each_concrete(b, |y| {
+                if let Some(r) = con(x, y) {
+                    counts.concrete += 1;
+                    all_ones &= r;
+                    any_ones |= r;
+                    any = true;
+                    if !res.contains_value(r) {
+                        counts.unsound += 1;
+                        counts.example.get_or_insert(WidthCounterexample {
+                            a,
+                            b: *b,
+                            x,
+                            y,
+                            r,
+                            result: res,
+                        });
+                    }
+                }
+            })
+        });
+        if any {
+            let best = Tnum::new(all_ones, all_ones ^ any_ones);
+            counts.defined += 1;
+            if res == best {
+                counts.optimal += 1;
+            } else if best.le(&res) {
+                let extra = res.mask.count_ones() - best.mask.count_ones();
+                counts.extra_unknown += extra as u64;
+            }
+        }
+    }
+    counts
+}
+
+/// 待验证的 tnum：8 位且 samples 为 0 时枚举所有至多 max_unknown 个未知位的 tnum；
+/// 否则用固定种子抽取 samples 个至多 max_unknown 个未知位的 tnum
+pub fn width_tnums<T: Word>(max_unknown: u32, samples: usize) -> Vec<Tnum<T>> {
+    if T::BITS <= 8 && samples == 0 {
+        let limit = 1u64 << T::BITS;
+        (0..limit)
+            .flat_map(|v| (0..limit).map(move |m| (v, m)))
+            .filter(|&(v, m)| v & m == 0 && m.count_ones() <= max_unknown)
+            .map(|(v, m)| Tnum::new(T::from_u64(v), T::from_u64(m)))
+            .collect()
+    } else {
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        (0..samples)
+            .map(|_| {
+                let mut mask = T::ZERO;
+                for _ in 0..max_unknown {
+                    mask |= T::ONE.wrapping_shl((next() % T::BITS as u64) as u32);
+                }
+                let mut value = T::from_u64(next());
+                if T::BITS > 64 {
+                    value |= T::from_u64(next()).wrapping_shl(64);
+                }
+                Tnum::new(value & !mask, mask)
+            })
+            .collect()
+    }
+}
+
+/// 检查 tnums 中每一对的抽象结果是否覆盖所有具体结果，并与最优抽象比较精度
+///
+/// 每一对都枚举全部具体输入，因此可靠性和精度比较都是精确的；反例按枚举顺序取第一个。
+pub fn check_width<T: Word>(
+    tnums: &[Tnum<T>],
+    abs: WidthAbstract<T>,
+    con: WidthConcrete<T>,
+) -> WidthReport<T> {
+    let rows: Vec<WidthReport<T>> = tnums
+        .par_iter()
+        .map(|&a| check_width_row(a, tnums, abs, con))
+        .collect();
+    let mut report = WidthReport::new();
+    for row in rows {
+        report.merge(row);
+    }
+    report
+}
+
+/// [`alu32_op`] 在 [`WIDTH_OPS`] 之外支持的运算
+pub const ALU32_OPS: &[&str] = &["sdiv", "srem"];
+
+/// ALU32 运算：`width_op` 中的 32 位运算，另加只有 `Tnum32` 才有的有符号除法与取余
+pub fn alu32_op(name: &str) -> Option<(WidthAbstract<u32>, WidthConcrete<u32>)> {
+    match name {
+        "sdiv" => Some((Tnum32::sdiv32, |x, y| {
+            (y != 0).then(|| (x as i32).wrapping_div(y as i32) as u32)
+        })),
+        "srem" => Some((Tnum32::srem32, |x, y| {
+            (y != 0).then(|| (x as i32).wrapping_rem(y as i32) as u32)
+        })),
+        _ => width_op::<u32>(name),
+    }
+}
+
+/// 一元运算的抽象转移函数
+pub type UnaryAbstract<T> = fn(&Tnum<T>) -> Tnum<T>;
+/// 一元运算的具体语义
+pub type UnaryConcrete<T> = fn(T) -> T;
+
+/// [`unary_op`] 支持的全部运算
+pub const UNARY_OPS: &[&str] = &["neg", "neg-sub", "not", "popcount", "trunc5", "sext4"];
+
+/// [`unary_op_u64`] 支持的全部运算
+pub const UNARY_OPS_U64: &[&str] = &[
+    "bswap16",
+    "bswap32",
+    "bswap64",
+    "popcount64",
+    "cast8",
+    "zext32",
+    "trunc1",
+    "sext8",
+    "sext16",
+    "sext32",
+];
+
+/// 按名称查找一元运算及其具体语义
+pub fn unary_op<T: Word>(name: &str) -> Option<(UnaryAbstract<T>, UnaryConcrete<T>)> {
+    let ops: (UnaryAbstract<T>, UnaryConcrete<T>) = match name {
+        "neg" => (Tnum::neg, |x| T::ZERO.wrapping_sub(x)),
+        // 用减法表达的取负，作为 `neg` 的对照
+        "neg-sub" => (
+            |a| Tnum::new(T::ZERO, T::ZERO).sub(*a),
+            |x| T::ZERO.wrapping_sub(x),
+        ),
+        "not" => (Tnum::not, |x| !x),
+        "popcount" => (Tnum::popcount, |x| T::from_u64(x.count_ones() as u64)),
+        "trunc5" => (|a| a.trunc(5), |x| x & T::from_u64(0x1f)),
+        "sext4" => (
+            |a| a.sign_extend(4),
+            |x| {
+                let low = x & T::from_u64(0xf);
+                if low & T::from_u64(0x8) != T::ZERO {
+                    low | !T::from_u64(0xf)
+                } else {
+                    low
+                }
+            },
+        ),
+        _ => return None,
+    };
+    Some(ops)
+}
+
+/// 64 位专有的一元运算及其具体语义
+pub fn unary_op_u64(name: &str) -> Option<(UnaryAbstract<u64>, UnaryConcrete<u64>)> {
+    let ops: (UnaryAbstract<u64>, UnaryConcrete<u64>) = match name {
+        "bswap16" => (Tnum::bswap16, |x| (x as u16).swap_bytes() as u64),
+        "bswap32" => (Tnum::bswap32, |x| (x as u32).swap_bytes() as u64),
+        "bswap64" => (Tnum::bswap64, u64::swap_bytes),
+        "popcount64" => (Tnum::popcount, |x| x.count_ones() as u64),
+        "cast8" => (|a| a.cast(8), |x| x),
+        "zext32" => (|a| a.zext(32), |x| x as u32 as u64),
+        "trunc1" => (|a| a.trunc(1), |x| x & 1),
+        "sext8" => (|a| a.sign_extend(8), |x| x as i8 as u64),
+        "sext16" => (|a| a.sign_extend(16), |x| x as i16 as u64),
+        "sext32" => (|a| a.sign_extend(32), |x| x as i32 as u64),
+        _ => return None,
+    };
+    Some(ops)
+}
+
+/// 一元运算的结果与最优抽象不同的输入
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnaryGap<T: Word> {
+    pub a: Tnum<T>,
+    pub result: Tnum<T>,
+    /// 全部具体结果的 join
+    pub best: Tnum<T>,
+}
+
+/// 一元运算的可靠性与精度统计
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnaryReport<T: Word> {
+    /// 检查的 tnum 个数
+    pub inputs: u64,
+    /// 没有覆盖全部具体结果的输入个数
+    pub unsound: u64,
+    /// 可靠但不是最优抽象的输入个数
+    pub imprecise: u64,
+    /// 第一个不可靠的输入
+    pub example: Option<UnaryGap<T>>,
+    /// 第一个可靠但非最优的输入
+    pub suboptimal: Option<UnaryGap<T>>,
+}
+
+impl<T: Word> UnaryReport<T> {
+    pub fn is_sound(&self) -> bool {
+        self.unsound == 0
+    }
+
+    pub fn is_optimal(&self) -> bool {
+        self.unsound == 0 && self.imprecise == 0
+    }
+}
+
+/// 检查一元转移函数在给定 tnum 上的可靠性与最优性
+///
+/// 最优抽象由每个输入的全部具体结果按位求交/并得到，结果可靠当且仅当最优抽象包含于其中。
+pub fn check_unary<T: Word>(
+    inputs: &[Tnum<T>],
+    abs: UnaryAbstract<T>,
+    con: UnaryConcrete<T>,
+) -> UnaryReport<T> {
+    let mut report = UnaryReport {
+        inputs: 0,
+        unsound: 0,
+        imprecise: 0,
+        example: None,
+        suboptimal: None,
+    };
+    for &a in inputs {
+        report.inputs += 1;
+        let result = abs(&a);
+        let (mut ones, mut any) = (T::MAX, T::ZERO);
+        for_each_concrete(&a, |x| {
+            let r = con(x);
+            ones &= r;
+            any |= r;
+        });
+        let best = Tnum::new(ones, any ^ ones);
+        let gap = UnaryGap { a, result, best };
+        if !best.le(&result) {
+            report.unsound += 1;
+            report.example.get_or_insert(gap);
+        } else if result != best {
+            report.imprecise += 1;
+            report.suboptimal.get_or_insert(gap);
+        }
+    }
+    report
+}
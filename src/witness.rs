@@ -0,0 +1,98 @@
+//! 未知位见证：为结果中的每个未知位寻找具体输入，区分固有的不精确与算法缺陷
+use crate::tnum::{testbit, Tnum};
+
+/// 单个未知输出位的见证证书
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BitWitness {
+    /// 该位确实可取 0 和 1：`zero`/`one` 为对应的具体输入 (x, y)，不精确是固有的
+    Inherent {
+        bit: u8,
+        zero: (u64, u64),
+        one: (u64, u64),
+    },
+    /// 所有具体输入下该位恒为 `known`，算法本可确定该位（算法缺陷）
+    CouldBeKnown { bit: u8, known: bool },
+}
+
+/// 为 `result` 中每个未知位寻找两组具体输入，分别使该位为 0 和 1。
+///
+/// `concrete` 返回 `None` 表示该输入无定义（如除以 0），会被跳过。
+/// 若 `a`、`b` 的具体值组合数超过 `budget` 则返回 `None`。
+pub fn unknown_bit_witnesses<F>(
+    a: Tnum,
+    b: Tnum,
+    result: Tnum,
+    concrete: F,
+    budget: u64,
+) -> Option<Vec<BitWitness>>
+where
+    F: Fn(u64, u64) -> Option<u64>,
+{
+    // top × top 有 2^128 个组合，超出 u128，按饱和计
+    let pairs = a.cardinality().saturating_mul(b.cardinality());
+    if a.is_bottom() || b.is_bottom() || pairs > budget as u128 {
+        return None;
+    }
+
+    let mut zero: [Option<(u64, u64)>; 64] = [None; 64];
+    let mut one: [Option<(u64, u64)>; 64] = [None; 64];
+    // 尚未同时找到 0/1 见证的未知位
+    let mut pending = result.mask;
+
+    crate::tnum::for_each_concrete(&a, |x| {
+        if pending == 0 {
+            return;
+        }
+        crate::tnum::for_each_concrete(&b, |y| {
+            if pending == 0 {
+                return;
+            }
+            let r = match concrete(x, y) {
+                Some(r) => r,
+                None => return,
+            };
+            let mut bits = pending;
+            while bits != 0 {
+                let i = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                let slot = if testbit(r, i as u8) {
+                    &mut one[i]
+                } else {
+                    &mut zero[i]
+                };
+                if slot.is_none() {
+                    *slot = Some((x, y));
+                }
+                if zero[i].is_some() && one[i].is_some() {
+                    pending &= !(1u64 << i);
+                }
+            }
+        })
+    });
+
+    let mut witnesses = Vec::new();
+    for i in 0..64u8 {
+        if !testbit(result.mask, i) {
+            continue;
+        }
+        let w = match (zero[i as usize], one[i as usize]) {
+            (Some(z), Some(o)) => BitWitness::Inherent {
+                bit: i,
+                zero: z,
+                one: o,
+            },
+            (Some(_), None) => BitWitness::CouldBeKnown {
+                bit: i,
+                known: false,
+            },
+            // 没有任何有定义的输入时，该位可以取任意确定值
+            (None, _) => BitWitness::CouldBeKnown {
+                bit: i,
+                known: true,
+            },
+        };
+        witnesses.push(w);
+    }
+    Some(witnesses)
+}
@@ -0,0 +1,106 @@
+//! 指针对齐：栈指针与包指针上的 is_aligned_to、align_down、align_up
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::frame::{MM_STACK_START, STACK_FRAME_SIZE};
+use tnum_z3_verification::tnum::{Tnum, TriState};
+
+/// v1 第一帧的帧指针
+fn frame_pointer() -> Tnum {
+    Tnum::const_val(MM_STACK_START + STACK_FRAME_SIZE)
+}
+
+/// 内核中包数据的起始地址满足 NET_IP_ALIGN：模 4 余 2，其余位未知
+fn packet_start() -> Tnum {
+    Tnum::new(2, !3)
+}
+
+#[test]
+fn stack_slots() {
+    let fp = frame_pointer();
+    for (off, align, expect) in [
+        (-8i64, 8, TriState::Definitely),
+        (-16, 16, TriState::Definitely),
+        (-4, 4, TriState::Definitely),
+        (-4, 8, TriState::Never),
+        (-1, 2, TriState::Never),
+    ] {
+        let addr = fp.add(Tnum::const_val(off as u64));
+        assert_eq!(
+            addr.is_aligned_to(align),
+            expect,
+            "fp{} 按 {} 对齐",
+            off,
+            align
+        );
+    }
+}
+
+#[test]
+fn stack_variable_offset() {
+    // fp - 64 + 8 * i，i 取 0..4：按 8 对齐，但不一定按 16 对齐
+    let index = Tnum::new(0, 0b11).shl_const(3);
+    let addr = frame_pointer().sub(Tnum::const_val(64)).add(index);
+    assert_eq!(addr.is_aligned_to(8), TriState::Definitely);
+    assert_eq!(addr.is_aligned_to(16), TriState::Maybe);
+    // 再加上未对齐的偏移后就必然不按 8 对齐
+    let odd = addr.add(Tnum::const_val(4));
+    assert_eq!(odd.is_aligned_to(8), TriState::Never);
+    assert_eq!(odd.is_aligned_to(4), TriState::Definitely);
+}
+
+#[test]
+fn packet_pointers() {
+    let pkt = packet_start();
+    assert_eq!(pkt.is_aligned_to(2), TriState::Definitely);
+    assert_eq!(pkt.is_aligned_to(4), TriState::Never);
+    // 跳过 14 字节的以太网头后 IP 头按 4 对齐，但是否按 8 对齐未知
+    let ip = pkt.add(Tnum::const_val(14));
+    assert_eq!(ip.is_aligned_to(4), TriState::Definitely);
+    assert_eq!(ip.is_aligned_to(8), TriState::Maybe);
+    // 对齐量为 1 时总是对齐
+    assert_eq!(Tnum::top().is_aligned_to(1), TriState::Definitely);
+}
+
+#[test]
+fn align_down_and_up() {
+    let pkt = packet_start();
+    assert_eq!(pkt.align_down(4).is_aligned_to(4), TriState::Definitely);
+    assert_eq!(pkt.align_up(4).is_aligned_to(4), TriState::Definitely);
+    // 已对齐的常数不变
+    let fp = frame_pointer();
+    assert_eq!(fp.align_down(8), fp);
+    assert_eq!(fp.align_up(8), fp);
+    assert_eq!(Tnum::const_val(13).align_up(8), Tnum::const_val(16));
+    assert_eq!(Tnum::const_val(13).align_down(8), Tnum::const_val(8));
+}
+
+#[test]
+fn align_transformers_are_sound() {
+    // 低 6 位上的全部 tnum，每个具体值的对齐结果都在抽象结果中，判断与具体值一致
+    for value in 0u64..64 {
+        for mask in (0u64..64).filter(|m| m & value == 0) {
+            let t = Tnum::new(value, mask);
+            for align in [1u64, 2, 4, 8, 16] {
+                let (down, up) = (t.align_down(align), t.align_up(align));
+                let mut aligned = (false, false);
+                for x in t.iter_concrete() {
+                    let r = x & !(align - 1);
+                    assert!(down.contains_value(r), "align_down({:?}, {})", t, align);
+                    let r = x.wrapping_add(align - 1) & !(align - 1);
+                    assert!(up.contains_value(r), "align_up({:?}, {})", t, align);
+                    if x % align == 0 {
+                        aligned.0 = true;
+                    } else {
+                        aligned.1 = true;
+                    }
+                }
+                let expect = match aligned {
+                    (true, false) => TriState::Definitely,
+                    (false, true) => TriState::Never,
+                    _ => TriState::Maybe,
+                };
+                assert_eq!(t.is_aligned_to(align), expect, "{:?} 按 {} 对齐", t, align);
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! ALU32 运算：在 64 位寄存器的低 32 位上求值后零扩展，高 32 位必须清零
+//!
+//! 操作数的未知位分布在整个 64 位中，高 32 位的未知位不应影响结果。
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{for_each_concrete, Tnum};
+use tnum_z3_verification::width::{alu32_op, width_tnums, ALU32_OPS, WIDTH_OPS};
+
+fn check(op: &str) {
+    let (abs, con) = alu32_op(op).expect(op);
+    let mut tnums = width_tnums::<u64>(4, 32);
+    tnums.extend([0, 1, u32::MAX as u64, 0x8000_0000, u64::MAX].map(Tnum::const_val));
+    for a in &tnums {
+        for b in &tnums {
+            let res = a.alu32(*b, |x, y| abs(&x, y));
+            // checked_* 必然溢出时没有结果，为 bottom
+            assert!(
+                res.is_bottom() || (res.value | res.mask) >> 32 == 0,
+                "{:?} {} {:?} = {:?}",
+                a,
+                op,
+                b,
+                res
+            );
+            for_each_concrete(a, |x| {
+                for_each_concrete(b, |y| {
+                    if let Some(r) = con(x as u32, y as u32) {
+                        assert!(
+                            res.contains_value(r as u64),
+                            "{:?} {} {:?}: {:#x} {} {:#x} = {:#x} 不在 {:?} 中",
+                            a,
+                            op,
+                            b,
+                            x,
+                            op,
+                            y,
+                            r,
+                            res
+                        );
+                    }
+                })
+            });
+        }
+    }
+}
+
+#[test]
+fn alu32_ops() {
+    for op in WIDTH_OPS.iter().chain(ALU32_OPS) {
+        check(op);
+    }
+}
+
+#[test]
+fn alu32_ignores_high_bits() {
+    let a = Tnum::new(0x0000_dead_0000_0007u64, 0xffff_0000_0000_0000);
+    let b = Tnum::const_val(0xffff_ffff_0000_0003u64);
+    let (abs, _) = alu32_op("add").unwrap();
+    assert_eq!(a.alu32(b, |x, y| abs(&x, y)), Tnum::const_val(10));
+}
@@ -0,0 +1,59 @@
+//! 比较谓词：may_* 恰好是「存在具体值对使其成立」，must_* 恰好是「所有具体值对都使其成立」
+//!
+//! 左操作数在 8 位上抽样，右操作数取抽样的至多 2 个未知位的 tnum。
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{for_each_concrete, Tnum};
+use tnum_z3_verification::width::width_tnums;
+
+type Predicate = fn(&Tnum<u8>, Tnum<u8>) -> bool;
+
+fn check(name: &str, may: Predicate, must: Predicate, con: fn(u8, u8) -> bool) {
+    let mut lhs = width_tnums::<u8>(8, 256);
+    lhs.extend([Tnum::top(), Tnum::const_val(0), Tnum::const_val(0x80)]);
+    let mut rhs = width_tnums::<u8>(2, 128);
+    rhs.extend([
+        Tnum::const_val(0),
+        Tnum::const_val(0x7f),
+        Tnum::const_val(0xff),
+    ]);
+    for a in &lhs {
+        for b in &rhs {
+            let (mut exists, mut forall) = (false, true);
+            for_each_concrete(a, |x| {
+                for_each_concrete(b, |y| {
+                    let holds = con(x, y);
+                    exists |= holds;
+                    forall &= holds;
+                })
+            });
+            assert_eq!(may(a, *b), exists, "may_{}({:?}, {:?})", name, a, b);
+            assert_eq!(must(a, *b), forall, "must_{}({:?}, {:?})", name, a, b);
+        }
+    }
+}
+
+#[test]
+fn eq() {
+    check("eq", Tnum::may_eq, Tnum::must_eq, |x, y| x == y);
+}
+
+#[test]
+fn ult() {
+    check("ult", Tnum::may_ult, Tnum::must_ult, |x, y| x < y);
+}
+
+#[test]
+fn slt() {
+    check("slt", Tnum::may_slt, Tnum::must_slt, |x, y| {
+        (x as i8) < (y as i8)
+    });
+}
+
+#[test]
+fn bottom_is_vacuous() {
+    // γ 为空：不存在使其成立的值对，全部值对都使其成立
+    let (bottom, one) = (Tnum::<u8>::bottom(), Tnum::const_val(1u8));
+    assert!(!bottom.may_eq(one) && bottom.must_eq(one));
+    assert!(!one.may_ult(bottom) && one.must_ult(bottom));
+}
@@ -0,0 +1,115 @@
+//! 各除零策略下的除法/取余（8 位值，无符号运算零扩展、有符号运算符号扩展到 64 位）
+//!
+//! 除数取可能为 0 的 8 位 tnum（value 为 0），除数为 0 时的具体结果：ReturnZero 为 0，
+//! ReturnDividend 为被除数，Top 与 Bottom 没有约束；除数恰为 0 时结果只由策略决定。
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{for_each_concrete, DivZeroPolicy, Tnum};
+use tnum_z3_verification::width::width_tnums;
+
+type DivWith = fn(&Tnum, Tnum, DivZeroPolicy) -> Tnum;
+
+const POLICIES: [DivZeroPolicy; 4] = [
+    DivZeroPolicy::ReturnZero,
+    DivZeroPolicy::ReturnDividend,
+    DivZeroPolicy::Top,
+    DivZeroPolicy::Bottom,
+];
+
+/// 至多 3 个未知位的除数 mask，外加全部未知
+fn divisors() -> Vec<Tnum<u8>> {
+    (0..=u8::MAX)
+        .filter(|m| m.count_ones() <= 3 || *m == u8::MAX)
+        .map(|m| Tnum::new(0, m))
+        .collect()
+}
+
+fn check(name: &str, op: DivWith, con: fn(u64, u64) -> u64, signed: bool) {
+    let ext = |t: &Tnum<u8>| {
+        let t = Tnum::new(t.value as u64, t.mask as u64);
+        if signed {
+            t.sign_extend(8)
+        } else {
+            t
+        }
+    };
+    let ext_val = |x: u8| if signed { x as i8 as u64 } else { x as u64 };
+    for policy in POLICIES {
+        for a8 in width_tnums::<u8>(4, 128) {
+            let a = ext(&a8);
+            for b8 in divisors() {
+                let b = ext(&b8);
+                let res = op(&a, b, policy);
+                if b.is_singleton() {
+                    let expected = match policy {
+                        DivZeroPolicy::ReturnZero => Tnum::const_val(0),
+                        DivZeroPolicy::ReturnDividend => a,
+                        DivZeroPolicy::Top => Tnum::top(),
+                        DivZeroPolicy::Bottom => Tnum::bottom(),
+                    };
+                    assert_eq!(res, expected, "{} {:?}: {:?} 除以 0", name, policy, a);
+                }
+                // 符号扩展后的 tnum 可能有 57 个未知位，具体值按 8 位枚举后再扩展
+                for_each_concrete(&a8, |x| {
+                    for_each_concrete(&b8, |y| {
+                        let (x, y) = (ext_val(x), ext_val(y));
+                        let r = match (y, policy) {
+                            (0, DivZeroPolicy::ReturnZero) => 0,
+                            (0, DivZeroPolicy::ReturnDividend) => x,
+                            (0, _) => return,
+                            _ => con(x, y),
+                        };
+                        assert!(
+                            res.contains_value(r),
+                            "{} {:?}: {:?} 除以 {:?}，{:#x} 与 {:#x} 的结果 {:#x} 不在 {:?} 中",
+                            name,
+                            policy,
+                            a,
+                            b,
+                            x,
+                            y,
+                            r,
+                            res
+                        );
+                    })
+                });
+            }
+        }
+    }
+}
+
+#[test]
+fn udiv_with() {
+    check("udiv", Tnum::udiv_with, |x, y| x / y, false);
+}
+
+#[test]
+fn urem_with() {
+    check("urem", Tnum::urem_with, |x, y| x % y, false);
+}
+
+#[test]
+fn sdiv_with() {
+    let con = |x, y| (x as i64).wrapping_div(y as i64) as u64;
+    check("sdiv", Tnum::sdiv_with, con, true);
+}
+
+#[test]
+fn srem_with() {
+    let con = |x, y| (x as i64).wrapping_rem(y as i64) as u64;
+    check("srem", Tnum::srem_with, con, true);
+}
+
+#[test]
+fn fast_divide_with() {
+    check("fast_divide", Tnum::fast_divide_with, |x, y| x / y, false);
+}
+
+#[test]
+fn fast_divide_with_many_unknown_bits_is_udiv_with() {
+    let a = Tnum::new(0x40u64, 0x3f);
+    let b = Tnum::new(0u64, 0xf0);
+    for policy in POLICIES {
+        assert_eq!(a.fast_divide_with(b, policy), a.udiv_with(b, policy));
+    }
+}
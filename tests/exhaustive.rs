@@ -0,0 +1,183 @@
+//! 指令转移函数在 4 位下的穷举可靠性
+//!
+//! 每个操作码一个测试，具体结果须落在抽象结果中；`verify-pqr` 与 `verify-interp` 模式打印同样的检查。
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::interp::{
+    self, Bitness, BpfAluOp, BpfAtomicOp, BpfJmpOp, Endian, IsaProfile,
+};
+use tnum_z3_verification::pqr;
+use tnum_z3_verification::tnum::Tnum;
+
+const BITS: u32 = 4;
+
+const PROFILES: [IsaProfile; 3] = [IsaProfile::LinuxEbpf, IsaProfile::SbfV1, IsaProfile::SbfV2];
+
+macro_rules! pqr {
+    ($($test:ident => $opcode:literal,)*) => {
+        $(
+            #[test]
+            fn $test() {
+                let insn = pqr::decode($opcode).expect("PQR 操作码");
+                let report = pqr::check_soundness(insn, BITS);
+                assert!(report.is_sound(), "{}: {:?}", insn.mnemonic(), report.example);
+            }
+        )*
+
+        #[test]
+        fn pqr_all_opcodes_listed() {
+            let listed = [$($opcode),*];
+            for opcode in 0..=u8::MAX {
+                if pqr::decode(opcode).is_some() {
+                    assert!(listed.contains(&opcode), "0x{:02x} 没有测试", opcode);
+                }
+            }
+        }
+    };
+}
+
+pqr! {
+    pqr_lmul32_imm => 0x86,
+    pqr_lmul32_reg => 0x8e,
+    pqr_lmul64_imm => 0x96,
+    pqr_lmul64_reg => 0x9e,
+    pqr_uhmul64_imm => 0x36,
+    pqr_uhmul64_reg => 0x3e,
+    pqr_shmul64_imm => 0xb6,
+    pqr_shmul64_reg => 0xbe,
+    pqr_udiv32_imm => 0x46,
+    pqr_udiv32_reg => 0x4e,
+    pqr_udiv64_imm => 0x56,
+    pqr_udiv64_reg => 0x5e,
+    pqr_urem32_imm => 0x66,
+    pqr_urem32_reg => 0x6e,
+    pqr_urem64_imm => 0x76,
+    pqr_urem64_reg => 0x7e,
+    pqr_sdiv32_imm => 0xc6,
+    pqr_sdiv32_reg => 0xce,
+    pqr_sdiv64_imm => 0xd6,
+    pqr_sdiv64_reg => 0xde,
+    pqr_srem32_imm => 0xe6,
+    pqr_srem32_reg => 0xee,
+    pqr_srem64_imm => 0xf6,
+    pqr_srem64_reg => 0xfe,
+}
+
+/// 每个 profile 下 32 位与 64 位中合法的形式
+fn alu_sound(op: BpfAluOp) {
+    for profile in PROFILES {
+        for bitness in [Bitness::B64, Bitness::B32] {
+            if !op.is_valid(profile, bitness) {
+                continue;
+            }
+            let report = interp::check_alu(profile, op, bitness, BITS);
+            assert!(
+                report.is_sound(),
+                "{} {:?} {}: {:?}",
+                profile.name(),
+                bitness,
+                op.name(),
+                report.example
+            );
+        }
+    }
+}
+
+macro_rules! alu {
+    ($($test:ident => $op:expr,)*) => {
+        $(
+            #[test]
+            fn $test() {
+                alu_sound($op);
+            }
+        )*
+
+        #[test]
+        fn alu_all_ops_listed() {
+            let listed = [$($op),*];
+            for op in interp::ALU_OPS {
+                assert!(listed.contains(op), "{} 没有测试", op.name());
+            }
+        }
+    };
+}
+
+alu! {
+    alu_add => BpfAluOp::Add,
+    alu_sub => BpfAluOp::Sub,
+    alu_mul => BpfAluOp::Mul,
+    alu_div => BpfAluOp::Div,
+    alu_sdiv => BpfAluOp::Sdiv,
+    alu_mod => BpfAluOp::Mod,
+    alu_smod => BpfAluOp::Smod,
+    alu_and => BpfAluOp::And,
+    alu_or => BpfAluOp::Or,
+    alu_xor => BpfAluOp::Xor,
+    alu_lsh => BpfAluOp::Lsh,
+    alu_rsh => BpfAluOp::Rsh,
+    alu_arsh => BpfAluOp::Arsh,
+    alu_neg => BpfAluOp::Neg,
+    alu_mov => BpfAluOp::Mov,
+    alu_movsx8 => BpfAluOp::Movsx(8),
+    alu_movsx16 => BpfAluOp::Movsx(16),
+    alu_movsx32 => BpfAluOp::Movsx(32),
+    alu_le16 => BpfAluOp::End(Endian::Le, 16),
+    alu_le32 => BpfAluOp::End(Endian::Le, 32),
+    alu_le64 => BpfAluOp::End(Endian::Le, 64),
+    alu_be16 => BpfAluOp::End(Endian::Be, 16),
+    alu_be32 => BpfAluOp::End(Endian::Be, 32),
+    alu_be64 => BpfAluOp::End(Endian::Be, 64),
+}
+
+macro_rules! assume {
+    ($($test:ident => $op:expr,)*) => {
+        $(
+            #[test]
+            fn $test() {
+                for bitness in [Bitness::B64, Bitness::B32] {
+                    let check = interp::check_assume($op, bitness, BITS);
+                    assert_eq!(check.example, None, "{:?} {}", bitness, $op.name());
+                }
+            }
+        )*
+    };
+}
+
+assume! {
+    assume_jeq => BpfJmpOp::Jeq,
+    assume_jne => BpfJmpOp::Jne,
+    assume_jgt => BpfJmpOp::Jgt,
+    assume_jge => BpfJmpOp::Jge,
+    assume_jlt => BpfJmpOp::Jlt,
+    assume_jle => BpfJmpOp::Jle,
+    assume_jsgt => BpfJmpOp::Jsgt,
+    assume_jsge => BpfJmpOp::Jsge,
+    assume_jslt => BpfJmpOp::Jslt,
+    assume_jsle => BpfJmpOp::Jsle,
+    assume_jset => BpfJmpOp::Jset,
+}
+
+macro_rules! atomic {
+    ($($test:ident => $op:expr,)*) => {
+        $(
+            #[test]
+            fn $test() {
+                let check = interp::check_atomic($op, BITS);
+                assert_eq!(check.example, None, "{}", $op.name());
+            }
+        )*
+    };
+}
+
+atomic! {
+    atomic_add => BpfAtomicOp::Add,
+    atomic_fetch_add => BpfAtomicOp::FetchAdd,
+    atomic_and => BpfAtomicOp::And,
+    atomic_fetch_and => BpfAtomicOp::FetchAnd,
+    atomic_or => BpfAtomicOp::Or,
+    atomic_fetch_or => BpfAtomicOp::FetchOr,
+    atomic_xor => BpfAtomicOp::Xor,
+    atomic_fetch_xor => BpfAtomicOp::FetchXor,
+    atomic_xchg => BpfAtomicOp::Xchg,
+    atomic_cmpxchg => BpfAtomicOp::Cmpxchg(Tnum::top()),
+}
@@ -0,0 +1,68 @@
+//! 截断、零扩展与符号扩展：每个源位宽下都是最优抽象
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{for_each_concrete, Tnum, Tnum32};
+use tnum_z3_verification::width::width_tnums;
+
+/// 全部具体结果的 join
+fn best(a: &Tnum<u8>, f: impl Fn(u8) -> u8) -> Tnum<u8> {
+    let (mut ones, mut any) = (u8::MAX, 0);
+    for_each_concrete(a, |x| {
+        ones &= f(x);
+        any |= f(x);
+    });
+    Tnum::new(ones, any ^ ones)
+}
+
+#[test]
+fn trunc_zext_sext_are_optimal() {
+    for a in width_tnums::<u8>(8, 0) {
+        for bits in 1..=8u32 {
+            let low = u8::MAX >> (8 - bits);
+            let shift = 8 - bits;
+            let trunc = best(&a, |x| x & low);
+            assert_eq!(a.trunc(bits), trunc, "{:?}.trunc({})", a, bits);
+            assert_eq!(a.zext(bits), trunc, "{:?}.zext({})", a, bits);
+            let sext = best(&a, |x| ((x << shift) as i8 >> shift) as u8);
+            assert_eq!(
+                a.sign_extend(bits as u8),
+                sext,
+                "{:?}.sign_extend({})",
+                a,
+                bits
+            );
+        }
+    }
+}
+
+#[test]
+fn cast_is_trunc_by_bytes() {
+    for a in width_tnums::<u64>(8, 256) {
+        for size in 1..=8u8 {
+            assert_eq!(a.cast(size), a.trunc(size as u32 * 8));
+        }
+        assert_eq!(a.cast(8), a);
+    }
+}
+
+#[test]
+fn tnum32_extends_to_64_bits() {
+    for a in width_tnums::<u32>(6, 256) {
+        let wide = Tnum::new(a.value as u64, a.mask as u64);
+        assert_eq!(a.zext64(), wide);
+        assert_eq!(a.sext64(), wide.sign_extend(32));
+        for_each_concrete(&a, |x: u32| {
+            assert!(a.zext64().contains_value(x as u64));
+            assert!(a.sext64().contains_value(x as i32 as i64 as u64));
+        });
+    }
+    let top = Tnum32::top();
+    assert_eq!(top.zext64(), Tnum::new(0, u32::MAX as u64));
+    assert_eq!(top.sext64(), Tnum::top());
+}
+
+#[test]
+#[should_panic]
+fn trunc_to_zero_bits_panics() {
+    Tnum::const_val(1u64).trunc(0);
+}
@@ -0,0 +1,110 @@
+//! 按常数取余的两条路径：乘回去相减（`fast_divide` / `fast_sdivide`）与直接的 `urem` / `srem`
+//!
+//! 两条路径与最终的 `fast_urem_const` / `fast_srem_const` 都须可靠；两条路径互不包含时
+//! 结果不与直接取余取 meet，这里固定这一点。
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{for_each_concrete, Tnum};
+use tnum_z3_verification::width::width_tnums;
+
+const DIVISORS: [u64; 12] = [
+    2,
+    3,
+    5,
+    7,
+    10,
+    12,
+    25,
+    100,
+    641,
+    1000,
+    1 << 20,
+    0x1234_5678_9abc,
+];
+
+/// 抽样的 tnum，一半的符号位未知
+fn tnums() -> Vec<Tnum> {
+    let mut tnums = width_tnums::<u64>(6, 64);
+    let sign = 1u64 << 63;
+    tnums.extend(
+        tnums
+            .clone()
+            .iter()
+            .map(|t| Tnum::new(t.value & !sign, t.mask | sign)),
+    );
+    tnums
+}
+
+fn check(name: &str, results: impl Fn(&Tnum, u64) -> [Tnum; 3], con: impl Fn(u64, u64) -> u64) {
+    for a in tnums() {
+        for c in DIVISORS {
+            let results = results(&a, c);
+            for_each_concrete(&a, |x| {
+                let r = con(x, c);
+                for (path, res) in ["乘回", "直接", "最终"].iter().zip(&results) {
+                    assert!(
+                        res.contains_value(r),
+                        "{} {}: {:?} 除以 {}，{:#x} 的余数 {:#x} 不在 {:?} 中",
+                        name,
+                        path,
+                        a,
+                        c,
+                        x,
+                        r,
+                        res
+                    );
+                }
+            });
+        }
+    }
+}
+
+#[test]
+fn urem_paths_are_sound() {
+    check(
+        "urem",
+        |a, c| {
+            let d = Tnum::const_val(c);
+            [
+                a.sub(a.fast_divide(d).mul(d)),
+                a.urem(d),
+                a.fast_urem_const(c),
+            ]
+        },
+        |x, c| x % c,
+    );
+}
+
+#[test]
+fn srem_paths_are_sound() {
+    check(
+        "srem",
+        |a, c| {
+            let d = Tnum::const_val(c);
+            [
+                a.sub(a.fast_sdivide(d).mul(d)),
+                a.srem(d),
+                a.fast_srem_const(c as i64),
+            ]
+        },
+        |x, c| (x as i64).wrapping_rem(c as i64) as u64,
+    );
+}
+
+#[test]
+fn final_results_use_the_composed_path() {
+    for a in tnums() {
+        for c in DIVISORS {
+            let d = Tnum::const_val(c);
+            let composed = a.sub(a.fast_divide(d).mul(d));
+            assert_eq!(
+                a.fast_urem_const(c),
+                composed.meet(&Tnum::from_range(0, c - 1))
+            );
+            let composed = a.sub(a.fast_sdivide(d).mul(d));
+            assert_eq!(a.fast_srem_const(c as i64), composed);
+        }
+    }
+    assert_eq!(Tnum::const_val(7u64).fast_urem_const(0), Tnum::top());
+    assert_eq!(Tnum::const_val(7u64).fast_srem_const(0), Tnum::top());
+}
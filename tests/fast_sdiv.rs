@@ -0,0 +1,89 @@
+//! `fast_sdivide` 的 magic 序列：常数被除数上恰好是 `wrapping_div`，抽样的 tnum 上可靠
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{for_each_concrete, Tnum};
+use tnum_z3_verification::width::width_tnums;
+
+fn divisors() -> Vec<i64> {
+    let mut divisors: Vec<i64> = vec![
+        2,
+        3,
+        5,
+        6,
+        7,
+        10,
+        12,
+        25,
+        100,
+        641,
+        1000,
+        1 << 20,
+        0x1234_5678_9abc,
+        i64::MAX,
+    ];
+    divisors.extend(divisors.clone().iter().map(|d| -d));
+    divisors.extend([1, -1, i64::MIN]);
+    divisors
+}
+
+/// 抽样的 tnum，一半的符号位未知
+fn tnums() -> Vec<Tnum> {
+    let mut tnums = width_tnums::<u64>(6, 64);
+    let sign = 1u64 << 63;
+    tnums.extend(
+        tnums
+            .clone()
+            .iter()
+            .map(|t| Tnum::new(t.value & !sign, t.mask | sign)),
+    );
+    tnums
+}
+
+#[test]
+fn constant_numerators_are_exact() {
+    let mut numerators: Vec<i64> = vec![0, 1, -1, i64::MIN, i64::MIN + 1, i64::MAX];
+    numerators.extend(width_tnums::<u64>(0, 256).iter().map(|t| t.value as i64));
+    numerators.extend((-300..300).map(|n| n * 167));
+    for d in divisors() {
+        for &n in &numerators {
+            // 含 i64::MIN / -1 回绕为 i64::MIN
+            assert_eq!(
+                Tnum::const_val(n as u64).fast_sdivide(Tnum::const_val(d as u64)),
+                Tnum::const_val(n.wrapping_div(d) as u64),
+                "{} / {}",
+                n,
+                d
+            );
+        }
+    }
+}
+
+#[test]
+fn sound_on_sampled_tnums() {
+    let tnums = tnums();
+    for d in divisors() {
+        let b = Tnum::const_val(d as u64);
+        for a in &tnums {
+            let res = a.fast_sdivide(b);
+            for_each_concrete(a, |x| {
+                let q = (x as i64).wrapping_div(d) as u64;
+                assert!(
+                    res.contains_value(q),
+                    "{:?} / {}: {} / {} = {} 不在 {:?} 中",
+                    a,
+                    d,
+                    x as i64,
+                    d,
+                    q as i64,
+                    res
+                );
+            });
+        }
+    }
+}
+
+#[test]
+fn non_constant_divisor_falls_back_to_sdiv() {
+    let (a, b) = (Tnum::new(100, 0b11), Tnum::new(4, 0b10));
+    assert_eq!(a.fast_sdivide(b), a.sdiv(b));
+}
@@ -0,0 +1,148 @@
+//! 三态溢出标志是精确的：所有具体输入都溢出时为 `Some(true)`，都不溢出时为 `Some(false)`，否则为 `None`
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{for_each_concrete, ArithFlags, MaybeOverflow, Tnum, Word};
+use tnum_z3_verification::width::width_tnums;
+
+/// 由「存在溢出」与「全部溢出」得到精确的三态标志
+fn exact(any: bool, all: bool) -> MaybeOverflow {
+    if all {
+        Some(true)
+    } else if !any {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn check_checked<T: Word>(
+    name: &str,
+    lhs: &[Tnum<T>],
+    rhs: &[Tnum<T>],
+    abs: fn(&Tnum<T>, Tnum<T>) -> MaybeOverflow,
+    con: fn(T, T) -> bool,
+) {
+    for a in lhs {
+        for b in rhs {
+            let (mut any, mut all) = (false, true);
+            for_each_concrete(a, |x| {
+                for_each_concrete(b, |y| {
+                    let o = con(x, y);
+                    any |= o;
+                    all &= o;
+                })
+            });
+            assert_eq!(abs(a, *b), exact(any, all), "{}({:?}, {:?})", name, a, b);
+        }
+    }
+}
+
+#[test]
+fn checked_overflow_u8() {
+    let lhs = width_tnums::<u8>(8, 0);
+    // 左操作数已含 top，右操作数抽样
+    let mut rhs = width_tnums::<u8>(3, 24);
+    rhs.extend([Tnum::const_val(0), Tnum::const_val(u8::MAX)]);
+    check_checked(
+        "checked_add",
+        &lhs,
+        &rhs,
+        |a, b| a.checked_add(b).1,
+        |x, y| x.checked_add(y).is_none(),
+    );
+    check_checked(
+        "checked_sub",
+        &lhs,
+        &rhs,
+        |a, b| a.checked_sub(b).1,
+        |x, y| x.checked_sub(y).is_none(),
+    );
+    check_checked(
+        "checked_mul",
+        &lhs,
+        &rhs,
+        |a, b| a.checked_mul(b).1,
+        |x, y| x.checked_mul(y).is_none(),
+    );
+}
+
+type FlagsAbstract = fn(&Tnum, Tnum) -> ArithFlags;
+type FlagsConcrete = fn(u64, u64) -> (bool, bool);
+
+#[test]
+fn arith_flags_u64() {
+    let ops: [(&str, FlagsAbstract, FlagsConcrete); 3] = [
+        (
+            "add",
+            |a, b| a.add_with_flags(b).1,
+            |x, y| {
+                (
+                    x.overflowing_add(y).1,
+                    (x as i64).overflowing_add(y as i64).1,
+                )
+            },
+        ),
+        (
+            "sub",
+            |a, b| a.sub_with_flags(b).1,
+            |x, y| {
+                (
+                    x.overflowing_sub(y).1,
+                    (x as i64).overflowing_sub(y as i64).1,
+                )
+            },
+        ),
+        (
+            "mul",
+            |a, b| a.mul_with_flags(b).1,
+            |x, y| {
+                (
+                    x.overflowing_mul(y).1,
+                    (x as i64).overflowing_mul(y as i64).1,
+                )
+            },
+        ),
+    ];
+    // 一半的 tnum 最高两位未知，使端点跨越有符号与无符号边界
+    let mut tnums = width_tnums::<u64>(4, 32);
+    let top2 = 0xc000_0000_0000_0000u64;
+    tnums.extend(
+        tnums
+            .clone()
+            .iter()
+            .map(|t| Tnum::new(t.value & !top2, t.mask | top2)),
+    );
+    for (name, abs, con) in ops {
+        for a in &tnums {
+            for b in &tnums {
+                let (mut any, mut all) = ([false; 2], [true; 2]);
+                for_each_concrete(a, |x| {
+                    for_each_concrete(b, |y| {
+                        let (carry, overflow) = con(x, y);
+                        for (i, o) in [carry, overflow].into_iter().enumerate() {
+                            any[i] |= o;
+                            all[i] &= o;
+                        }
+                    })
+                });
+                let flags = abs(a, *b);
+                assert_eq!(
+                    flags.carry,
+                    exact(any[0], all[0]),
+                    "{} 进位 {:?}, {:?}",
+                    name,
+                    a,
+                    b
+                );
+                assert_eq!(
+                    flags.overflow,
+                    exact(any[1], all[1]),
+                    "{} 有符号溢出 {:?}, {:?}",
+                    name,
+                    a,
+                    b
+                );
+            }
+        }
+    }
+}
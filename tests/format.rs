@@ -0,0 +1,65 @@
+//! x 记法的输出与解析：往返、指定宽度与非法输入
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{ParseTnumError, Tnum};
+use tnum_z3_verification::width::width_tnums;
+
+#[test]
+fn roundtrip_u8() {
+    let mut tnums = width_tnums::<u8>(8, 0);
+    tnums.push(Tnum::bottom());
+    for t in tnums {
+        let s = t.to_string();
+        assert_eq!(s.parse::<Tnum<u8>>(), Ok(t), "{}", s);
+    }
+}
+
+#[test]
+fn roundtrip_wide() {
+    for t in width_tnums::<u64>(16, 256) {
+        assert_eq!(t.to_string().parse::<Tnum>(), Ok(t));
+    }
+    for t in width_tnums::<u128>(16, 256) {
+        assert_eq!(t.to_string().parse::<Tnum<u128>>(), Ok(t));
+    }
+}
+
+#[test]
+fn display() {
+    let t: Tnum = "0b1x0x".parse().unwrap();
+    assert_eq!(t, Tnum::new(0b1000, 0b0101));
+    assert_eq!(format!("{:6}", t), "0b001x0x");
+    assert_eq!(format!("{:2}", t), "0b0x");
+    assert_eq!(format!("{}", t), format!("0b{}1x0x", "0".repeat(60)));
+    assert_eq!(
+        Tnum::<u16>::new(0x8001, 0x0f00).to_string(),
+        "0b1000xxxx00000001"
+    );
+    assert_eq!(Tnum::<u8>::bottom().to_string(), "⊥");
+    assert_eq!(t.to_sbin(4), "1x0x");
+}
+
+#[test]
+fn parse() {
+    let big: Tnum<u128> = "0b1_xxxx_0000".parse().unwrap();
+    assert_eq!(big, Tnum::new(0x100, 0xf0));
+    // 前缀可省略，x 不区分大小写，首尾空白忽略
+    assert_eq!(" 1X0 ".parse::<Tnum<u8>>(), Ok(Tnum::new(0b100, 0b010)));
+    assert_eq!("⊥".parse::<Tnum>(), Ok(Tnum::bottom()));
+    assert_eq!("".parse::<Tnum>(), Err(ParseTnumError::Empty));
+    assert_eq!("0b".parse::<Tnum>(), Err(ParseTnumError::Empty));
+    assert_eq!("0b_".parse::<Tnum>(), Err(ParseTnumError::Empty));
+    assert_eq!(
+        "0b10z1".parse::<Tnum>(),
+        Err(ParseTnumError::InvalidDigit('z'))
+    );
+    assert_eq!(
+        "1".repeat(64).parse::<Tnum>(),
+        Ok(Tnum::const_val(u64::MAX))
+    );
+    assert_eq!("1".repeat(65).parse::<Tnum>(), Err(ParseTnumError::TooLong));
+    assert_eq!(
+        "x".repeat(9).parse::<Tnum<u8>>(),
+        Err(ParseTnumError::TooLong)
+    );
+}
@@ -0,0 +1,77 @@
+//! fast_divide 的 General 路径（((n - q) >> 1) + q >> shift）
+//!
+//! 被除数是零扩展到 64 位的 8 位（全部）与 16 位（抽样）tnum，除数取走 `DividerU64::General`
+//! 的常数。结果须可靠，且不比原先的 64 位序列粗糙。
+#![cfg(feature = "std")]
+
+use fastdivide::DividerU64;
+use tnum_z3_verification::tnum::Tnum;
+use tnum_z3_verification::width::width_tnums;
+
+/// 2..=1000 中每隔几个取一个 General 除数，外加 7 与 274177
+fn divisors() -> Vec<(u64, u64, u8)> {
+    let general = |d| match DividerU64::divide_by(d) {
+        DividerU64::General { magic_low, shift } => Some((d, magic_low, shift)),
+        _ => None,
+    };
+    let mut divisors: Vec<_> = (2..=1000).filter_map(general).step_by(8).collect();
+    divisors.extend([7, 274177].into_iter().filter_map(general));
+    divisors
+}
+
+/// 原先在 64 位上计算的序列
+fn old(a: &Tnum, magic_low: u64, shift: u8) -> Tnum {
+    let q = a.umulh(Tnum::const_val(magic_low));
+    a.sub(q).tnum_rshift(1).add(q).tnum_rshift(shift)
+}
+
+fn check(tnums: &[Tnum]) {
+    for (d, magic_low, shift) in divisors() {
+        for a in tnums {
+            let new = a.fast_divide(Tnum::const_val(d));
+            let best = Tnum::join_many(a.iter_concrete().map(|x| Tnum::const_val(x / d)));
+            assert!(
+                best.le(&new),
+                "{:?} / {}: {:?} 不包含全部商 {:?}",
+                a,
+                d,
+                new,
+                best
+            );
+            let prev = old(a, magic_low, shift);
+            assert!(
+                new == prev || !prev.le(&new),
+                "{:?} / {}: {:?} 比原先的 {:?} 粗糙",
+                a,
+                d,
+                new,
+                prev
+            );
+        }
+    }
+}
+
+#[test]
+fn general_divisors_exist() {
+    let divisors: Vec<_> = divisors().iter().map(|&(d, ..)| d).collect();
+    assert!(divisors.contains(&7) && divisors.contains(&274177));
+    assert!(divisors.len() > 10);
+}
+
+#[test]
+fn general_path_8_bits() {
+    let tnums: Vec<_> = width_tnums::<u8>(8, 0)
+        .iter()
+        .map(|t| Tnum::new(t.value as u64, t.mask as u64))
+        .collect();
+    check(&tnums);
+}
+
+#[test]
+fn general_path_16_bits() {
+    let tnums: Vec<_> = width_tnums::<u16>(8, 256)
+        .iter()
+        .map(|t| Tnum::new(t.value as u64, t.mask as u64))
+        .collect();
+    check(&tnums);
+}
@@ -0,0 +1,63 @@
+//! [`Lattice`] 的基本性质：join 是上界、meet 是下界、`le` 自反且反对称，bottom 与 top 分别是最小、最大元；
+//! 以及 `Hash` 与 `==` 一致
+#![cfg(feature = "std")]
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::Hash;
+use tnum_z3_verification::lattice::Lattice;
+use tnum_z3_verification::regval::RegValue;
+use tnum_z3_verification::tnum::Tnum;
+use tnum_z3_verification::width::width_tnums;
+
+fn check_lattice<L: Lattice + Hash + Eq + Debug>(elems: &[L], others: &[L]) {
+    let (top, bottom) = (L::top(), L::bottom());
+    for a in elems {
+        assert!(a.le(a) && bottom.le(a) && a.le(&top), "{:?}", a);
+        for b in others {
+            let (j, m) = (a.join(b), a.meet(b));
+            assert!(a.le(&j) && b.le(&j), "{:?} ⊔ {:?} = {:?}", a, b, j);
+            assert!(m.le(a) && m.le(b), "{:?} ⊓ {:?} = {:?}", a, b, m);
+            if a.le(b) && b.le(a) && !a.is_bottom() {
+                assert_eq!(a, b);
+            }
+        }
+    }
+    // 去重后的个数等于互不相等的元素个数
+    let distinct: HashSet<&L> = elems.iter().collect();
+    let unequal = elems
+        .iter()
+        .enumerate()
+        .filter(|&(i, a)| elems[..i].iter().all(|b| b != a))
+        .count();
+    assert_eq!(distinct.len(), unequal);
+}
+
+#[test]
+fn tnum_u8() {
+    let tnums = width_tnums::<u8>(8, 0);
+    let mut others = width_tnums::<u8>(8, 64);
+    others.extend([Tnum::top(), Tnum::bottom(), Tnum::const_val(0)]);
+    check_lattice(&tnums, &others);
+}
+
+#[test]
+fn tnum_u128() {
+    let wide = width_tnums::<u128>(6, 256);
+    check_lattice(&wide, &wide);
+}
+
+#[test]
+fn regvalue() {
+    // 每个 tnum 再分别与其具体值的两个子区间求交
+    let regs: Vec<RegValue> = width_tnums::<u64>(3, 128)
+        .iter()
+        .flat_map(|&t| {
+            let r = RegValue::from_tnum(t);
+            let (lo, hi) = t.to_unsigned_range();
+            let mid = lo + (hi - lo) / 2;
+            [r, r.with_urange(lo, mid), r.with_urange(mid, hi)]
+        })
+        .collect();
+    check_lattice(&regs, &regs);
+}
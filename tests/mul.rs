@@ -0,0 +1,124 @@
+//! `TnumU128` 上的乘法：缩减位宽的穷举、u16 × u16 → u32，以及与 64 位 `mul` 的差分
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{for_each_concrete, Tnum, TnumU128};
+
+/// xorshift64，固定种子使每次运行的输入相同
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// 低 bits 位中至多 unknown 个未知位的 tnum
+    fn tnum(&mut self, bits: u32, unknown: u32) -> Tnum {
+        let low = u64::MAX >> (64 - bits);
+        let mut mask = 0;
+        for _ in 0..unknown {
+            mask |= 1 << (self.next() % bits as u64);
+        }
+        Tnum::new(self.next() & low & !mask, mask)
+    }
+}
+
+/// 每个具体乘积都在 `mul_width` 的结果中，且结果没有超出 2*bits 位
+fn check_mul_width(a: Tnum, b: Tnum, bits: u32) {
+    let res = a.widen_u128().mul_width(b.widen_u128(), 2 * bits);
+    assert_eq!((res.value | res.mask) >> (2 * bits), 0, "{:?}", res);
+    for_each_concrete(&a, |x| {
+        for_each_concrete(&b, |y| {
+            let p = x as u128 * y as u128;
+            assert!(
+                res.contains_value(p),
+                "a={:?}, b={:?}, {} * {} = {} 不在 {:?} 中",
+                a,
+                b,
+                x,
+                y,
+                p,
+                res
+            );
+        })
+    });
+}
+
+#[test]
+fn mul_width_exhaustive() {
+    const BITS: u32 = 5;
+    let tnums: Vec<Tnum> = (0..1u64 << BITS)
+        .flat_map(|v| (0..1u64 << BITS).map(move |m| (v, m)))
+        .filter(|&(v, m)| v & m == 0)
+        .map(|(v, m)| Tnum::new(v, m))
+        .collect();
+    for &a in &tnums {
+        for &b in &tnums {
+            check_mul_width(a, b, BITS);
+        }
+    }
+}
+
+#[test]
+fn mul_width_u16() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    let tnums: Vec<Tnum> = (0..128).map(|_| rng.tnum(16, 4)).collect();
+    for &a in &tnums {
+        for &b in &tnums {
+            check_mul_width(a, b, 16);
+            // 16 位操作数的乘积不超过 32 位，高 64 位已知为 0
+            assert_eq!(a.widening_mul(b).1, Tnum::const_val(0));
+        }
+    }
+}
+
+#[test]
+fn mul_wide_matches_mul() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut tnums: Vec<Tnum> = (0..256)
+        .map(|i| rng.tnum(64, [0, 2, 8, 32][i % 4]))
+        .collect();
+    tnums.extend([Tnum::top(), Tnum::const_val(u64::MAX)]);
+    for &a in &tnums {
+        for &b in &tnums {
+            let wide = a.mul_wide(b);
+            // 低 64 位与 64 位的乘法逐位相同；64 位的 mul 遇到 top 操作数直接返回 top
+            if a.is_top() || b.is_top() {
+                assert!(wide.low().le(&a.mul(b)));
+            } else {
+                assert_eq!(wide.low(), a.mul(b), "a={:?}, b={:?}", a, b);
+            }
+            assert_eq!(
+                a.widen_u128().mul_width(b.widen_u128(), 64),
+                wide.low().widen_u128(),
+                "a={:?}, b={:?}",
+                a,
+                b
+            );
+            let mut next = || rng.next();
+            let (xs, ys) = (a.sample_n(&mut next, 4), b.sample_n(&mut next, 4));
+            for (x, y) in xs.into_iter().zip(ys) {
+                let p = x as u128 * y as u128;
+                assert!(
+                    wide.contains_value(p),
+                    "{:#x} * {:#x} 不在 {:?} 中",
+                    x,
+                    y,
+                    wide
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn mul_width_full_width() {
+    let mut rng = Rng(0x853c_49e6_748f_ea9b);
+    for _ in 0..256 {
+        let (a, b) = (rng.tnum(64, 6), rng.tnum(64, 6));
+        let (a, b): (TnumU128, TnumU128) = (a.mul_wide(b), b.mul_wide(a));
+        assert_eq!(a.mul_width(b, 128), a.mul(b));
+    }
+}
@@ -0,0 +1,109 @@
+//! 序关系与 γ：`le`、`contains`、`contains_value`、`PartialOrd`、`meet` 与 `iter_concrete` 在 4 位上穷举
+//!
+//! 包括 value 与 mask 重叠的非规范 bottom，它们的 γ 为空集。
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::Tnum;
+
+const BITS: u32 = 4;
+
+/// 全部 (value, mask) 对，不经过 `new`，保留非规范的 bottom
+fn all_pairs() -> Vec<Tnum> {
+    let limit = 1u64 << BITS;
+    (0..limit)
+        .flat_map(|value| (0..limit).map(move |mask| Tnum { value, mask }))
+        .collect()
+}
+
+/// γ(t) 在低 6 位上的位图，直接按定义 x & !mask == value 计算
+fn gamma(t: &Tnum) -> u64 {
+    (0..64u64)
+        .filter(|x| x & !t.mask == t.value)
+        .fold(0, |set, x| set | 1 << x)
+}
+
+fn included(a: &Tnum, b: &Tnum) -> bool {
+    gamma(a) & !gamma(b) == 0
+}
+
+#[test]
+fn le_is_gamma_inclusion() {
+    let tnums = all_pairs();
+    for a in &tnums {
+        for b in &tnums {
+            assert_eq!(a.le(b), included(a, b), "{:?}.le({:?})", a, b);
+        }
+    }
+}
+
+#[test]
+fn contains_is_gamma_inclusion() {
+    let tnums = all_pairs();
+    for a in &tnums {
+        for b in &tnums {
+            assert_eq!(b.contains(*a), included(a, b), "{:?}.contains({:?})", b, a);
+        }
+    }
+}
+
+#[test]
+fn partial_ord_is_gamma_inclusion() {
+    let tnums = all_pairs();
+    for a in &tnums {
+        for b in &tnums {
+            // 不同表示的 bottom 在 PartialOrd 下不可比较
+            if a.is_bottom() && b.is_bottom() && a != b {
+                assert_eq!(a.partial_cmp(b), None, "{:?}, {:?}", a, b);
+            } else {
+                assert_eq!(a <= b, included(a, b), "{:?} <= {:?}", a, b);
+            }
+        }
+    }
+}
+
+#[test]
+fn meet_is_gamma_intersection() {
+    let tnums = all_pairs();
+    for a in &tnums {
+        for b in &tnums {
+            let m = a.meet(b);
+            assert_eq!(gamma(&m), gamma(a) & gamma(b), "{:?}.meet({:?})", a, b);
+            if m.is_bottom() {
+                assert_eq!(m, Tnum::bottom(), "{:?}.meet({:?}) 不是规范的 bottom", a, b);
+            }
+        }
+    }
+}
+
+#[test]
+fn iter_concrete_is_gamma() {
+    for t in all_pairs() {
+        let values: Vec<u64> = t.iter_concrete().collect();
+        assert!(values.windows(2).all(|w| w[0] < w[1]), "{:?}", t);
+        let set = values.iter().fold(0u64, |set, x| set | 1 << x);
+        assert_eq!(set, gamma(&t), "{:?}", t);
+        assert_eq!(values.len() as u128, t.cardinality(), "{:?}", t);
+    }
+}
+
+#[test]
+fn contains_value_is_membership() {
+    for t in all_pairs() {
+        let values: Vec<u64> = t.iter_concrete().collect();
+        for x in 0..64 {
+            assert_eq!(t.contains_value(x), values.contains(&x), "{:?} ∋ {}", t, x);
+        }
+        // 高位已知为 0
+        assert!(!t.contains_value(1 << 40), "{:?}", t);
+    }
+    for x in 0..16u64 {
+        for y in 0..16u64 {
+            assert_eq!(Tnum::const_val(x).contains_value(y), x == y);
+        }
+    }
+    assert!(Tnum::top().contains_value(u64::MAX));
+    assert!(!Tnum::bottom().contains_value(0u64));
+    let wide = Tnum::<u128>::new(1 << 100, 1);
+    assert!(wide.contains_value(1 << 100) && wide.contains_value((1 << 100) + 1));
+    assert!(!wide.contains_value(1));
+}
@@ -0,0 +1,96 @@
+//! 除数的非零具体值都是 2^k 时的快速路径：udiv 逻辑右移，sdiv 向 0 取整的算术右移
+//!
+//! 8 位 tnum 零扩展或符号扩展到 64 位，除数取 2^k（k 为 0..=6、62、63）与 {0, 2^k}，
+//! 后者用 `*_with(ReturnZero)` 求值。
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{for_each_concrete, DivZeroPolicy, Tnum};
+use tnum_z3_verification::width::width_tnums;
+
+const SHIFTS: [u32; 9] = [0, 1, 2, 3, 4, 5, 6, 62, 63];
+
+fn divisors() -> Vec<Tnum> {
+    SHIFTS
+        .iter()
+        .flat_map(|k| [Tnum::const_val(1 << k), Tnum::new(0, 1 << k)])
+        .collect()
+}
+
+/// 全部具体结果的 join
+fn best(a8: &Tnum<u8>, b: &Tnum, f: impl Fn(u8, u64) -> u64) -> Tnum {
+    let (mut ones, mut any) = (u64::MAX, 0);
+    for_each_concrete(a8, |x| {
+        for_each_concrete(b, |y| {
+            ones &= f(x, y);
+            any |= f(x, y);
+        })
+    });
+    Tnum::new(ones, any ^ ones)
+}
+
+#[test]
+fn pow2_amount_is_a_single_shift() {
+    assert_eq!(
+        Tnum::const_val(8u64).pow2_amount(),
+        Some(Tnum::const_val(3))
+    );
+    assert_eq!(Tnum::new(0u64, 8).pow2_amount(), Some(Tnum::const_val(3)));
+    assert_eq!(Tnum::new(0u64, 0b1100).pow2_amount(), None);
+    assert_eq!(Tnum::const_val(0u64).pow2_amount(), None);
+    assert_eq!(Tnum::<u64>::bottom().pow2_amount(), None);
+}
+
+#[test]
+fn udiv_by_pow2_is_optimal() {
+    let div = |x: u8, y: u64| (x as u64).checked_div(y).unwrap_or(0);
+    for a8 in width_tnums::<u8>(8, 0) {
+        let a = Tnum::new(a8.value as u64, a8.mask as u64);
+        for b in divisors() {
+            let res = a.udiv_with(b, DivZeroPolicy::ReturnZero);
+            assert_eq!(res, best(&a8, &b, div), "{:?} / {:?}", a, b);
+            if b.is_singleton() {
+                assert_eq!(a.udiv(b), res);
+            }
+        }
+    }
+}
+
+#[test]
+fn sdiv_by_pow2_is_sound() {
+    let div = |x: u8, y: u64| match y {
+        0 => 0,
+        _ => (x as i8 as i64).wrapping_div(y as i64) as u64,
+    };
+    // 符号位未知时扩展后的 tnum 有 57 个未知位，具体值按 8 位枚举后再符号扩展
+    for a8 in width_tnums::<u8>(8, 0) {
+        let a = Tnum::new(a8.value as u64, a8.mask as u64).sign_extend(8);
+        for b in divisors() {
+            let res = a.sdiv_with(b, DivZeroPolicy::ReturnZero);
+            let best = best(&a8, &b, div);
+            assert!(
+                best.le(&res),
+                "{:?} / {:?}: {:?} 不包含 {:?}",
+                a,
+                b,
+                res,
+                best
+            );
+            if b.is_singleton() {
+                assert_eq!(a.sdiv(b), res);
+                // 非负被除数只是逻辑右移，是最优的
+                if a8.value & 0x80 == 0 && a8.mask & 0x80 == 0 {
+                    assert_eq!(res, best, "{:?} / {:?}", a, b);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn sdiv_by_i64_min() {
+    let min = Tnum::const_val(1u64 << 63);
+    assert_eq!(min.sdiv(min), Tnum::const_val(1));
+    assert_eq!(Tnum::const_val(5u64).sdiv(min), Tnum::const_val(0));
+    assert_eq!(Tnum::top().sdiv(min), Tnum::new(0, 1));
+    assert_eq!(Tnum::new(0u64, 0xff).sdiv(min), Tnum::const_val(0));
+}
@@ -0,0 +1,88 @@
+//! 区间与 tnum 之间的转换
+//!
+//! `from_range` / `from_signed_range` 须是区间的最优抽象；`to_unsigned_range` / `to_signed_range`
+//! 须恰好是具体值的最小、最大值。
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{for_each_concrete, Tnum};
+use tnum_z3_verification::width::width_tnums;
+
+/// 区间内全部值的 join
+fn best(lo: i64, hi: i64) -> Tnum {
+    let (mut ones, mut any) = (u64::MAX, 0);
+    for x in lo..=hi {
+        ones &= x as u64;
+        any |= x as u64;
+    }
+    Tnum::new(ones, any ^ ones)
+}
+
+#[test]
+fn signed_range_crossing_zero_is_top() {
+    // 同时包含 0 与 -1，每一位都可取 0 和 1
+    for (lo, hi) in [
+        (-1, 0),
+        (-1, 1),
+        (-5, 3),
+        (i64::MIN, i64::MAX),
+        (i64::MIN, 0),
+    ] {
+        assert_eq!(
+            Tnum::from_signed_range(lo, hi),
+            Tnum::top(),
+            "[{}, {}]",
+            lo,
+            hi
+        );
+    }
+    for lo in -16..0 {
+        for hi in 0..16 {
+            assert_eq!(best(lo, hi), Tnum::top());
+        }
+    }
+}
+
+#[test]
+fn signed_range_not_crossing_zero_is_optimal() {
+    for lo in -64..=64i64 {
+        for hi in lo..=64 {
+            if lo < 0 && hi >= 0 {
+                continue;
+            }
+            assert_eq!(
+                Tnum::from_signed_range(lo, hi),
+                best(lo, hi),
+                "[{}, {}]",
+                lo,
+                hi
+            );
+        }
+    }
+    assert_eq!(
+        Tnum::from_signed_range(i64::MIN, i64::MIN + 3),
+        Tnum::new(1 << 63, 0b11)
+    );
+    assert_eq!(
+        Tnum::from_signed_range(-8, -5),
+        Tnum::new(-8i64 as u64, 0b11)
+    );
+}
+
+#[test]
+fn to_ranges_are_tight() {
+    for t in width_tnums::<u64>(6, 1024) {
+        let (mut umin, mut umax) = (u64::MAX, 0);
+        let (mut smin, mut smax) = (i64::MAX, i64::MIN);
+        for_each_concrete(&t, |x| {
+            umin = umin.min(x);
+            umax = umax.max(x);
+            smin = smin.min(x as i64);
+            smax = smax.max(x as i64);
+        });
+        assert_eq!(t.to_unsigned_range(), (umin, umax), "{:?}", t);
+        assert_eq!(t.to_signed_range(), (smin, smax), "{:?}", t);
+        // 转换回 tnum 后仍包含原 tnum
+        let back = Tnum::from_range(umin, umax).and(&Tnum::from_signed_range(smin, smax));
+        assert!(back.contains(t), "{:?} -> {:?}", t, back);
+    }
+}
@@ -0,0 +1,106 @@
+//! 分支细化：细化结果须保留所有满足条件的具体值，且等于它们的最优抽象
+//!
+//! 被细化的 tnum 在 8 位上抽样，比较对象取至多 2 个未知位的 tnum；常数比较取全部 256 个常数。
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{for_each_concrete, Tnum};
+use tnum_z3_verification::width::width_tnums;
+
+fn refined() -> Vec<Tnum<u8>> {
+    let mut tnums = width_tnums::<u8>(6, 128);
+    tnums.extend([Tnum::top(), Tnum::const_val(0), Tnum::const_val(u8::MAX)]);
+    tnums
+}
+
+fn others() -> Vec<Tnum<u8>> {
+    width_tnums::<u8>(2, 96)
+}
+
+fn constants() -> Vec<Tnum<u8>> {
+    (0..=u8::MAX).map(Tnum::const_val).collect()
+}
+
+/// 满足条件的具体值的 join，没有时为 bottom
+fn best(a: &Tnum<u8>, cond: impl Fn(u8) -> bool) -> Tnum<u8> {
+    let (mut ones, mut any, mut kept) = (u8::MAX, 0, false);
+    for_each_concrete(a, |x| {
+        if cond(x) {
+            ones &= x;
+            any |= x;
+            kept = true;
+        }
+    });
+    if kept {
+        Tnum::new(ones, any ^ ones)
+    } else {
+        Tnum::bottom()
+    }
+}
+
+/// 对每一对检查可靠性与最优性
+fn check(
+    name: &str,
+    bs: &[Tnum<u8>],
+    abs: impl Fn(&Tnum<u8>, Tnum<u8>) -> Tnum<u8>,
+    cond: impl Fn(u8, &Tnum<u8>) -> bool,
+) {
+    for a in refined() {
+        for b in bs {
+            let res = abs(&a, *b);
+            let best = best(&a, |x| cond(x, b));
+            assert!(
+                best.le(&res),
+                "{}: a={:?}, b={:?} 细化为 {:?}，丢掉了 {:?} 中的值",
+                name,
+                a,
+                b,
+                res,
+                best
+            );
+            assert!(
+                res == best || res.is_bottom() && best.is_bottom(),
+                "{}: a={:?}, b={:?} 细化为 {:?}，最优为 {:?}",
+                name,
+                a,
+                b,
+                res,
+                best
+            );
+        }
+    }
+}
+
+#[test]
+fn refine_eq() {
+    check("eq", &others(), Tnum::refine_eq, |x, b| b.contains_value(x));
+}
+
+#[test]
+fn refine_ne() {
+    let ne = |x, b: &Tnum<u8>| !(b.is_singleton() && b.value == x);
+    check("ne", &others(), Tnum::refine_ne, ne);
+}
+
+#[test]
+fn refine_lt_const() {
+    let abs = |a: &Tnum<u8>, b: Tnum<u8>| a.refine_lt_const(b.value);
+    check("lt", &constants(), abs, |x, b| x < b.value);
+}
+
+#[test]
+fn refine_ge_const() {
+    let abs = |a: &Tnum<u8>, b: Tnum<u8>| a.refine_ge_const(b.value);
+    check("ge", &constants(), abs, |x, b| x >= b.value);
+}
+
+#[test]
+fn refine_jset() {
+    let abs = |a: &Tnum<u8>, b: Tnum<u8>| a.refine_jset(b.value);
+    check("jset", &constants(), abs, |x, b| x & b.value != 0);
+}
+
+#[test]
+fn refine_jset_not() {
+    let abs = |a: &Tnum<u8>, b: Tnum<u8>| a.refine_jset_not(b.value);
+    check("jset-not", &constants(), abs, |x, b| x & b.value == 0);
+}
@@ -0,0 +1,74 @@
+//! 循环移位：常数移位量的结果是精确的位置换，tnum 移位量按每个可能的移位量求 join
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{for_each_concrete, Tnum};
+use tnum_z3_verification::width::width_tnums;
+
+/// 全部具体结果的 join
+fn best(a: &Tnum<u8>, f: impl Fn(u8) -> u8) -> Tnum<u8> {
+    let (mut ones, mut any) = (u8::MAX, 0);
+    for_each_concrete(a, |x| {
+        ones &= f(x);
+        any |= f(x);
+    });
+    Tnum::new(ones, any ^ ones)
+}
+
+#[test]
+fn rotate_by_constant_is_optimal() {
+    for a in width_tnums::<u8>(8, 0) {
+        // 移位量超过位宽时按位宽取模
+        for k in 0..20u64 {
+            let r = (k % 8) as u32;
+            assert_eq!(
+                a.rotl_const(k),
+                best(&a, |x| x.rotate_left(r)),
+                "{:?} rotl {}",
+                a,
+                k
+            );
+            assert_eq!(
+                a.rotr_const(k),
+                best(&a, |x| x.rotate_right(r)),
+                "{:?} rotr {}",
+                a,
+                k
+            );
+            assert_eq!(a.rotl_const(k).rotr_const(k), a);
+        }
+    }
+}
+
+#[test]
+fn rotate_by_singleton_matches_constant() {
+    for a in width_tnums::<u8>(3, 0) {
+        for k in 0..=u8::MAX {
+            let amount = Tnum::const_val(k);
+            assert_eq!(
+                a.rotl(&amount),
+                a.rotl_const(k as u64),
+                "{:?} rotl {}",
+                a,
+                k
+            );
+            assert_eq!(
+                a.rotr(&amount),
+                a.rotr_const(k as u64),
+                "{:?} rotr {}",
+                a,
+                k
+            );
+        }
+    }
+}
+
+#[test]
+fn rotate_by_tnum_joins_amounts() {
+    // 移位量 {1, 3}：1 循环左移得到 {0b10, 0b1000}
+    let a = Tnum::const_val(1u8);
+    let amount = Tnum::new(1u8, 0b10);
+    assert_eq!(a.rotl(&amount), Tnum::new(0, 0b1010));
+    assert_eq!(a.rotr(&amount), Tnum::new(0, 0b1010_0000));
+    // 移位量 8 与 0 同余，结果不变
+    assert_eq!(a.rotl(&Tnum::new(0, 0b1000)), a);
+}
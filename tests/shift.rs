@@ -0,0 +1,80 @@
+//! 移位量的语义：`Masked` 按位宽取模（eBPF 的 `& 63` 与 ALU32 的 `& 31`），
+//! `Saturating` 在移位量不小于位宽时移出全部位；tnum 移位量的结果是最优抽象
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{for_each_concrete, ShiftSemantics, Tnum};
+use tnum_z3_verification::width::width_tnums;
+
+type Shift = fn(&Tnum<u8>, &Tnum<u8>, ShiftSemantics) -> Tnum<u8>;
+
+/// 8 位上的具体语义
+fn concrete(op: &str, x: u8, k: u8, sem: ShiftSemantics) -> u8 {
+    let k = match sem {
+        ShiftSemantics::Masked => (k % 8) as u32,
+        ShiftSemantics::Saturating => (k as u32).min(8),
+    };
+    match (op, k) {
+        ("shl", 8) | ("lshr", 8) => 0,
+        ("ashr", 8) => ((x as i8) >> 7) as u8,
+        ("shl", k) => x << k,
+        ("lshr", k) => x >> k,
+        (_, k) => ((x as i8) >> k) as u8,
+    }
+}
+
+#[test]
+fn shift_by_tnum_is_optimal() {
+    let ops: [(&str, Shift); 3] = [
+        ("shl", Tnum::shl_with),
+        ("lshr", Tnum::lshr_with),
+        ("ashr", Tnum::ashr_with),
+    ];
+    let values = width_tnums::<u8>(8, 0);
+    let mut amounts = width_tnums::<u8>(3, 12);
+    amounts.extend([7, 8, 9, 15, 16, 255].map(Tnum::const_val));
+    for (op, abs) in ops {
+        for sem in [ShiftSemantics::Masked, ShiftSemantics::Saturating] {
+            for a in &values {
+                for k in &amounts {
+                    let (mut ones, mut any) = (u8::MAX, 0);
+                    for_each_concrete(a, |x| {
+                        for_each_concrete(k, |y| {
+                            let r = concrete(op, x, y, sem);
+                            ones &= r;
+                            any |= r;
+                        })
+                    });
+                    let best = Tnum::new(ones, any ^ ones);
+                    assert_eq!(abs(a, k, sem), best, "{:?} {} {:?} ({:?})", a, op, k, sem);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn masked_amount_wraps() {
+    let t = Tnum::new(0x8000_0000_0000_0001u64, 0x10);
+    // eBPF 按 & 63 取移位量：64 即 0，65 即 1
+    assert_eq!(t.shl_const(64), t);
+    assert_eq!(t.shl_const(65), t.shl_const(1));
+    assert_eq!(t.lshr_const(127), t.lshr_const(63));
+    assert_eq!(t.ashr_const(64), t);
+    assert_eq!(t.shl(&Tnum::const_val(64)), t);
+    // ALU32 按 & 31 取移位量
+    let t32 = Tnum::new(0x8000_0001u32, 0x10);
+    assert_eq!(t32.shl_const(32), t32);
+    assert_eq!(t32.lshr_const(33), t32.lshr_const(1));
+}
+
+#[test]
+fn saturating_amount_shifts_out() {
+    let t = Tnum::new(0x8000_0000_0000_0001u64, 0x10);
+    let sat = ShiftSemantics::Saturating;
+    assert_eq!(t.shl_const_with(64, sat), Tnum::const_val(0));
+    assert_eq!(t.lshr_const_with(100, sat), Tnum::const_val(0));
+    // 符号位为 1：算术右移得全 1；符号位未知：全部未知
+    assert_eq!(t.ashr_const_with(64, sat), Tnum::const_val(u64::MAX));
+    assert_eq!(Tnum::new(0u64, 1 << 63).ashr_const_with(64, sat), Tnum::top());
+    assert_eq!(t.shl_const_with(63, sat), t.shl_const(63));
+}
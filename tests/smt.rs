@@ -0,0 +1,31 @@
+//! smt 模块的编码与 Rust 实现对拍，不需要 z3 特性
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::smt;
+
+const SAMPLES: usize = 2_000;
+
+#[test]
+fn symbolic_ops_match_implementation() {
+    for &(name, _) in smt::SYMBOLIC_OPS {
+        if let Err(e) = smt::self_check(name, SAMPLES) {
+            panic!("{}: {}", name, e);
+        }
+    }
+}
+
+#[test]
+fn smtlib_shares_subterms() {
+    // 展开 64 步的乘法写成树会有指数个节点
+    let script = smt::to_smtlib("mul", 64).expect("mul 有符号编码");
+    assert!(script.len() < 1 << 20, "{} 字节", script.len());
+}
+
+#[test]
+fn const_div_ops_match_implementation() {
+    for &(name, _) in smt::CONST_DIV_OPS {
+        if let Err(e) = smt::self_check(name, SAMPLES) {
+            panic!("{}: {}", name, e);
+        }
+    }
+}
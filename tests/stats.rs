@@ -0,0 +1,64 @@
+//! 全局统计：已知输入下各计数器的取值
+//!
+//! 计数器是进程内全局的，所有检查放在同一个测试里，避免与并行测试互相干扰。
+use tnum_z3_verification::stats::{self, Counter, Snapshot};
+use tnum_z3_verification::tnum::Tnum;
+
+/// 清零后执行 `f`，返回期间的计数
+fn counted(f: impl FnOnce()) -> Snapshot {
+    stats::reset();
+    f();
+    stats::snapshot()
+}
+
+#[test]
+fn counters() {
+    stats::enable();
+
+    // 除数为 top：提前返回 top，不算除数为 0
+    let s = counted(|| {
+        Tnum::const_val(7u64).udiv(Tnum::top());
+    });
+    assert_eq!(s.get(Counter::TopProduced), 1);
+    assert_eq!(s.get(Counter::DivisorZeroTop), 0);
+
+    // 除数恒为 0
+    let s = counted(|| {
+        Tnum::const_val(7u64).udiv(Tnum::const_val(0u64));
+        Tnum::const_val(7u64).urem(Tnum::const_val(0u64));
+    });
+    assert_eq!(s.get(Counter::DivisorZeroTop), 2);
+    assert_eq!(s.get(Counter::TopProduced), 2);
+
+    // 常数除法既不超预算也不产生 top
+    let s = counted(|| {
+        assert!(Tnum::const_val(100u64)
+            .udiv(Tnum::const_val(7u64))
+            .contains_value(14));
+    });
+    assert!(s.iter().all(|(_, n)| n == 0), "{:?}", s);
+
+    // 2^64 × 2 个组合超出枚举预算
+    let s = counted(|| {
+        assert_eq!(
+            Tnum::<u64>::top().udiv_exact_small(Tnum::new(2u64, 1), 1 << 16),
+            None
+        );
+    });
+    assert_eq!(s.get(Counter::BudgetExceeded), 1);
+
+    // 4 个未知位超出 fast_divide 的分情况预算，回退到 sdiv
+    let s = counted(|| {
+        Tnum::const_val(100u64).fast_divide(Tnum::new(1u64, 0xf0));
+    });
+    assert_eq!(s.get(Counter::BudgetExceeded), 1);
+    assert_eq!(s.get(Counter::FastDivideFallback), 1);
+    assert_eq!(s.get(Counter::FastDivideSplit), 0);
+
+    // 关闭后不再计数
+    stats::disable();
+    let s = counted(|| {
+        Tnum::const_val(7u64).udiv(Tnum::const_val(0u64));
+    });
+    assert!(s.iter().all(|(_, n)| n == 0), "{:?}", s);
+}
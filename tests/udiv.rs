@@ -0,0 +1,72 @@
+//! udiv 的已知位：高位来自商的上界，低位来自商的区间 [min(x) / max(y), max(x) / min(y)]
+//!
+//! 8 位上检查可靠性，除数为 2 的幂常数时须是最优抽象，常数相除须是精确的。
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{for_each_concrete, Tnum};
+use tnum_z3_verification::width::width_tnums;
+
+#[test]
+fn udiv_is_sound() {
+    let divisors = width_tnums::<u8>(2, 128);
+    for a in width_tnums::<u8>(8, 256) {
+        for b in &divisors {
+            let res = a.udiv(*b);
+            for_each_concrete(&a, |x| {
+                for_each_concrete(b, |y| {
+                    if let Some(q) = x.checked_div(y) {
+                        assert!(
+                            res.contains_value(q),
+                            "{:?} / {:?}: {} / {} = {} 不在 {:?} 中",
+                            a,
+                            b,
+                            x,
+                            y,
+                            q,
+                            res
+                        );
+                    }
+                })
+            });
+        }
+    }
+}
+
+#[test]
+fn udiv_by_power_of_two_is_optimal() {
+    for a in width_tnums::<u8>(8, 0) {
+        for k in 0..8 {
+            let b = Tnum::const_val(1u8 << k);
+            assert_eq!(
+                Some(a.udiv(b)),
+                a.udiv_exact_small(b, usize::MAX),
+                "{:?} / {}",
+                a,
+                1 << k
+            );
+        }
+    }
+}
+
+#[test]
+fn udiv_of_constants_is_exact() {
+    for x in 0..=u8::MAX {
+        for y in 1..=u8::MAX {
+            let res = Tnum::const_val(x).udiv(Tnum::const_val(y));
+            assert_eq!(res, Tnum::const_val(x / y), "{} / {}", x, y);
+        }
+    }
+}
+
+#[test]
+fn narrow_quotient_range_fixes_low_bits() {
+    // [100, 103] / 7 全部为 14
+    let a = Tnum::new(100u64, 0b11);
+    assert_eq!(a.udiv(Tnum::const_val(7)), Tnum::const_val(14));
+    // [96, 127] / {5, 7}：商在 [13, 25] 中，只有高位已知
+    let a = Tnum::new(96u64, 0x1f);
+    let res = a.udiv(Tnum::new(5u64, 0b10));
+    assert_eq!(res, Tnum::from_range(13, 25));
+    // 除数可能为 0 时仍为 top
+    assert_eq!(a.udiv(Tnum::new(0u64, 0b11)), Tnum::top());
+}
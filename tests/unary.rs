@@ -0,0 +1,99 @@
+//! 一元转移函数的可靠性：取负、按位取反、popcount、截断、扩展与字节交换
+//!
+//! 通用的运算在 8 位上穷举、在 64 位上抽样；64 位专有的运算只抽样。`verify-unary` 模式打印同样的检查。
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::Tnum;
+use tnum_z3_verification::width::{
+    check_unary, unary_op, unary_op_u64, width_tnums, UNARY_OPS, UNARY_OPS_U64,
+};
+
+fn u64_tnums() -> Vec<Tnum> {
+    let mut tnums = width_tnums::<u64>(8, 512);
+    tnums.extend([0, 1, u64::MAX, 1 << 63, 0x80, 0x8000, 0x8000_0000].map(Tnum::const_val));
+    tnums
+}
+
+macro_rules! unary {
+    ($($test:ident => $op:literal,)*) => {
+        $(
+            #[test]
+            fn $test() {
+                let (abs, con) = unary_op::<u8>($op).unwrap();
+                let report = check_unary(&width_tnums::<u8>(8, 0), abs, con);
+                assert!(report.is_sound(), "{} 在 8 位上不可靠: {:?}", $op, report.example);
+                let (abs, con) = unary_op::<u64>($op).unwrap();
+                let report = check_unary(&u64_tnums(), abs, con);
+                assert!(report.is_sound(), "{} 在 64 位上不可靠: {:?}", $op, report.example);
+            }
+        )*
+
+        #[test]
+        fn unary_all_ops_listed() {
+            let listed = [$($op),*];
+            for op in UNARY_OPS {
+                assert!(listed.contains(op), "{} 没有测试", op);
+            }
+        }
+    };
+}
+
+macro_rules! unary_u64 {
+    ($($test:ident => $op:literal,)*) => {
+        $(
+            #[test]
+            fn $test() {
+                let (abs, con) = unary_op_u64($op).unwrap();
+                let report = check_unary(&u64_tnums(), abs, con);
+                assert!(report.is_sound(), "{} 不可靠: {:?}", $op, report.example);
+            }
+        )*
+
+        #[test]
+        fn unary_u64_all_ops_listed() {
+            let listed = [$($op),*];
+            for op in UNARY_OPS_U64 {
+                assert!(listed.contains(op), "{} 没有测试", op);
+            }
+        }
+    };
+}
+
+unary! {
+    unary_neg => "neg",
+    unary_neg_sub => "neg-sub",
+    unary_not => "not",
+    unary_popcount => "popcount",
+    unary_trunc5 => "trunc5",
+    unary_sext4 => "sext4",
+}
+
+unary_u64! {
+    unary_bswap16 => "bswap16",
+    unary_bswap32 => "bswap32",
+    unary_bswap64 => "bswap64",
+    unary_popcount64 => "popcount64",
+    unary_cast8 => "cast8",
+    unary_zext32 => "zext32",
+    unary_trunc1 => "trunc1",
+    unary_sext8 => "sext8",
+    unary_sext16 => "sext16",
+    unary_sext32 => "sext32",
+}
+
+#[test]
+fn neg_is_optimal() {
+    // 8 位上每个 tnum 取负的结果都等于全部具体结果的 join
+    let (abs, con) = unary_op::<u8>("neg").unwrap();
+    let report = check_unary(&width_tnums::<u8>(8, 0), abs, con);
+    assert!(report.is_optimal(), "{:?}", report);
+}
+
+#[test]
+fn bitwise_ops_are_optimal() {
+    for op in ["not", "trunc5", "sext4"] {
+        let (abs, con) = unary_op::<u8>(op).unwrap();
+        let report = check_unary(&width_tnums::<u8>(8, 0), abs, con);
+        assert!(report.is_optimal(), "{}: {:?}", op, report);
+    }
+}
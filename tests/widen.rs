@@ -0,0 +1,91 @@
+//! 加宽与收窄：加宽是上界、加宽链在位宽步内稳定，以及循环计数器的不动点
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::Tnum;
+use tnum_z3_verification::width::width_tnums;
+
+#[test]
+fn widen_is_upper_bound() {
+    let tnums = width_tnums::<u8>(8, 0);
+    let others = width_tnums::<u8>(8, 192);
+    let thresholds = [0x0fu8];
+    for a in &tnums {
+        for b in &others {
+            let w = a.widen(b);
+            assert!(a.le(&w) && b.le(&w), "{:?} ∇ {:?} = {:?}", a, b, w);
+            let t = a.widen_with_thresholds(b, &thresholds);
+            assert!(a.le(&t) && b.le(&t), "{:?} ∇ {:?} = {:?}", a, b, t);
+            // 阈值只会限制向上置为未知的范围
+            assert!(t.le(&w), "{:?}, {:?}", t, w);
+        }
+    }
+}
+
+#[test]
+fn widening_chain_stabilizes() {
+    // 每次严格增大都至少多出一个未知位，任意序列至多 8 步后不再变化
+    let values = width_tnums::<u8>(3, 512);
+    for start in 0..16 {
+        let mut x = values[start];
+        let mut changes = 0;
+        for v in &values[start..] {
+            let next = x.widen(v);
+            if next != x {
+                changes += 1;
+            }
+            x = next;
+        }
+        assert!(changes <= 8, "{} 次变化", changes);
+    }
+}
+
+/// `for (i = 0; i < 200; i++)` 达到不动点的轮数与结果
+fn counter_fixpoint(step: impl Fn(&Tnum, &Tnum) -> Tnum) -> (u32, Tnum) {
+    let init = Tnum::const_val(0);
+    let body = |i: Tnum| i.refine_lt_const(200).add(Tnum::const_val(1));
+    let (mut x, mut rounds) = (init, 0);
+    loop {
+        rounds += 1;
+        let next = step(&x, &init.or(&body(x)));
+        if next == x {
+            return (rounds, x);
+        }
+        x = next;
+    }
+}
+
+#[test]
+fn loop_counter() {
+    let (join_rounds, joined) = counter_fixpoint(|a, b| a.or(b));
+    let (widen_rounds, widened) = counter_fixpoint(|a, b| a.widen(b));
+    let (threshold_rounds, thresholded) =
+        counter_fixpoint(|a, b| a.widen_with_thresholds(b, &[0xff, 0xffff, 0xffff_ffff]));
+    assert!(
+        widen_rounds < join_rounds,
+        "{} / {}",
+        widen_rounds,
+        join_rounds
+    );
+    assert!(threshold_rounds <= join_rounds);
+    // 不动点包含 0..=200 的每个值
+    for fixpoint in [joined, widened, thresholded] {
+        assert!(
+            (0..=200).all(|i| fixpoint.contains_value(i)),
+            "{:?}",
+            fixpoint
+        );
+    }
+    // 低 8 位未知时加 1 可能进位到第 8 位，阈值把未知位限制在低 16 位，不加阈值时一直向上置为未知
+    assert_eq!(thresholded, Tnum::new(0, 0xffff));
+    assert!(thresholded.le(&widened) && thresholded != widened);
+    // 收窄一轮仍包含全部值
+    let init = Tnum::const_val(0);
+    let next = init.or(&widened.refine_lt_const(200).add(Tnum::const_val(1)));
+    let narrowed = widened.narrow(&next);
+    assert!(narrowed.le(&widened));
+    assert!(
+        (0..=200).all(|i| narrowed.contains_value(i)),
+        "{:?}",
+        narrowed
+    );
+}
@@ -0,0 +1,107 @@
+//! 任意位宽 `Tnum<T>` 上的转移函数可靠性
+//!
+//! 每个运算一个测试，在 8、16 与 128 位上抽样检查，具体结果须落在抽象结果中；8 位上另外穷举
+//! 只有低 4 位或只有高 4 位可能非零的全部 tnum 对。`verify-width` 与 `exhaustive` 模式打印
+//! 同样的检查以及精度统计，后者穷举全部 8 位 tnum 对，在调试构建下太慢，不放在这里。
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::{Tnum, Word};
+use tnum_z3_verification::width::{check_width, width_op, width_tnums, WIDTH_OPS};
+
+/// 抽样的 tnum 加上边界常数：0、1、最大值、符号位，以及等于或超过位宽的移位量
+fn tnums<T: Word>(samples: usize) -> Vec<Tnum<T>> {
+    let mut tnums = width_tnums::<T>(4, samples);
+    tnums.extend(
+        [
+            T::ZERO,
+            T::ONE,
+            T::MAX,
+            T::sign_bit(),
+            T::from_u64(T::BITS as u64 - 1),
+            T::from_u64(T::BITS as u64),
+        ]
+        .map(Tnum::const_val),
+    );
+    tnums
+}
+
+/// value | mask 只落在低 4 位或只落在高 4 位的全部 8 位 tnum（各 81 个，共用常数 0）
+fn windows() -> Vec<Tnum<u8>> {
+    width_tnums::<u8>(8, 0)
+        .into_iter()
+        .filter(|t| {
+            let bits = t.value | t.mask;
+            bits & 0xf0 == 0 || bits & 0x0f == 0
+        })
+        .collect()
+}
+
+fn sound<T: Word>(op: &str, tnums: &[Tnum<T>]) {
+    let (abs, con) = width_op::<T>(op).expect(op);
+    let report = check_width(tnums, abs, con);
+    assert!(
+        report.is_sound(),
+        "{} 在 {} 位上不可靠: {:?}",
+        op,
+        T::BITS,
+        report.example
+    );
+}
+
+macro_rules! width {
+    ($($test:ident => $op:literal,)*) => {
+        $(
+            #[test]
+            fn $test() {
+                let mut u8s = tnums::<u8>(64);
+                u8s.push(Tnum::top());
+                sound::<u8>($op, &u8s);
+                sound::<u8>($op, &windows());
+                sound::<u16>($op, &tnums(32));
+                sound::<u128>($op, &tnums(32));
+            }
+        )*
+
+        #[test]
+        fn width_all_ops_listed() {
+            let listed = [$($op),*];
+            for op in WIDTH_OPS {
+                assert!(listed.contains(op), "{} 没有测试", op);
+            }
+        }
+    };
+}
+
+width! {
+    width_add => "add",
+    width_sub => "sub",
+    width_mul => "mul",
+    width_xor => "xor",
+    width_and => "and",
+    width_or => "or",
+    width_udiv => "udiv",
+    width_sat_add => "sat_add",
+    width_sat_sub => "sat_sub",
+    width_sat_mul => "sat_mul",
+    width_checked_add => "checked_add",
+    width_checked_sub => "checked_sub",
+    width_checked_mul => "checked_mul",
+    width_urem => "urem",
+    width_shl => "shl",
+    width_lshr => "lshr",
+    width_shl_sat => "shl-sat",
+    width_lshr_sat => "lshr-sat",
+    width_ashr_sat => "ashr-sat",
+    width_ashr => "ashr",
+    width_umax => "umax",
+    width_umin => "umin",
+    width_smax => "smax",
+    width_smin => "smin",
+    width_rotl => "rotl",
+    width_rotr => "rotr",
+}
+
+#[test]
+fn windows_are_complete() {
+    assert_eq!(windows().len(), 81 + 81 - 1);
+}
@@ -0,0 +1,75 @@
+//! 未知位见证：固有与可确定的位、枚举预算
+#![cfg(feature = "std")]
+
+use tnum_z3_verification::tnum::Tnum;
+use tnum_z3_verification::witness::{unknown_bit_witnesses, BitWitness};
+
+fn mul(x: u64, y: u64) -> Option<u64> {
+    Some(x.wrapping_mul(y))
+}
+
+#[test]
+fn top_times_top_exceeds_budget() {
+    // 具体值组合数 2^128 超出 u128，不能溢出
+    let (a, b) = (Tnum::top(), Tnum::top());
+    assert_eq!(unknown_bit_witnesses(a, b, a.mul(b), mul, u64::MAX), None);
+}
+
+#[test]
+fn singletons() {
+    let (a, b) = (Tnum::const_val(6), Tnum::const_val(7));
+    assert_eq!(
+        unknown_bit_witnesses(a, b, a.mul(b), mul, 1),
+        Some(Vec::new())
+    );
+    // 结果放宽为 top 时每一位都可确定为 42 的对应位
+    let witnesses = unknown_bit_witnesses(a, b, Tnum::top(), mul, 1).unwrap();
+    assert_eq!(witnesses.len(), 64);
+    for w in witnesses {
+        match w {
+            BitWitness::CouldBeKnown { bit, known } => assert_eq!(known, 42u64 >> bit & 1 == 1),
+            w => panic!("{:?}", w),
+        }
+    }
+}
+
+#[test]
+fn inherent_bits() {
+    // x ∈ {4, 5}，x + 1 ∈ {5, 6}：低两位都可取 0 和 1
+    let (a, b) = (Tnum::new(4, 1), Tnum::const_val(1));
+    let witnesses = unknown_bit_witnesses(a, b, a.add(b), |x, y| Some(x + y), 2).unwrap();
+    assert_eq!(witnesses.len(), 2);
+    for w in witnesses {
+        match w {
+            BitWitness::Inherent { bit, zero, one } => {
+                assert_eq!((zero.0 + zero.1) >> bit & 1, 0);
+                assert_eq!((one.0 + one.1) >> bit & 1, 1);
+            }
+            w => panic!("{:?}", w),
+        }
+    }
+}
+
+#[test]
+fn budget_cutoff() {
+    // 2 个未知位 × 3 个未知位，共 32 个具体值组合
+    let (a, b) = (Tnum::new(0, 0b11), Tnum::new(0, 0b111));
+    let result = a.mul(b);
+    assert_eq!(unknown_bit_witnesses(a, b, result, mul, 31), None);
+    let witnesses = unknown_bit_witnesses(a, b, result, mul, 32).unwrap();
+    assert_eq!(witnesses.len(), result.mask.count_ones() as usize);
+}
+
+#[test]
+fn undefined_inputs_are_skipped() {
+    // 除数只能是 0：没有有定义的输入，每一位都可取确定值
+    let (a, b) = (Tnum::new(0, 1), Tnum::const_val(0));
+    let witnesses = unknown_bit_witnesses(a, b, Tnum::new(0, 1), u64::checked_div, 2).unwrap();
+    assert_eq!(
+        witnesses,
+        vec![BitWitness::CouldBeKnown {
+            bit: 0,
+            known: true
+        }]
+    );
+}